@@ -0,0 +1,349 @@
+use super::provider::{EmbeddingError, EmbeddingProvider};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Hit/miss counters since the cache was created
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    /// Served from memory
+    pub hits: u64,
+    /// Served from the on-disk store after a memory miss
+    pub disk_hits: u64,
+    /// Sent to the underlying provider
+    pub misses: u64,
+    pub evictions: u64,
+    /// Entries currently held in memory
+    pub entries: usize,
+}
+
+/// Content-addressed cache in front of another provider.
+///
+/// Entries are keyed by model identifier and normalized text, so switching
+/// models never serves stale vectors. An in-memory LRU sits in front of an
+/// optional on-disk store that survives restarts.
+pub struct CachedEmbeddingProvider {
+    inner: Arc<dyn EmbeddingProvider>,
+    memory: Mutex<Lru>,
+    disk: Option<PathBuf>,
+    hits: AtomicU64,
+    disk_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CachedEmbeddingProvider {
+    pub fn new(inner: Arc<dyn EmbeddingProvider>, capacity: usize, disk: Option<PathBuf>) -> Self {
+        Self {
+            inner,
+            memory: Mutex::new(Lru::new(capacity)),
+            disk,
+            hits: AtomicU64::new(0),
+            disk_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn key(&self, text: &str) -> String {
+        format!("{}\n{}", self.inner.model(), normalize(text))
+    }
+
+    fn memory(&self) -> std::sync::MutexGuard<'_, Lru> {
+        // A panic mid-update can at worst leave a stale entry behind
+        self.memory.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn lookup(&self, key: &str) -> Option<Vec<f64>> {
+        if let Some(embedding) = self.memory().get(key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(embedding);
+        }
+
+        let embedding = self.read_disk(key).await?;
+        self.disk_hits.fetch_add(1, Ordering::Relaxed);
+        self.memory().insert(key.to_string(), embedding.clone());
+        Some(embedding)
+    }
+
+    async fn store(&self, key: String, embedding: Vec<f64>) {
+        self.write_disk(&key, &embedding).await;
+        self.memory().insert(key, embedding);
+    }
+
+    fn disk_path(&self, key: &str) -> Option<PathBuf> {
+        let hash = format!("{:016x}", fnv1a(key.as_bytes()));
+        self.disk
+            .as_ref()
+            .map(|dir| dir.join(&hash[..2]).join(format!("{}.json", hash)))
+    }
+
+    async fn read_disk(&self, key: &str) -> Option<Vec<f64>> {
+        let bytes = tokio::fs::read(self.disk_path(key)?).await.ok()?;
+        let entry: DiskEntry = serde_json::from_slice(&bytes).ok()?;
+        // The file name is only a hash; the stored key settles collisions
+        (entry.key == key).then_some(entry.embedding)
+    }
+
+    /// Best effort: a failed write only costs a future re-embed
+    async fn write_disk(&self, key: &str, embedding: &[f64]) {
+        let Some(path) = self.disk_path(key) else {
+            return;
+        };
+        let entry = DiskEntry {
+            key: key.to_string(),
+            embedding: embedding.to_vec(),
+        };
+        let result = async {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, serde_json::to_vec(&entry)?).await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to write embedding cache entry {:?}: {}", path, e);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    embedding: Vec<f64>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for CachedEmbeddingProvider {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f64>>, EmbeddingError> {
+        let keys: Vec<String> = texts.iter().map(|t| self.key(t)).collect();
+        let mut results: Vec<Option<Vec<f64>>> = Vec::with_capacity(texts.len());
+        for key in &keys {
+            results.push(self.lookup(key).await);
+        }
+
+        // Embed each distinct missing text once
+        let mut missing: Vec<usize> = Vec::new();
+        let mut seen: HashSet<&str> = HashSet::new();
+        for (i, result) in results.iter().enumerate() {
+            if result.is_none() && seen.insert(&keys[i]) {
+                missing.push(i);
+            }
+        }
+        if missing.is_empty() {
+            return Ok(results.into_iter().flatten().collect());
+        }
+
+        self.misses
+            .fetch_add(missing.len() as u64, Ordering::Relaxed);
+        let batch: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+        let embeddings = self.inner.embed_batch(&batch).await?;
+        let mut fresh: HashMap<&str, Vec<f64>> = HashMap::new();
+        for (&i, embedding) in missing.iter().zip(embeddings) {
+            self.store(keys[i].clone(), embedding.clone()).await;
+            fresh.insert(&keys[i], embedding);
+        }
+
+        results
+            .into_iter()
+            .zip(&keys)
+            .map(|(result, key)| {
+                result
+                    .or_else(|| fresh.get(key.as_str()).cloned())
+                    .ok_or_else(|| EmbeddingError::InvalidResponse("Missing embedding".to_string()))
+            })
+            .collect()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        let memory = self.memory();
+        Some(CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            disk_hits: self.disk_hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: memory.evictions,
+            entries: memory.entries.len(),
+        })
+    }
+}
+
+/// Texts differing only in case or whitespace share an entry
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Least-recently-used map; `order` maps last-use tick to key
+struct Lru {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, (Vec<f64>, u64)>,
+    order: BTreeMap<u64, String>,
+    evictions: u64,
+}
+
+impl Lru {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            evictions: 0,
+        }
+    }
+
+    fn touch(&mut self, key: &str) {
+        self.tick += 1;
+        if let Some((_, last_used)) = self.entries.get_mut(key) {
+            self.order.remove(last_used);
+            *last_used = self.tick;
+            self.order.insert(self.tick, key.to_string());
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<Vec<f64>> {
+        if !self.entries.contains_key(key) {
+            return None;
+        }
+        self.touch(key);
+        self.entries
+            .get(key)
+            .map(|(embedding, _)| embedding.clone())
+    }
+
+    fn insert(&mut self, key: String, embedding: Vec<f64>) {
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.0 = embedding;
+            self.touch(&key);
+            return;
+        }
+        if self.entries.len() >= self.capacity
+            && let Some((_, oldest)) = self.order.pop_first()
+        {
+            self.entries.remove(&oldest);
+            self.evictions += 1;
+        }
+        self.tick += 1;
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, (embedding, self.tick));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::hash::HashEmbeddingProvider;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Hash provider that counts the texts it is asked to embed
+    struct Counting {
+        inner: HashEmbeddingProvider,
+        embedded: AtomicUsize,
+    }
+
+    impl Counting {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                inner: HashEmbeddingProvider::new(8),
+                embedded: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for Counting {
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f64>>, EmbeddingError> {
+            self.embedded.fetch_add(texts.len(), Ordering::Relaxed);
+            self.inner.embed_batch(texts).await
+        }
+
+        fn model(&self) -> &str {
+            self.inner.model()
+        }
+
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+    }
+
+    #[test]
+    fn lru_evicts_the_least_recently_used_entry() {
+        let mut lru = Lru::new(2);
+        lru.insert("a".to_string(), vec![1.0]);
+        lru.insert("b".to_string(), vec![2.0]);
+        assert_eq!(lru.get("a"), Some(vec![1.0]));
+
+        lru.insert("c".to_string(), vec![3.0]);
+        assert_eq!(lru.get("b"), None);
+        assert_eq!(lru.get("a"), Some(vec![1.0]));
+        assert_eq!(lru.get("c"), Some(vec![3.0]));
+        assert_eq!(lru.evictions, 1);
+        assert_eq!(lru.entries.len(), lru.order.len());
+    }
+
+    #[test]
+    fn reinserting_a_key_replaces_it_without_evicting() {
+        let mut lru = Lru::new(1);
+        lru.insert("a".to_string(), vec![1.0]);
+        lru.insert("a".to_string(), vec![2.0]);
+        assert_eq!(lru.get("a"), Some(vec![2.0]));
+        assert_eq!(lru.evictions, 0);
+    }
+
+    #[tokio::test]
+    async fn case_and_whitespace_variants_share_an_entry() {
+        assert_eq!(normalize("  Chess\tTactics \n"), "chess tactics");
+
+        let inner = Counting::new();
+        let cache = CachedEmbeddingProvider::new(inner.clone(), 10, None);
+        let first = cache.embed("Chess Tactics").await.unwrap();
+        let second = cache.embed("chess   tactics").await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(inner.embedded.load(Ordering::Relaxed), 1);
+        let stats = cache.cache_stats().unwrap();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+    }
+
+    #[tokio::test]
+    async fn disk_entries_survive_a_new_cache() {
+        let dir =
+            std::env::temp_dir().join(format!("atlas-embedding-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let texts = vec!["Opening theory".to_string(), "Endgames".to_string()];
+        let written = CachedEmbeddingProvider::new(Counting::new(), 10, Some(dir.clone()))
+            .embed_batch(&texts)
+            .await
+            .unwrap();
+
+        let inner = Counting::new();
+        let reloaded = CachedEmbeddingProvider::new(inner.clone(), 10, Some(dir.clone()));
+        let read = reloaded.embed_batch(&texts).await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(read, written);
+        assert_eq!(inner.embedded.load(Ordering::Relaxed), 0);
+        let stats = reloaded.cache_stats().unwrap();
+        assert_eq!((stats.disk_hits, stats.misses), (2, 0));
+    }
+}
@@ -0,0 +1,103 @@
+use super::provider::{EmbeddingError, EmbeddingProvider};
+use async_trait::async_trait;
+
+/// Deterministic, offline provider for tests and local development.
+///
+/// Each lowercased word is hashed into a signed bucket and the result is
+/// L2-normalized, so texts sharing words get a positive cosine similarity.
+/// The vectors carry no meaning beyond word overlap.
+pub struct HashEmbeddingProvider {
+    dimensions: usize,
+    model: String,
+}
+
+impl HashEmbeddingProvider {
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions: dimensions.max(1),
+            model: Self::model_name(dimensions),
+        }
+    }
+
+    pub fn model_name(dimensions: usize) -> String {
+        format!("hash-{}", dimensions.max(1))
+    }
+
+    fn embed_text(&self, text: &str) -> Vec<f64> {
+        let mut vector = vec![0.0; self.dimensions];
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            let hash = fnv1a(word.to_lowercase().as_bytes());
+            let bucket = (hash % self.dimensions as u64) as usize;
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[bucket] += sign;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        } else {
+            // Keep empty text from producing a zero vector, which has no cosine
+            vector[0] = 1.0;
+        }
+        vector
+    }
+}
+
+/// FNV-1a, chosen over `DefaultHasher` because its output is stable across builds
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[async_trait]
+impl EmbeddingProvider for HashEmbeddingProvider {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f64>>, EmbeddingError> {
+        Ok(texts.iter().map(|t| self.embed_text(t)).collect())
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn name(&self) -> &'static str {
+        "hash"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_matches_the_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn same_words_give_the_same_unit_vector() {
+        let provider = HashEmbeddingProvider::new(64);
+        let a = provider.embed_text("Chess tactics");
+        let b = provider.embed_text("chess, TACTICS");
+
+        assert_eq!(a.len(), 64);
+        assert_eq!(a, b);
+        assert_eq!(
+            a,
+            HashEmbeddingProvider::new(64).embed_text("Chess tactics")
+        );
+        let norm = a.iter().map(|v| v * v).sum::<f64>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-12);
+        assert_ne!(a, provider.embed_text("Opening theory"));
+    }
+
+    #[test]
+    fn empty_text_is_not_a_zero_vector() {
+        let vector = HashEmbeddingProvider::new(4).embed_text("  ");
+        assert_eq!(vector, vec![1.0, 0.0, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,81 @@
+use super::provider::{EmbeddingError, EmbeddingProvider, check_batch};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    content: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponseItem {
+    #[serde(default)]
+    index: Option<usize>,
+    embedding: Vec<Vec<f64>>,
+}
+
+/// Provider for the llama.cpp server `/embedding` endpoint (`embeddings_api`)
+pub struct LlamaCppEmbeddingProvider {
+    client: Client,
+    endpoint: String,
+    model: String,
+}
+
+impl LlamaCppEmbeddingProvider {
+    pub fn new(client: Client, endpoint: String, model: String) -> Self {
+        Self {
+            client,
+            endpoint,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for LlamaCppEmbeddingProvider {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f64>>, EmbeddingError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .json(&EmbeddingRequest { content: texts })
+            .send()
+            .await
+            .map_err(|e| EmbeddingError::ConnectionFailed(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(EmbeddingError::RequestFailed(format!(
+                "Embedding endpoint returned status: {}",
+                response.status()
+            )));
+        }
+
+        let mut items: Vec<EmbeddingResponseItem> = response
+            .json()
+            .await
+            .map_err(|e| EmbeddingError::ParseError(e.to_string()))?;
+
+        // Older servers omit `index` and answer in request order
+        if items.iter().all(|item| item.index.is_some()) {
+            items.sort_by_key(|item| item.index);
+        }
+
+        let vectors = items
+            .into_iter()
+            .map(|item| item.embedding.into_iter().next().unwrap_or_default())
+            .collect();
+        check_batch(vectors, texts.len())
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn name(&self) -> &'static str {
+        "llama_cpp"
+    }
+}
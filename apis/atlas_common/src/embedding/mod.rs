@@ -0,0 +1,233 @@
+//! Text embeddings behind a pluggable [`EmbeddingProvider`].
+//!
+//! The backend is chosen with `EMBEDDING_PROVIDER`:
+//! - `llama_cpp` (default): the llama.cpp server in `embeddings_api`
+//! - `openai`: any OpenAI-compatible `/v1/embeddings` endpoint
+//! - `hash`: deterministic offline vectors for tests and local development
+//!
+//! One provider, and with it one pooled HTTP client, is shared by the whole
+//! process, so the worker reuses it across warm Lambda invocations. It is
+//! wrapped in a [`CachedEmbeddingProvider`] holding `EMBEDDING_CACHE_SIZE`
+//! entries in memory (default 10000, `0` disables the cache) and, when
+//! `EMBEDDING_CACHE_DIR` is set (e.g. under `/tmp` on Lambda), persisting them
+//! on disk.
+
+pub mod cache;
+pub mod hash;
+pub mod llama_cpp;
+pub mod openai;
+pub mod provider;
+
+use cache::CachedEmbeddingProvider;
+use hash::HashEmbeddingProvider;
+use llama_cpp::LlamaCppEmbeddingProvider;
+use openai::OpenAiEmbeddingProvider;
+use reqwest::Client;
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+pub use cache::CacheStats;
+pub use provider::{EmbeddingError, EmbeddingProvider};
+
+const DEFAULT_LLAMA_CPP_MODEL: &str = "all-MiniLM-L6-v2";
+const DEFAULT_OPENAI_MODEL: &str = "text-embedding-3-small";
+const DEFAULT_BATCH_SIZE: usize = 32;
+const DEFAULT_CACHE_SIZE: usize = 10_000;
+/// all-MiniLM-L6-v2, the model served by `embeddings_api`
+const DEFAULT_EMBEDDING_DIMENSIONS: usize = 384;
+
+/// Version of the text recipe behind stored node embeddings (see
+/// `node_embedding_text`); bump it to have the re-embed job refresh every node
+pub const EMBEDDING_VERSION: i64 = 1;
+
+/// Available embedding provider types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProviderType {
+    #[default]
+    LlamaCpp,
+    OpenAi,
+    Hash,
+}
+
+impl ProviderType {
+    pub fn from_env() -> Result<Self, EmbeddingError> {
+        Self::from_name(env::var("EMBEDDING_PROVIDER").ok().as_deref())
+    }
+
+    /// Provider for an `EMBEDDING_PROVIDER` value; unset means `llama_cpp`
+    fn from_name(name: Option<&str>) -> Result<Self, EmbeddingError> {
+        match name {
+            None | Some("llama_cpp") => Ok(ProviderType::LlamaCpp),
+            Some("openai") => Ok(ProviderType::OpenAi),
+            Some("hash") => Ok(ProviderType::Hash),
+            Some(other) => Err(EmbeddingError::NotConfigured(format!(
+                "Unknown EMBEDDING_PROVIDER '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Factory function to create an embedding provider from the environment
+pub fn create_provider(
+    provider_type: ProviderType,
+) -> Result<Arc<dyn EmbeddingProvider>, EmbeddingError> {
+    let endpoint = || {
+        env::var("EMBEDDING_ENDPOINT").map_err(|_| {
+            EmbeddingError::NotConfigured(
+                "EMBEDDING_ENDPOINT environment variable not set".to_string(),
+            )
+        })
+    };
+    let provider: Arc<dyn EmbeddingProvider> = match provider_type {
+        ProviderType::LlamaCpp => Arc::new(LlamaCppEmbeddingProvider::new(
+            http_client()?,
+            endpoint()?,
+            model_for(provider_type),
+        )),
+        ProviderType::OpenAi => Arc::new(OpenAiEmbeddingProvider::new(
+            http_client()?,
+            endpoint()?,
+            model_for(provider_type),
+            env::var("EMBEDDING_API_KEY").ok(),
+        )),
+        ProviderType::Hash => Arc::new(HashEmbeddingProvider::new(embedding_dimensions())),
+    };
+
+    tracing::info!(
+        "Embedding provider '{}' initialized with model {}",
+        provider.name(),
+        provider.model()
+    );
+    Ok(provider)
+}
+
+/// Model identifier a provider of this type reports
+fn model_for(provider_type: ProviderType) -> String {
+    let configured =
+        |default: &str| env::var("EMBEDDING_MODEL").unwrap_or_else(|_| default.to_string());
+    match provider_type {
+        ProviderType::LlamaCpp => configured(DEFAULT_LLAMA_CPP_MODEL),
+        ProviderType::OpenAi => configured(DEFAULT_OPENAI_MODEL),
+        ProviderType::Hash => HashEmbeddingProvider::model_name(embedding_dimensions()),
+    }
+}
+
+/// Model identifier of the configured provider, without connecting to it
+pub fn configured_model() -> Result<String, EmbeddingError> {
+    Ok(model_for(ProviderType::from_env()?))
+}
+
+fn http_client() -> Result<Client, EmbeddingError> {
+    Client::builder()
+        .timeout(Duration::from_secs(60))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .build()
+        .map_err(|e| EmbeddingError::ConnectionFailed(e.to_string()))
+}
+
+static PROVIDER: OnceLock<Arc<dyn EmbeddingProvider>> = OnceLock::new();
+
+/// The process-wide provider, created from the environment on first use
+pub fn default_provider() -> Result<Arc<dyn EmbeddingProvider>, EmbeddingError> {
+    if let Some(provider) = PROVIDER.get() {
+        return Ok(provider.clone());
+    }
+    let provider = with_cache(create_provider(ProviderType::from_env()?)?);
+    Ok(PROVIDER.get_or_init(|| provider).clone())
+}
+
+/// Wrap `provider` in the cache configured by the environment, if any
+pub fn with_cache(provider: Arc<dyn EmbeddingProvider>) -> Arc<dyn EmbeddingProvider> {
+    let capacity = env::var("EMBEDDING_CACHE_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_CACHE_SIZE);
+    if capacity == 0 {
+        return provider;
+    }
+    let disk = env::var("EMBEDDING_CACHE_DIR").ok().map(PathBuf::from);
+    Arc::new(CachedEmbeddingProvider::new(provider, capacity, disk))
+}
+
+/// Counters of the default provider's cache, if it has one
+pub fn cache_stats() -> Option<CacheStats> {
+    default_provider()
+        .ok()
+        .and_then(|provider| provider.cache_stats())
+}
+
+/// Vector length of the configured model (`EMBEDDING_DIMENSIONS`)
+pub fn embedding_dimensions() -> usize {
+    env::var("EMBEDDING_DIMENSIONS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_EMBEDDING_DIMENSIONS)
+}
+
+/// Largest number of texts sent in one request (`EMBEDDING_BATCH_SIZE`)
+fn batch_size() -> usize {
+    env::var("EMBEDDING_BATCH_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(DEFAULT_BATCH_SIZE)
+}
+
+/// The text embedded for a node; changing it requires bumping `EMBEDDING_VERSION`
+pub fn node_embedding_text(name: &str, description: &str) -> String {
+    format!("{}: {}", name, description)
+}
+
+/// Embed a single text with the default provider
+pub async fn generate_embedding(text_to_embed: &str) -> Result<Vec<f64>, EmbeddingError> {
+    default_provider()?.embed(text_to_embed).await
+}
+
+/// Embed many texts with the default provider, one request per batch
+pub async fn generate_embeddings(texts: &[String]) -> Result<Vec<Vec<f64>>, EmbeddingError> {
+    let provider = default_provider()?;
+    let mut embeddings = Vec::with_capacity(texts.len());
+    for chunk in texts.chunks(batch_size()) {
+        embeddings.extend(provider.embed_batch(chunk).await?);
+    }
+    Ok(embeddings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_defaults_to_llama_cpp_and_rejects_unknown_names() {
+        assert_eq!(
+            ProviderType::from_name(None).unwrap(),
+            ProviderType::LlamaCpp
+        );
+        assert_eq!(
+            ProviderType::from_name(Some("llama_cpp")).unwrap(),
+            ProviderType::LlamaCpp
+        );
+        assert_eq!(
+            ProviderType::from_name(Some("openai")).unwrap(),
+            ProviderType::OpenAi
+        );
+        assert_eq!(
+            ProviderType::from_name(Some("hash")).unwrap(),
+            ProviderType::Hash
+        );
+        assert!(matches!(
+            ProviderType::from_name(Some("OpenAI")),
+            Err(EmbeddingError::NotConfigured(_))
+        ));
+    }
+
+    #[test]
+    fn hash_provider_needs_no_endpoint() {
+        let provider = create_provider(ProviderType::Hash).unwrap();
+        assert_eq!(provider.name(), "hash");
+        assert_eq!(provider.model(), model_for(ProviderType::Hash));
+    }
+}
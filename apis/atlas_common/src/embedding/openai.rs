@@ -0,0 +1,88 @@
+use super::provider::{EmbeddingError, EmbeddingProvider, check_batch};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f64>,
+}
+
+/// Provider for OpenAI-compatible `/v1/embeddings` endpoints
+pub struct OpenAiEmbeddingProvider {
+    client: Client,
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl OpenAiEmbeddingProvider {
+    pub fn new(client: Client, endpoint: String, model: String, api_key: Option<String>) -> Self {
+        Self {
+            client,
+            endpoint,
+            model,
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddingProvider {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f64>>, EmbeddingError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut request = self.client.post(&self.endpoint).json(&EmbeddingRequest {
+            model: &self.model,
+            input: texts,
+        });
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| EmbeddingError::ConnectionFailed(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(EmbeddingError::RequestFailed(format!(
+                "Embedding endpoint returned status: {}",
+                response.status()
+            )));
+        }
+
+        let mut body: EmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| EmbeddingError::ParseError(e.to_string()))?;
+        body.data.sort_by_key(|d| d.index);
+
+        check_batch(
+            body.data.into_iter().map(|d| d.embedding).collect(),
+            texts.len(),
+        )
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+}
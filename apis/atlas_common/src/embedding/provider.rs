@@ -0,0 +1,74 @@
+use super::cache::CacheStats;
+use async_trait::async_trait;
+use std::fmt;
+
+/// Error type for embedding operations
+#[derive(Debug)]
+pub enum EmbeddingError {
+    NotConfigured(String),
+    ConnectionFailed(String),
+    RequestFailed(String),
+    ParseError(String),
+    InvalidResponse(String),
+}
+
+impl fmt::Display for EmbeddingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmbeddingError::NotConfigured(msg) => write!(f, "Not configured: {}", msg),
+            EmbeddingError::ConnectionFailed(msg) => write!(f, "Connection failed: {}", msg),
+            EmbeddingError::RequestFailed(msg) => write!(f, "Request failed: {}", msg),
+            EmbeddingError::ParseError(msg) => write!(f, "Parse error: {}", msg),
+            EmbeddingError::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for EmbeddingError {}
+
+/// Core trait for embedding backends
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Embed several texts, returning one vector per text in input order
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f64>>, EmbeddingError>;
+
+    /// Embed a single text
+    async fn embed(&self, text: &str) -> Result<Vec<f64>, EmbeddingError> {
+        self.embed_batch(&[text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| EmbeddingError::InvalidResponse("Empty embedding response".to_string()))
+    }
+
+    /// Identifier of the model producing the vectors; vectors from different
+    /// models are not comparable
+    fn model(&self) -> &str;
+
+    /// Get provider name for logging/debugging
+    fn name(&self) -> &'static str;
+
+    /// Cache counters, for providers wrapped in a cache
+    fn cache_stats(&self) -> Option<CacheStats> {
+        None
+    }
+}
+
+/// Check a batch response has one non-empty vector per input
+pub(super) fn check_batch(
+    vectors: Vec<Vec<f64>>,
+    expected: usize,
+) -> Result<Vec<Vec<f64>>, EmbeddingError> {
+    if vectors.len() != expected {
+        return Err(EmbeddingError::InvalidResponse(format!(
+            "Expected {} embeddings, got {}",
+            expected,
+            vectors.len()
+        )));
+    }
+    if vectors.iter().any(|v| v.is_empty()) {
+        return Err(EmbeddingError::InvalidResponse(
+            "Empty embedding vector".to_string(),
+        ));
+    }
+    Ok(vectors)
+}
@@ -0,0 +1,5 @@
+//! Code shared by the REST API and the domain worker, so both embed text and
+//! judge similarity the same way.
+
+pub mod embedding;
+pub mod thresholds;
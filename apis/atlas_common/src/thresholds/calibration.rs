@@ -0,0 +1,283 @@
+//! Offline threshold calibration from labelled pairs.
+//!
+//! Each line of the input is a JSON object describing two texts, in the same
+//! `"name: description"` form nodes are embedded with, and whether they are
+//! duplicates:
+//!
+//! ```json
+//! {"label": "Skill", "a": "Jab: a straight punch", "b": "Lead straight: ...", "duplicate": true}
+//! ```
+//!
+//! `label` is optional (pairs without one are reported under `default`), and a
+//! precomputed `score` skips embedding the pair. Every threshold from 0.50 to
+//! 0.99 is replayed, treating `score >= threshold` as "duplicate":
+//!
+//! ```text
+//! atlas_of_us_rest_api calibrate-thresholds pairs.jsonl
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::SimilarityThresholds;
+use crate::embedding::{EmbeddingError, generate_embeddings};
+
+const DEFAULT_SCOPE: &str = "default";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LabelledPair {
+    pub label: Option<String>,
+    pub a: String,
+    pub b: String,
+    pub duplicate: bool,
+    pub score: Option<f64>,
+}
+
+/// Confusion counts and derived metrics at one threshold
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdResult {
+    pub threshold: f64,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub true_negatives: usize,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelCalibration {
+    pub label: String,
+    pub pairs: usize,
+    pub duplicates: usize,
+    pub results: Vec<ThresholdResult>,
+    /// Threshold with the highest F1 (the higher threshold on ties)
+    pub best: Option<ThresholdResult>,
+}
+
+/// Parse JSON lines, skipping blank lines
+pub fn parse_pairs(input: &str) -> Result<Vec<LabelledPair>, String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {}", i + 1, e)))
+        .collect()
+}
+
+/// Score every pair (embedding those without a `score`) and sweep thresholds
+/// per label
+pub async fn calibrate(pairs: &[LabelledPair]) -> Result<Vec<LabelCalibration>, EmbeddingError> {
+    let scores = score_pairs(pairs).await?;
+
+    let mut by_label: BTreeMap<&str, Vec<(f64, bool)>> = BTreeMap::new();
+    for (pair, score) in pairs.iter().zip(scores) {
+        let label = pair.label.as_deref().unwrap_or(DEFAULT_SCOPE);
+        by_label
+            .entry(label)
+            .or_default()
+            .push((score, pair.duplicate));
+    }
+
+    Ok(by_label
+        .into_iter()
+        .map(|(label, scored)| sweep(label, &scored))
+        .collect())
+}
+
+async fn score_pairs(pairs: &[LabelledPair]) -> Result<Vec<f64>, EmbeddingError> {
+    let texts: Vec<String> = pairs
+        .iter()
+        .filter(|p| p.score.is_none())
+        .flat_map(|p| [p.a.clone(), p.b.clone()])
+        .collect();
+    let embeddings = generate_embeddings(&texts).await?;
+    let mut embeddings = embeddings.chunks(2);
+
+    Ok(pairs
+        .iter()
+        .map(|pair| match pair.score {
+            Some(score) => score,
+            None => embeddings
+                .next()
+                .map_or(0.0, |pair| cosine_similarity(&pair[0], &pair[1])),
+        })
+        .collect())
+}
+
+/// Cosine similarity mapped to `0..=1`, the scale Neo4j's vector index reports
+fn cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        return 0.0;
+    }
+    (1.0 + dot / denominator) / 2.0
+}
+
+fn sweep(label: &str, scored: &[(f64, bool)]) -> LabelCalibration {
+    let results: Vec<ThresholdResult> = (50..=99)
+        .map(|step| evaluate(f64::from(step) / 100.0, scored))
+        .collect();
+    let best = results
+        .iter()
+        .copied()
+        .filter(|r| r.true_positives > 0)
+        .max_by(|a, b| {
+            a.f1.total_cmp(&b.f1)
+                .then(a.threshold.total_cmp(&b.threshold))
+        });
+
+    LabelCalibration {
+        label: label.to_string(),
+        pairs: scored.len(),
+        duplicates: scored.iter().filter(|(_, duplicate)| *duplicate).count(),
+        results,
+        best,
+    }
+}
+
+fn evaluate(threshold: f64, scored: &[(f64, bool)]) -> ThresholdResult {
+    let (mut tp, mut fp, mut fn_, mut tn) = (0, 0, 0, 0);
+    for &(score, duplicate) in scored {
+        match (score >= threshold, duplicate) {
+            (true, true) => tp += 1,
+            (true, false) => fp += 1,
+            (false, true) => fn_ += 1,
+            (false, false) => tn += 1,
+        }
+    }
+    let ratio = |n: usize, d: usize| if d == 0 { 0.0 } else { n as f64 / d as f64 };
+    let precision = ratio(tp, tp + fp);
+    let recall = ratio(tp, tp + fn_);
+    let f1 = if precision + recall == 0.0 {
+        0.0
+    } else {
+        2.0 * precision * recall / (precision + recall)
+    };
+
+    ThresholdResult {
+        threshold,
+        true_positives: tp,
+        false_positives: fp,
+        false_negatives: fn_,
+        true_negatives: tn,
+        precision,
+        recall,
+        f1,
+    }
+}
+
+/// Plain-text report comparing each label's best threshold with the
+/// currently configured ones
+pub fn render(calibrations: &[LabelCalibration], configured: &SimilarityThresholds) -> String {
+    let mut out = String::new();
+    for calibration in calibrations {
+        let current = configured.for_label(&calibration.label);
+        let _ = writeln!(
+            out,
+            "{} ({} pairs, {} duplicates)",
+            calibration.label, calibration.pairs, calibration.duplicates
+        );
+        let _ = writeln!(
+            out,
+            "  threshold  precision  recall     f1    tp    fp    fn    tn"
+        );
+        for r in &calibration.results {
+            let mut marks = Vec::new();
+            if calibration.best.is_some_and(|b| b.threshold == r.threshold) {
+                marks.push("best");
+            }
+            for (name, value) in [
+                ("duplicate", current.duplicate),
+                ("autoReuse", current.auto_reuse),
+                ("verify", current.verify),
+            ] {
+                if (value - r.threshold).abs() < 0.005 {
+                    marks.push(name);
+                }
+            }
+            let row = format!(
+                "  {:>9.2}  {:>9.3}  {:>6.3}  {:>5.3}  {:>4}  {:>4}  {:>4}  {:>4}  {}",
+                r.threshold,
+                r.precision,
+                r.recall,
+                r.f1,
+                r.true_positives,
+                r.false_positives,
+                r.false_negatives,
+                r.true_negatives,
+                marks.join(", ")
+            );
+            let _ = writeln!(out, "{}", row.trim_end());
+        }
+        match calibration.best {
+            Some(best) => {
+                let _ = writeln!(
+                    out,
+                    "  best F1 {:.3} at {:.2}; configured duplicate {:.2}, autoReuse {:.2}, verify {:.2}\n",
+                    best.f1, best.threshold, current.duplicate, current.auto_reuse, current.verify
+                );
+            }
+            None => {
+                let _ = writeln!(out, "  no threshold finds any duplicate\n");
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluate_counts_scores_at_or_above_the_threshold_as_duplicates() {
+        let scored = [
+            (0.9, true),
+            (0.8, true),
+            (0.8, false),
+            (0.6, true),
+            (0.5, false),
+        ];
+        let result = evaluate(0.8, &scored);
+
+        assert_eq!(
+            (
+                result.true_positives,
+                result.false_positives,
+                result.false_negatives,
+                result.true_negatives
+            ),
+            (2, 1, 1, 1)
+        );
+        assert!((result.precision - 2.0 / 3.0).abs() < 1e-9);
+        assert!((result.recall - 2.0 / 3.0).abs() < 1e-9);
+        assert!((result.f1 - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sweep_prefers_the_highest_threshold_among_the_best() {
+        // Anything in (0.75, 0.80] separates the pairs perfectly
+        let scored = [(0.9, true), (0.8, true), (0.75, false), (0.6, false)];
+        let calibration = sweep("Skill", &scored);
+
+        assert_eq!(calibration.results.len(), 50);
+        assert_eq!((calibration.pairs, calibration.duplicates), (4, 2));
+        let best = calibration.best.unwrap();
+        assert_eq!(best.threshold, 0.8);
+        assert_eq!(best.f1, 1.0);
+    }
+
+    #[test]
+    fn sweep_has_no_best_without_a_detected_duplicate() {
+        let calibration = sweep("Trait", &[(0.4, true), (0.3, false)]);
+        assert!(calibration.best.is_none());
+    }
+}
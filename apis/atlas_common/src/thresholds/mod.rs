@@ -0,0 +1,271 @@
+//! Similarity thresholds per node label and operation.
+//!
+//! Three decisions compare a similarity score against a threshold:
+//! - `duplicate`: `create-node` rejects a node whose nearest neighbour scores
+//!   above it, and domain generation refuses a name close to an existing domain
+//! - `autoReuse`: generation reuses the nearest existing node at or above it
+//!   without asking the LLM
+//! - `verify`: generation creates a new node below it; scores between `verify`
+//!   and `autoReuse` are settled by the LLM
+//!
+//! Built-in values can be overridden from a JSON file named by
+//! `SIMILARITY_THRESHOLDS_FILE`, and again per request:
+//!
+//! ```json
+//! {
+//!   "default": {"duplicate": 0.7, "autoReuse": 0.95, "verify": 0.7},
+//!   "labels": {"Trait": {"autoReuse": 0.85, "verify": 0.65}}
+//! }
+//! ```
+//!
+//! The domain worker reads the same file, and applies the request overrides the
+//! REST API forwards with each `NodeGenerationJob`. A label entry wins over
+//! `default` field by field. Use `calibrate-thresholds`
+//! (see [`calibration`]) to pick values from labelled pairs.
+
+pub mod calibration;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::OnceLock;
+
+/// Resolved thresholds for one label
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Thresholds {
+    pub duplicate: f64,
+    pub auto_reuse: f64,
+    pub verify: f64,
+}
+
+/// Thresholds where any field may be left to a lower layer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PartialThresholds {
+    pub duplicate: Option<f64>,
+    pub auto_reuse: Option<f64>,
+    pub verify: Option<f64>,
+}
+
+impl PartialThresholds {
+    fn merge(&mut self, other: &PartialThresholds) {
+        self.duplicate = other.duplicate.or(self.duplicate);
+        self.auto_reuse = other.auto_reuse.or(self.auto_reuse);
+        self.verify = other.verify.or(self.verify);
+    }
+}
+
+/// Overrides as written in the configuration file or a request body
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThresholdOverrides {
+    #[serde(default)]
+    pub default: PartialThresholds,
+    #[serde(default)]
+    pub labels: BTreeMap<String, PartialThresholds>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarityThresholds {
+    default: Thresholds,
+    labels: BTreeMap<String, PartialThresholds>,
+}
+
+impl SimilarityThresholds {
+    /// Values used before any configuration is applied
+    pub fn builtin() -> Self {
+        let mut labels = BTreeMap::new();
+        // Traits are generic, so reusing an existing one is preferred
+        labels.insert(
+            "Trait".to_string(),
+            PartialThresholds {
+                auto_reuse: Some(0.85),
+                verify: Some(0.65),
+                ..Default::default()
+            },
+        );
+        // Domain names are short, so near-identical ones score lower
+        labels.insert(
+            "Domain".to_string(),
+            PartialThresholds {
+                duplicate: Some(0.85),
+                ..Default::default()
+            },
+        );
+        SimilarityThresholds {
+            default: Thresholds {
+                duplicate: 0.7,
+                auto_reuse: 0.95,
+                verify: 0.7,
+            },
+            labels,
+        }
+    }
+
+    /// Thresholds applying to nodes with `label`
+    pub fn for_label(&self, label: &str) -> Thresholds {
+        let partial = self.labels.get(label).copied().unwrap_or_default();
+        Thresholds {
+            duplicate: partial.duplicate.unwrap_or(self.default.duplicate),
+            auto_reuse: partial.auto_reuse.unwrap_or(self.default.auto_reuse),
+            verify: partial.verify.unwrap_or(self.default.verify),
+        }
+    }
+
+    /// Thresholds for the first of `labels` with its own entry, else the defaults
+    pub fn for_labels(&self, labels: &[String]) -> Thresholds {
+        let label = labels.iter().find(|l| self.labels.contains_key(l.as_str()));
+        self.for_label(label.map_or("", String::as_str))
+    }
+
+    /// Layer `overrides` on top, rejecting values outside `0..=1` or a
+    /// `verify` threshold above `autoReuse`
+    pub fn with_overrides(&self, overrides: &ThresholdOverrides) -> Result<Self, String> {
+        let mut default = PartialThresholds {
+            duplicate: Some(self.default.duplicate),
+            auto_reuse: Some(self.default.auto_reuse),
+            verify: Some(self.default.verify),
+        };
+        default.merge(&overrides.default);
+
+        let mut labels = self.labels.clone();
+        for (label, partial) in &overrides.labels {
+            labels.entry(label.clone()).or_default().merge(partial);
+        }
+
+        let merged = SimilarityThresholds {
+            default: Thresholds {
+                duplicate: default.duplicate.unwrap_or_default(),
+                auto_reuse: default.auto_reuse.unwrap_or_default(),
+                verify: default.verify.unwrap_or_default(),
+            },
+            labels,
+        };
+        merged.validate()?;
+        Ok(merged)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let scopes = std::iter::once("default").chain(self.labels.keys().map(String::as_str));
+        for scope in scopes {
+            let t = if scope == "default" {
+                self.default
+            } else {
+                self.for_label(scope)
+            };
+            for (name, value) in [
+                ("duplicate", t.duplicate),
+                ("autoReuse", t.auto_reuse),
+                ("verify", t.verify),
+            ] {
+                if !(0.0..=1.0).contains(&value) {
+                    return Err(format!(
+                        "{} threshold for {} must be between 0 and 1, got {}",
+                        name, scope, value
+                    ));
+                }
+            }
+            if t.verify > t.auto_reuse {
+                return Err(format!(
+                    "verify threshold for {} ({}) is above autoReuse ({})",
+                    scope, t.verify, t.auto_reuse
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+static THRESHOLDS: OnceLock<SimilarityThresholds> = OnceLock::new();
+
+/// The process-wide thresholds: built-in values with `SIMILARITY_THRESHOLDS_FILE`
+/// applied. An unreadable or invalid file is logged and ignored.
+pub fn similarity_thresholds() -> &'static SimilarityThresholds {
+    THRESHOLDS.get_or_init(|| {
+        let builtin = SimilarityThresholds::builtin();
+        let Ok(path) = env::var("SIMILARITY_THRESHOLDS_FILE") else {
+            return builtin;
+        };
+        match load_overrides(&path).and_then(|o| builtin.with_overrides(&o)) {
+            Ok(thresholds) => {
+                tracing::info!("Loaded similarity thresholds from {}", path);
+                thresholds
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Ignoring similarity thresholds in {}, using built-in values: {}",
+                    path,
+                    e
+                );
+                builtin
+            }
+        }
+    })
+}
+
+/// Configured thresholds with per-request overrides applied
+pub fn resolve(overrides: Option<&ThresholdOverrides>) -> Result<SimilarityThresholds, String> {
+    match overrides {
+        Some(overrides) => similarity_thresholds().with_overrides(overrides),
+        None => Ok(similarity_thresholds().clone()),
+    }
+}
+
+fn load_overrides(path: &str) -> Result<ThresholdOverrides, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&contents).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(json: &str) -> ThresholdOverrides {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn overrides_keep_the_fields_they_leave_out() {
+        let merged = SimilarityThresholds::builtin()
+            .with_overrides(&overrides(
+                r#"{"default": {"duplicate": 0.8}, "labels": {"Skill": {"verify": 0.6}}}"#,
+            ))
+            .unwrap();
+
+        let skill = merged.for_label("Skill");
+        assert_eq!(
+            (skill.duplicate, skill.auto_reuse, skill.verify),
+            (0.8, 0.95, 0.6)
+        );
+        // Built-in label entries survive a default override
+        let trait_ = merged.for_label("Trait");
+        assert_eq!(
+            (trait_.duplicate, trait_.auto_reuse, trait_.verify),
+            (0.8, 0.85, 0.65)
+        );
+        assert_eq!(merged.for_label("Domain").duplicate, 0.85);
+        assert_eq!(
+            merged.for_labels(&["L1".to_string(), "Skill".to_string()]),
+            skill
+        );
+    }
+
+    #[test]
+    fn overrides_are_validated_after_merging() {
+        let builtin = SimilarityThresholds::builtin();
+        assert!(
+            builtin
+                .with_overrides(&overrides(r#"{"labels": {"Trait": {"verify": 0.9}}}"#))
+                .is_err()
+        );
+        assert!(
+            builtin
+                .with_overrides(&overrides(r#"{"default": {"duplicate": 1.2}}"#))
+                .is_err()
+        );
+        assert!(
+            serde_json::from_str::<ThresholdOverrides>(r#"{"default": {"reuse": 0.9}}"#).is_err()
+        );
+    }
+}
@@ -44,8 +44,8 @@ echo "=== Building for Lambda ($TARGET) ==="
 # Use cargo-lambda for cross-compilation to Linux
 cargo lambda build --release --target $TARGET
 
-# The binary should be at target/lambda/atlas_domain_worker/bootstrap
-BINARY_PATH="target/lambda/atlas_domain_worker/bootstrap"
+# The workspace builds into apis/target, so the binary is at ../target/lambda/atlas_domain_worker/bootstrap
+BINARY_PATH="../target/lambda/atlas_domain_worker/bootstrap"
 
 if [ ! -f "$BINARY_PATH" ]; then
     echo "Error: Binary not found at $BINARY_PATH"
@@ -23,8 +23,7 @@ impl Config {
     /// Load configuration from environment variables
     pub fn from_env() -> Result<Self, ConfigError> {
         Ok(Self {
-            neo4j_uri: env::var("NEO4J_URI")
-                .map_err(|_| ConfigError::MissingVar("NEO4J_URI"))?,
+            neo4j_uri: env::var("NEO4J_URI").map_err(|_| ConfigError::MissingVar("NEO4J_URI"))?,
             neo4j_user: env::var("NEO4J_USER")
                 .map_err(|_| ConfigError::MissingVar("NEO4J_USER"))?,
             neo4j_password: env::var("NEO4J_PASSWORD")
@@ -33,8 +32,7 @@ impl Config {
                 .map_err(|_| ConfigError::MissingVar("EMBEDDING_ENDPOINT"))?,
             llm_endpoint: env::var("LLM_ENDPOINT").ok(),
             anthropic_api_key: env::var("ANTHROPIC_API_KEY").ok(),
-            queue_url: env::var("QUEUE_URL")
-                .map_err(|_| ConfigError::MissingVar("QUEUE_URL"))?,
+            queue_url: env::var("QUEUE_URL").map_err(|_| ConfigError::MissingVar("QUEUE_URL"))?,
         })
     }
 }
@@ -1,19 +1,16 @@
 use aws_lambda_events::event::sqs::SqsEvent;
 use lambda_runtime::{Error, LambdaEvent};
-use neo4rs::Graph;
+use neo4rs::{Graph, Query};
 
 use crate::config::Config;
 use crate::messages::{NodeGenerationJob, NodeGenerationResult, NodeType};
 use crate::processors::{
     KnowledgeProcessor, MilestoneProcessor, NodeProcessor, SkillProcessor, TraitProcessor,
 };
-use crate::services::LlmService;
+use crate::services::{LlmService, embedding_cache_stats};
 
 /// Handle an SQS event containing node generation jobs
-pub async fn handle_sqs_event(
-    event: LambdaEvent<SqsEvent>,
-    config: &Config,
-) -> Result<(), Error> {
+pub async fn handle_sqs_event(event: LambdaEvent<SqsEvent>, config: &Config) -> Result<(), Error> {
     let records = event.payload.records;
 
     if records.is_empty() {
@@ -60,6 +57,9 @@ pub async fn handle_sqs_event(
         );
 
         let result = process_job(&job, &graph, llm_service.as_ref()).await;
+        let finished = result
+            .as_ref()
+            .map_or_else(|e| !is_retryable_error(e), |_| true);
 
         match result {
             Ok(gen_result) => {
@@ -87,6 +87,24 @@ pub async fn handle_sqs_event(
                 // The message will eventually go to DLQ after max retries
             }
         }
+
+        if finished && let Err(e) = record_job_finished(&graph, &job).await {
+            tracing::warn!(
+                job_id = %job.job_id,
+                error = %e,
+                "Failed to record finished job on its generation run"
+            );
+        }
+    }
+
+    if let Some(stats) = embedding_cache_stats() {
+        tracing::info!(
+            hits = stats.hits,
+            disk_hits = stats.disk_hits,
+            misses = stats.misses,
+            entries = stats.entries,
+            "Embedding cache stats"
+        );
     }
 
     Ok(())
@@ -125,6 +143,30 @@ async fn process_job(
     }
 }
 
+/// Count a job against its `Domain_Generation` run. The REST API watches the
+/// run and validates the domain once every queued job has finished. Job ids are
+/// kept so a batch that SQS redelivers is not counted twice.
+async fn record_job_finished(
+    graph: &Graph,
+    job: &NodeGenerationJob,
+) -> Result<(), ProcessingError> {
+    let query = Query::new(
+        r#"
+        MATCH (g:Domain_Generation {generation_id: $generation_id})
+        WHERE NOT $job_id IN g.finished_jobs
+        SET g.finished_jobs = g.finished_jobs + $job_id
+        "#
+        .to_string(),
+    )
+    .param("generation_id", job.generation_id.as_str())
+    .param("job_id", job.job_id.as_str());
+
+    graph
+        .run(query)
+        .await
+        .map_err(|e| ProcessingError::Neo4j(e.to_string()))
+}
+
 /// Create a Neo4j graph connection
 async fn create_graph_connection(config: &Config) -> Result<Graph, Error> {
     let graph = Graph::new(
@@ -1,8 +1,8 @@
 use aws_lambda_events::event::sqs::SqsEvent;
-use lambda_runtime::{run, service_fn, Error, LambdaEvent};
+use lambda_runtime::{Error, LambdaEvent, run, service_fn};
 use tracing_subscriber::EnvFilter;
 
-use atlas_domain_worker::{handle_sqs_event, Config};
+use atlas_domain_worker::{Config, handle_sqs_event};
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
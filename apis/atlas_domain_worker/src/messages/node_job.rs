@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::services::ThresholdOverrides;
+
 /// The type of node being generated
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -35,7 +37,7 @@ impl NodeType {
 /// Information about a domain level
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DomainLevelInfo {
-    /// Level number (1-5)
+    /// Level number (1-based, up to the domain's level count)
     pub level: u8,
     /// Level name (e.g., "Novice", "Master")
     pub name: String,
@@ -66,15 +68,18 @@ pub struct NodeGenerationJob {
     pub domain_description: Option<String>,
     /// Neo4j element ID of the domain node
     pub domain_element_id: String,
-    /// All 5 domain levels with their element IDs
+    /// All domain levels with their element IDs
     pub domain_levels: Vec<DomainLevelInfo>,
 
-    /// Suggested level (1-5) from conceptualization
+    /// Suggested level (1-based) from conceptualization
     pub suggested_level: Option<u8>,
     /// ISO 8601 timestamp when job was created
     pub created_at: String,
     /// Number of times this job has been retried
     pub retry_count: u32,
+    /// Similarity threshold overrides from the generation request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity_thresholds: Option<ThresholdOverrides>,
 }
 
 impl NodeGenerationJob {
@@ -105,14 +110,14 @@ impl NodeGenerationJob {
             suggested_level,
             created_at: chrono::Utc::now().to_rfc3339(),
             retry_count: 0,
+            similarity_thresholds: None,
         }
     }
 
     /// Get the domain level info for the suggested level
     pub fn get_suggested_level_info(&self) -> Option<&DomainLevelInfo> {
-        self.suggested_level.and_then(|level| {
-            self.domain_levels.iter().find(|l| l.level == level)
-        })
+        self.suggested_level
+            .and_then(|level| self.domain_levels.iter().find(|l| l.level == level))
     }
 }
 
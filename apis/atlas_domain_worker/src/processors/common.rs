@@ -4,7 +4,9 @@ use serde::Deserialize;
 
 use crate::handler::ProcessingError;
 use crate::messages::{NodeGenerationJob, NodeGenerationResult};
-use crate::services::{GenerationConfig, LlmService, SimilarNodeResult};
+use crate::services::{
+    GenerationConfig, LlmService, SimilarNodeResult, Thresholds, cypher, resolve_thresholds,
+};
 
 /// Trait for node processors
 #[async_trait]
@@ -17,28 +19,11 @@ pub trait NodeProcessor {
     ) -> Result<NodeGenerationResult, ProcessingError>;
 }
 
-/// Similarity thresholds for different node types
-pub struct SimilarityThresholds {
-    pub auto_reuse: f64,  // Above this, auto-reuse existing node
-    pub auto_create: f64, // Below this, auto-create new node
-}
-
-impl SimilarityThresholds {
-    /// Thresholds for Knowledge and Skill nodes
-    pub fn standard() -> Self {
-        Self {
-            auto_reuse: 0.95,
-            auto_create: 0.70,
-        }
-    }
-
-    /// Thresholds for Trait nodes (more likely to reuse)
-    pub fn trait_node() -> Self {
-        Self {
-            auto_reuse: 0.85,
-            auto_create: 0.65,
-        }
-    }
+/// Similarity thresholds for `label` nodes, with the job's overrides applied
+pub fn thresholds_for(job: &NodeGenerationJob, label: &str) -> Result<Thresholds, ProcessingError> {
+    resolve_thresholds(job.similarity_thresholds.as_ref())
+        .map(|thresholds| thresholds.for_label(label))
+        .map_err(ProcessingError::InvalidData)
 }
 
 /// Decision for how to handle a concept based on similarity
@@ -68,7 +53,7 @@ pub struct VerificationResponse {
 pub async fn decide_concept_action(
     concept_name: &str,
     similar_nodes: &[SimilarNodeResult],
-    thresholds: &SimilarityThresholds,
+    thresholds: &Thresholds,
     llm: &LlmService,
     domain_name: &str,
     node_label: &str,
@@ -91,7 +76,7 @@ pub async fn decide_concept_action(
 
     // Check for low similarity - auto create
     let top_score = similar_nodes.first().map(|n| n.score).unwrap_or(0.0);
-    if top_score < thresholds.auto_create {
+    if top_score < thresholds.verify {
         tracing::info!(
             concept = %concept_name,
             top_score = top_score,
@@ -128,8 +113,9 @@ pub async fn decide_concept_action(
         }
         "create_and_generalize" => {
             // Find the target node to generalize to
-            if let Some(target_name) =
-                verification.existing_node_name.or(verification.suggested_target)
+            if let Some(target_name) = verification
+                .existing_node_name
+                .or(verification.suggested_target)
             {
                 if let Some(target) = similar_nodes.iter().find(|n| n.name == target_name) {
                     Ok(ConceptDecision::CreateAndGeneralize {
@@ -221,10 +207,20 @@ Should this concept reuse an existing node, be created as new, or generalize to
 pub fn determine_level(
     suggested_level: Option<u8>,
     _node_name: &str,
-    _domain_levels: &[crate::messages::DomainLevelInfo],
+    domain_levels: &[crate::messages::DomainLevelInfo],
 ) -> u8 {
-    // Use suggested level if provided, otherwise default to middle (level 3)
-    suggested_level.unwrap_or(3).clamp(1, 5)
+    // Domains created before level templates always had five levels
+    let max_level = domain_levels
+        .iter()
+        .map(|l| l.level)
+        .max()
+        .unwrap_or(5)
+        .max(1);
+
+    // Use suggested level if provided, otherwise default to the middle level
+    suggested_level
+        .unwrap_or(max_level.div_ceil(2))
+        .clamp(1, max_level)
 }
 
 // =============================================================================
@@ -251,7 +247,8 @@ pub async fn create_level_requirement(
         MATCH (node) WHERE elementId(node) = $node_id
         CREATE (level)-[:{}]->(node)
         "#,
-        relationship_type
+        cypher::relationship_type(relationship_type)
+            .map_err(|e| ProcessingError::InvalidData(e.to_string()))?
     );
 
     let query = Query::new(query_str)
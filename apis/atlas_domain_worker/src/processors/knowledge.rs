@@ -4,11 +4,14 @@ use serde::Deserialize;
 
 use crate::handler::ProcessingError;
 use crate::messages::{NodeGenerationJob, NodeGenerationResult};
-use crate::services::{find_similar_by_text, generate_embedding, GenerationConfig, LlmService};
+use crate::services::{
+    EMBEDDING_VERSION, GenerationConfig, LlmService, embedding_model, find_similar_by_text,
+    generate_embedding, node_embedding_text,
+};
 
 use super::common::{
-    create_generalization, create_level_requirement, decide_concept_action, determine_level,
-    extract_json, ConceptDecision, CreatedNode, NodeProcessor, SimilarityThresholds,
+    ConceptDecision, CreatedNode, NodeProcessor, create_generalization, create_level_requirement,
+    decide_concept_action, determine_level, extract_json, thresholds_for,
 };
 
 /// Properties for a Knowledge node
@@ -92,13 +95,14 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
         graph: &Graph,
         props: &KnowledgeProperties,
     ) -> Result<CreatedNode, ProcessingError> {
-        let embedding = generate_embedding(&props.name)
+        let embedding = generate_embedding(&node_embedding_text(&props.name, &props.description))
             .await
             .map_err(|e| ProcessingError::Embedding(e.to_string()))?;
+        let model = embedding_model().map_err(|e| ProcessingError::Embedding(e.to_string()))?;
 
         let query = Query::new(
             r#"
-            CREATE (n:Knowledge {
+            CREATE (n:Knowledge:L1 {
                 name: $name,
                 description: $description,
                 how_to_learn: $how_to_learn,
@@ -108,7 +112,9 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
                 analyze_level: $analyze_level,
                 evaluate_level: $evaluate_level,
                 create_level: $create_level,
-                embedding: $embedding
+                embedding: $embedding,
+                embedding_model: $embedding_model,
+                embedding_version: $embedding_version
             })
             RETURN elementId(n) as element_id, n.name as name
             "#
@@ -123,7 +129,9 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
         .param("analyze_level", props.analyze_level.clone())
         .param("evaluate_level", props.evaluate_level.clone())
         .param("create_level", props.create_level.clone())
-        .param("embedding", embedding);
+        .param("embedding", embedding)
+        .param("embedding_model", model)
+        .param("embedding_version", EMBEDDING_VERSION);
 
         let mut result = graph
             .execute(query)
@@ -164,7 +172,7 @@ impl<'a> NodeProcessor for KnowledgeProcessor<'a> {
             .map_err(|e| ProcessingError::Similarity(e.to_string()))?;
 
         // Step 2: Decide what to do
-        let thresholds = SimilarityThresholds::standard();
+        let thresholds = thresholds_for(job, "Knowledge")?;
         let decision = decide_concept_action(
             &job.node_name,
             &similar_nodes,
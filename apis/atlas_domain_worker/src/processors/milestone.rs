@@ -4,10 +4,13 @@ use serde::Deserialize;
 
 use crate::handler::ProcessingError;
 use crate::messages::{NodeGenerationJob, NodeGenerationResult};
-use crate::services::{generate_embedding, GenerationConfig, LlmService};
+use crate::services::{
+    EMBEDDING_VERSION, GenerationConfig, LlmService, embedding_model, generate_embedding,
+    node_embedding_text,
+};
 
 use super::common::{
-    create_level_requirement, determine_level, extract_json, CreatedNode, NodeProcessor,
+    CreatedNode, NodeProcessor, create_level_requirement, determine_level, extract_json,
 };
 
 /// Properties for a Milestone node
@@ -80,17 +83,20 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
         graph: &Graph,
         props: &MilestoneProperties,
     ) -> Result<CreatedNode, ProcessingError> {
-        let embedding = generate_embedding(&props.name)
+        let embedding = generate_embedding(&node_embedding_text(&props.name, &props.description))
             .await
             .map_err(|e| ProcessingError::Embedding(e.to_string()))?;
+        let model = embedding_model().map_err(|e| ProcessingError::Embedding(e.to_string()))?;
 
         let query = Query::new(
             r#"
-            CREATE (n:Milestone {
+            CREATE (n:Milestone:L1 {
                 name: $name,
                 description: $description,
                 how_to_achieve: $how_to_achieve,
-                embedding: $embedding
+                embedding: $embedding,
+                embedding_model: $embedding_model,
+                embedding_version: $embedding_version
             })
             RETURN elementId(n) as element_id, n.name as name
             "#
@@ -99,7 +105,9 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
         .param("name", props.name.clone())
         .param("description", props.description.clone())
         .param("how_to_achieve", props.how_to_achieve.clone())
-        .param("embedding", embedding);
+        .param("embedding", embedding)
+        .param("embedding_model", model)
+        .param("embedding_version", EMBEDDING_VERSION);
 
         let mut result = graph
             .execute(query)
@@ -4,11 +4,14 @@ use serde::Deserialize;
 
 use crate::handler::ProcessingError;
 use crate::messages::{NodeGenerationJob, NodeGenerationResult};
-use crate::services::{find_similar_by_text, generate_embedding, GenerationConfig, LlmService};
+use crate::services::{
+    EMBEDDING_VERSION, GenerationConfig, LlmService, embedding_model, find_similar_by_text,
+    generate_embedding, node_embedding_text,
+};
 
 use super::common::{
-    create_generalization, create_level_requirement, decide_concept_action, determine_level,
-    extract_json, ConceptDecision, CreatedNode, NodeProcessor, SimilarityThresholds,
+    ConceptDecision, CreatedNode, NodeProcessor, create_generalization, create_level_requirement,
+    decide_concept_action, determine_level, extract_json, thresholds_for,
 };
 
 /// Properties for a Skill node
@@ -90,13 +93,14 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
         graph: &Graph,
         props: &SkillProperties,
     ) -> Result<CreatedNode, ProcessingError> {
-        let embedding = generate_embedding(&props.name)
+        let embedding = generate_embedding(&node_embedding_text(&props.name, &props.description))
             .await
             .map_err(|e| ProcessingError::Embedding(e.to_string()))?;
+        let model = embedding_model().map_err(|e| ProcessingError::Embedding(e.to_string()))?;
 
         let query = Query::new(
             r#"
-            CREATE (n:Skill {
+            CREATE (n:Skill:L1 {
                 name: $name,
                 description: $description,
                 how_to_develop: $how_to_develop,
@@ -105,7 +109,9 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
                 competent_level: $competent_level,
                 proficient_level: $proficient_level,
                 expert_level: $expert_level,
-                embedding: $embedding
+                embedding: $embedding,
+                embedding_model: $embedding_model,
+                embedding_version: $embedding_version
             })
             RETURN elementId(n) as element_id, n.name as name
             "#
@@ -115,11 +121,16 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
         .param("description", props.description.clone())
         .param("how_to_develop", props.how_to_develop.clone())
         .param("novice_level", props.novice_level.clone())
-        .param("advanced_beginner_level", props.advanced_beginner_level.clone())
+        .param(
+            "advanced_beginner_level",
+            props.advanced_beginner_level.clone(),
+        )
         .param("competent_level", props.competent_level.clone())
         .param("proficient_level", props.proficient_level.clone())
         .param("expert_level", props.expert_level.clone())
-        .param("embedding", embedding);
+        .param("embedding", embedding)
+        .param("embedding_model", model)
+        .param("embedding_version", EMBEDDING_VERSION);
 
         let mut result = graph
             .execute(query)
@@ -160,7 +171,7 @@ impl<'a> NodeProcessor for SkillProcessor<'a> {
             .map_err(|e| ProcessingError::Similarity(e.to_string()))?;
 
         // Step 2: Decide what to do
-        let thresholds = SimilarityThresholds::standard();
+        let thresholds = thresholds_for(job, "Skill")?;
         let decision = decide_concept_action(
             &job.node_name,
             &similar_nodes,
@@ -4,11 +4,14 @@ use serde::Deserialize;
 
 use crate::handler::ProcessingError;
 use crate::messages::{NodeGenerationJob, NodeGenerationResult};
-use crate::services::{find_similar_by_text, generate_embedding, GenerationConfig, LlmService};
+use crate::services::{
+    EMBEDDING_VERSION, GenerationConfig, LlmService, embedding_model, find_similar_by_text,
+    generate_embedding, node_embedding_text,
+};
 
 use super::common::{
-    create_level_requirement, decide_concept_action, determine_level, extract_json,
-    ConceptDecision, CreatedNode, NodeProcessor, SimilarityThresholds,
+    ConceptDecision, CreatedNode, NodeProcessor, create_level_requirement, decide_concept_action,
+    determine_level, extract_json, thresholds_for,
 };
 
 /// Properties for a Trait node
@@ -81,17 +84,20 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
         graph: &Graph,
         props: &TraitProperties,
     ) -> Result<CreatedNode, ProcessingError> {
-        let embedding = generate_embedding(&props.name)
+        let embedding = generate_embedding(&node_embedding_text(&props.name, &props.description))
             .await
             .map_err(|e| ProcessingError::Embedding(e.to_string()))?;
+        let model = embedding_model().map_err(|e| ProcessingError::Embedding(e.to_string()))?;
 
         let query = Query::new(
             r#"
-            CREATE (n:Trait {
+            CREATE (n:Trait:L1 {
                 name: $name,
                 description: $description,
                 measurement_criteria: $measurement_criteria,
-                embedding: $embedding
+                embedding: $embedding,
+                embedding_model: $embedding_model,
+                embedding_version: $embedding_version
             })
             RETURN elementId(n) as element_id, n.name as name
             "#
@@ -100,7 +106,9 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
         .param("name", props.name.clone())
         .param("description", props.description.clone())
         .param("measurement_criteria", props.measurement_criteria.clone())
-        .param("embedding", embedding);
+        .param("embedding", embedding)
+        .param("embedding_model", model)
+        .param("embedding_version", EMBEDDING_VERSION);
 
         let mut result = graph
             .execute(query)
@@ -141,7 +149,7 @@ impl<'a> NodeProcessor for TraitProcessor<'a> {
             .map_err(|e| ProcessingError::Similarity(e.to_string()))?;
 
         // Step 2: Decide what to do (traits use different thresholds, more likely to reuse)
-        let thresholds = SimilarityThresholds::trait_node();
+        let thresholds = thresholds_for(job, "Trait")?;
         let decision = decide_concept_action(
             &job.node_name,
             &similar_nodes,
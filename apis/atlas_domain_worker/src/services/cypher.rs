@@ -0,0 +1,57 @@
+//! Safe construction of dynamic Cypher fragments.
+//!
+//! Mirrors `common::cypher` in the REST API. Labels and relationship types
+//! cannot be bound as query parameters, so any that are interpolated into a
+//! query are checked against the whitelist below and backtick-escaped first.
+
+/// Node labels the worker is allowed to interpolate into queries
+pub const KNOWN_LABELS: &[&str] = &[
+    "Knowledge",
+    "Skill",
+    "Trait",
+    "Milestone",
+    "Domain",
+    "Domain_Level",
+];
+
+/// Relationship types the worker is allowed to interpolate into queries
+pub const KNOWN_RELATIONSHIP_TYPES: &[&str] = &[
+    "HAS_DOMAIN_LEVEL",
+    "REQUIRES_KNOWLEDGE",
+    "REQUIRES_SKILL",
+    "REQUIRES_TRAIT",
+    "REQUIRES_MILESTONE",
+    "GENERALIZES_TO",
+    "MILESTONE_STRENGTHENS_TRAIT",
+];
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CypherError {
+    #[error("unknown label '{0}'")]
+    UnknownLabel(String),
+    #[error("unknown relationship type '{0}'")]
+    UnknownRelationshipType(String),
+}
+
+/// Quote an identifier with backticks, doubling any embedded backticks.
+pub fn escape_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// Validate a node label against the whitelist and return it escaped.
+pub fn label(name: &str) -> Result<String, CypherError> {
+    if KNOWN_LABELS.contains(&name) {
+        Ok(escape_identifier(name))
+    } else {
+        Err(CypherError::UnknownLabel(name.to_string()))
+    }
+}
+
+/// Validate a relationship type against the whitelist and return it escaped.
+pub fn relationship_type(name: &str) -> Result<String, CypherError> {
+    if KNOWN_RELATIONSHIP_TYPES.contains(&name) {
+        Ok(escape_identifier(name))
+    } else {
+        Err(CypherError::UnknownRelationshipType(name.to_string()))
+    }
+}
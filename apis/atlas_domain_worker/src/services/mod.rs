@@ -1,10 +1,17 @@
-mod embedding;
+pub mod cypher;
 mod llm;
 mod similarity;
 
-pub use embedding::{generate_embedding, EmbeddingError};
+pub use atlas_common::embedding::{
+    EMBEDDING_VERSION, EmbeddingError, EmbeddingProvider, cache_stats as embedding_cache_stats,
+    configured_model as embedding_model, generate_embedding, generate_embeddings,
+    node_embedding_text,
+};
+pub use atlas_common::thresholds::{
+    SimilarityThresholds, ThresholdOverrides, Thresholds, resolve as resolve_thresholds,
+};
 pub use llm::{GenerationConfig, LlmError, LlmService};
 pub use similarity::{
-    find_similar_by_text, find_similar_nodes, FindSimilarNodesRequest, SimilarNodeResult,
-    SimilarityError,
+    FindSimilarNodesRequest, SimilarNodeResult, SimilarityError, find_similar_by_text,
+    find_similar_nodes,
 };
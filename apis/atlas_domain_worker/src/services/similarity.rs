@@ -2,7 +2,21 @@ use neo4rs::{Graph, Query};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use super::embedding::generate_embedding;
+use super::cypher;
+use atlas_common::embedding::{default_provider, embedding_dimensions, generate_embedding};
+
+/// Label of the node recording which model the `nodeEmbeddings` index holds
+const EMBEDDING_SPACE_LABEL: &str = "_EmbeddingSpace";
+/// Layer-one label every embedded node carries
+const EMBEDDED_LABEL: &str = "L1";
+/// Property marking a node as soft-deleted
+const SOFT_DELETE_PROPERTY: &str = "deleted_at";
+/// Relationships from a domain level to the components it requires
+const COMPONENT_RELATIONSHIPS: &str =
+    "REQUIRES_KNOWLEDGE|REQUIRES_SKILL|REQUIRES_TRAIT|REQUIRES_MILESTONE";
+/// Index candidates fetched per requested result when filtering
+const CANDIDATE_FACTOR: i32 = 10;
+const MIN_FILTERED_CANDIDATES: i32 = 100;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct FindSimilarNodesRequest {
@@ -10,6 +24,15 @@ pub struct FindSimilarNodesRequest {
     pub embedding: Option<Vec<f64>>,
     pub text: Option<String>,
     pub label: Option<String>,
+    /// Match nodes carrying any of these labels (combined with `label`)
+    pub labels: Option<Vec<String>>,
+    /// Only components required by a level of the named domain
+    pub domain: Option<String>,
+    /// Also match soft-deleted nodes
+    pub include_deleted: Option<bool>,
+    pub min_score: Option<f64>,
+    /// Return each match's labels and the domains requiring it
+    pub explain: Option<bool>,
     pub limit: Option<i32>,
 }
 
@@ -19,40 +42,72 @@ pub struct SimilarNodeResult {
     pub description: Option<String>,
     pub id: String,
     pub score: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domains: Option<Vec<String>>,
 }
 
 /// Find similar nodes using node_id, embedding, or text (in order of priority).
-/// Optionally filter results by node label.
+/// Results can be narrowed by label, domain, soft-deletion and score, and
+/// explained with each match's labels and domains. Query vectors whose length
+/// or model differs from the index are rejected.
 pub async fn find_similar_nodes(
     graph: &Graph,
     request: FindSimilarNodesRequest,
 ) -> Result<Vec<SimilarNodeResult>, SimilarityError> {
     let limit = request.limit.unwrap_or(5);
+    if let Some(min_score) = request.min_score
+        && !(0.0..=1.0).contains(&min_score)
+    {
+        return Err(SimilarityError::InvalidRequest(format!(
+            "min_score must be between 0 and 1, got {}",
+            min_score
+        )));
+    }
+    let filters = build_filters(&request)?;
+    // Filters are applied after the index lookup, so ask it for more candidates
+    let candidates = if filters.is_empty() {
+        limit
+    } else {
+        (limit * CANDIDATE_FACTOR).max(MIN_FILTERED_CANDIDATES)
+    };
+    let returns = return_clause(request.explain.unwrap_or(false));
+
+    let mut params: HashMap<String, neo4rs::BoltType> = HashMap::new();
+    params.insert("limit".to_string(), limit.into());
+    params.insert("candidates".to_string(), candidates.into());
+    if let Some(domain) = request.domain {
+        params.insert("domain".to_string(), domain.into());
+    }
+    if let Some(min_score) = request.min_score {
+        params.insert("minScore".to_string(), min_score.into());
+    }
 
-    let (query_str, params) = if let Some(node_id) = request.node_id {
+    let query_str = if let Some(node_id) = request.node_id {
         // Search using a reference node ID
-        let query = build_node_id_query(request.label.as_deref());
-        let mut params: HashMap<String, neo4rs::BoltType> = HashMap::new();
+        if let Some((dimensions, model)) = node_embedding_info(graph, &node_id).await? {
+            check_compatible(graph, dimensions, model.as_deref()).await?;
+        }
         params.insert("nodeId".to_string(), node_id.into());
-        params.insert("limit".to_string(), limit.into());
-        (query, params)
+        build_node_id_query(&filters, &returns)
     } else if let Some(embedding) = request.embedding {
         // Search using provided embedding vector
-        let query = build_embedding_query(request.label.as_deref());
-        let mut params: HashMap<String, neo4rs::BoltType> = HashMap::new();
+        check_compatible(graph, embedding.len(), None).await?;
         params.insert("embedding".to_string(), embedding.into());
-        params.insert("limit".to_string(), limit.into());
-        (query, params)
+        build_embedding_query(&filters, &returns)
     } else if let Some(text) = request.text {
         // Generate embedding from text, then search
         let embedding = generate_embedding(&text)
             .await
             .map_err(|e| SimilarityError::EmbeddingFailed(e.to_string()))?;
-        let query = build_embedding_query(request.label.as_deref());
-        let mut params: HashMap<String, neo4rs::BoltType> = HashMap::new();
+        let model = default_provider()
+            .map_err(|e| SimilarityError::EmbeddingFailed(e.to_string()))?
+            .model()
+            .to_string();
+        check_compatible(graph, embedding.len(), Some(&model)).await?;
         params.insert("embedding".to_string(), embedding.into());
-        params.insert("limit".to_string(), limit.into());
-        (query, params)
+        build_embedding_query(&filters, &returns)
     } else {
         return Err(SimilarityError::InvalidRequest(
             "Either node_id, embedding, or text must be provided".to_string(),
@@ -62,6 +117,76 @@ pub async fn find_similar_nodes(
     execute_similarity_query(graph, query_str, params).await
 }
 
+/// Reject a query vector of the wrong length, or produced by a model other
+/// than the one the index was built with (`model` is unknown for raw vectors)
+async fn check_compatible(
+    graph: &Graph,
+    dimensions: usize,
+    model: Option<&str>,
+) -> Result<(), SimilarityError> {
+    let expected = embedding_dimensions();
+    if dimensions != expected {
+        return Err(SimilarityError::IncompatibleEmbedding(format!(
+            "expected {} dimensions, got {}",
+            expected, dimensions
+        )));
+    }
+    let Some(model) = model else {
+        return Ok(());
+    };
+
+    let query = Query::new(format!(
+        "MATCH (s:{} {{index: 'nodeEmbeddings'}}) RETURN s.model AS model",
+        EMBEDDING_SPACE_LABEL
+    ));
+    let mut result = graph
+        .execute(query)
+        .await
+        .map_err(|e| SimilarityError::QueryFailed(e.to_string()))?;
+    let row = result
+        .next()
+        .await
+        .map_err(|e| SimilarityError::QueryFailed(e.to_string()))?;
+    if let Some(indexed) = row.and_then(|row| row.get::<String>("model").ok())
+        && indexed != model
+    {
+        return Err(SimilarityError::IncompatibleEmbedding(format!(
+            "index holds '{}' embeddings, query was embedded with '{}'",
+            indexed, model
+        )));
+    }
+    Ok(())
+}
+
+/// Length and model of a stored node embedding, if the node has one
+async fn node_embedding_info(
+    graph: &Graph,
+    node_id: &str,
+) -> Result<Option<(usize, Option<String>)>, SimilarityError> {
+    let query = Query::new(
+        r#"
+        MATCH (n) WHERE elementId(n) = $nodeId AND n.embedding IS NOT NULL
+        RETURN size(n.embedding) AS dimensions, n.embedding_model AS model
+        "#
+        .to_string(),
+    )
+    .param("nodeId", node_id);
+    let mut result = graph
+        .execute(query)
+        .await
+        .map_err(|e| SimilarityError::QueryFailed(e.to_string()))?;
+    let row = result
+        .next()
+        .await
+        .map_err(|e| SimilarityError::QueryFailed(e.to_string()))?;
+    Ok(row.map(|row| {
+        (
+            row.get::<i64>("dimensions").unwrap_or_default() as usize,
+            row.get("model").ok(),
+        )
+    }))
+}
+
 /// Convenience function to find similar nodes by text with a label filter
 pub async fn find_similar_by_text(
     graph: &Graph,
@@ -81,40 +206,92 @@ pub async fn find_similar_by_text(
     .await
 }
 
-/// Build query for node ID-based similarity search
-fn build_node_id_query(label: Option<&str>) -> String {
-    let label_filter = label
-        .map(|l| format!(" AND node:{}", l))
-        .unwrap_or_default();
+/// Conditions on `node` and `score` applied to the vector index candidates
+fn build_filters(request: &FindSimilarNodesRequest) -> Result<Vec<String>, SimilarityError> {
+    let mut filters = Vec::new();
+
+    let labels = request
+        .label
+        .iter()
+        .chain(request.labels.iter().flatten())
+        .map(|l| cypher::label(l).map(|escaped| format!("node:{}", escaped)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if !labels.is_empty() {
+        filters.push(format!("({})", labels.join(" OR ")));
+    }
+    if request.domain.is_some() {
+        filters.push(format!(
+            "EXISTS {{ MATCH (:Domain {{name: $domain}})-[:HAS_DOMAIN_LEVEL]->(:Domain_Level)-[:{}]->(node) }}",
+            COMPONENT_RELATIONSHIPS
+        ));
+    }
+    if !request.include_deleted.unwrap_or(false) {
+        filters.push(format!("node.{} IS NULL", SOFT_DELETE_PROPERTY));
+    }
+    if request.min_score.is_some() {
+        filters.push("score >= $minScore".to_string());
+    }
+
+    Ok(filters)
+}
 
+fn return_clause(explain: bool) -> String {
+    let mut returns =
+        "node.name as name, node.description as description, elementId(node) as id, score"
+            .to_string();
+    if explain {
+        returns.push_str(&format!(
+            r#",
+            [l IN labels(node) WHERE l <> '{}'] as labels,
+            COLLECT {{
+                MATCH (d:Domain)-[:HAS_DOMAIN_LEVEL]->(:Domain_Level)-[:{}]->(node)
+                RETURN DISTINCT d.name
+            }} as domains"#,
+            EMBEDDED_LABEL, COMPONENT_RELATIONSHIPS
+        ));
+    }
+    returns
+}
+
+fn and_filters(filters: &[String]) -> String {
+    filters.iter().map(|f| format!(" AND {}", f)).collect()
+}
+
+/// Build query for node ID-based similarity search
+fn build_node_id_query(filters: &[String], returns: &str) -> String {
     format!(
         r#"
         MATCH (n)
         WHERE elementId(n) = $nodeId
-        CALL db.index.vector.queryNodes('nodeEmbeddings', $limit, n.embedding)
+        CALL db.index.vector.queryNodes('nodeEmbeddings', $candidates, n.embedding)
         YIELD node, score
         WHERE elementId(node) <> $nodeId{}
-        RETURN node.name as name, node.description as description, elementId(node) as id, score
+        RETURN {}
         ORDER BY score DESC
+        LIMIT $limit
         "#,
-        label_filter
+        and_filters(filters),
+        returns
     )
 }
 
 /// Build query for embedding-based similarity search
-fn build_embedding_query(label: Option<&str>) -> String {
-    let label_filter = label
-        .map(|l| format!("WHERE node:{}\n            ", l))
-        .unwrap_or_default();
+fn build_embedding_query(filters: &[String], returns: &str) -> String {
+    let where_clause = if filters.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}\n        ", filters.join(" AND "))
+    };
 
     format!(
         r#"
-        CALL db.index.vector.queryNodes('nodeEmbeddings', $limit, $embedding)
+        CALL db.index.vector.queryNodes('nodeEmbeddings', $candidates, $embedding)
         YIELD node, score
-        {}RETURN node.name as name, node.description as description, elementId(node) as id, score
+        {}RETURN {}
         ORDER BY score DESC
+        LIMIT $limit
         "#,
-        label_filter
+        where_clause, returns
     )
 }
 
@@ -146,6 +323,8 @@ async fn execute_similarity_query(
             description: row.get("description").ok(),
             id: row.get("id").unwrap_or_default(),
             score: row.get("score").unwrap_or(0.0),
+            labels: row.get("labels").ok(),
+            domains: row.get("domains").ok(),
         });
     }
 
@@ -160,4 +339,8 @@ pub enum SimilarityError {
     QueryFailed(String),
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
+    #[error("Incompatible query embedding: {0}")]
+    IncompatibleEmbedding(String),
+    #[error("Invalid query: {0}")]
+    InvalidQuery(#[from] cypher::CypherError),
 }
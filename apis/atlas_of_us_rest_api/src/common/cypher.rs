@@ -0,0 +1,144 @@
+//! Safe construction of dynamic Cypher fragments.
+//!
+//! Labels, relationship types and property keys cannot be passed as query
+//! parameters, so whenever one of them comes from a request it has to be
+//! interpolated into the query text. Every such identifier goes through this
+//! module: labels and relationship types are checked against the schema
+//! registry, property keys must be plain identifiers, and everything is
+//! backtick-escaped before it reaches the query string.
+
+use crate::domains::graph::schema;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CypherError {
+    UnknownLabel(String),
+    UnknownRelationshipType(String),
+    InvalidPropertyKey(String),
+}
+
+impl std::fmt::Display for CypherError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CypherError::UnknownLabel(label) => write!(f, "unknown label '{}'", label),
+            CypherError::UnknownRelationshipType(rel_type) => {
+                write!(f, "unknown relationship type '{}'", rel_type)
+            }
+            CypherError::InvalidPropertyKey(key) => write!(f, "invalid property key '{}'", key),
+        }
+    }
+}
+
+impl std::error::Error for CypherError {}
+
+/// Quote an identifier with backticks, doubling any embedded backticks.
+pub fn escape_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// True for `[A-Za-z_][A-Za-z0-9_]*` - the only shape we accept for keys that
+/// double as parameter names.
+fn is_plain_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Validate a node label against the schema registry and return it escaped.
+pub fn label(name: &str) -> Result<String, CypherError> {
+    if schema::is_known_label(name) {
+        Ok(escape_identifier(name))
+    } else {
+        Err(CypherError::UnknownLabel(name.to_string()))
+    }
+}
+
+/// Build a label suffix such as ``:`Knowledge`:`L3` `` for a node pattern.
+pub fn label_suffix<S: AsRef<str>>(names: &[S]) -> Result<String, CypherError> {
+    names
+        .iter()
+        .map(|name| label(name.as_ref()).map(|escaped| format!(":{}", escaped)))
+        .collect()
+}
+
+/// Build a predicate matching any of the given labels, e.g. ``(n:`Skill` OR n:`Trait`)``.
+pub fn label_predicate<S: AsRef<str>>(variable: &str, names: &[S]) -> Result<String, CypherError> {
+    let conditions = names
+        .iter()
+        .map(|name| label(name.as_ref()).map(|escaped| format!("{}:{}", variable, escaped)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("({})", conditions.join(" OR ")))
+}
+
+/// Validate a relationship type against the schema registry and return it escaped.
+pub fn relationship_type(name: &str) -> Result<String, CypherError> {
+    if schema::relationship_schema(name).is_some() {
+        Ok(escape_identifier(name))
+    } else {
+        Err(CypherError::UnknownRelationshipType(name.to_string()))
+    }
+}
+
+/// Validate a property key and return it escaped.
+pub fn property_key(name: &str) -> Result<String, CypherError> {
+    if is_plain_identifier(name) {
+        Ok(escape_identifier(name))
+    } else {
+        Err(CypherError::InvalidPropertyKey(name.to_string()))
+    }
+}
+
+/// Build `SET`-style assignments binding each property key to the parameter of
+/// the same name, e.g. ``n.`name` = $name, n.`description` = $description``.
+pub fn set_clauses<'a, I>(variable: &str, keys: I) -> Result<String, CypherError>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    prefixed_set_clauses(variable, "", keys)
+}
+
+/// Like [`set_clauses`], but binds each key to `$<prefix><key>`, so property
+/// names cannot collide with the query's own parameters.
+pub fn prefixed_set_clauses<'a, I>(
+    variable: &str,
+    prefix: &str,
+    keys: I,
+) -> Result<String, CypherError>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let clauses = keys
+        .into_iter()
+        .map(|key| {
+            property_key(key)
+                .map(|escaped| format!("{}.{} = ${}{}", variable, escaped, prefix, key))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(clauses.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixed_set_clauses_bind_prefixed_parameters() {
+        let keys = vec!["name".to_string(), "targetId".to_string()];
+        assert_eq!(
+            prefixed_set_clauses("n", "prop_", &keys).unwrap(),
+            "n.`name` = $prop_name, n.`targetId` = $prop_targetId"
+        );
+        assert_eq!(
+            set_clauses("n", &keys).unwrap(),
+            "n.`name` = $name, n.`targetId` = $targetId"
+        );
+    }
+
+    #[test]
+    fn set_clauses_reject_keys_that_are_not_identifiers() {
+        let keys = vec!["bad key".to_string()];
+        assert!(prefixed_set_clauses("n", "prop_", &keys).is_err());
+    }
+}
@@ -7,7 +7,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 
 use super::{
-    embedding::generate_embedding,
+    embedding::{cache_stats, generate_embedding},
     s3::{S3ObjectParams, UploadParams, get_s3_object, upload_object_to_s3},
 };
 
@@ -83,3 +83,11 @@ pub async fn create_embedding_from_text(
         }
     }
 }
+
+/// Embedding cache counters; `enabled` is false when the cache is turned off
+pub async fn get_embedding_cache_stats() -> Json<serde_json::Value> {
+    match cache_stats() {
+        Some(stats) => Json(serde_json::json!({ "enabled": true, "stats": stats })),
+        None => Json(serde_json::json!({ "enabled": false })),
+    }
+}
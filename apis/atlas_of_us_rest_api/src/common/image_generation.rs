@@ -9,7 +9,9 @@ struct ImageGenerationRequest {
     num_inference_steps: u32,
 }
 
-pub async fn generate_image(text_to_generate_image_from: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+pub async fn generate_image(
+    text_to_generate_image_from: &str,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let image_generation_endpoint = env::var("IMAGE_GEN_ENDPOINT")
         .map_err(|_| "IMAGE_GEN_ENDPOINT environment variable not set")?;
 
@@ -31,10 +33,14 @@ pub async fn generate_image(text_to_generate_image_from: &str) -> Result<Vec<u8>
     if !response.status().is_success() {
         let status = response.status();
         let error_body = response.text().await.unwrap_or_default();
-        return Err(format!("Image generation failed with status {}: {}", status, error_body).into());
+        return Err(format!(
+            "Image generation failed with status {}: {}",
+            status, error_body
+        )
+        .into());
     }
 
     // Response is PNG image bytes directly
     let image_bytes = response.bytes().await?;
     Ok(image_bytes.to_vec())
-}
\ No newline at end of file
+}
@@ -1,8 +1,11 @@
-pub mod embedding;
+pub mod cypher;
 pub mod handlers;
 pub mod image_generation;
 pub mod logging_middleware;
 pub mod neo4j_utils;
+pub mod pagination;
 pub mod s3;
 pub mod similarity;
 pub mod sqs;
+
+pub use atlas_common::{embedding, thresholds};
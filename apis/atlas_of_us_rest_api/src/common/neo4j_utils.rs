@@ -1,5 +1,29 @@
+use neo4rs::{BoltNull, BoltType, Graph, Txn};
 use serde_json::{Value, json};
-use neo4rs::{BoltNull, BoltType};
+
+/// Run `f` inside a single write transaction.
+/// Commits when `f` returns `Ok`, rolls back when it returns `Err`; either way the
+/// caller sees all of the writes or none of them.
+pub async fn with_transaction<T, E, F>(graph: &Graph, f: F) -> Result<T, E>
+where
+    F: AsyncFnOnce(&mut Txn) -> Result<T, E>,
+    E: From<neo4rs::Error>,
+{
+    let mut txn = graph.start_txn().await?;
+
+    match f(&mut txn).await {
+        Ok(value) => {
+            txn.commit().await?;
+            Ok(value)
+        }
+        Err(e) => {
+            if let Err(rollback_err) = txn.rollback().await {
+                tracing::error!("Failed to roll back transaction: {}", rollback_err);
+            }
+            Err(e)
+        }
+    }
+}
 
 // Helper function to convert serde_json::Value to BoltType (infallible)
 pub fn json_value_to_bolt_type(value: &Value) -> BoltType {
@@ -17,15 +41,10 @@ pub fn json_value_to_bolt_type(value: &Value) -> BoltType {
         }
         Value::String(s) => BoltType::from(s.as_str()),
         Value::Array(arr) => {
-            let bolt_values: Vec<BoltType> = arr
-                .iter()
-                .map(json_value_to_bolt_type)
-                .collect();
+            let bolt_values: Vec<BoltType> = arr.iter().map(json_value_to_bolt_type).collect();
             BoltType::from(bolt_values)
         }
-        Value::Object(_) => {
-            BoltType::from(value.to_string().as_str())
-        }
+        Value::Object(_) => BoltType::from(value.to_string().as_str()),
     }
 }
 
@@ -35,7 +54,11 @@ pub fn map_bolt4_to_bolt5_node(node_data: &Value) -> Value {
     if let Some(obj) = node_data.as_object() {
         let id = obj.get("id").and_then(|v| v.as_i64()).unwrap_or(0);
         let element_id = obj.get("elementId").and_then(|v| v.as_str()).unwrap_or("");
-        let labels = obj.get("labels").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        let labels = obj
+            .get("labels")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
         let generalizes_to_element_id = obj.get("generalizesToElementId").cloned();
 
         let props = if let Some(nested_props) = obj.get("props") {
@@ -54,7 +77,10 @@ pub fn map_bolt4_to_bolt5_node(node_data: &Value) -> Value {
         } else {
             let mut props_map = serde_json::Map::new();
             for (key, value) in obj {
-                if !matches!(key.as_str(), "id" | "elementId" | "labels" | "generalizesToElementId" | "embedding") {
+                if !matches!(
+                    key.as_str(),
+                    "id" | "elementId" | "labels" | "generalizesToElementId" | "embedding"
+                ) {
                     props_map.insert(key.clone(), value.clone());
                 }
             }
@@ -84,17 +110,31 @@ pub fn map_bolt4_to_bolt5_relationship(rel_data: &Value) -> Value {
         let id = obj.get("id").and_then(|v| v.as_i64()).unwrap_or(0);
         let element_id = obj.get("elementId").and_then(|v| v.as_str()).unwrap_or("");
         let start_id = obj.get("startId").and_then(|v| v.as_i64()).unwrap_or(0);
-        let start_element_id = obj.get("startElementId").and_then(|v| v.as_str()).unwrap_or("");
+        let start_element_id = obj
+            .get("startElementId")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
         let end_id = obj.get("endId").and_then(|v| v.as_i64()).unwrap_or(0);
-        let end_element_id = obj.get("endElementId").and_then(|v| v.as_str()).unwrap_or("");
+        let end_element_id = obj
+            .get("endElementId")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
         let rel_type = obj.get("type").and_then(|v| v.as_str()).unwrap_or("");
-        
+
         let props = if let Some(nested_props) = obj.get("props") {
             nested_props.clone()
         } else {
             let mut props_map = serde_json::Map::new();
             for (key, value) in obj {
-                if !matches!(key.as_str(), "id" | "elementId" | "startId" | "startElementId" | "endId" | "endElementId" | "type") {
+                if !matches!(
+                    key.as_str(),
+                    "id" | "elementId"
+                        | "startId"
+                        | "startElementId"
+                        | "endId"
+                        | "endElementId"
+                        | "type"
+                ) {
                     props_map.insert(key.clone(), value.clone());
                 }
             }
@@ -124,9 +164,14 @@ pub fn map_bolt4_to_bolt5(data: Vec<Value>) -> Value {
     }
 
     // Check if this is a complex query result (has node/relationships/affiliatedNodes structure)
-    let is_complex = data.first()
+    let is_complex = data
+        .first()
         .and_then(|item| item.as_object())
-        .map(|obj| obj.contains_key("node") || obj.contains_key("relationships") || obj.contains_key("affiliatedNodes"))
+        .map(|obj| {
+            obj.contains_key("node")
+                || obj.contains_key("relationships")
+                || obj.contains_key("affiliatedNodes")
+        })
         .unwrap_or(false);
 
     if is_complex {
@@ -135,14 +180,15 @@ pub fn map_bolt4_to_bolt5(data: Vec<Value>) -> Value {
         for node_result in data {
             if let Some(obj) = node_result.as_object() {
                 let mut values = Vec::new();
-                
+
                 if let Some(node) = obj.get("node") {
                     values.push(map_bolt4_to_bolt5_node(node));
                 }
-                
+
                 if let Some(relationships) = obj.get("relationships") {
                     if let Some(rel_array) = relationships.as_array() {
-                        let mapped_rels: Vec<Value> = rel_array.iter()
+                        let mapped_rels: Vec<Value> = rel_array
+                            .iter()
                             .map(map_bolt4_to_bolt5_relationship)
                             .collect();
                         values.push(json!(mapped_rels));
@@ -150,12 +196,11 @@ pub fn map_bolt4_to_bolt5(data: Vec<Value>) -> Value {
                         values.push(json!([]));
                     }
                 }
-                
+
                 if let Some(affiliated_nodes) = obj.get("affiliatedNodes") {
                     if let Some(nodes_array) = affiliated_nodes.as_array() {
-                        let mapped_nodes: Vec<Value> = nodes_array.iter()
-                            .map(map_bolt4_to_bolt5_node)
-                            .collect();
+                        let mapped_nodes: Vec<Value> =
+                            nodes_array.iter().map(map_bolt4_to_bolt5_node).collect();
                         values.push(json!(mapped_nodes));
                     } else {
                         values.push(json!([]));
@@ -172,12 +217,14 @@ pub fn map_bolt4_to_bolt5(data: Vec<Value>) -> Value {
         json!(bolt5_results)
     } else {
         let first_item = &data[0];
-        
+
         if let Some(obj) = first_item.as_object() {
-            let is_relationship = obj.contains_key("startId") || obj.contains_key("type") || obj.contains_key("Type");
-            
+            let is_relationship =
+                obj.contains_key("startId") || obj.contains_key("type") || obj.contains_key("Type");
+
             if is_relationship {
-                let mapped_rels: Vec<Value> = data.iter().map(map_bolt4_to_bolt5_relationship).collect();
+                let mapped_rels: Vec<Value> =
+                    data.iter().map(map_bolt4_to_bolt5_relationship).collect();
                 json!(mapped_rels)
             } else {
                 let mapped_nodes: Vec<Value> = data.iter().map(map_bolt4_to_bolt5_node).collect();
@@ -188,4 +235,4 @@ pub fn map_bolt4_to_bolt5(data: Vec<Value>) -> Value {
             json!(data)
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,70 @@
+//! Cursor pagination shared by list endpoints.
+//!
+//! A cursor is an opaque, URL-safe token wrapping whatever keyset position the
+//! endpoint needs to resume after the last item of a page (typically the sort
+//! key plus a unique tie-breaker). Clients only ever echo it back.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Requested page size, defaulted and clamped to `1..=MAX_PAGE_SIZE`
+pub fn page_size(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+pub fn encode_cursor<T: Serialize>(position: &T) -> String {
+    // Serializing plain position structs cannot fail
+    let json = serde_json::to_vec(position).unwrap_or_default();
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+/// Decode a cursor produced by `encode_cursor`; `None` if it was tampered with
+/// or belongs to a different endpoint
+pub fn decode_cursor<T: DeserializeOwned>(cursor: &str) -> Option<T> {
+    let json = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    serde_json::from_slice(&json).ok()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OffsetCursor {
+    offset: usize,
+}
+
+/// Page through a list that is loaded whole (short lists such as categories
+/// or tags), resuming at the offset the cursor carries. `None` if the cursor
+/// is invalid.
+pub fn page_of<T>(mut items: Vec<T>, limit: Option<i64>, cursor: Option<&str>) -> Option<Page<T>> {
+    let offset = match cursor {
+        Some(raw) => decode_cursor::<OffsetCursor>(raw)?.offset,
+        None => 0,
+    };
+    let limit = page_size(limit) as usize;
+    let total = items.len() as i64;
+
+    let end = offset.saturating_add(limit);
+    let next_cursor = (items.len() > end).then(|| encode_cursor(&OffsetCursor { offset: end }));
+    items.truncate(end);
+    let items = items.into_iter().skip(offset).collect();
+
+    Some(Page {
+        items,
+        next_cursor,
+        total,
+    })
+}
+
+/// One page of results
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Pass back as `cursor` to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
+    /// Items matching the filters across all pages
+    pub total: i64,
+}
@@ -2,7 +2,17 @@ use neo4rs::{Graph, Query};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use super::embedding::generate_embedding;
+use super::cypher;
+use super::embedding::{default_provider, generate_embedding};
+use crate::config;
+use crate::domains::graph::schema::{EMBEDDED_LABEL, EMBEDDING_SPACE_LABEL, SOFT_DELETE_PROPERTY};
+
+/// Relationships from a domain level to the components it requires
+const COMPONENT_RELATIONSHIPS: &str =
+    "REQUIRES_KNOWLEDGE|REQUIRES_SKILL|REQUIRES_TRAIT|REQUIRES_MILESTONE";
+/// Index candidates fetched per requested result when filtering
+const CANDIDATE_FACTOR: i32 = 10;
+const MIN_FILTERED_CANDIDATES: i32 = 100;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct FindSimilarNodesRequest {
@@ -10,6 +20,15 @@ pub struct FindSimilarNodesRequest {
     pub embedding: Option<Vec<f64>>,
     pub text: Option<String>,
     pub label: Option<String>,
+    /// Match nodes carrying any of these labels (combined with `label`)
+    pub labels: Option<Vec<String>>,
+    /// Only components required by a level of the named domain
+    pub domain: Option<String>,
+    /// Also match soft-deleted nodes
+    pub include_deleted: Option<bool>,
+    pub min_score: Option<f64>,
+    /// Return each match's labels and the domains requiring it
+    pub explain: Option<bool>,
     pub limit: Option<i32>,
 }
 
@@ -19,38 +38,135 @@ pub struct SimilarNodeResult {
     pub description: Option<String>,
     pub id: String,
     pub score: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domains: Option<Vec<String>>,
+}
+
+/// A query vector that cannot be compared with the indexed embeddings, because
+/// of its length or the model that produced it
+#[derive(Debug)]
+pub struct IncompatibleEmbedding(pub String);
+
+impl std::fmt::Display for IncompatibleEmbedding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Incompatible query embedding: {}", self.0)
+    }
+}
+
+impl std::error::Error for IncompatibleEmbedding {}
+
+/// Model and text recipe version the vector index holds, as recorded on the
+/// `_EmbeddingSpace` node
+pub async fn embedding_space(graph: &Graph) -> Result<Option<(String, i64)>, neo4rs::Error> {
+    let query = Query::new(format!(
+        "MATCH (s:{} {{index: 'nodeEmbeddings'}}) RETURN s.model AS model, s.version AS version",
+        EMBEDDING_SPACE_LABEL
+    ));
+    let mut result = graph.execute(query).await?;
+    Ok(result.next().await?.map(|row| {
+        (
+            row.get("model").unwrap_or_default(),
+            row.get("version").unwrap_or_default(),
+        )
+    }))
+}
+
+/// Reject a query vector of the wrong length, or produced by a model other
+/// than the one the index was built with (`model` is unknown for raw vectors)
+async fn check_compatible(
+    graph: &Graph,
+    dimensions: usize,
+    model: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let expected = config::embedding_dimensions();
+    if dimensions != expected {
+        return Err(Box::new(IncompatibleEmbedding(format!(
+            "expected {} dimensions, got {}",
+            expected, dimensions
+        ))));
+    }
+    if let Some(model) = model
+        && let Some((indexed, _)) = embedding_space(graph).await?
+        && indexed != model
+    {
+        return Err(Box::new(IncompatibleEmbedding(format!(
+            "index holds '{}' embeddings, query was embedded with '{}'",
+            indexed, model
+        ))));
+    }
+    Ok(())
+}
+
+/// Length and model of a stored node embedding, if the node has one
+async fn node_embedding_info(
+    graph: &Graph,
+    node_id: &str,
+) -> Result<Option<(usize, Option<String>)>, neo4rs::Error> {
+    let query = Query::new(
+        r#"
+        MATCH (n) WHERE elementId(n) = $nodeId AND n.embedding IS NOT NULL
+        RETURN size(n.embedding) AS dimensions, n.embedding_model AS model
+        "#
+        .to_string(),
+    )
+    .param("nodeId", node_id);
+    let mut result = graph.execute(query).await?;
+    Ok(result.next().await?.map(|row| {
+        (
+            row.get::<i64>("dimensions").unwrap_or_default() as usize,
+            row.get("model").ok(),
+        )
+    }))
 }
 
 /// Find similar nodes using node_id, embedding, or text (in order of priority).
-/// Optionally filter results by node label.
+/// Results can be narrowed by label, domain, soft-deletion and score, and
+/// explained with each match's labels and domains. Query vectors whose length
+/// or model differs from the index are rejected with [`IncompatibleEmbedding`].
 pub async fn find_similar_nodes(
     graph: &Graph,
     request: FindSimilarNodesRequest,
 ) -> Result<Vec<SimilarNodeResult>, Box<dyn std::error::Error>> {
     let limit = request.limit.unwrap_or(5);
+    let filters = build_filters(&request)?;
+    // Filters are applied after the index lookup, so ask it for more candidates
+    let candidates = if filters.is_empty() {
+        limit
+    } else {
+        (limit * CANDIDATE_FACTOR).max(MIN_FILTERED_CANDIDATES)
+    };
+    let returns = return_clause(request.explain.unwrap_or(false));
 
-    let (query_str, params) = if let Some(node_id) = request.node_id {
+    let mut params: HashMap<String, neo4rs::BoltType> = HashMap::new();
+    params.insert("limit".to_string(), limit.into());
+    params.insert("candidates".to_string(), candidates.into());
+    if let Some(domain) = request.domain {
+        params.insert("domain".to_string(), domain.into());
+    }
+    if let Some(min_score) = request.min_score {
+        params.insert("minScore".to_string(), min_score.into());
+    }
+
+    let query_str = if let Some(node_id) = request.node_id {
         // Search using a reference node ID
-        let query = build_node_id_query(request.label.as_deref());
-        let mut params: HashMap<String, neo4rs::BoltType> = HashMap::new();
+        if let Some((dimensions, model)) = node_embedding_info(graph, &node_id).await? {
+            check_compatible(graph, dimensions, model.as_deref()).await?;
+        }
         params.insert("nodeId".to_string(), node_id.into());
-        params.insert("limit".to_string(), limit.into());
-        (query, params)
+        build_node_id_query(&filters, &returns)
     } else if let Some(embedding) = request.embedding {
         // Search using provided embedding vector
-        let query = build_embedding_query(request.label.as_deref());
-        let mut params: HashMap<String, neo4rs::BoltType> = HashMap::new();
+        check_compatible(graph, embedding.len(), None).await?;
         params.insert("embedding".to_string(), embedding.into());
-        params.insert("limit".to_string(), limit.into());
-        (query, params)
+        build_embedding_query(&filters, &returns)
     } else if let Some(text) = request.text {
         // Generate embedding from text, then search
         let embedding = generate_embedding(&text).await?;
-        let query = build_embedding_query(request.label.as_deref());
-        let mut params: HashMap<String, neo4rs::BoltType> = HashMap::new();
+        check_compatible(graph, embedding.len(), Some(default_provider()?.model())).await?;
         params.insert("embedding".to_string(), embedding.into());
-        params.insert("limit".to_string(), limit.into());
-        (query, params)
+        build_embedding_query(&filters, &returns)
     } else {
         return Err("Either node_id, embedding, or text must be provided".into());
     };
@@ -58,40 +174,92 @@ pub async fn find_similar_nodes(
     execute_similarity_query(graph, query_str, params).await
 }
 
-/// Build query for node ID-based similarity search
-fn build_node_id_query(label: Option<&str>) -> String {
-    let label_filter = label
-        .map(|l| format!(" AND node:{}", l))
-        .unwrap_or_default();
+/// Conditions on `node` and `score` applied to the vector index candidates
+fn build_filters(request: &FindSimilarNodesRequest) -> Result<Vec<String>, cypher::CypherError> {
+    let mut filters = Vec::new();
+
+    let labels: Vec<&str> = request
+        .label
+        .iter()
+        .chain(request.labels.iter().flatten())
+        .map(String::as_str)
+        .collect();
+    if !labels.is_empty() {
+        filters.push(cypher::label_predicate("node", &labels)?);
+    }
+    if request.domain.is_some() {
+        filters.push(format!(
+            "EXISTS {{ MATCH (:Domain {{name: $domain}})-[:HAS_DOMAIN_LEVEL]->(:Domain_Level)-[:{}]->(node) }}",
+            COMPONENT_RELATIONSHIPS
+        ));
+    }
+    if !request.include_deleted.unwrap_or(false) {
+        filters.push(format!("node.{} IS NULL", SOFT_DELETE_PROPERTY));
+    }
+    if request.min_score.is_some() {
+        filters.push("score >= $minScore".to_string());
+    }
 
+    Ok(filters)
+}
+
+fn return_clause(explain: bool) -> String {
+    let mut returns =
+        "node.name as name, node.description as description, elementId(node) as id, score"
+            .to_string();
+    if explain {
+        returns.push_str(&format!(
+            r#",
+            [l IN labels(node) WHERE l <> '{}'] as labels,
+            COLLECT {{
+                MATCH (d:Domain)-[:HAS_DOMAIN_LEVEL]->(:Domain_Level)-[:{}]->(node)
+                RETURN DISTINCT d.name
+            }} as domains"#,
+            EMBEDDED_LABEL, COMPONENT_RELATIONSHIPS
+        ));
+    }
+    returns
+}
+
+fn and_filters(filters: &[String]) -> String {
+    filters.iter().map(|f| format!(" AND {}", f)).collect()
+}
+
+/// Build query for node ID-based similarity search
+fn build_node_id_query(filters: &[String], returns: &str) -> String {
     format!(
         r#"
         MATCH (n)
         WHERE elementId(n) = $nodeId
-        CALL db.index.vector.queryNodes('nodeEmbeddings', $limit, n.embedding)
+        CALL db.index.vector.queryNodes('nodeEmbeddings', $candidates, n.embedding)
         YIELD node, score
         WHERE elementId(node) <> $nodeId{}
-        RETURN node.name as name, node.description as description, elementId(node) as id, score
+        RETURN {}
         ORDER BY score DESC
+        LIMIT $limit
         "#,
-        label_filter
+        and_filters(filters),
+        returns
     )
 }
 
 /// Build query for embedding-based similarity search
-fn build_embedding_query(label: Option<&str>) -> String {
-    let label_filter = label
-        .map(|l| format!("WHERE node:{}\n            ", l))
-        .unwrap_or_default();
+fn build_embedding_query(filters: &[String], returns: &str) -> String {
+    let where_clause = if filters.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}\n        ", filters.join(" AND "))
+    };
 
     format!(
         r#"
-        CALL db.index.vector.queryNodes('nodeEmbeddings', $limit, $embedding)
+        CALL db.index.vector.queryNodes('nodeEmbeddings', $candidates, $embedding)
         YIELD node, score
-        {}RETURN node.name as name, node.description as description, elementId(node) as id, score
+        {}RETURN {}
         ORDER BY score DESC
+        LIMIT $limit
         "#,
-        label_filter
+        where_clause, returns
     )
 }
 
@@ -110,14 +278,14 @@ async fn execute_similarity_query(
     let mut similar_nodes = Vec::new();
 
     while let Some(row) = result.next().await? {
-        similar_nodes.push(
-            SimilarNodeResult {
-                name: row.get("name").unwrap_or_default(),
-                description: row.get("description").ok(),
-                id: row.get("id").unwrap_or_default(),
-                score: row.get("score").unwrap_or(0.0),
-            }
-        );
+        similar_nodes.push(SimilarNodeResult {
+            name: row.get("name").unwrap_or_default(),
+            description: row.get("description").ok(),
+            id: row.get("id").unwrap_or_default(),
+            score: row.get("score").unwrap_or(0.0),
+            labels: row.get("labels").ok(),
+            domains: row.get("domains").ok(),
+        });
     }
 
     Ok(similar_nodes)
@@ -3,6 +3,8 @@ use aws_sdk_sqs::Client;
 use serde::{Deserialize, Serialize};
 use std::env;
 
+use super::thresholds::ThresholdOverrides;
+
 /// Job message for async image generation
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ImageGenJob {
@@ -34,7 +36,9 @@ impl ImageGenJob {
 /// Returns Ok(()) if the message was sent successfully, or an error if:
 /// - IMAGE_GEN_QUEUE_URL environment variable is not set
 /// - Failed to send the message to SQS
-pub async fn queue_image_generation(job: ImageGenJob) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+pub async fn queue_image_generation(
+    job: ImageGenJob,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let queue_url = env::var("IMAGE_GEN_QUEUE_URL")
         .map_err(|_| "IMAGE_GEN_QUEUE_URL environment variable not set")?;
 
@@ -46,8 +50,8 @@ pub async fn queue_image_generation(job: ImageGenJob) -> Result<(), Box<dyn std:
 
     let client = Client::new(&config);
 
-    let message_body = serde_json::to_string(&job)
-        .map_err(|e| format!("Failed to serialize job: {}", e))?;
+    let message_body =
+        serde_json::to_string(&job).map_err(|e| format!("Failed to serialize job: {}", e))?;
 
     tracing::info!(
         domain = %job.domain_name,
@@ -114,10 +118,14 @@ pub struct NodeGenerationJob {
     pub suggested_level: Option<u8>,
     pub created_at: String,
     pub retry_count: u32,
+    /// Per-request similarity threshold overrides, applied on top of the worker's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity_thresholds: Option<ThresholdOverrides>,
 }
 
 impl NodeGenerationJob {
     /// Create a new node generation job
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         generation_id: String,
         sequence_number: u32,
@@ -158,8 +166,14 @@ impl NodeGenerationJob {
             suggested_level,
             created_at,
             retry_count: 0,
+            similarity_thresholds: None,
         }
     }
+
+    pub fn with_thresholds(mut self, overrides: Option<ThresholdOverrides>) -> Self {
+        self.similarity_thresholds = overrides;
+        self
+    }
 }
 
 /// Queue a node generation job to SQS
@@ -24,6 +24,8 @@ pub struct AppConfig {
 
     // External Services
     pub embedding_endpoint: String,
+    /// Length of the vectors the embedding endpoint returns
+    pub embedding_dimensions: usize,
     pub llm_endpoint: Option<String>,
     pub image_gen_endpoint: Option<String>,
     pub image_gen_api_key: Option<String>,
@@ -31,6 +33,10 @@ pub struct AppConfig {
 
 static CONFIG: OnceLock<AppConfig> = OnceLock::new();
 
+/// Embedding vector length from `EMBEDDING_DIMENSIONS`.
+/// Also needed by the migrations, which run before the full config is loaded.
+pub use atlas_common::embedding::embedding_dimensions;
+
 impl AppConfig {
     /// Load configuration from environment variables.
     /// This should be called once at application startup.
@@ -62,6 +68,7 @@ impl AppConfig {
             // External Services
             embedding_endpoint: env::var("EMBEDDING_ENDPOINT")
                 .map_err(|_| "EMBEDDING_ENDPOINT environment variable not set".to_string())?,
+            embedding_dimensions: embedding_dimensions(),
             llm_endpoint: env::var("LLM_ENDPOINT").ok(),
             image_gen_endpoint: env::var("IMAGE_GEN_ENDPOINT").ok(),
             image_gen_api_key: env::var("IMAGE_GEN_API_KEY").ok(),
@@ -0,0 +1,168 @@
+//! Completion tracking for async (SQS) domain generation.
+//!
+//! `generate_domain_async` records a run before queuing its node jobs:
+//!
+//! ```text
+//! (:Domain_Generation {generation_id, domain_name, total, finished_jobs, status})
+//! ```
+//!
+//! Each worker adds its job id to `finished_jobs` once the job succeeds or fails
+//! for good. A background watch here waits for every queued job, then runs the
+//! same integrity check the SSE orchestrator runs after its last agent and keeps
+//! the outcome on the run.
+
+use neo4rs::{Graph, Query as Neo4jQuery};
+use std::time::Duration;
+
+use crate::domains::graph::models::{DomainIntegrityReport, ServiceError};
+use crate::domains::graph::services::validate_domain;
+
+/// How often the watch checks whether the workers are done
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Jobs that exhaust their retries never report back, so stop waiting and
+/// validate whatever was generated after this long
+const MAX_WAIT: Duration = Duration::from_secs(60 * 60);
+
+/// Record a run expecting `total` jobs; call before queuing any of them
+pub async fn start_run(
+    graph: &Graph,
+    generation_id: &str,
+    domain_name: &str,
+    total: u32,
+) -> Result<(), ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        CREATE (:Domain_Generation {
+            generation_id: $generationId,
+            domain_name: $domainName,
+            total: $total,
+            finished_jobs: [],
+            status: 'running',
+            started_at: toString(datetime())
+        })
+        "#
+        .to_string(),
+    )
+    .param("generationId", generation_id)
+    .param("domainName", domain_name)
+    .param("total", total as i64);
+
+    graph.run(query).await?;
+    Ok(())
+}
+
+/// Lower the expected job count to the number actually queued
+pub async fn set_queued(
+    graph: &Graph,
+    generation_id: &str,
+    queued: u32,
+) -> Result<(), ServiceError> {
+    let query = Neo4jQuery::new(
+        "MATCH (g:Domain_Generation {generation_id: $generationId}) SET g.total = $total"
+            .to_string(),
+    )
+    .param("generationId", generation_id)
+    .param("total", queued as i64);
+
+    graph.run(query).await?;
+    Ok(())
+}
+
+/// Validate the domain in the background once the run's jobs have finished
+pub fn spawn_integrity_watch(graph: Graph, generation_id: String, domain_name: String) {
+    tokio::spawn(async move {
+        let finished = wait_for_jobs(&graph, &generation_id).await;
+        if !finished {
+            tracing::warn!(
+                "Generation {} still has unfinished jobs after {}s; validating '{}' anyway",
+                generation_id,
+                MAX_WAIT.as_secs(),
+                domain_name
+            );
+        }
+
+        // Report structural problems without failing an otherwise finished generation
+        let report = match validate_domain(&graph, &domain_name, None).await {
+            Ok(report) => report,
+            Err(e) => {
+                tracing::warn!("Integrity check of '{}' failed: {}", domain_name, e);
+                return;
+            }
+        };
+        if !report.valid {
+            tracing::warn!(
+                "Generated domain '{}' has {} integrity errors",
+                domain_name,
+                report.error_count
+            );
+        }
+
+        let status = if finished { "completed" } else { "timed_out" };
+        if let Err(e) = record_integrity(&graph, &generation_id, status, &report).await {
+            tracing::warn!(
+                "Failed to record integrity of generation {}: {}",
+                generation_id,
+                e
+            );
+        }
+    });
+}
+
+/// Whether every queued job finished before `MAX_WAIT`
+async fn wait_for_jobs(graph: &Graph, generation_id: &str) -> bool {
+    let started = tokio::time::Instant::now();
+    while started.elapsed() < MAX_WAIT {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        match jobs_finished(graph, generation_id).await {
+            Ok(true) => return true,
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to poll generation {}: {}", generation_id, e),
+        }
+    }
+    false
+}
+
+async fn jobs_finished(graph: &Graph, generation_id: &str) -> Result<bool, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (g:Domain_Generation {generation_id: $generationId})
+        RETURN size(g.finished_jobs) >= g.total AS finished
+        "#
+        .to_string(),
+    )
+    .param("generationId", generation_id);
+
+    let mut result = graph.execute(query).await?;
+    Ok(match result.next().await? {
+        Some(row) => row.get("finished").unwrap_or(false),
+        None => false,
+    })
+}
+
+async fn record_integrity(
+    graph: &Graph,
+    generation_id: &str,
+    status: &str,
+    report: &DomainIntegrityReport,
+) -> Result<(), ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (g:Domain_Generation {generation_id: $generationId})
+        SET g.status = $status,
+            g.finished_at = toString(datetime()),
+            g.integrity_valid = $valid,
+            g.integrity_errors = $errors,
+            g.integrity_warnings = $warnings
+        "#
+        .to_string(),
+    )
+    .param("generationId", generation_id)
+    .param("status", status)
+    .param("valid", report.valid)
+    .param("errors", report.error_count as i64)
+    .param("warnings", report.warning_count as i64);
+
+    graph.run(query).await?;
+    Ok(())
+}
@@ -1,25 +1,27 @@
 use axum::{
+    Json,
     extract::State,
     http::StatusCode,
     response::{
-        sse::{Event, KeepAlive, Sse},
         IntoResponse,
+        sse::{Event, KeepAlive, Sse},
     },
-    Json,
 };
 use futures::stream::Stream;
 use neo4rs::Graph;
-use serde_json::{json, Value};
+use serde_json::{Value, json};
 use std::convert::Infallible;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
 use validator::Validate;
 
-use super::llm::{ProviderType, GenerationConfig, create_provider};
+use super::generation;
+use super::llm::{GenerationConfig, ProviderType, create_provider};
 use super::models::{GenerateDomainRequest, SseEvent};
 use super::orchestrator::AgentOrchestrator;
+use crate::common::thresholds::{self, SimilarityThresholds};
 
 /// POST /api/secure/agent/generate-domain
 ///
@@ -34,6 +36,7 @@ use super::orchestrator::AgentOrchestrator;
 /// - `node_created` - Node created in database
 /// - `agent_completed` - Agent finished successfully
 /// - `agent_failed` - Agent failed (workflow will stop)
+/// - `integrity_checked` - Integrity report for the generated domain
 /// - `completed` - All agents finished
 /// - `failed` - Workflow failed
 pub async fn generate_domain_sse(
@@ -51,6 +54,18 @@ pub async fn generate_domain_sse(
         ));
     }
 
+    let level_template = request.level_template.resolve().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Invalid level template",
+                "details": e.to_string()
+            })),
+        )
+    })?;
+    let level_template_key = request.level_template.key().to_string();
+    let thresholds = resolve_thresholds(&request)?;
+
     tracing::info!("Starting domain generation for: {}", request.domain_name);
 
     // Create event channel with buffer
@@ -76,7 +91,16 @@ pub async fn generate_domain_sse(
 
     // Spawn the generation task in the background
     tokio::spawn(async move {
-        match orchestrator.generate_domain(domain_name, description).await {
+        match orchestrator
+            .generate_domain(
+                domain_name,
+                description,
+                level_template_key,
+                level_template,
+                thresholds,
+            )
+            .await
+        {
             Ok(result) => {
                 tracing::info!(
                     "Domain generation completed. Created {} nodes ({} reused) in {}ms",
@@ -115,17 +139,36 @@ fn create_sse_stream(
     })
 }
 
+/// Configured similarity thresholds with the request's overrides applied
+fn resolve_thresholds(
+    request: &GenerateDomainRequest,
+) -> Result<SimilarityThresholds, (StatusCode, Json<Value>)> {
+    thresholds::resolve(request.thresholds.as_ref()).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Invalid similarity thresholds",
+                "details": e
+            })),
+        )
+    })
+}
+
 // =============================================================================
 // ASYNC QUEUE-BASED DOMAIN GENERATION
 // =============================================================================
 
-use std::collections::HashMap;
+use crate::common::embedding::{generate_embeddings, node_embedding_text};
+use crate::common::neo4j_utils::with_transaction;
 use crate::common::sqs::{
-    queue_image_generation, queue_node_generation, is_queue_configured, is_node_queue_configured,
-    ImageGenJob, NodeGenerationJob, NodeType, DomainLevelInfo,
+    DomainLevelInfo, ImageGenJob, NodeGenerationJob, NodeType, is_node_queue_configured,
+    is_queue_configured, queue_image_generation, queue_node_generation,
 };
-use crate::domains::graph::models::{CreateNodeRequest, CreateRelationshipRequest};
+use crate::domains::graph::level_template::LevelTemplate;
+use crate::domains::graph::models::{CreateNodeRequest, CreateRelationshipRequest, ServiceError};
 use crate::domains::graph::services;
+use neo4rs::Txn;
+use std::collections::HashMap;
 
 /// Response for async domain generation
 #[derive(serde::Serialize)]
@@ -157,6 +200,18 @@ pub async fn generate_domain_async(
         ));
     }
 
+    let level_template = request.level_template.resolve().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Invalid level template",
+                "details": e.to_string()
+            })),
+        )
+    })?;
+    // Validated here; the workers apply the overrides to their own configuration
+    resolve_thresholds(&request)?;
+
     // Check if queue is configured
     if !is_node_queue_configured() {
         return Err((
@@ -168,7 +223,10 @@ pub async fn generate_domain_async(
         ));
     }
 
-    tracing::info!("Starting async domain generation for: {}", request.domain_name);
+    tracing::info!(
+        "Starting async domain generation for: {}",
+        request.domain_name
+    );
 
     // Generate a unique generation ID
     let generation_id = format!(
@@ -185,6 +243,8 @@ pub async fn generate_domain_async(
         &graph,
         &request.domain_name,
         request.description.as_deref(),
+        request.level_template.key(),
+        &level_template,
     )
     .await
     .map_err(|e| {
@@ -230,6 +290,7 @@ pub async fn generate_domain_async(
     let concepts = conceptualize_all_nodes(
         &request.domain_name,
         request.description.as_deref(),
+        &level_template,
     )
     .await
     .map_err(|e| {
@@ -257,8 +318,22 @@ pub async fn generate_domain_async(
         concepts.milestones.len()
     );
 
-    // Step 4: Queue all node generation jobs
+    // Step 4: Record the run so the workers can report back, then queue all node generation jobs
+    generation::start_run(&graph, &generation_id, &request.domain_name, total_nodes)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to record generation run: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": "Failed to record generation run",
+                    "details": e.to_string()
+                })),
+            )
+        })?;
+
     let mut sequence = 0u32;
+    let mut queued = 0u32;
 
     // Queue knowledge nodes
     for concept in &concepts.knowledge {
@@ -273,10 +348,12 @@ pub async fn generate_domain_async(
             domain_element_id.clone(),
             domain_levels.clone(),
             concept.suggested_level,
-        );
+        )
+        .with_thresholds(request.thresholds.clone());
 
-        if let Err(e) = queue_node_generation(job).await {
-            tracing::error!("Failed to queue knowledge node: {}", e);
+        match queue_node_generation(job).await {
+            Ok(_) => queued += 1,
+            Err(e) => tracing::error!("Failed to queue knowledge node: {}", e),
         }
         sequence += 1;
     }
@@ -294,10 +371,12 @@ pub async fn generate_domain_async(
             domain_element_id.clone(),
             domain_levels.clone(),
             concept.suggested_level,
-        );
+        )
+        .with_thresholds(request.thresholds.clone());
 
-        if let Err(e) = queue_node_generation(job).await {
-            tracing::error!("Failed to queue skill node: {}", e);
+        match queue_node_generation(job).await {
+            Ok(_) => queued += 1,
+            Err(e) => tracing::error!("Failed to queue skill node: {}", e),
         }
         sequence += 1;
     }
@@ -315,10 +394,12 @@ pub async fn generate_domain_async(
             domain_element_id.clone(),
             domain_levels.clone(),
             concept.suggested_level,
-        );
+        )
+        .with_thresholds(request.thresholds.clone());
 
-        if let Err(e) = queue_node_generation(job).await {
-            tracing::error!("Failed to queue trait node: {}", e);
+        match queue_node_generation(job).await {
+            Ok(_) => queued += 1,
+            Err(e) => tracing::error!("Failed to queue trait node: {}", e),
         }
         sequence += 1;
     }
@@ -336,10 +417,12 @@ pub async fn generate_domain_async(
             domain_element_id.clone(),
             domain_levels.clone(),
             concept.suggested_level,
-        );
+        )
+        .with_thresholds(request.thresholds.clone());
 
-        if let Err(e) = queue_node_generation(job).await {
-            tracing::error!("Failed to queue milestone node: {}", e);
+        match queue_node_generation(job).await {
+            Ok(_) => queued += 1,
+            Err(e) => tracing::error!("Failed to queue milestone node: {}", e),
         }
         sequence += 1;
     }
@@ -350,81 +433,115 @@ pub async fn generate_domain_async(
         request.domain_name
     );
 
+    // Jobs that failed to queue will never report back
+    if queued < total_nodes
+        && let Err(e) = generation::set_queued(&graph, &generation_id, queued).await
+    {
+        tracing::warn!(
+            "Failed to update queued job count of {}: {}",
+            generation_id,
+            e
+        );
+    }
+    generation::spawn_integrity_watch(
+        graph.clone(),
+        generation_id.clone(),
+        request.domain_name.clone(),
+    );
+
     Ok(Json(AsyncGenerationResponse {
         generation_id,
         domain_element_id,
         domain_name: request.domain_name,
         total_nodes,
-        message: format!("Domain created. {} nodes queued for generation.", total_nodes),
+        message: format!(
+            "Domain created. {} nodes queued for generation.",
+            total_nodes
+        ),
     }))
 }
 
-/// Create Domain node and 5 Domain_Level nodes
+/// Create Domain node and one Domain_Level node per template tier in a single transaction
 async fn create_domain_and_levels(
     graph: &Graph,
     domain_name: &str,
     description: Option<&str>,
+    level_template_key: &str,
+    level_template: &LevelTemplate,
 ) -> Result<(String, Vec<DomainLevelInfo>), Box<dyn std::error::Error + Send + Sync>> {
-    // Create Domain node
-    let mut domain_props: HashMap<String, serde_json::Value> = HashMap::new();
-    domain_props.insert("name".to_string(), json!(domain_name));
-    domain_props.insert("description".to_string(), json!(description.unwrap_or("")));
-
-    let domain_request = CreateNodeRequest {
-        labels: vec!["Domain".to_string()],
-        properties: domain_props,
-    };
-
-    let domain_result = services::create_node(graph, domain_request, false).await?;
-
-    // Create 5 Domain_Level nodes
-    let levels = vec![
-        (1, "Novice", 100),
-        (2, "Developing", 200),
-        (3, "Competent", 400),
-        (4, "Advanced", 700),
-        (5, "Master", 1000),
-    ];
-
-    let mut domain_levels = Vec::new();
-
-    for (level, suffix, points) in &levels {
-        let level_name = format!("{} {}", domain_name, suffix);
-
-        let mut level_props: HashMap<String, serde_json::Value> = HashMap::new();
-        level_props.insert("name".to_string(), json!(level_name));
-        level_props.insert(
-            "description".to_string(),
-            json!(format!("{} level in {}", suffix, domain_name)),
-        );
-        level_props.insert("level".to_string(), json!(level));
-        level_props.insert("total_points_required".to_string(), json!(points));
-
-        let level_request = CreateNodeRequest {
-            labels: vec!["Domain_Level".to_string()],
-            properties: level_props,
-        };
-
-        let level_result = services::create_node(graph, level_request, false).await?;
-
-        // Create HAS_DOMAIN_LEVEL relationship
-        let rel_request = CreateRelationshipRequest {
-            source_id: domain_result.element_id.clone(),
-            target_id: level_result.element_id.clone(),
-            relationship_type: "HAS_DOMAIN_LEVEL".to_string(),
-            properties: None,
+    let description = description.unwrap_or("");
+    let levels = level_template.levels_for(domain_name);
+
+    // Embed the Domain and its levels up front, so no transaction waits on the provider
+    let mut texts = vec![node_embedding_text(domain_name, description)];
+    texts.extend(
+        levels
+            .iter()
+            .map(|level| node_embedding_text(&level.name, &level.description)),
+    );
+    let mut embeddings = generate_embeddings(&texts)
+        .await
+        .map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?
+        .into_iter();
+    let domain_embedding = embeddings.next().unwrap_or_default();
+
+    let created = with_transaction(graph, async |txn: &mut Txn| {
+        // Create Domain node
+        let mut domain_props: HashMap<String, serde_json::Value> = HashMap::new();
+        domain_props.insert("name".to_string(), json!(domain_name));
+        domain_props.insert("description".to_string(), json!(description));
+        domain_props.insert("level_template".to_string(), json!(level_template_key));
+
+        let domain_request = CreateNodeRequest {
+            labels: vec!["Domain".to_string()],
+            properties: domain_props,
         };
 
-        services::create_relationship(graph, rel_request).await?;
+        let domain_result =
+            services::create_node_in_txn(txn, domain_request, domain_embedding).await?;
+
+        // Create the Domain_Level nodes
+        let mut domain_levels = Vec::new();
+
+        for (level, embedding) in levels.into_iter().zip(embeddings) {
+            let mut level_props: HashMap<String, serde_json::Value> = HashMap::new();
+            level_props.insert("name".to_string(), json!(level.name));
+            level_props.insert("description".to_string(), json!(level.description));
+            level_props.insert("level".to_string(), json!(level.level));
+            level_props.insert(
+                "total_points_required".to_string(),
+                json!(level.points_required),
+            );
+
+            let level_request = CreateNodeRequest {
+                labels: vec!["Domain_Level".to_string()],
+                properties: level_props,
+            };
+
+            let level_result = services::create_node_in_txn(txn, level_request, embedding).await?;
+
+            // Create HAS_DOMAIN_LEVEL relationship
+            let rel_request = CreateRelationshipRequest {
+                source_id: domain_result.element_id.clone(),
+                target_id: level_result.element_id.clone(),
+                relationship_type: "HAS_DOMAIN_LEVEL".to_string(),
+                properties: None,
+            };
+
+            services::create_relationship_in_txn(txn, rel_request).await?;
+
+            domain_levels.push(DomainLevelInfo {
+                level: level.level,
+                name: level.name,
+                element_id: level_result.element_id,
+            });
+        }
 
-        domain_levels.push(DomainLevelInfo {
-            level: *level as u8,
-            name: level_name,
-            element_id: level_result.element_id,
-        });
-    }
+        Ok::<_, ServiceError>((domain_result.element_id, domain_levels))
+    })
+    .await?;
 
-    Ok((domain_result.element_id, domain_levels))
+    Ok(created)
 }
 
 /// Conceptualized node with name and suggested level
@@ -447,35 +564,47 @@ struct ConceptualizedDomain {
 async fn conceptualize_all_nodes(
     domain_name: &str,
     description: Option<&str>,
+    level_template: &LevelTemplate,
 ) -> Result<ConceptualizedDomain, Box<dyn std::error::Error + Send + Sync>> {
-    let provider = create_provider(ProviderType::Claude)
-        .map_err(|e| format!("LLM not configured: {}", e))?;
-
-    let system_prompt = r#"You are an expert at designing learning domains for a personal development knowledge graph.
+    let provider =
+        create_provider(ProviderType::Claude).map_err(|e| format!("LLM not configured: {}", e))?;
+
+    let level_count = level_template.tiers.len();
+    let level_names = level_template
+        .tiers
+        .iter()
+        .enumerate()
+        .map(|(i, tier)| format!("{} = {}", i + 1, tier.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let system_prompt = format!(
+        r#"You are an expert at designing learning domains for a personal development knowledge graph.
 Given a domain name and optional description, generate a comprehensive list of:
 1. Knowledge concepts (things to learn)
 2. Skills (abilities to develop)
 3. Traits (characteristics to cultivate)
 4. Milestones (achievements to reach)
 
-For each item, provide a suggested level (1-5) where:
-1 = Novice, 2 = Developing, 3 = Competent, 4 = Advanced, 5 = Master
+For each item, provide a suggested level (1-{level_count}) where:
+{level_names}
 
 Respond with a JSON object:
-{
-  "knowledge": [{"name": "...", "level": 1-5}, ...],
-  "skills": [{"name": "...", "level": 1-5}, ...],
-  "traits": [{"name": "...", "level": 1-5}, ...],
-  "milestones": [{"name": "...", "level": 1-5}, ...]
-}
+{{
+  "knowledge": [{{"name": "...", "level": 1-{level_count}}}, ...],
+  "skills": [{{"name": "...", "level": 1-{level_count}}}, ...],
+  "traits": [{{"name": "...", "level": 1-{level_count}}}, ...],
+  "milestones": [{{"name": "...", "level": 1-{level_count}}}, ...]
+}}
 
 Guidelines:
 - Generate 5-10 items per category
 - Prefix domain-specific items with the domain name
 - Items should be atomic (no "and" combinations)
-- Distribute items across all 5 levels
+- Distribute items across all {level_count} levels
 
-IMPORTANT: Only respond with valid JSON, no other text."#;
+IMPORTANT: Only respond with valid JSON, no other text."#
+    );
 
     let description_context = description
         .map(|d| format!("\nDescription: {}", d))
@@ -492,7 +621,9 @@ IMPORTANT: Only respond with valid JSON, no other text."#;
         stop_sequences: None,
     };
 
-    let response = provider.generate(system_prompt, &user_prompt, &config).await
+    let response = provider
+        .generate(&system_prompt, &user_prompt, &config)
+        .await
         .map_err(|e| format!("LLM generation failed: {}", e))?;
 
     // Parse JSON response
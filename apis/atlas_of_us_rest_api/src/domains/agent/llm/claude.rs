@@ -33,6 +33,7 @@ struct ClaudeResponse {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct ClaudeUsage {
     input_tokens: Option<u32>,
     output_tokens: Option<u32>,
@@ -75,7 +76,8 @@ impl ClaudeProvider {
             LlmError::NotConfigured("CLAUDE_API_KEY environment variable not set".to_string())
         })?;
 
-        let model = env::var("CLAUDE_MODEL").unwrap_or_else(|_| "claude-sonnet-4-20250514".to_string());
+        let model =
+            env::var("CLAUDE_MODEL").unwrap_or_else(|_| "claude-sonnet-4-20250514".to_string());
 
         let client = Client::builder()
             .timeout(Duration::from_secs(300)) // 5 minute timeout for long generations
@@ -194,9 +196,7 @@ impl LlmProvider for ClaudeProvider {
         // Claude doesn't have a dedicated health endpoint
         // We verify the API key is set and valid format
         if self.api_key.is_empty() {
-            return Err(LlmError::NotConfigured(
-                "API key is empty".to_string(),
-            ));
+            return Err(LlmError::NotConfigured("API key is empty".to_string()));
         }
 
         // Optionally, we could do a minimal request to verify connectivity
@@ -47,8 +47,9 @@ pub struct LlamaCppProvider {
 impl LlamaCppProvider {
     /// Create a new LlamaCppProvider
     pub fn new() -> Result<Self, LlmError> {
-        let endpoint = env::var("LLM_ENDPOINT")
-            .map_err(|_| LlmError::ConnectionFailed("LLM_ENDPOINT environment variable not set".to_string()))?;
+        let endpoint = env::var("LLM_ENDPOINT").map_err(|_| {
+            LlmError::ConnectionFailed("LLM_ENDPOINT environment variable not set".to_string())
+        })?;
 
         let client = Client::builder()
             .timeout(Duration::from_secs(300)) // 5 minute timeout for long generations
@@ -10,18 +10,13 @@ use std::sync::Arc;
 pub use provider::{GenerationConfig, LlmError, LlmProvider};
 
 /// Available LLM provider types
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ProviderType {
+    #[default]
     LlamaCpp,
     Claude,
 }
 
-impl Default for ProviderType {
-    fn default() -> Self {
-        ProviderType::LlamaCpp
-    }
-}
-
 /// Factory function to create an LLM provider instance
 pub fn create_provider(provider_type: ProviderType) -> Result<Arc<dyn LlmProvider>, LlmError> {
     match provider_type {
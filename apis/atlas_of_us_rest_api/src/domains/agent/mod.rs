@@ -1,3 +1,4 @@
+pub mod generation;
 pub mod handlers;
 pub mod llm;
 pub mod models;
@@ -5,4 +6,4 @@ pub mod orchestrator;
 pub mod prompts;
 pub mod steps;
 
-pub use handlers::generate_domain_sse;
\ No newline at end of file
+pub use handlers::generate_domain_sse;
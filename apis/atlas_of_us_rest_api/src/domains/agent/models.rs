@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::common::thresholds::{SimilarityThresholds, ThresholdOverrides};
+use crate::domains::graph::level_template::{LevelTemplate, LevelTemplateChoice};
+use crate::domains::graph::models::DomainIntegrityReport;
+
 // ========== Request/Response Models ==========
 
 #[derive(Debug, Deserialize, Validate)]
@@ -11,9 +15,17 @@ pub struct GenerateDomainRequest {
 
     #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
     pub description: Option<String>,
+
+    /// Built-in template name or a custom template; defaults to the standard five tiers
+    #[serde(rename = "levelTemplate", default)]
+    pub level_template: LevelTemplateChoice,
+
+    /// Similarity threshold overrides for this generation only
+    #[serde(default)]
+    pub thresholds: Option<ThresholdOverrides>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct DomainStatistics {
     #[serde(rename = "domainLevelsCreated")]
     pub domain_levels_created: usize,
@@ -33,21 +45,6 @@ pub struct DomainStatistics {
     pub generation_time_ms: u64,
 }
 
-impl Default for DomainStatistics {
-    fn default() -> Self {
-        Self {
-            domain_levels_created: 0,
-            knowledge_created: 0,
-            skills_created: 0,
-            traits_created: 0,
-            milestones_created: 0,
-            relationships_created: 0,
-            nodes_reused: 0,
-            generation_time_ms: 0,
-        }
-    }
-}
-
 // ========== Domain Graph Registry ==========
 
 /// A node that was created or reused in the database
@@ -149,7 +146,10 @@ impl DomainGraphRegistry {
     pub fn summary_for_llm(&self) -> String {
         format!(
             "Created nodes:\n- Domain Levels: {:?}\n- Knowledge: {:?}\n- Skills: {:?}\n- Traits: {:?}\n- Milestones: {:?}",
-            self.domain_levels.iter().map(|n| &n.name).collect::<Vec<_>>(),
+            self.domain_levels
+                .iter()
+                .map(|n| &n.name)
+                .collect::<Vec<_>>(),
             self.knowledge.iter().map(|n| &n.name).collect::<Vec<_>>(),
             self.skills.iter().map(|n| &n.name).collect::<Vec<_>>(),
             self.traits.iter().map(|n| &n.name).collect::<Vec<_>>(),
@@ -159,7 +159,8 @@ impl DomainGraphRegistry {
 
     /// Find a node by name across all types
     pub fn find_by_name(&self, name: &str) -> Option<&CreatedNode> {
-        self.knowledge.iter()
+        self.knowledge
+            .iter()
             .chain(self.skills.iter())
             .chain(self.traits.iter())
             .chain(self.milestones.iter())
@@ -169,7 +170,8 @@ impl DomainGraphRegistry {
 
     /// Find a node by element ID across all types
     pub fn find_by_id(&self, element_id: &str) -> Option<&CreatedNode> {
-        self.all_nodes().into_iter()
+        self.all_nodes()
+            .into_iter()
             .find(|n| n.element_id == element_id)
     }
 
@@ -214,21 +216,24 @@ impl DomainGraphRegistry {
 
     /// Get all generalizations for a specific node
     pub fn get_generalizations_for(&self, specific_id: &str) -> Vec<&GeneralizationLink> {
-        self.generalizations.iter()
+        self.generalizations
+            .iter()
             .filter(|g| g.specific_id == specific_id)
             .collect()
     }
 
     /// Get all level requirements for a specific level
     pub fn get_requirements_for_level(&self, level_id: &str) -> Vec<&LevelRequirement> {
-        self.level_requirements.iter()
+        self.level_requirements
+            .iter()
             .filter(|r| r.level_id == level_id)
             .collect()
     }
 
     /// Get all prerequisites for a specific node
     pub fn get_prerequisites_for(&self, source_id: &str) -> Vec<&PrerequisiteLink> {
-        self.prerequisites.iter()
+        self.prerequisites
+            .iter()
             .filter(|p| p.source_id == source_id)
             .collect()
     }
@@ -249,10 +254,12 @@ impl DomainGraphRegistry {
         self.traits.retain(|n| seen.insert(n.element_id.clone()));
 
         seen.clear();
-        self.milestones.retain(|n| seen.insert(n.element_id.clone()));
+        self.milestones
+            .retain(|n| seen.insert(n.element_id.clone()));
 
         seen.clear();
-        self.domain_levels.retain(|n| seen.insert(n.element_id.clone()));
+        self.domain_levels
+            .retain(|n| seen.insert(n.element_id.clone()));
     }
 }
 
@@ -285,7 +292,12 @@ impl VerifiedConcept {
         }
     }
 
-    pub fn create_and_generalize(name: &str, generalizes_to_id: &str, generalizes_to_name: &str, needs_creation: bool) -> Self {
+    pub fn create_and_generalize(
+        name: &str,
+        generalizes_to_id: &str,
+        generalizes_to_name: &str,
+        needs_creation: bool,
+    ) -> Self {
         Self {
             name: name.to_string(),
             action: ConceptAction::CreateAndGeneralize {
@@ -381,6 +393,7 @@ impl AgentType {
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum SseEvent {
     /// Workflow started
     Started {
@@ -408,10 +421,7 @@ pub enum SseEvent {
     },
 
     /// Progress update during agent execution
-    StepProgress {
-        agent: AgentType,
-        message: String,
-    },
+    StepProgress { agent: AgentType, message: String },
 
     /// A similarity check was performed
     SimilarityCheck {
@@ -454,6 +464,9 @@ pub enum SseEvent {
     /// Agent execution failed
     AgentFailed { agent: AgentType, error: String },
 
+    /// Integrity check of the generated domain, run after the last agent
+    IntegrityChecked { report: DomainIntegrityReport },
+
     /// Workflow completed successfully
     Completed {
         #[serde(rename = "domainName")]
@@ -478,15 +491,29 @@ pub enum SseEvent {
 pub struct AgentContext {
     pub domain_name: String,
     pub description: Option<String>,
+    /// Name of the chosen level template, recorded on the Domain node
+    pub level_template_key: String,
+    pub level_template: LevelTemplate,
     pub domain_element_id: Option<String>,
     pub domain_graph: DomainGraphRegistry,
+    /// Configured thresholds with the request's overrides applied
+    pub thresholds: SimilarityThresholds,
 }
 
 impl AgentContext {
-    pub fn new(domain_name: String, description: Option<String>) -> Self {
+    pub fn new(
+        domain_name: String,
+        description: Option<String>,
+        level_template_key: String,
+        level_template: LevelTemplate,
+        thresholds: SimilarityThresholds,
+    ) -> Self {
         Self {
             domain_name,
             description,
+            level_template_key,
+            level_template,
+            thresholds,
             domain_element_id: None,
             domain_graph: DomainGraphRegistry::default(),
         }
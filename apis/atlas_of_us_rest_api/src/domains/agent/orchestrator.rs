@@ -3,18 +3,16 @@ use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::mpsc;
 
-use super::llm::{create_provider, LlmProvider, ProviderType};
-use super::models::{
-    AgentContext, AgentType, DomainGenerationResult, DomainStatistics, SseEvent,
-};
+use super::llm::{LlmProvider, ProviderType, create_provider};
+use super::models::{AgentContext, AgentType, DomainGenerationResult, DomainStatistics, SseEvent};
 use super::steps::{
-    DomainArchitectStep, KnowledgeGeneratorStep, MilestoneGeneratorStep,
-    LevelDistributorStep, PrerequisiteMapperStep, SkillGeneratorStep, TraitGeneratorStep, AgentStep,
+    AgentStep, DomainArchitectStep, KnowledgeGeneratorStep, LevelDistributorStep,
+    MilestoneGeneratorStep, PrerequisiteMapperStep, SkillGeneratorStep, TraitGeneratorStep,
 };
-use crate::common::similarity::{find_similar_nodes, FindSimilarNodesRequest};
-
-/// Threshold for blocking domain generation if similar domain exists
-const DOMAIN_SIMILARITY_THRESHOLD: f64 = 0.85;
+use crate::common::similarity::{FindSimilarNodesRequest, find_similar_nodes};
+use crate::common::thresholds::SimilarityThresholds;
+use crate::domains::graph::level_template::LevelTemplate;
+use crate::domains::graph::services::validate_domain;
 
 /// Agent orchestrator manages the sequential execution of all agent steps
 pub struct AgentOrchestrator {
@@ -40,18 +38,25 @@ impl AgentOrchestrator {
         })
     }
 
-    /// Check if a similar domain already exists in the database
-    async fn check_domain_exists(&self, domain_name: &str) -> Result<Option<String>, String> {
-        let similar = find_similar_nodes(&self.graph, FindSimilarNodesRequest {
-            text: Some(domain_name.to_string()),
-            label: Some("Domain".to_string()),
-            limit: Some(1),
-            ..Default::default()
-        })
+    /// Check if a domain scoring at or above `threshold` already exists in the database
+    async fn check_domain_exists(
+        &self,
+        domain_name: &str,
+        threshold: f64,
+    ) -> Result<Option<String>, String> {
+        let similar = find_similar_nodes(
+            &self.graph,
+            FindSimilarNodesRequest {
+                text: Some(domain_name.to_string()),
+                label: Some("Domain".to_string()),
+                limit: Some(1),
+                ..Default::default()
+            },
+        )
         .await
         .map_err(|e| format!("Similarity search failed: {}", e))?;
 
-        if !similar.is_empty() && similar[0].score >= DOMAIN_SIMILARITY_THRESHOLD {
+        if !similar.is_empty() && similar[0].score >= threshold {
             return Ok(Some(similar[0].name.clone()));
         }
 
@@ -63,17 +68,33 @@ impl AgentOrchestrator {
         &self,
         domain_name: String,
         description: Option<String>,
+        level_template_key: String,
+        level_template: LevelTemplate,
+        thresholds: SimilarityThresholds,
     ) -> Result<DomainGenerationResult, String> {
         let start_time = Instant::now();
-        let mut context = AgentContext::new(domain_name.clone(), description);
+        let mut context = AgentContext::new(
+            domain_name.clone(),
+            description,
+            level_template_key,
+            level_template,
+            thresholds,
+        );
         let mut stats = DomainStatistics::default();
 
         // Check if similar domain already exists
-        if let Some(existing_domain) = self.check_domain_exists(&domain_name).await? {
+        if let Some(existing_domain) = self
+            .check_domain_exists(
+                &domain_name,
+                context.thresholds.for_label("Domain").duplicate,
+            )
+            .await?
+        {
             self.send_event(SseEvent::DomainExists {
                 requested_name: domain_name.clone(),
                 existing_domain: existing_domain.clone(),
-            }).await;
+            })
+            .await;
 
             return Err(format!(
                 "A similar domain '{}' already exists. Please choose a different name.",
@@ -85,11 +106,13 @@ impl AgentOrchestrator {
         self.send_event(SseEvent::Started {
             domain_name: domain_name.clone(),
             total_agents: 7,
-        }).await;
+        })
+        .await;
 
         // Execute each agent step
         let agents: Vec<Box<dyn AgentStep + Send + Sync>> = vec![
             Box::new(DomainArchitectStep::new(
+                self.llm_provider.clone(),
                 self.graph.clone(),
                 self.event_tx.clone(),
             )),
@@ -133,7 +156,8 @@ impl AgentOrchestrator {
                 agent: agent_type,
                 agent_number: (idx + 1) as u8,
                 agent_name: agent_type.display_name().to_string(),
-            }).await;
+            })
+            .await;
 
             tracing::info!("Starting agent: {:?}", agent_type);
 
@@ -150,10 +174,15 @@ impl AgentOrchestrator {
                         agent: agent_type,
                         nodes_created: created,
                         nodes_reused: reused,
-                    }).await;
+                    })
+                    .await;
 
-                    tracing::info!("Agent {:?} completed: {} created, {} reused",
-                        agent_type, created, reused);
+                    tracing::info!(
+                        "Agent {:?} completed: {} created, {} reused",
+                        agent_type,
+                        created,
+                        reused
+                    );
                 }
                 Err(e) => {
                     tracing::error!("Agent {:?} failed: {}", agent_type, e);
@@ -161,19 +190,36 @@ impl AgentOrchestrator {
                     self.send_event(SseEvent::AgentFailed {
                         agent: agent_type,
                         error: e.clone(),
-                    }).await;
+                    })
+                    .await;
 
                     self.send_event(SseEvent::Failed {
                         error: e.clone(),
                         last_agent: Some(agent_type),
                         nodes_created_before_failure: Some(context.domain_graph.clone()),
-                    }).await;
+                    })
+                    .await;
 
                     return Err(e);
                 }
             }
         }
 
+        // Report structural problems without failing an otherwise finished generation
+        match validate_domain(&self.graph, &domain_name, None).await {
+            Ok(report) => {
+                if !report.valid {
+                    tracing::warn!(
+                        "Generated domain '{}' has {} integrity errors",
+                        domain_name,
+                        report.error_count
+                    );
+                }
+                self.send_event(SseEvent::IntegrityChecked { report }).await;
+            }
+            Err(e) => tracing::warn!("Integrity check of '{}' failed: {}", domain_name, e),
+        }
+
         stats.generation_time_ms = start_time.elapsed().as_millis() as u64;
         stats.nodes_reused = context.domain_graph.count_reused();
 
@@ -181,10 +227,10 @@ impl AgentOrchestrator {
         self.send_event(SseEvent::Completed {
             domain_name: domain_name.clone(),
             statistics: stats.clone(),
-        }).await;
+        })
+        .await;
 
-        let domain_element_id = context.domain_element_id.clone()
-            .unwrap_or_default();
+        let domain_element_id = context.domain_element_id.clone().unwrap_or_default();
 
         Ok(DomainGenerationResult {
             domain_name,
@@ -228,20 +274,36 @@ impl AgentOrchestrator {
                 stats.domain_levels_created = context.domain_graph.domain_levels.len();
             }
             AgentType::KnowledgeGenerator => {
-                stats.knowledge_created = context.domain_graph.knowledge
-                    .iter().filter(|n| !n.was_reused).count();
+                stats.knowledge_created = context
+                    .domain_graph
+                    .knowledge
+                    .iter()
+                    .filter(|n| !n.was_reused)
+                    .count();
             }
             AgentType::SkillGenerator => {
-                stats.skills_created = context.domain_graph.skills
-                    .iter().filter(|n| !n.was_reused).count();
+                stats.skills_created = context
+                    .domain_graph
+                    .skills
+                    .iter()
+                    .filter(|n| !n.was_reused)
+                    .count();
             }
             AgentType::TraitGenerator => {
-                stats.traits_created = context.domain_graph.traits
-                    .iter().filter(|n| !n.was_reused).count();
+                stats.traits_created = context
+                    .domain_graph
+                    .traits
+                    .iter()
+                    .filter(|n| !n.was_reused)
+                    .count();
             }
             AgentType::MilestoneGenerator => {
-                stats.milestones_created = context.domain_graph.milestones
-                    .iter().filter(|n| !n.was_reused).count();
+                stats.milestones_created = context
+                    .domain_graph
+                    .milestones
+                    .iter()
+                    .filter(|n| !n.was_reused)
+                    .count();
             }
             AgentType::LevelDistributor => {
                 // Level relationships count could be tracked here if needed
@@ -1,56 +1,63 @@
-/// Prompt templates for multi-pass agent execution
-/// Each agent has multiple passes with specific focused prompts
+//! Prompt templates for multi-pass agent execution
+//! Each agent has multiple passes with specific focused prompts
 
 use crate::common::similarity::SimilarNodeResult;
+use crate::domains::graph::level_template::proficiency_guideline;
 
 pub struct PromptTemplates;
 
 impl PromptTemplates {
     // ========== Domain Architect Prompts ==========
 
-    /// Generate domain structure including levels and scope
-    pub fn domain_structure(domain_name: &str, description: &str) -> String {
+    /// Describe the domain and its template levels, and pick the category it is filed under
+    pub fn domain_structure(
+        domain_name: &str,
+        description: &str,
+        levels: &[String],
+        categories: &str,
+    ) -> String {
         format!(
             r#"You are designing the structure for the domain "{domain_name}".
 {desc_section}
 
+The domain has these levels, lowest first:
+{levels}
+
+Existing categories, one path per line:
+{categories}
+
+For the category, prefer an existing path. If none fits, extend an existing path
+or propose a new one, from the broadest category down (e.g. ["Sports", "Combat Sports"]).
+Keep paths short (1-3 categories) and names general enough to hold other domains.
+Never include the domain itself in the path.
+
 Generate a JSON object with the following structure:
 {{
-    "domain": {{
-        "name": "{domain_name}",
-        "description": "A comprehensive description of the domain"
-    }},
+    "description": "A comprehensive description of the domain",
     "levels": [
         {{
             "level": 1,
-            "name": "{domain_name} Novice",
-            "description": "Description of novice level"
-        }},
-        {{
-            "level": 2,
-            "name": "{domain_name} Developing",
-            "description": "Description of developing level"
-        }},
-        {{
-            "level": 3,
-            "name": "{domain_name} Competent",
-            "description": "Description of competent level"
-        }},
-        {{
-            "level": 4,
-            "name": "{domain_name} Advanced",
-            "description": "Description of advanced level"
-        }},
-        {{
-            "level": 5,
-            "name": "{domain_name} Master",
-            "description": "Description of master level"
+            "description": "What someone at this level of {domain_name} can do"
         }}
-    ]
+    ],
+    "category": ["Broad Category", "Narrower Category"]
 }}
 
+Include one entry in "levels" per level above, numbered as listed.
+
 Output ONLY valid JSON, no explanation or markdown."#,
             domain_name = domain_name,
+            levels = levels
+                .iter()
+                .enumerate()
+                .map(|(i, name)| format!("{}. {}", i + 1, name))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            categories = if categories.is_empty() {
+                "(none yet)"
+            } else {
+                categories
+            },
             desc_section = if description.is_empty() {
                 String::new()
             } else {
@@ -98,12 +105,21 @@ Output ONLY a JSON array of concept names no explanation:
     }
 
     /// Pass 3: Verify if a similar node should be used
-    pub fn verify_similar_knowledge(concept: &str, domain_name: &str, similar_nodes: &[SimilarNodeResult]) -> String {
-        let similar_list: Vec<String> = similar_nodes.iter()
-            .map(|n| format!("- {} (similarity: {:.2}): {}",
-                n.name,
-                n.score,
-                n.description.as_deref().unwrap_or("No description")))
+    pub fn verify_similar_knowledge(
+        concept: &str,
+        domain_name: &str,
+        similar_nodes: &[SimilarNodeResult],
+    ) -> String {
+        let similar_list: Vec<String> = similar_nodes
+            .iter()
+            .map(|n| {
+                format!(
+                    "- {} (similarity: {:.2}): {}",
+                    n.name,
+                    n.score,
+                    n.description.as_deref().unwrap_or("No description")
+                )
+            })
             .collect();
 
         format!(
@@ -136,9 +152,16 @@ Output ONLY a JSON object:
     }
 
     /// Pass 4: Generate full knowledge node properties
-    pub fn knowledge_properties(domain_name: &str, concept: &str, generalizes_to: Option<&str>) -> String {
+    pub fn knowledge_properties(
+        domain_name: &str,
+        concept: &str,
+        generalizes_to: Option<&str>,
+    ) -> String {
         let generalization_note = if let Some(general) = generalizes_to {
-            format!("\nNote: This is a domain-specific version that generalizes to '{}'.", general)
+            format!(
+                "\nNote: This is a domain-specific version that generalizes to '{}'.",
+                general
+            )
         } else {
             String::new()
         };
@@ -191,11 +214,18 @@ Output ONLY a JSON object with these fields:
     // ========== Skill Generator Prompts ==========
 
     /// Pass 1: Generate list of skill concepts
-    pub fn skill_concepts(domain_name: &str, description: &str, existing_knowledge: &[String]) -> String {
+    pub fn skill_concepts(
+        domain_name: &str,
+        description: &str,
+        existing_knowledge: &[String],
+    ) -> String {
         let knowledge_context = if existing_knowledge.is_empty() {
             String::new()
         } else {
-            format!("\nExisting knowledge in this domain: {}", existing_knowledge.join(", "))
+            format!(
+                "\nExisting knowledge in this domain: {}",
+                existing_knowledge.join(", ")
+            )
         };
 
         format!(
@@ -235,12 +265,21 @@ Output ONLY a JSON array of skill names no explanation:
     }
 
     /// Pass 3: Verify if a similar skill should be used
-    pub fn verify_similar_skill(concept: &str, domain_name: &str, similar_nodes: &[SimilarNodeResult]) -> String {
-        let similar_list: Vec<String> = similar_nodes.iter()
-            .map(|n| format!("- {} (similarity: {:.2}): {}",
-                n.name,
-                n.score,
-                n.description.as_deref().unwrap_or("No description")))
+    pub fn verify_similar_skill(
+        concept: &str,
+        domain_name: &str,
+        similar_nodes: &[SimilarNodeResult],
+    ) -> String {
+        let similar_list: Vec<String> = similar_nodes
+            .iter()
+            .map(|n| {
+                format!(
+                    "- {} (similarity: {:.2}): {}",
+                    n.name,
+                    n.score,
+                    n.description.as_deref().unwrap_or("No description")
+                )
+            })
             .collect();
 
         format!(
@@ -273,9 +312,16 @@ Output ONLY a JSON object:
     }
 
     /// Pass 4: Generate full skill node properties
-    pub fn skill_properties(domain_name: &str, concept: &str, generalizes_to: Option<&str>) -> String {
+    pub fn skill_properties(
+        domain_name: &str,
+        concept: &str,
+        generalizes_to: Option<&str>,
+    ) -> String {
         let generalization_note = if let Some(general) = generalizes_to {
-            format!("\nNote: This is a domain-specific version that generalizes to '{}'.", general)
+            format!(
+                "\nNote: This is a domain-specific version that generalizes to '{}'.",
+                general
+            )
         } else {
             String::new()
         };
@@ -366,12 +412,21 @@ Output ONLY a JSON array of trait names no explanation:
     }
 
     /// Pass 3: Verify if a similar trait should be used
-    pub fn verify_similar_trait(concept: &str, domain_name: &str, similar_nodes: &[SimilarNodeResult]) -> String {
-        let similar_list: Vec<String> = similar_nodes.iter()
-            .map(|n| format!("- {} (similarity: {:.2}): {}",
-                n.name,
-                n.score,
-                n.description.as_deref().unwrap_or("No description")))
+    pub fn verify_similar_trait(
+        concept: &str,
+        domain_name: &str,
+        similar_nodes: &[SimilarNodeResult],
+    ) -> String {
+        let similar_list: Vec<String> = similar_nodes
+            .iter()
+            .map(|n| {
+                format!(
+                    "- {} (similarity: {:.2}): {}",
+                    n.name,
+                    n.score,
+                    n.description.as_deref().unwrap_or("No description")
+                )
+            })
             .collect();
 
         format!(
@@ -434,7 +489,7 @@ Output ONLY a JSON object with these fields:
 Milestones should be:
 - Binary (achieved or not achieved)
 - Concrete and measurable
-- Distributed across all 5 levels (novice to master)
+- Distributed across all levels (novice to master)
 - Types: performance, achievement, participation, creation, recognition
 
 CRITICAL RULES:
@@ -458,12 +513,21 @@ Output ONLY a JSON array of milestone names (Generate 0-3 milestones per level),
     }
 
     /// Pass 3: Verify if a similar milestone should be used
-    pub fn verify_similar_milestone(concept: &str, domain_name: &str, similar_nodes: &[SimilarNodeResult]) -> String {
-        let similar_list: Vec<String> = similar_nodes.iter()
-            .map(|n| format!("- {} (similarity: {:.2}): {}",
-                n.name,
-                n.score,
-                n.description.as_deref().unwrap_or("No description")))
+    pub fn verify_similar_milestone(
+        concept: &str,
+        domain_name: &str,
+        similar_nodes: &[SimilarNodeResult],
+    ) -> String {
+        let similar_list: Vec<String> = similar_nodes
+            .iter()
+            .map(|n| {
+                format!(
+                    "- {} (similarity: {:.2}): {}",
+                    n.name,
+                    n.score,
+                    n.description.as_deref().unwrap_or("No description")
+                )
+            })
             .collect();
 
         format!(
@@ -490,9 +554,16 @@ Output ONLY a JSON object:
     }
 
     /// Pass 4: Generate full milestone node properties
-    pub fn milestone_properties(domain_name: &str, concept: &str, generalizes_to: Option<&str>) -> String {
+    pub fn milestone_properties(
+        domain_name: &str,
+        concept: &str,
+        generalizes_to: Option<&str>,
+    ) -> String {
         let generalization_note = if let Some(general) = generalizes_to {
-            format!("\nNote: This is a domain-specific version that generalizes to '{}'.", general)
+            format!(
+                "\nNote: This is a domain-specific version that generalizes to '{}'.",
+                general
+            )
         } else {
             String::new()
         };
@@ -544,14 +615,29 @@ Output a JSON object mapping component names to their prerequisites:
     }
 
     /// Pass 2: Assign components to domain levels
-    pub fn level_assignment(domain_name: &str, context: &str) -> String {
+    pub fn level_assignment(domain_name: &str, context: &str, tiers: &[&str]) -> String {
+        let level_count = tiers.len();
+        let guidelines = tiers
+            .iter()
+            .enumerate()
+            .map(|(i, tier)| {
+                format!(
+                    "- Level {} ({}): {}",
+                    i + 1,
+                    tier,
+                    proficiency_guideline(i + 1, level_count)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
         format!(
             r#"Assign components to domain levels for "{domain_name}".
 
 {context}
 
 For each component, determine:
-1. Which level (1-5) it should be required at
+1. Which level (1-{level_count}) it should be required at
 2. The proficiency level required:
    - For Knowledge: bloom_level = "Remember" | "Understand" | "Apply" | "Analyze" | "Evaluate" | "Create"
    - For Skills: dreyfus_level = "Novice" | "Advanced Beginner" | "Competent" | "Proficient" | "Expert"
@@ -559,11 +645,7 @@ For each component, determine:
    - For Milestones: no proficiency needed, just level assignment
 
 Level distribution guidelines:
-- Level 1 (Novice): Basic knowledge at Remember, skills at Novice, no trait requirements
-- Level 2 (Developing): Knowledge at Understand, skills at Advanced Beginner, traits ~40
-- Level 3 (Competent): Knowledge at Apply, skills at Competent, traits ~50-60
-- Level 4 (Advanced): Knowledge at Analyze, skills at Proficient, traits ~70-75
-- Level 5 (Master): Knowledge at Evaluate/Create, skills at Expert, traits ~85+
+{guidelines}
 
 Output a JSON object:
 {{
@@ -571,13 +653,15 @@ Output a JSON object:
         {{
             "component": "Component Name",
             "component_type": "Knowledge" | "Skill" | "Trait" | "Milestone",
-            "level": 1-5,
+            "level": 1-{level_count},
             "proficiency": "bloom/dreyfus level string or min_score integer"
         }}
     ]
 }}"#,
             domain_name = domain_name,
-            context = context
+            context = context,
+            level_count = level_count,
+            guidelines = guidelines
         )
     }
 }
@@ -1,31 +1,154 @@
 use neo4rs::Graph;
+use serde_json::{Value, json};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::sync::mpsc;
-use serde_json::{json, Value};
 
-use super::{emit_event, AgentStep};
+use super::{AgentStep, emit_event};
+use crate::common::sqs::{ImageGenJob, is_queue_configured, queue_image_generation};
+use crate::domains::agent::llm::{GenerationConfig, LlmProvider};
 use crate::domains::agent::models::{AgentContext, AgentType, CreatedNode, SseEvent};
-use crate::domains::graph::models::{CreateNodeRequest, CreateRelationshipRequest};
+use crate::domains::agent::prompts::{PromptTemplates, SystemPrompts};
+use crate::domains::graph::level_template::TemplateLevel;
+use crate::domains::graph::models::{CategoryNode, CreateNodeRequest, CreateRelationshipRequest};
 use crate::domains::graph::services;
-use crate::common::sqs::{queue_image_generation, is_queue_configured, ImageGenJob};
 
-/// Domain Architect Step - creates Domain and Domain_Level nodes with fixed structure
+/// Domain Architect Step - creates Domain and Domain_Level nodes from the level template
 ///
 /// This step:
 /// 1. Creates a Domain node with the given name and description
 /// 2. Queues async image generation for domain avatar (processed by Lambda)
-/// 3. Creates 5 Domain_Level nodes (Novice, Developing, Competent, Advanced, Master)
+/// 3. Creates one Domain_Level node per tier of the context's level template
+/// 4. Files the domain under an existing or newly proposed category
+///
+/// A single LLM pass up front writes the domain and level descriptions and
+/// proposes the category.
+/// What the architect pass proposed; empty when it failed
+#[derive(Debug, Default)]
+struct DomainStructure {
+    description: Option<String>,
+    /// Level number -> description
+    level_descriptions: HashMap<u64, String>,
+    /// Category path from the broadest category down
+    category: Vec<String>,
+}
+
 pub struct DomainArchitectStep {
+    llm: Arc<dyn LlmProvider>,
     graph: Graph,
     event_tx: mpsc::Sender<SseEvent>,
 }
 
 impl DomainArchitectStep {
-    pub fn new(
-        graph: Graph,
-        event_tx: mpsc::Sender<SseEvent>,
-    ) -> Self {
-        Self { graph, event_tx }
+    pub fn new(llm: Arc<dyn LlmProvider>, graph: Graph, event_tx: mpsc::Sender<SseEvent>) -> Self {
+        Self {
+            llm,
+            graph,
+            event_tx,
+        }
+    }
+
+    /// One LLM pass describing the domain and its levels and proposing a category path
+    async fn design_structure(
+        &self,
+        domain_name: &str,
+        description: &str,
+        levels: &[TemplateLevel],
+    ) -> Result<DomainStructure, String> {
+        let categories = services::category_tree(&self.graph)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut paths = Vec::new();
+        for category in &categories {
+            collect_category_paths(category, &mut Vec::new(), &mut paths);
+        }
+
+        let level_names: Vec<String> = levels.iter().map(|l| l.name.clone()).collect();
+        let prompt = PromptTemplates::domain_structure(
+            domain_name,
+            description,
+            &level_names,
+            &paths.join("\n"),
+        );
+        let config = GenerationConfig {
+            max_tokens: Some(2048),
+            temperature: Some(0.2),
+            stop_sequences: None,
+        };
+
+        let response = self
+            .llm
+            .generate(SystemPrompts::domain_architect(), &prompt, &config)
+            .await
+            .map_err(|e| format!("LLM error: {:?}", e))?;
+
+        let trimmed = response.trim();
+        let start = trimmed.find('{').ok_or("No JSON object found")?;
+        let end = trimmed.rfind('}').ok_or("No closing brace found")?;
+        let parsed: Value = serde_json::from_str(&trimmed[start..=end])
+            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+        let text = |v: &Value| {
+            v.as_str()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        let level_descriptions = parsed
+            .get("levels")
+            .and_then(|v| v.as_array())
+            .map(|levels| {
+                levels
+                    .iter()
+                    .filter_map(|l| {
+                        let level = l.get("level")?.as_u64()?;
+                        Some((level, text(l.get("description")?)?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let category = parsed
+            .get("category")
+            .and_then(|v| v.as_array())
+            .map(|path| {
+                path.iter()
+                    .filter_map(text)
+                    .filter(|s| !s.eq_ignore_ascii_case(domain_name))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(DomainStructure {
+            description: parsed.get("description").and_then(text),
+            level_descriptions,
+            category,
+        })
+    }
+
+    /// File the domain under the proposed category; failures only skip categorisation
+    async fn categorize_domain(&self, domain_name: &str, path: &[String]) {
+        if path.is_empty() {
+            tracing::warn!("No category proposed for '{}'", domain_name);
+            return;
+        }
+
+        match services::file_domain_under_path(&self.graph, domain_name, path).await {
+            Ok(()) => {
+                emit_event(
+                    &self.event_tx,
+                    SseEvent::StepProgress {
+                        agent: AgentType::DomainArchitect,
+                        message: format!("Filed under {}", path.join(" > ")),
+                    },
+                )
+                .await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to file '{}' under {:?}: {}", domain_name, path, e);
+            }
+        }
     }
 
     /// Queue async avatar generation for the domain
@@ -58,52 +181,20 @@ impl DomainArchitectStep {
 
         match queue_image_generation(job).await {
             Ok(()) => {
-                emit_event(&self.event_tx, SseEvent::StepProgress {
-                    agent: AgentType::DomainArchitect,
-                    message: "Domain avatar queued for generation".to_string(),
-                }).await;
+                emit_event(
+                    &self.event_tx,
+                    SseEvent::StepProgress {
+                        agent: AgentType::DomainArchitect,
+                        message: "Domain avatar queued for generation".to_string(),
+                    },
+                )
+                .await;
             }
             Err(e) => {
                 tracing::warn!("Failed to queue avatar generation: {}", e);
             }
         }
     }
-
-    /// Get the fixed 5-level structure for any domain
-    fn get_level_structure(domain_name: &str) -> Vec<LevelInfo> {
-        vec![
-            LevelInfo {
-                level: 1,
-                name: format!("{} Novice", domain_name),
-                description: format!("Beginning your journey in {}", domain_name),
-                total_points_required: 100,
-            },
-            LevelInfo {
-                level: 2,
-                name: format!("{} Developing", domain_name),
-                description: format!("Building foundational skills in {}", domain_name),
-                total_points_required: 200,
-            },
-            LevelInfo {
-                level: 3,
-                name: format!("{} Competent", domain_name),
-                description: format!("Demonstrating solid competence in {}", domain_name),
-                total_points_required: 400,
-            },
-            LevelInfo {
-                level: 4,
-                name: format!("{} Advanced", domain_name),
-                description: format!("Achieving advanced mastery in {}", domain_name),
-                total_points_required: 700,
-            },
-            LevelInfo {
-                level: 5,
-                name: format!("{} Master", domain_name),
-                description: format!("Expert-level mastery of {}", domain_name),
-                total_points_required: 1000,
-            },
-        ]
-    }
 }
 
 #[async_trait::async_trait]
@@ -114,24 +205,62 @@ impl AgentStep for DomainArchitectStep {
 
     async fn execute(&self, context: &mut AgentContext) -> Result<(), String> {
         let domain_name = context.domain_name.clone();
-        let description = context.description.clone().unwrap_or_default();
+        let levels = context.level_template.levels_for(&domain_name);
+
+        // Step 1: Describe the domain and its levels; the template's wording is the fallback
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Designing domain structure...".to_string(),
+            },
+        )
+        .await;
 
-        // Step 1: Create Domain node
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Creating domain node...".to_string(),
-        }).await;
+        let given_description = context.description.clone().unwrap_or_default();
+        let structure = match self
+            .design_structure(&domain_name, &given_description, &levels)
+            .await
+        {
+            Ok(structure) => structure,
+            Err(e) => {
+                tracing::warn!("Could not design the structure of '{}': {}", domain_name, e);
+                DomainStructure::default()
+            }
+        };
+        let description = if given_description.is_empty() {
+            structure.description.clone().unwrap_or_default()
+        } else {
+            given_description
+        };
+        if context.description.is_none() && !description.is_empty() {
+            context.description = Some(description.clone());
+        }
+
+        // Step 2: Create Domain node
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Creating domain node...".to_string(),
+            },
+        )
+        .await;
 
         let mut domain_props: HashMap<String, Value> = HashMap::new();
         domain_props.insert("name".to_string(), json!(domain_name));
         domain_props.insert("description".to_string(), json!(description));
+        domain_props.insert(
+            "level_template".to_string(),
+            json!(context.level_template_key),
+        );
 
         let domain_request = CreateNodeRequest {
             labels: vec!["Domain".to_string()],
             properties: domain_props,
         };
 
-        let domain_result = services::create_node(&self.graph, domain_request, false)
+        let domain_result = services::create_node(&self.graph, domain_request, None)
             .await
             .map_err(|e| e.to_string())?;
 
@@ -141,39 +270,53 @@ impl AgentStep for DomainArchitectStep {
             "Domain".to_string(),
         );
 
-        emit_event(&self.event_tx, SseEvent::NodeCreated {
-            agent: self.agent_type(),
-            node_name: domain_node.name.clone(),
-            label: "Domain".to_string(),
-            was_reused: false,
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::NodeCreated {
+                agent: self.agent_type(),
+                node_name: domain_node.name.clone(),
+                label: "Domain".to_string(),
+                was_reused: false,
+            },
+        )
+        .await;
 
-        // Step 2: Queue async avatar generation (non-blocking)
-        self.queue_avatar_generation(&domain_name, &domain_result.element_id).await;
+        // Step 3: Queue async avatar generation (non-blocking)
+        self.queue_avatar_generation(&domain_name, &domain_result.element_id)
+            .await;
 
         context.set_domain(domain_node);
 
-        // Step 3: Create 5 Domain_Level nodes with fixed structure
-        let levels = Self::get_level_structure(&domain_name);
-
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: format!("Creating {} domain levels...", levels.len()),
-        }).await;
+        // Step 4: Create Domain_Level nodes from the level template
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: format!("Creating {} domain levels...", levels.len()),
+            },
+        )
+        .await;
 
         for level_info in &levels {
             let mut level_props: HashMap<String, Value> = HashMap::new();
             level_props.insert("name".to_string(), json!(level_info.name));
-            level_props.insert("description".to_string(), json!(level_info.description));
+            let level_description = structure
+                .level_descriptions
+                .get(&(level_info.level as u64))
+                .unwrap_or(&level_info.description);
+            level_props.insert("description".to_string(), json!(level_description));
             level_props.insert("level".to_string(), json!(level_info.level));
-            level_props.insert("total_points_required".to_string(), json!(level_info.total_points_required));
+            level_props.insert(
+                "total_points_required".to_string(),
+                json!(level_info.points_required),
+            );
 
             let level_request = CreateNodeRequest {
                 labels: vec!["Domain_Level".to_string()],
                 properties: level_props,
             };
 
-            let level_result = services::create_node(&self.graph, level_request, false)
+            let level_result = services::create_node(&self.graph, level_request, None)
                 .await
                 .map_err(|e| e.to_string())?;
 
@@ -195,24 +338,38 @@ impl AgentStep for DomainArchitectStep {
                 "Domain_Level".to_string(),
             );
 
-            emit_event(&self.event_tx, SseEvent::NodeCreated {
-                agent: self.agent_type(),
-                node_name: level_node.name.clone(),
-                label: "Domain_Level".to_string(),
-                was_reused: false,
-            }).await;
+            emit_event(
+                &self.event_tx,
+                SseEvent::NodeCreated {
+                    agent: self.agent_type(),
+                    node_name: level_node.name.clone(),
+                    label: "Domain_Level".to_string(),
+                    was_reused: false,
+                },
+            )
+            .await;
 
             context.add_domain_level(level_node);
         }
 
+        // Step 5: File the domain under the proposed category for browsing
+        self.categorize_domain(&domain_name, &structure.category)
+            .await;
+
         Ok(())
     }
 }
 
-/// Internal structure for level info
-struct LevelInfo {
-    level: i32,
-    name: String,
-    description: String,
-    total_points_required: i32,
+/// Flatten the category tree into "Parent > Child" paths
+fn collect_category_paths(
+    category: &CategoryNode,
+    prefix: &mut Vec<String>,
+    paths: &mut Vec<String>,
+) {
+    prefix.push(category.name.clone());
+    paths.push(prefix.join(" > "));
+    for child in &category.children {
+        collect_category_paths(child, prefix, paths);
+    }
+    prefix.pop();
 }
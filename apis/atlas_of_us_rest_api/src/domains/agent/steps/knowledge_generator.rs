@@ -1,17 +1,20 @@
+use futures::{StreamExt, stream};
 use neo4rs::Graph;
+use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use serde_json::{json, Value};
-use futures::{stream, StreamExt};
 
-use super::{emit_event, AgentStep, StepUtils, BATCH_SIZE};
+use super::{AgentStep, BATCH_SIZE, StepUtils, emit_event};
+use crate::common::similarity::{FindSimilarNodesRequest, SimilarNodeResult, find_similar_nodes};
 use crate::domains::agent::llm::{GenerationConfig, LlmProvider};
-use crate::domains::agent::models::{AgentContext, AgentType, ConceptAction, CreatedNode, GeneralizationLink, SseEvent, VerifiedConcept};
+use crate::domains::agent::models::{
+    AgentContext, AgentType, ConceptAction, CreatedNode, GeneralizationLink, SseEvent,
+    VerifiedConcept,
+};
 use crate::domains::agent::prompts::{PromptTemplates, SystemPrompts};
 use crate::domains::graph::models::{CreateNodeRequest, CreateRelationshipRequest};
 use crate::domains::graph::services;
-use crate::common::similarity::{find_similar_nodes, FindSimilarNodesRequest, SimilarNodeResult};
 
 /// Knowledge Generator Step - creates Knowledge nodes with multi-pass logic
 pub struct KnowledgeGeneratorStep {
@@ -21,12 +24,12 @@ pub struct KnowledgeGeneratorStep {
 }
 
 impl KnowledgeGeneratorStep {
-    pub fn new(
-        llm: Arc<dyn LlmProvider>,
-        graph: Graph,
-        event_tx: mpsc::Sender<SseEvent>,
-    ) -> Self {
-        Self { llm, graph, event_tx }
+    pub fn new(llm: Arc<dyn LlmProvider>, graph: Graph, event_tx: mpsc::Sender<SseEvent>) -> Self {
+        Self {
+            llm,
+            graph,
+            event_tx,
+        }
     }
 
     /// Pass 1: Get concept list from LLM
@@ -40,38 +43,45 @@ impl KnowledgeGeneratorStep {
             stop_sequences: None,
         };
 
-        let response = self.llm.generate(
-            SystemPrompts::knowledge_expert(),
-            &prompt,
-            &config,
-        ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+        let response = self
+            .llm
+            .generate(SystemPrompts::knowledge_expert(), &prompt, &config)
+            .await
+            .map_err(|e| format!("LLM error: {:?}", e))?;
         print!("LLM RESPONSE: {}", &response);
         StepUtils::parse_concept_list(&response)
     }
 
     /// Pass 2: Find similar nodes for each concept (batched)
-    async fn pass2_similarity_search(&self, concepts: &[String]) -> Result<Vec<(String, Vec<SimilarNodeResult>)>, String> {
+    async fn pass2_similarity_search(
+        &self,
+        concepts: &[String],
+    ) -> Result<Vec<(String, Vec<SimilarNodeResult>)>, String> {
         let graph = self.graph.clone();
 
-        let results: Vec<Result<(String, Vec<SimilarNodeResult>), String>> = stream::iter(concepts.to_vec())
-            .map(|concept| {
-                let graph = graph.clone();
-                async move {
-                    let similar = find_similar_nodes(&graph, FindSimilarNodesRequest {
-                        text: Some(concept.clone()),
-                        label: Some("Knowledge".to_string()),
-                        limit: Some(3),
-                        ..Default::default()
-                    })
-                    .await
-                    .map_err(|e| format!("Similarity search failed: {}", e))?;
+        let results: Vec<Result<(String, Vec<SimilarNodeResult>), String>> =
+            stream::iter(concepts.to_vec())
+                .map(|concept| {
+                    let graph = graph.clone();
+                    async move {
+                        let similar = find_similar_nodes(
+                            &graph,
+                            FindSimilarNodesRequest {
+                                text: Some(concept.clone()),
+                                label: Some("Knowledge".to_string()),
+                                limit: Some(3),
+                                ..Default::default()
+                            },
+                        )
+                        .await
+                        .map_err(|e| format!("Similarity search failed: {}", e))?;
 
-                    Ok((concept, similar))
-                }
-            })
-            .buffer_unordered(BATCH_SIZE)
-            .collect()
-            .await;
+                        Ok((concept, similar))
+                    }
+                })
+                .buffer_unordered(BATCH_SIZE)
+                .collect()
+                .await;
 
         results.into_iter().collect()
     }
@@ -87,27 +97,39 @@ impl KnowledgeGeneratorStep {
         for (concept, similar_nodes) in similarity_results {
             // Emit similarity check event
             let top_score = similar_nodes.first().map(|n| n.score);
-            emit_event(&self.event_tx, SseEvent::SimilarityCheck {
-                agent: AgentType::KnowledgeGenerator,
-                concept: concept.clone(),
-                similar_found: similar_nodes.len(),
-                top_score,
-            }).await;
+            emit_event(
+                &self.event_tx,
+                SseEvent::SimilarityCheck {
+                    agent: AgentType::KnowledgeGenerator,
+                    concept: concept.clone(),
+                    similar_found: similar_nodes.len(),
+                    top_score,
+                },
+            )
+            .await;
 
             // Check if we can decide without LLM
-            if let Some(decision) = StepUtils::determine_action_by_score(concept, similar_nodes) {
+            if let Some(decision) = StepUtils::determine_action_by_score(
+                concept,
+                similar_nodes,
+                &context.thresholds.for_label("Knowledge"),
+            ) {
                 let decision_str = match &decision.action {
                     ConceptAction::CreateNew => "create_new",
                     ConceptAction::UseExisting { .. } => "use_existing",
                     ConceptAction::CreateAndGeneralize { .. } => "create_and_generalize",
                 };
 
-                emit_event(&self.event_tx, SseEvent::VerificationResult {
-                    agent: AgentType::KnowledgeGenerator,
-                    concept: concept.clone(),
-                    decision: decision_str.to_string(),
-                    generalizes_to: None,
-                }).await;
+                emit_event(
+                    &self.event_tx,
+                    SseEvent::VerificationResult {
+                        agent: AgentType::KnowledgeGenerator,
+                        concept: concept.clone(),
+                        decision: decision_str.to_string(),
+                        generalizes_to: None,
+                    },
+                )
+                .await;
 
                 verified.push(decision);
             } else {
@@ -124,16 +146,20 @@ impl KnowledgeGeneratorStep {
                     stop_sequences: None,
                 };
 
-                let response = self.llm.generate(
-                    SystemPrompts::knowledge_expert(),
-                    &prompt,
-                    &config,
-                ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+                let response = self
+                    .llm
+                    .generate(SystemPrompts::knowledge_expert(), &prompt, &config)
+                    .await
+                    .map_err(|e| format!("LLM error: {:?}", e))?;
 
                 let mut decision = StepUtils::parse_verification_response(&response, concept)?;
 
                 // If create_and_generalize, resolve the target node ID
-                if let ConceptAction::CreateAndGeneralize { generalizes_to_name, .. } = &decision.action {
+                if let ConceptAction::CreateAndGeneralize {
+                    generalizes_to_name,
+                    ..
+                } = &decision.action
+                {
                     let target_name = generalizes_to_name.clone();
 
                     // 1. Check if target is in the similar_nodes from current search
@@ -146,17 +172,23 @@ impl KnowledgeGeneratorStep {
                         );
                     } else {
                         // 2. Search DB for the target node by name
-                        let search_results = find_similar_nodes(&self.graph, FindSimilarNodesRequest {
-                            text: Some(target_name.clone()),
-                            label: Some("Knowledge".to_string()),
-                            limit: Some(1),
-                            ..Default::default()
-                        })
+                        let search_results = find_similar_nodes(
+                            &self.graph,
+                            FindSimilarNodesRequest {
+                                text: Some(target_name.clone()),
+                                label: Some("Knowledge".to_string()),
+                                limit: Some(1),
+                                ..Default::default()
+                            },
+                        )
                         .await
                         .map_err(|e| format!("Similarity search failed: {}", e))?;
 
                         // Check for exact name match or very high similarity
-                        if let Some(found) = search_results.first().filter(|n| n.score >= 0.95 || n.name == target_name) {
+                        if let Some(found) = search_results.first().filter(|n| {
+                            n.score >= context.thresholds.for_label("Knowledge").auto_reuse
+                                || n.name == target_name
+                        }) {
                             decision = VerifiedConcept::create_and_generalize(
                                 concept,
                                 &found.id,
@@ -178,18 +210,29 @@ impl KnowledgeGeneratorStep {
                 let (decision_str, generalizes_to) = match &decision.action {
                     ConceptAction::CreateNew => ("create_new", None),
                     ConceptAction::UseExisting { .. } => ("use_existing", None),
-                    ConceptAction::CreateAndGeneralize { generalizes_to_name, needs_creation, .. } => {
+                    ConceptAction::CreateAndGeneralize {
+                        generalizes_to_name,
+                        needs_creation,
+                        ..
+                    } => {
                         let suffix = if *needs_creation { " (new)" } else { "" };
-                        ("create_and_generalize", Some(format!("{}{}", generalizes_to_name, suffix)))
+                        (
+                            "create_and_generalize",
+                            Some(format!("{}{}", generalizes_to_name, suffix)),
+                        )
                     }
                 };
 
-                emit_event(&self.event_tx, SseEvent::VerificationResult {
-                    agent: AgentType::KnowledgeGenerator,
-                    concept: concept.clone(),
-                    decision: decision_str.to_string(),
-                    generalizes_to,
-                }).await;
+                emit_event(
+                    &self.event_tx,
+                    SseEvent::VerificationResult {
+                        agent: AgentType::KnowledgeGenerator,
+                        concept: concept.clone(),
+                        decision: decision_str.to_string(),
+                        generalizes_to,
+                    },
+                )
+                .await;
 
                 verified.push(decision);
             }
@@ -201,7 +244,7 @@ impl KnowledgeGeneratorStep {
     /// Pass 4: Create missing generalization target nodes (batched LLM calls)
     async fn pass4_create_missing_targets(
         &self,
-        verified: &mut Vec<VerifiedConcept>,
+        verified: &mut [VerifiedConcept],
     ) -> Result<Vec<CreatedNode>, String> {
         // Collect targets that need creation with their indices
         let targets_to_create: Vec<(usize, String)> = verified
@@ -240,11 +283,10 @@ impl KnowledgeGeneratorStep {
                             stop_sequences: None,
                         };
 
-                        let response = llm.generate(
-                            SystemPrompts::knowledge_expert(),
-                            &prompt,
-                            &config,
-                        ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+                        let response = llm
+                            .generate(SystemPrompts::knowledge_expert(), &prompt, &config)
+                            .await
+                            .map_err(|e| format!("LLM error: {:?}", e))?;
 
                         let props = Self::parse_knowledge_properties(&response, &target_name)?;
                         Ok((idx, target_name, props))
@@ -267,7 +309,10 @@ impl KnowledgeGeneratorStep {
             node_props.insert("description".to_string(), json!(props.description));
             node_props.insert("how_to_learn".to_string(), json!(props.how_to_learn));
             node_props.insert("remember_level".to_string(), json!(props.remember_level));
-            node_props.insert("understand_level".to_string(), json!(props.understand_level));
+            node_props.insert(
+                "understand_level".to_string(),
+                json!(props.understand_level),
+            );
             node_props.insert("apply_level".to_string(), json!(props.apply_level));
             node_props.insert("analyze_level".to_string(), json!(props.analyze_level));
             node_props.insert("evaluate_level".to_string(), json!(props.evaluate_level));
@@ -278,7 +323,7 @@ impl KnowledgeGeneratorStep {
                 properties: node_props,
             };
 
-            let result = services::create_node(&self.graph, request, false)
+            let result = services::create_node(&self.graph, request, None)
                 .await
                 .map_err(|e| e.to_string())?;
 
@@ -310,7 +355,11 @@ impl KnowledgeGeneratorStep {
             .iter()
             .filter(|c| !matches!(c.action, ConceptAction::UseExisting { .. }))
             .map(|concept| {
-                let generalizes_to = if let ConceptAction::CreateAndGeneralize { generalizes_to_name, .. } = &concept.action {
+                let generalizes_to = if let ConceptAction::CreateAndGeneralize {
+                    generalizes_to_name,
+                    ..
+                } = &concept.action
+                {
                     Some(generalizes_to_name.clone())
                 } else {
                     None
@@ -343,11 +392,10 @@ impl KnowledgeGeneratorStep {
                         stop_sequences: None,
                     };
 
-                    let response = llm.generate(
-                        SystemPrompts::knowledge_expert(),
-                        &prompt,
-                        &config,
-                    ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+                    let response = llm
+                        .generate(SystemPrompts::knowledge_expert(), &prompt, &config)
+                        .await
+                        .map_err(|e| format!("LLM error: {:?}", e))?;
                     println!("Node: {} - Properties: {}", &concept_name, &response);
                     Self::parse_knowledge_properties(&response, &concept_name)
                 }
@@ -360,7 +408,10 @@ impl KnowledgeGeneratorStep {
     }
 
     /// Parse knowledge properties from LLM response
-    fn parse_knowledge_properties(response: &str, concept_name: &str) -> Result<KnowledgeProperties, String> {
+    fn parse_knowledge_properties(
+        response: &str,
+        concept_name: &str,
+    ) -> Result<KnowledgeProperties, String> {
         let trimmed = response.trim();
 
         let start = trimmed.find('{').ok_or("No JSON object found")?;
@@ -371,43 +422,52 @@ impl KnowledgeGeneratorStep {
         }
 
         let json_str = &trimmed[start..=end];
-        let parsed: Value = serde_json::from_str(json_str)
-            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        let parsed: Value =
+            serde_json::from_str(json_str).map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
         Ok(KnowledgeProperties {
-            name: parsed.get("name")
+            name: parsed
+                .get("name")
                 .and_then(|v| v.as_str())
                 .unwrap_or(concept_name)
                 .to_string(),
-            description: parsed.get("description")
+            description: parsed
+                .get("description")
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string(),
-            how_to_learn: parsed.get("how_to_learn")
+            how_to_learn: parsed
+                .get("how_to_learn")
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string(),
-            remember_level: parsed.get("remember_level")
+            remember_level: parsed
+                .get("remember_level")
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string(),
-            understand_level: parsed.get("understand_level")
+            understand_level: parsed
+                .get("understand_level")
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string(),
-            apply_level: parsed.get("apply_level")
+            apply_level: parsed
+                .get("apply_level")
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string(),
-            analyze_level: parsed.get("analyze_level")
+            analyze_level: parsed
+                .get("analyze_level")
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string(),
-            evaluate_level: parsed.get("evaluate_level")
+            evaluate_level: parsed
+                .get("evaluate_level")
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string(),
-            create_level: parsed.get("create_level")
+            create_level: parsed
+                .get("create_level")
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string(),
@@ -435,8 +495,7 @@ impl KnowledgeGeneratorStep {
                 }
                 ConceptAction::CreateNew | ConceptAction::CreateAndGeneralize { .. } => {
                     // Get the properties for this concept
-                    let props = props_iter.next()
-                        .ok_or("Missing properties for concept")?;
+                    let props = props_iter.next().ok_or("Missing properties for concept")?;
 
                     // Build node properties
                     let mut node_props: HashMap<String, Value> = HashMap::new();
@@ -444,7 +503,10 @@ impl KnowledgeGeneratorStep {
                     node_props.insert("description".to_string(), json!(props.description));
                     node_props.insert("how_to_learn".to_string(), json!(props.how_to_learn));
                     node_props.insert("remember_level".to_string(), json!(props.remember_level));
-                    node_props.insert("understand_level".to_string(), json!(props.understand_level));
+                    node_props.insert(
+                        "understand_level".to_string(),
+                        json!(props.understand_level),
+                    );
                     node_props.insert("apply_level".to_string(), json!(props.apply_level));
                     node_props.insert("analyze_level".to_string(), json!(props.analyze_level));
                     node_props.insert("evaluate_level".to_string(), json!(props.evaluate_level));
@@ -455,7 +517,7 @@ impl KnowledgeGeneratorStep {
                         properties: node_props,
                     };
 
-                    let result = services::create_node(&self.graph, request, false)
+                    let result = services::create_node(&self.graph, request, None)
                         .await
                         .map_err(|e| e.to_string())?;
 
@@ -479,9 +541,15 @@ impl KnowledgeGeneratorStep {
         context: &mut AgentContext,
     ) -> Result<(), String> {
         for concept in verified {
-            if let ConceptAction::CreateAndGeneralize { generalizes_to_id, generalizes_to_name, .. } = &concept.action {
+            if let ConceptAction::CreateAndGeneralize {
+                generalizes_to_id,
+                generalizes_to_name,
+                ..
+            } = &concept.action
+            {
                 // Find the created node's element ID
-                let source = created.iter()
+                let source = created
+                    .iter()
                     .find(|n| n.name == concept.name)
                     .ok_or_else(|| format!("Node not found: {}", concept.name))?;
 
@@ -492,7 +560,10 @@ impl KnowledgeGeneratorStep {
                     properties: None,
                 };
 
-                if services::create_relationship(&self.graph, rel_request).await.is_ok() {
+                if services::create_relationship(&self.graph, rel_request)
+                    .await
+                    .is_ok()
+                {
                     // Track generalization in context
                     context.add_generalization(GeneralizationLink {
                         specific_id: source.element_id.clone(),
@@ -517,87 +588,138 @@ impl AgentStep for KnowledgeGeneratorStep {
 
     async fn execute(&self, context: &mut AgentContext) -> Result<(), String> {
         // Pass 1: Get concept list
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Identifying knowledge concepts...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Identifying knowledge concepts...".to_string(),
+            },
+        )
+        .await;
 
         let concepts = self.pass1_conceptualize(context).await?;
 
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: format!("Found {} knowledge concepts", concepts.len()),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: format!("Found {} knowledge concepts", concepts.len()),
+            },
+        )
+        .await;
 
         // Pass 2: Similarity search
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Searching for similar existing nodes...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Searching for similar existing nodes...".to_string(),
+            },
+        )
+        .await;
 
         let similarity_results = self.pass2_similarity_search(&concepts).await?;
 
         // Pass 3: Verify matches and resolve target IDs
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Verifying node matches...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Verifying node matches...".to_string(),
+            },
+        )
+        .await;
 
-        let mut verified = self.pass3_verify_matches(context, &similarity_results).await?;
+        let mut verified = self
+            .pass3_verify_matches(context, &similarity_results)
+            .await?;
 
         // Pass 4: Create missing generalization targets
-        let targets_to_create = verified.iter()
-            .filter(|c| matches!(&c.action, ConceptAction::CreateAndGeneralize { needs_creation: true, .. }))
+        let targets_to_create = verified
+            .iter()
+            .filter(|c| {
+                matches!(
+                    &c.action,
+                    ConceptAction::CreateAndGeneralize {
+                        needs_creation: true,
+                        ..
+                    }
+                )
+            })
             .count();
 
         if targets_to_create > 0 {
-            emit_event(&self.event_tx, SseEvent::StepProgress {
-                agent: self.agent_type(),
-                message: format!("Creating {} missing generalization targets...", targets_to_create),
-            }).await;
+            emit_event(
+                &self.event_tx,
+                SseEvent::StepProgress {
+                    agent: self.agent_type(),
+                    message: format!(
+                        "Creating {} missing generalization targets...",
+                        targets_to_create
+                    ),
+                },
+            )
+            .await;
 
             let created_targets = self.pass4_create_missing_targets(&mut verified).await?;
 
             // Emit events for created target nodes
             for node in &created_targets {
-                emit_event(&self.event_tx, SseEvent::NodeCreated {
-                    agent: self.agent_type(),
-                    node_name: node.name.clone(),
-                    label: "Knowledge".to_string(),
-                    was_reused: false,
-                }).await;
+                emit_event(
+                    &self.event_tx,
+                    SseEvent::NodeCreated {
+                        agent: self.agent_type(),
+                        node_name: node.name.clone(),
+                        label: "Knowledge".to_string(),
+                        was_reused: false,
+                    },
+                )
+                .await;
 
                 context.add_knowledge(node.clone());
             }
         }
 
         // Pass 5: Generate properties for source nodes
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Generating knowledge properties...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Generating knowledge properties...".to_string(),
+            },
+        )
+        .await;
 
         let properties = self.pass5_generate_properties(context, &verified).await?;
 
         // Pass 6: Create source nodes
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Creating nodes in database...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Creating nodes in database...".to_string(),
+            },
+        )
+        .await;
 
         let created = self.pass6_create_nodes(&verified, &properties).await?;
 
         // Pass 7: Create GENERALIZES_TO relationships
-        self.pass7_create_generalization_relationships(&verified, &created, context).await?;
+        self.pass7_create_generalization_relationships(&verified, &created, context)
+            .await?;
 
         // Emit node created events and update context
         for node in created {
-            emit_event(&self.event_tx, SseEvent::NodeCreated {
-                agent: self.agent_type(),
-                node_name: node.name.clone(),
-                label: "Knowledge".to_string(),
-                was_reused: node.was_reused,
-            }).await;
+            emit_event(
+                &self.event_tx,
+                SseEvent::NodeCreated {
+                    agent: self.agent_type(),
+                    node_name: node.name.clone(),
+                    label: "Knowledge".to_string(),
+                    was_reused: node.was_reused,
+                },
+            )
+            .await;
 
             context.add_knowledge(node);
         }
@@ -1,10 +1,10 @@
 use neo4rs::Graph;
+use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use serde_json::Value;
 
-use super::{emit_event, AgentStep};
+use super::{AgentStep, emit_event};
 use crate::domains::agent::llm::{GenerationConfig, LlmProvider};
 use crate::domains::agent::models::{AgentContext, AgentType, LevelRequirement, SseEvent};
 use crate::domains::agent::prompts::{PromptTemplates, SystemPrompts};
@@ -20,12 +20,12 @@ pub struct LevelDistributorStep {
 }
 
 impl LevelDistributorStep {
-    pub fn new(
-        llm: Arc<dyn LlmProvider>,
-        graph: Graph,
-        event_tx: mpsc::Sender<SseEvent>,
-    ) -> Self {
-        Self { llm, graph, event_tx }
+    pub fn new(llm: Arc<dyn LlmProvider>, graph: Graph, event_tx: mpsc::Sender<SseEvent>) -> Self {
+        Self {
+            llm,
+            graph,
+            event_tx,
+        }
     }
 
     /// Build context string for a specific component type
@@ -33,8 +33,13 @@ impl LevelDistributorStep {
         let mut ctx = format!("Domain: {}\n\n", context.domain_name);
 
         ctx.push_str("Domain Levels:\n");
-        for level in &context.domain_graph.domain_levels {
-            ctx.push_str(&format!("- {} (ID: {})\n", level.name, level.element_id));
+        for (i, level) in context.domain_graph.domain_levels.iter().enumerate() {
+            ctx.push_str(&format!(
+                "- Level {}: {} (ID: {})\n",
+                i + 1,
+                level.name,
+                level.element_id
+            ));
         }
 
         ctx.push_str(&format!("\n{} Nodes to assign:\n", component_type));
@@ -60,7 +65,13 @@ impl LevelDistributorStep {
         component_type: &str,
     ) -> Result<Vec<LevelAssignment>, String> {
         let ctx = self.build_context_for_type(context, component_type);
-        let prompt = PromptTemplates::level_assignment(&context.domain_name, &ctx);
+        let tiers: Vec<&str> = context
+            .level_template
+            .tiers
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        let prompt = PromptTemplates::level_assignment(&context.domain_name, &ctx, &tiers);
 
         let config = GenerationConfig {
             max_tokens: Some(16384),
@@ -68,57 +79,75 @@ impl LevelDistributorStep {
             stop_sequences: None,
         };
 
-        let response = self.llm.generate(
-            SystemPrompts::relationship_architect(),
-            &prompt,
-            &config,
-        ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+        let response = self
+            .llm
+            .generate(SystemPrompts::relationship_architect(), &prompt, &config)
+            .await
+            .map_err(|e| format!("LLM error: {:?}", e))?;
 
         self.parse_level_assignments(&response, context, component_type)
     }
 
     /// Analyze and assign all components to levels (by type to avoid token limits)
-    async fn analyze_level_assignments(&self, context: &AgentContext) -> Result<Vec<LevelAssignment>, String> {
+    async fn analyze_level_assignments(
+        &self,
+        context: &AgentContext,
+    ) -> Result<Vec<LevelAssignment>, String> {
         let mut all_assignments = Vec::new();
 
         for component_type in &["Knowledge", "Skill", "Trait", "Milestone"] {
-            emit_event(&self.event_tx, SseEvent::StepProgress {
-                agent: AgentType::LevelDistributor,
-                message: format!("Assigning {} nodes to levels...", component_type),
-            }).await;
-
-            let assignments = self.analyze_level_assignments_for_type(context, component_type).await?;
+            emit_event(
+                &self.event_tx,
+                SseEvent::StepProgress {
+                    agent: AgentType::LevelDistributor,
+                    message: format!("Assigning {} nodes to levels...", component_type),
+                },
+            )
+            .await;
+
+            let assignments = self
+                .analyze_level_assignments_for_type(context, component_type)
+                .await?;
             all_assignments.extend(assignments);
         }
 
         Ok(all_assignments)
     }
 
-    fn parse_level_assignments(&self, response: &str, context: &AgentContext, component_type: &str) -> Result<Vec<LevelAssignment>, String> {
+    fn parse_level_assignments(
+        &self,
+        response: &str,
+        context: &AgentContext,
+        component_type: &str,
+    ) -> Result<Vec<LevelAssignment>, String> {
         let trimmed = response.trim();
         let start = trimmed.find('{').ok_or("No JSON object found")?;
         let end = trimmed.rfind('}').ok_or("No closing brace found")?;
 
         let json_str = &trimmed[start..=end];
-        let parsed: Value = serde_json::from_str(json_str)
-            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        let parsed: Value =
+            serde_json::from_str(json_str).map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
-        let assignments = parsed.get("level_assignments")
+        let assignments = parsed
+            .get("level_assignments")
             .and_then(|v| v.as_array())
             .ok_or("Missing level_assignments array")?;
 
         let mut level_assignments = Vec::new();
 
         for assignment in assignments {
-            let component_name = assignment.get("component")
+            let component_name = assignment
+                .get("component")
                 .and_then(|v| v.as_str())
                 .ok_or("Missing component name")?;
 
-            let level = assignment.get("level")
+            let level = assignment
+                .get("level")
                 .and_then(|v| v.as_i64())
                 .ok_or("Missing level")?;
 
-            let proficiency = assignment.get("proficiency")
+            let proficiency = assignment
+                .get("proficiency")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
 
@@ -128,18 +157,10 @@ impl LevelDistributorStep {
                 continue;
             }
 
-            // Find level element ID
-            let level_id = context.domain_graph.domain_levels
-                .iter()
-                .find(|l| l.name.contains(&format!(" {}", match level {
-                    1 => "Novice",
-                    2 => "Developing",
-                    3 => "Competent",
-                    4 => "Advanced",
-                    5 => "Master",
-                    _ => "",
-                })))
-                .or_else(|| context.domain_graph.domain_levels.get((level - 1) as usize))
+            // Find level element ID (levels are stored in template order)
+            let level_id = usize::try_from(level - 1)
+                .ok()
+                .and_then(|i| context.domain_graph.domain_levels.get(i))
                 .map(|l| l.element_id.clone());
 
             if level_id.is_none() {
@@ -158,18 +179,35 @@ impl LevelDistributorStep {
         Ok(level_assignments)
     }
 
-    fn find_element_id(&self, context: &AgentContext, name: &str, node_type: &str) -> Option<String> {
+    fn find_element_id(
+        &self,
+        context: &AgentContext,
+        name: &str,
+        node_type: &str,
+    ) -> Option<String> {
         match node_type {
-            "Knowledge" => context.domain_graph.knowledge.iter()
+            "Knowledge" => context
+                .domain_graph
+                .knowledge
+                .iter()
                 .find(|n| n.name == name)
                 .map(|n| n.element_id.clone()),
-            "Skill" => context.domain_graph.skills.iter()
+            "Skill" => context
+                .domain_graph
+                .skills
+                .iter()
                 .find(|n| n.name == name)
                 .map(|n| n.element_id.clone()),
-            "Trait" => context.domain_graph.traits.iter()
+            "Trait" => context
+                .domain_graph
+                .traits
+                .iter()
                 .find(|n| n.name == name)
                 .map(|n| n.element_id.clone()),
-            "Milestone" => context.domain_graph.milestones.iter()
+            "Milestone" => context
+                .domain_graph
+                .milestones
+                .iter()
                 .find(|n| n.name == name)
                 .map(|n| n.element_id.clone()),
             _ => None,
@@ -218,7 +256,10 @@ impl LevelDistributorStep {
                 properties: if props.is_empty() { None } else { Some(props) },
             };
 
-            if services::create_relationship(&self.graph, request).await.is_ok() {
+            if services::create_relationship(&self.graph, request)
+                .await
+                .is_ok()
+            {
                 count += 1;
 
                 // Track relationship in context
@@ -246,22 +287,36 @@ impl AgentStep for LevelDistributorStep {
         // analyze_level_assignments now emits progress events per component type
         let level_assignments = self.analyze_level_assignments(context).await?;
 
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: format!("Identified {} level assignments", level_assignments.len()),
-        }).await;
-
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Creating level relationships in database...".to_string(),
-        }).await;
-
-        let count = self.create_level_relationships(&level_assignments, context).await?;
-
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: format!("Created {} level relationships", count),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: format!("Identified {} level assignments", level_assignments.len()),
+            },
+        )
+        .await;
+
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Creating level relationships in database...".to_string(),
+            },
+        )
+        .await;
+
+        let count = self
+            .create_level_relationships(&level_assignments, context)
+            .await?;
+
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: format!("Created {} level relationships", count),
+            },
+        )
+        .await;
 
         Ok(())
     }
@@ -1,11 +1,11 @@
+use futures::{StreamExt, stream};
 use neo4rs::Graph;
+use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use serde_json::{json, Value};
-use futures::{stream, StreamExt};
 
-use super::{emit_event, AgentStep, StepUtils, BATCH_SIZE};
+use super::{AgentStep, BATCH_SIZE, StepUtils, emit_event};
 use crate::domains::agent::llm::{GenerationConfig, LlmProvider};
 use crate::domains::agent::models::{AgentContext, AgentType, CreatedNode, SseEvent};
 use crate::domains::agent::prompts::{PromptTemplates, SystemPrompts};
@@ -20,12 +20,12 @@ pub struct MilestoneGeneratorStep {
 }
 
 impl MilestoneGeneratorStep {
-    pub fn new(
-        llm: Arc<dyn LlmProvider>,
-        graph: Graph,
-        event_tx: mpsc::Sender<SseEvent>,
-    ) -> Self {
-        Self { llm, graph, event_tx }
+    pub fn new(llm: Arc<dyn LlmProvider>, graph: Graph, event_tx: mpsc::Sender<SseEvent>) -> Self {
+        Self {
+            llm,
+            graph,
+            event_tx,
+        }
     }
 
     async fn conceptualize(&self, context: &AgentContext) -> Result<Vec<String>, String> {
@@ -34,12 +34,31 @@ impl MilestoneGeneratorStep {
         // Build context from existing components
         let context_str = format!(
             "Existing components:\n- Knowledge: {}\n- Skills: {}\n- Traits: {}",
-            context.domain_graph.knowledge.iter().map(|n| n.name.as_str()).collect::<Vec<_>>().join(", "),
-            context.domain_graph.skills.iter().map(|n| n.name.as_str()).collect::<Vec<_>>().join(", "),
-            context.domain_graph.traits.iter().map(|n| n.name.as_str()).collect::<Vec<_>>().join(", "),
+            context
+                .domain_graph
+                .knowledge
+                .iter()
+                .map(|n| n.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            context
+                .domain_graph
+                .skills
+                .iter()
+                .map(|n| n.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            context
+                .domain_graph
+                .traits
+                .iter()
+                .map(|n| n.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
         );
 
-        let prompt = PromptTemplates::milestone_concepts(&context.domain_name, &description, &context_str);
+        let prompt =
+            PromptTemplates::milestone_concepts(&context.domain_name, &description, &context_str);
 
         let config = GenerationConfig {
             max_tokens: Some(16384),
@@ -47,11 +66,11 @@ impl MilestoneGeneratorStep {
             stop_sequences: None,
         };
 
-        let response = self.llm.generate(
-            SystemPrompts::milestone_designer(),
-            &prompt,
-            &config,
-        ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+        let response = self
+            .llm
+            .generate(SystemPrompts::milestone_designer(), &prompt, &config)
+            .await
+            .map_err(|e| format!("LLM error: {:?}", e))?;
 
         StepUtils::parse_concept_list(&response)
     }
@@ -73,11 +92,8 @@ impl MilestoneGeneratorStep {
                 let llm = llm.clone();
                 let domain_name = domain_name.clone();
                 async move {
-                    let prompt = PromptTemplates::milestone_properties(
-                        &domain_name,
-                        &concept,
-                        None,
-                    );
+                    let prompt =
+                        PromptTemplates::milestone_properties(&domain_name, &concept, None);
 
                     let config = GenerationConfig {
                         max_tokens: Some(1024),
@@ -85,11 +101,10 @@ impl MilestoneGeneratorStep {
                         stop_sequences: None,
                     };
 
-                    let response = llm.generate(
-                        SystemPrompts::milestone_designer(),
-                        &prompt,
-                        &config,
-                    ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+                    let response = llm
+                        .generate(SystemPrompts::milestone_designer(), &prompt, &config)
+                        .await
+                        .map_err(|e| format!("LLM error: {:?}", e))?;
 
                     Self::parse_milestone_properties(&response, &concept)
                 }
@@ -101,19 +116,34 @@ impl MilestoneGeneratorStep {
         results.into_iter().collect()
     }
 
-    fn parse_milestone_properties(response: &str, concept_name: &str) -> Result<MilestoneProperties, String> {
+    fn parse_milestone_properties(
+        response: &str,
+        concept_name: &str,
+    ) -> Result<MilestoneProperties, String> {
         let trimmed = response.trim();
         let start = trimmed.find('{').ok_or("No JSON object found")?;
         let end = trimmed.rfind('}').ok_or("No closing brace found")?;
 
         let json_str = &trimmed[start..=end];
-        let parsed: Value = serde_json::from_str(json_str)
-            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        let parsed: Value =
+            serde_json::from_str(json_str).map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
         Ok(MilestoneProperties {
-            name: parsed.get("name").and_then(|v| v.as_str()).unwrap_or(concept_name).to_string(),
-            description: parsed.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            how_to_achieve: parsed.get("how_to_achieve").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            name: parsed
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or(concept_name)
+                .to_string(),
+            description: parsed
+                .get("description")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            how_to_achieve: parsed
+                .get("how_to_achieve")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
         })
     }
 
@@ -134,7 +164,7 @@ impl MilestoneGeneratorStep {
                 properties: node_props,
             };
 
-            let result = services::create_node(&self.graph, request, false)
+            let result = services::create_node(&self.graph, request, None)
                 .await
                 .map_err(|e| e.to_string())?;
 
@@ -156,28 +186,40 @@ impl AgentStep for MilestoneGeneratorStep {
     }
 
     async fn execute(&self, context: &mut AgentContext) -> Result<(), String> {
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Identifying milestone achievements...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Identifying milestone achievements...".to_string(),
+            },
+        )
+        .await;
 
         let concepts = self.conceptualize(context).await?;
 
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: format!("Found {} milestone concepts", concepts.len()),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: format!("Found {} milestone concepts", concepts.len()),
+            },
+        )
+        .await;
 
         let properties = self.generate_properties(context, &concepts).await?;
         let created = self.create_nodes(&properties).await?;
 
         for node in created {
-            emit_event(&self.event_tx, SseEvent::NodeCreated {
-                agent: self.agent_type(),
-                node_name: node.name.clone(),
-                label: "Milestone".to_string(),
-                was_reused: node.was_reused,
-            }).await;
+            emit_event(
+                &self.event_tx,
+                SseEvent::NodeCreated {
+                    agent: self.agent_type(),
+                    node_name: node.name.clone(),
+                    label: "Milestone".to_string(),
+                    was_reused: node.was_reused,
+                },
+            )
+            .await;
 
             context.add_milestone(node);
         }
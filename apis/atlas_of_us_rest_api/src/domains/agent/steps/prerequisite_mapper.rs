@@ -1,9 +1,9 @@
 use neo4rs::Graph;
+use serde_json::Value;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use serde_json::Value;
 
-use super::{emit_event, AgentStep};
+use super::{AgentStep, emit_event};
 use crate::domains::agent::llm::{GenerationConfig, LlmProvider};
 use crate::domains::agent::models::{AgentContext, AgentType, PrerequisiteLink, SseEvent};
 use crate::domains::agent::prompts::{PromptTemplates, SystemPrompts};
@@ -19,12 +19,12 @@ pub struct PrerequisiteMapperStep {
 }
 
 impl PrerequisiteMapperStep {
-    pub fn new(
-        llm: Arc<dyn LlmProvider>,
-        graph: Graph,
-        event_tx: mpsc::Sender<SseEvent>,
-    ) -> Self {
-        Self { llm, graph, event_tx }
+    pub fn new(llm: Arc<dyn LlmProvider>, graph: Graph, event_tx: mpsc::Sender<SseEvent>) -> Self {
+        Self {
+            llm,
+            graph,
+            event_tx,
+        }
     }
 
     /// Build context string for a specific component type
@@ -33,7 +33,10 @@ impl PrerequisiteMapperStep {
         let mut ctx = format!("Domain: {}\n\n", context.domain_name);
 
         // Source components (the ones we're finding prerequisites for)
-        ctx.push_str(&format!("{} Nodes (find prerequisites for these):\n", source_type));
+        ctx.push_str(&format!(
+            "{} Nodes (find prerequisites for these):\n",
+            source_type
+        ));
         let source_nodes: Vec<_> = match source_type {
             "Knowledge" => context.domain_graph.knowledge.iter().collect(),
             "Skill" => context.domain_graph.skills.iter().collect(),
@@ -84,51 +87,67 @@ impl PrerequisiteMapperStep {
             stop_sequences: None,
         };
 
-        let response = self.llm.generate(
-            SystemPrompts::relationship_architect(),
-            &prompt,
-            &config,
-        ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+        let response = self
+            .llm
+            .generate(SystemPrompts::relationship_architect(), &prompt, &config)
+            .await
+            .map_err(|e| format!("LLM error: {:?}", e))?;
 
         self.parse_prerequisites(&response, context, source_type)
     }
 
     /// Analyze all prerequisites (by source type to avoid token limits)
-    async fn analyze_prerequisites(&self, context: &AgentContext) -> Result<Vec<PrerequisiteRelationship>, String> {
+    async fn analyze_prerequisites(
+        &self,
+        context: &AgentContext,
+    ) -> Result<Vec<PrerequisiteRelationship>, String> {
         let mut all_prerequisites = Vec::new();
 
         for source_type in &["Skill", "Milestone"] {
             // Skills typically require Knowledge, Milestones require Skills/Knowledge
             // Knowledge and Traits typically don't have prerequisites
-            emit_event(&self.event_tx, SseEvent::StepProgress {
-                agent: AgentType::PrerequisiteMapper,
-                message: format!("Analyzing {} prerequisites...", source_type),
-            }).await;
-
-            let prerequisites = self.analyze_prerequisites_for_type(context, source_type).await?;
+            emit_event(
+                &self.event_tx,
+                SseEvent::StepProgress {
+                    agent: AgentType::PrerequisiteMapper,
+                    message: format!("Analyzing {} prerequisites...", source_type),
+                },
+            )
+            .await;
+
+            let prerequisites = self
+                .analyze_prerequisites_for_type(context, source_type)
+                .await?;
             all_prerequisites.extend(prerequisites);
         }
 
         Ok(all_prerequisites)
     }
 
-    fn parse_prerequisites(&self, response: &str, context: &AgentContext, source_type: &str) -> Result<Vec<PrerequisiteRelationship>, String> {
+    fn parse_prerequisites(
+        &self,
+        response: &str,
+        context: &AgentContext,
+        source_type: &str,
+    ) -> Result<Vec<PrerequisiteRelationship>, String> {
         let trimmed = response.trim();
         let start = trimmed.find('{').ok_or("No JSON object found")?;
         let end = trimmed.rfind('}').ok_or("No closing brace found")?;
 
         let json_str = &trimmed[start..=end];
-        let parsed: Value = serde_json::from_str(json_str)
-            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        let parsed: Value =
+            serde_json::from_str(json_str).map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
-        let prerequisites = parsed.get("prerequisites")
+        let prerequisites = parsed
+            .get("prerequisites")
             .and_then(|v| v.as_array())
             .ok_or("Missing prerequisites array")?;
 
         let mut relationships = Vec::new();
 
         for prereq in prerequisites {
-            let component_name = prereq.get("component")
+            let component_name = prereq
+                .get("component")
                 .and_then(|v| v.as_str())
                 .ok_or("Missing component name")?;
 
@@ -139,18 +158,15 @@ impl PrerequisiteMapperStep {
             }
 
             let empty_vec = Vec::new();
-            let requires = prereq.get("requires")
+            let requires = prereq
+                .get("requires")
                 .and_then(|v| v.as_array())
                 .unwrap_or(&empty_vec);
 
             for req in requires {
-                let req_name = req.get("name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("");
+                let req_name = req.get("name").and_then(|v| v.as_str()).unwrap_or("");
 
-                let req_type = req.get("type")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("");
+                let req_type = req.get("type").and_then(|v| v.as_str()).unwrap_or("");
 
                 let target_id = self.find_element_id(context, req_name, req_type);
                 if target_id.is_none() {
@@ -178,18 +194,35 @@ impl PrerequisiteMapperStep {
         Ok(relationships)
     }
 
-    fn find_element_id(&self, context: &AgentContext, name: &str, node_type: &str) -> Option<String> {
+    fn find_element_id(
+        &self,
+        context: &AgentContext,
+        name: &str,
+        node_type: &str,
+    ) -> Option<String> {
         match node_type {
-            "Knowledge" => context.domain_graph.knowledge.iter()
+            "Knowledge" => context
+                .domain_graph
+                .knowledge
+                .iter()
                 .find(|n| n.name == name)
                 .map(|n| n.element_id.clone()),
-            "Skill" => context.domain_graph.skills.iter()
+            "Skill" => context
+                .domain_graph
+                .skills
+                .iter()
                 .find(|n| n.name == name)
                 .map(|n| n.element_id.clone()),
-            "Trait" => context.domain_graph.traits.iter()
+            "Trait" => context
+                .domain_graph
+                .traits
+                .iter()
                 .find(|n| n.name == name)
                 .map(|n| n.element_id.clone()),
-            "Milestone" => context.domain_graph.milestones.iter()
+            "Milestone" => context
+                .domain_graph
+                .milestones
+                .iter()
                 .find(|n| n.name == name)
                 .map(|n| n.element_id.clone()),
             _ => None,
@@ -212,7 +245,10 @@ impl PrerequisiteMapperStep {
                 properties: None,
             };
 
-            if services::create_relationship(&self.graph, request).await.is_ok() {
+            if services::create_relationship(&self.graph, request)
+                .await
+                .is_ok()
+            {
                 count += 1;
 
                 // Track relationship in context
@@ -240,22 +276,39 @@ impl AgentStep for PrerequisiteMapperStep {
         // analyze_prerequisites now emits progress events per source type
         let prerequisites = self.analyze_prerequisites(context).await?;
 
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: format!("Identified {} prerequisite relationships", prerequisites.len()),
-        }).await;
-
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Creating prerequisite relationships in database...".to_string(),
-        }).await;
-
-        let count = self.create_prerequisite_relationships(&prerequisites, context).await?;
-
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: format!("Created {} prerequisite relationships", count),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: format!(
+                    "Identified {} prerequisite relationships",
+                    prerequisites.len()
+                ),
+            },
+        )
+        .await;
+
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Creating prerequisite relationships in database...".to_string(),
+            },
+        )
+        .await;
+
+        let count = self
+            .create_prerequisite_relationships(&prerequisites, context)
+            .await?;
+
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: format!("Created {} prerequisite relationships", count),
+            },
+        )
+        .await;
 
         Ok(())
     }
@@ -1,17 +1,20 @@
+use futures::{StreamExt, stream};
 use neo4rs::Graph;
+use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use serde_json::{json, Value};
-use futures::{stream, StreamExt};
 
-use super::{emit_event, AgentStep, StepUtils, BATCH_SIZE};
+use super::{AgentStep, BATCH_SIZE, StepUtils, emit_event};
+use crate::common::similarity::{FindSimilarNodesRequest, find_similar_nodes};
 use crate::domains::agent::llm::{GenerationConfig, LlmProvider};
-use crate::domains::agent::models::{AgentContext, AgentType, ConceptAction, CreatedNode, GeneralizationLink, SseEvent, VerifiedConcept};
+use crate::domains::agent::models::{
+    AgentContext, AgentType, ConceptAction, CreatedNode, GeneralizationLink, SseEvent,
+    VerifiedConcept,
+};
 use crate::domains::agent::prompts::{PromptTemplates, SystemPrompts};
 use crate::domains::graph::models::{CreateNodeRequest, CreateRelationshipRequest};
 use crate::domains::graph::services;
-use crate::common::similarity::{find_similar_nodes, FindSimilarNodesRequest};
 
 /// Skill Generator Step - creates Skill nodes with multi-pass logic
 pub struct SkillGeneratorStep {
@@ -21,22 +24,28 @@ pub struct SkillGeneratorStep {
 }
 
 impl SkillGeneratorStep {
-    pub fn new(
-        llm: Arc<dyn LlmProvider>,
-        graph: Graph,
-        event_tx: mpsc::Sender<SseEvent>,
-    ) -> Self {
-        Self { llm, graph, event_tx }
+    pub fn new(llm: Arc<dyn LlmProvider>, graph: Graph, event_tx: mpsc::Sender<SseEvent>) -> Self {
+        Self {
+            llm,
+            graph,
+            event_tx,
+        }
     }
 
     async fn pass1_conceptualize(&self, context: &AgentContext) -> Result<Vec<String>, String> {
         let description = context.description.clone().unwrap_or_default();
-        let existing_knowledge: Vec<String> = context.domain_graph.knowledge
+        let existing_knowledge: Vec<String> = context
+            .domain_graph
+            .knowledge
             .iter()
             .map(|n| n.name.clone())
             .collect();
 
-        let prompt = PromptTemplates::skill_concepts(&context.domain_name, &description, &existing_knowledge);
+        let prompt = PromptTemplates::skill_concepts(
+            &context.domain_name,
+            &description,
+            &existing_knowledge,
+        );
 
         let config = GenerationConfig {
             max_tokens: Some(16384),
@@ -44,38 +53,45 @@ impl SkillGeneratorStep {
             stop_sequences: None,
         };
 
-        let response = self.llm.generate(
-            SystemPrompts::skill_expert(),
-            &prompt,
-            &config,
-        ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+        let response = self
+            .llm
+            .generate(SystemPrompts::skill_expert(), &prompt, &config)
+            .await
+            .map_err(|e| format!("LLM error: {:?}", e))?;
 
         StepUtils::parse_concept_list(&response)
     }
 
-    async fn pass2_similarity_search(&self, concepts: &[String]) -> Result<Vec<(String, Vec<crate::common::similarity::SimilarNodeResult>)>, String> {
+    async fn pass2_similarity_search(
+        &self,
+        concepts: &[String],
+    ) -> Result<Vec<(String, Vec<crate::common::similarity::SimilarNodeResult>)>, String> {
         let graph = self.graph.clone();
 
-        let results: Vec<Result<(String, Vec<crate::common::similarity::SimilarNodeResult>), String>> =
-            stream::iter(concepts.to_vec())
-                .map(|concept| {
-                    let graph = graph.clone();
-                    async move {
-                        let similar = find_similar_nodes(&graph, FindSimilarNodesRequest {
+        let results: Vec<
+            Result<(String, Vec<crate::common::similarity::SimilarNodeResult>), String>,
+        > = stream::iter(concepts.to_vec())
+            .map(|concept| {
+                let graph = graph.clone();
+                async move {
+                    let similar = find_similar_nodes(
+                        &graph,
+                        FindSimilarNodesRequest {
                             text: Some(concept.clone()),
                             label: Some("Skill".to_string()),
                             limit: Some(3),
                             ..Default::default()
-                        })
-                        .await
-                        .map_err(|e| format!("Similarity search failed: {}", e))?;
+                        },
+                    )
+                    .await
+                    .map_err(|e| format!("Similarity search failed: {}", e))?;
 
-                        Ok((concept, similar))
-                    }
-                })
-                .buffer_unordered(BATCH_SIZE)
-                .collect()
-                .await;
+                    Ok((concept, similar))
+                }
+            })
+            .buffer_unordered(BATCH_SIZE)
+            .collect()
+            .await;
 
         results.into_iter().collect()
     }
@@ -89,14 +105,22 @@ impl SkillGeneratorStep {
 
         for (concept, similar_nodes) in similarity_results {
             let top_score = similar_nodes.first().map(|n| n.score);
-            emit_event(&self.event_tx, SseEvent::SimilarityCheck {
-                agent: AgentType::SkillGenerator,
-                concept: concept.clone(),
-                similar_found: similar_nodes.len(),
-                top_score,
-            }).await;
-
-            if let Some(decision) = StepUtils::determine_action_by_score(concept, similar_nodes) {
+            emit_event(
+                &self.event_tx,
+                SseEvent::SimilarityCheck {
+                    agent: AgentType::SkillGenerator,
+                    concept: concept.clone(),
+                    similar_found: similar_nodes.len(),
+                    top_score,
+                },
+            )
+            .await;
+
+            if let Some(decision) = StepUtils::determine_action_by_score(
+                concept,
+                similar_nodes,
+                &context.thresholds.for_label("Skill"),
+            ) {
                 verified.push(decision);
             } else {
                 let prompt = PromptTemplates::verify_similar_skill(
@@ -111,16 +135,20 @@ impl SkillGeneratorStep {
                     stop_sequences: None,
                 };
 
-                let response = self.llm.generate(
-                    SystemPrompts::skill_expert(),
-                    &prompt,
-                    &config,
-                ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+                let response = self
+                    .llm
+                    .generate(SystemPrompts::skill_expert(), &prompt, &config)
+                    .await
+                    .map_err(|e| format!("LLM error: {:?}", e))?;
 
                 let mut decision = StepUtils::parse_verification_response(&response, concept)?;
 
                 // If create_and_generalize, resolve the target node ID
-                if let ConceptAction::CreateAndGeneralize { generalizes_to_name, .. } = &decision.action {
+                if let ConceptAction::CreateAndGeneralize {
+                    generalizes_to_name,
+                    ..
+                } = &decision.action
+                {
                     let target_name = generalizes_to_name.clone();
 
                     // 1. Check if target is in the similar_nodes from current search
@@ -133,17 +161,23 @@ impl SkillGeneratorStep {
                         );
                     } else {
                         // 2. Search DB for the target node by name
-                        let search_results = find_similar_nodes(&self.graph, FindSimilarNodesRequest {
-                            text: Some(target_name.clone()),
-                            label: Some("Skill".to_string()),
-                            limit: Some(1),
-                            ..Default::default()
-                        })
+                        let search_results = find_similar_nodes(
+                            &self.graph,
+                            FindSimilarNodesRequest {
+                                text: Some(target_name.clone()),
+                                label: Some("Skill".to_string()),
+                                limit: Some(1),
+                                ..Default::default()
+                            },
+                        )
                         .await
                         .map_err(|e| format!("Similarity search failed: {}", e))?;
 
                         // Check for exact name match or very high similarity
-                        if let Some(found) = search_results.first().filter(|n| n.score >= 0.95 || n.name == target_name) {
+                        if let Some(found) = search_results.first().filter(|n| {
+                            n.score >= context.thresholds.for_label("Skill").auto_reuse
+                                || n.name == target_name
+                        }) {
                             decision = VerifiedConcept::create_and_generalize(
                                 concept,
                                 &found.id,
@@ -172,7 +206,7 @@ impl SkillGeneratorStep {
     /// Pass 4: Create missing generalization target nodes (batched LLM calls)
     async fn pass4_create_missing_targets(
         &self,
-        verified: &mut Vec<VerifiedConcept>,
+        verified: &mut [VerifiedConcept],
     ) -> Result<Vec<CreatedNode>, String> {
         // Collect targets that need creation with their indices
         let targets_to_create: Vec<(usize, String)> = verified
@@ -211,11 +245,10 @@ impl SkillGeneratorStep {
                             stop_sequences: None,
                         };
 
-                        let response = llm.generate(
-                            SystemPrompts::skill_expert(),
-                            &prompt,
-                            &config,
-                        ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+                        let response = llm
+                            .generate(SystemPrompts::skill_expert(), &prompt, &config)
+                            .await
+                            .map_err(|e| format!("LLM error: {:?}", e))?;
 
                         let props = Self::parse_skill_properties(&response, &target_name)?;
                         Ok((idx, target_name, props))
@@ -238,9 +271,15 @@ impl SkillGeneratorStep {
             node_props.insert("description".to_string(), json!(props.description));
             node_props.insert("how_to_develop".to_string(), json!(props.how_to_develop));
             node_props.insert("novice_level".to_string(), json!(props.novice_level));
-            node_props.insert("advanced_beginner_level".to_string(), json!(props.advanced_beginner_level));
+            node_props.insert(
+                "advanced_beginner_level".to_string(),
+                json!(props.advanced_beginner_level),
+            );
             node_props.insert("competent_level".to_string(), json!(props.competent_level));
-            node_props.insert("proficient_level".to_string(), json!(props.proficient_level));
+            node_props.insert(
+                "proficient_level".to_string(),
+                json!(props.proficient_level),
+            );
             node_props.insert("expert_level".to_string(), json!(props.expert_level));
 
             let request = CreateNodeRequest {
@@ -248,7 +287,7 @@ impl SkillGeneratorStep {
                 properties: node_props,
             };
 
-            let result = services::create_node(&self.graph, request, false)
+            let result = services::create_node(&self.graph, request, None)
                 .await
                 .map_err(|e| e.to_string())?;
 
@@ -280,7 +319,11 @@ impl SkillGeneratorStep {
             .iter()
             .filter(|c| !matches!(c.action, ConceptAction::UseExisting { .. }))
             .map(|concept| {
-                let generalizes_to = if let ConceptAction::CreateAndGeneralize { generalizes_to_name, .. } = &concept.action {
+                let generalizes_to = if let ConceptAction::CreateAndGeneralize {
+                    generalizes_to_name,
+                    ..
+                } = &concept.action
+                {
                     Some(generalizes_to_name.clone())
                 } else {
                     None
@@ -313,11 +356,10 @@ impl SkillGeneratorStep {
                         stop_sequences: None,
                     };
 
-                    let response = llm.generate(
-                        SystemPrompts::skill_expert(),
-                        &prompt,
-                        &config,
-                    ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+                    let response = llm
+                        .generate(SystemPrompts::skill_expert(), &prompt, &config)
+                        .await
+                        .map_err(|e| format!("LLM error: {:?}", e))?;
 
                     Self::parse_skill_properties(&response, &concept_name)
                 }
@@ -329,24 +371,59 @@ impl SkillGeneratorStep {
         results.into_iter().collect()
     }
 
-    fn parse_skill_properties(response: &str, concept_name: &str) -> Result<SkillProperties, String> {
+    fn parse_skill_properties(
+        response: &str,
+        concept_name: &str,
+    ) -> Result<SkillProperties, String> {
         let trimmed = response.trim();
         let start = trimmed.find('{').ok_or("No JSON object found")?;
         let end = trimmed.rfind('}').ok_or("No closing brace found")?;
 
         let json_str = &trimmed[start..=end];
-        let parsed: Value = serde_json::from_str(json_str)
-            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        let parsed: Value =
+            serde_json::from_str(json_str).map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
         Ok(SkillProperties {
-            name: parsed.get("name").and_then(|v| v.as_str()).unwrap_or(concept_name).to_string(),
-            description: parsed.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            how_to_develop: parsed.get("how_to_develop").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            novice_level: parsed.get("novice_level").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            advanced_beginner_level: parsed.get("advanced_beginner_level").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            competent_level: parsed.get("competent_level").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            proficient_level: parsed.get("proficient_level").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            expert_level: parsed.get("expert_level").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            name: parsed
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or(concept_name)
+                .to_string(),
+            description: parsed
+                .get("description")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            how_to_develop: parsed
+                .get("how_to_develop")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            novice_level: parsed
+                .get("novice_level")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            advanced_beginner_level: parsed
+                .get("advanced_beginner_level")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            competent_level: parsed
+                .get("competent_level")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            proficient_level: parsed
+                .get("proficient_level")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            expert_level: parsed
+                .get("expert_level")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
         })
     }
 
@@ -376,9 +453,15 @@ impl SkillGeneratorStep {
                     node_props.insert("description".to_string(), json!(props.description));
                     node_props.insert("how_to_develop".to_string(), json!(props.how_to_develop));
                     node_props.insert("novice_level".to_string(), json!(props.novice_level));
-                    node_props.insert("advanced_beginner_level".to_string(), json!(props.advanced_beginner_level));
+                    node_props.insert(
+                        "advanced_beginner_level".to_string(),
+                        json!(props.advanced_beginner_level),
+                    );
                     node_props.insert("competent_level".to_string(), json!(props.competent_level));
-                    node_props.insert("proficient_level".to_string(), json!(props.proficient_level));
+                    node_props.insert(
+                        "proficient_level".to_string(),
+                        json!(props.proficient_level),
+                    );
                     node_props.insert("expert_level".to_string(), json!(props.expert_level));
 
                     let request = CreateNodeRequest {
@@ -386,7 +469,7 @@ impl SkillGeneratorStep {
                         properties: node_props,
                     };
 
-                    let result = services::create_node(&self.graph, request, false)
+                    let result = services::create_node(&self.graph, request, None)
                         .await
                         .map_err(|e| e.to_string())?;
 
@@ -410,8 +493,14 @@ impl SkillGeneratorStep {
         context: &mut AgentContext,
     ) -> Result<(), String> {
         for concept in verified {
-            if let ConceptAction::CreateAndGeneralize { generalizes_to_id, generalizes_to_name, .. } = &concept.action {
-                let source = created.iter()
+            if let ConceptAction::CreateAndGeneralize {
+                generalizes_to_id,
+                generalizes_to_name,
+                ..
+            } = &concept.action
+            {
+                let source = created
+                    .iter()
                     .find(|n| n.name == concept.name)
                     .ok_or_else(|| format!("Node not found: {}", concept.name))?;
 
@@ -422,7 +511,10 @@ impl SkillGeneratorStep {
                     properties: None,
                 };
 
-                if services::create_relationship(&self.graph, rel_request).await.is_ok() {
+                if services::create_relationship(&self.graph, rel_request)
+                    .await
+                    .is_ok()
+                {
                     // Track generalization in context
                     context.add_generalization(GeneralizationLink {
                         specific_id: source.element_id.clone(),
@@ -447,87 +539,138 @@ impl AgentStep for SkillGeneratorStep {
 
     async fn execute(&self, context: &mut AgentContext) -> Result<(), String> {
         // Pass 1: Get concept list
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Identifying skill concepts...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Identifying skill concepts...".to_string(),
+            },
+        )
+        .await;
 
         let concepts = self.pass1_conceptualize(context).await?;
 
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: format!("Found {} skill concepts", concepts.len()),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: format!("Found {} skill concepts", concepts.len()),
+            },
+        )
+        .await;
 
         // Pass 2: Similarity search
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Searching for similar existing nodes...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Searching for similar existing nodes...".to_string(),
+            },
+        )
+        .await;
 
         let similarity_results = self.pass2_similarity_search(&concepts).await?;
 
         // Pass 3: Verify matches and resolve target IDs
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Verifying node matches...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Verifying node matches...".to_string(),
+            },
+        )
+        .await;
 
-        let mut verified = self.pass3_verify_matches(context, &similarity_results).await?;
+        let mut verified = self
+            .pass3_verify_matches(context, &similarity_results)
+            .await?;
 
         // Pass 4: Create missing generalization targets
-        let targets_to_create = verified.iter()
-            .filter(|c| matches!(&c.action, ConceptAction::CreateAndGeneralize { needs_creation: true, .. }))
+        let targets_to_create = verified
+            .iter()
+            .filter(|c| {
+                matches!(
+                    &c.action,
+                    ConceptAction::CreateAndGeneralize {
+                        needs_creation: true,
+                        ..
+                    }
+                )
+            })
             .count();
 
         if targets_to_create > 0 {
-            emit_event(&self.event_tx, SseEvent::StepProgress {
-                agent: self.agent_type(),
-                message: format!("Creating {} missing generalization targets...", targets_to_create),
-            }).await;
+            emit_event(
+                &self.event_tx,
+                SseEvent::StepProgress {
+                    agent: self.agent_type(),
+                    message: format!(
+                        "Creating {} missing generalization targets...",
+                        targets_to_create
+                    ),
+                },
+            )
+            .await;
 
             let created_targets = self.pass4_create_missing_targets(&mut verified).await?;
 
             // Emit events for created target nodes
             for node in &created_targets {
-                emit_event(&self.event_tx, SseEvent::NodeCreated {
-                    agent: self.agent_type(),
-                    node_name: node.name.clone(),
-                    label: "Skill".to_string(),
-                    was_reused: false,
-                }).await;
+                emit_event(
+                    &self.event_tx,
+                    SseEvent::NodeCreated {
+                        agent: self.agent_type(),
+                        node_name: node.name.clone(),
+                        label: "Skill".to_string(),
+                        was_reused: false,
+                    },
+                )
+                .await;
 
                 context.add_skill(node.clone());
             }
         }
 
         // Pass 5: Generate properties for source nodes
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Generating skill properties...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Generating skill properties...".to_string(),
+            },
+        )
+        .await;
 
         let properties = self.pass5_generate_properties(context, &verified).await?;
 
         // Pass 6: Create source nodes
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Creating nodes in database...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Creating nodes in database...".to_string(),
+            },
+        )
+        .await;
 
         let created = self.pass6_create_nodes(&verified, &properties).await?;
 
         // Pass 7: Create GENERALIZES_TO relationships
-        self.pass7_create_generalization_relationships(&verified, &created, context).await?;
+        self.pass7_create_generalization_relationships(&verified, &created, context)
+            .await?;
 
         // Emit node created events and update context
         for node in created {
-            emit_event(&self.event_tx, SseEvent::NodeCreated {
-                agent: self.agent_type(),
-                node_name: node.name.clone(),
-                label: "Skill".to_string(),
-                was_reused: node.was_reused,
-            }).await;
+            emit_event(
+                &self.event_tx,
+                SseEvent::NodeCreated {
+                    agent: self.agent_type(),
+                    node_name: node.name.clone(),
+                    label: "Skill".to_string(),
+                    was_reused: node.was_reused,
+                },
+            )
+            .await;
 
             context.add_skill(node);
         }
@@ -1,17 +1,19 @@
+use futures::{StreamExt, stream};
 use neo4rs::Graph;
+use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use serde_json::{json, Value};
-use futures::{stream, StreamExt};
 
-use super::{emit_event, AgentStep, StepUtils, BATCH_SIZE};
+use super::{AgentStep, BATCH_SIZE, StepUtils, emit_event};
+use crate::common::similarity::{FindSimilarNodesRequest, find_similar_nodes};
 use crate::domains::agent::llm::{GenerationConfig, LlmProvider};
-use crate::domains::agent::models::{AgentContext, AgentType, ConceptAction, CreatedNode, SseEvent, VerifiedConcept};
+use crate::domains::agent::models::{
+    AgentContext, AgentType, ConceptAction, CreatedNode, SseEvent, VerifiedConcept,
+};
 use crate::domains::agent::prompts::{PromptTemplates, SystemPrompts};
 use crate::domains::graph::models::CreateNodeRequest;
 use crate::domains::graph::services;
-use crate::common::similarity::{find_similar_nodes, FindSimilarNodesRequest};
 
 /// Trait Generator Step - creates Trait nodes
 /// Note: Traits are generic (shared across domains) so we prefer reusing existing ones
@@ -22,12 +24,12 @@ pub struct TraitGeneratorStep {
 }
 
 impl TraitGeneratorStep {
-    pub fn new(
-        llm: Arc<dyn LlmProvider>,
-        graph: Graph,
-        event_tx: mpsc::Sender<SseEvent>,
-    ) -> Self {
-        Self { llm, graph, event_tx }
+    pub fn new(llm: Arc<dyn LlmProvider>, graph: Graph, event_tx: mpsc::Sender<SseEvent>) -> Self {
+        Self {
+            llm,
+            graph,
+            event_tx,
+        }
     }
 
     async fn pass1_conceptualize(&self, context: &AgentContext) -> Result<Vec<String>, String> {
@@ -40,38 +42,45 @@ impl TraitGeneratorStep {
             stop_sequences: None,
         };
 
-        let response = self.llm.generate(
-            SystemPrompts::trait_analyst(),
-            &prompt,
-            &config,
-        ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+        let response = self
+            .llm
+            .generate(SystemPrompts::trait_analyst(), &prompt, &config)
+            .await
+            .map_err(|e| format!("LLM error: {:?}", e))?;
 
         StepUtils::parse_concept_list(&response)
     }
 
-    async fn pass2_similarity_search(&self, concepts: &[String]) -> Result<Vec<(String, Vec<crate::common::similarity::SimilarNodeResult>)>, String> {
+    async fn pass2_similarity_search(
+        &self,
+        concepts: &[String],
+    ) -> Result<Vec<(String, Vec<crate::common::similarity::SimilarNodeResult>)>, String> {
         let graph = self.graph.clone();
 
-        let results: Vec<Result<(String, Vec<crate::common::similarity::SimilarNodeResult>), String>> =
-            stream::iter(concepts.to_vec())
-                .map(|concept| {
-                    let graph = graph.clone();
-                    async move {
-                        let similar = find_similar_nodes(&graph, FindSimilarNodesRequest {
+        let results: Vec<
+            Result<(String, Vec<crate::common::similarity::SimilarNodeResult>), String>,
+        > = stream::iter(concepts.to_vec())
+            .map(|concept| {
+                let graph = graph.clone();
+                async move {
+                    let similar = find_similar_nodes(
+                        &graph,
+                        FindSimilarNodesRequest {
                             text: Some(concept.clone()),
                             label: Some("Trait".to_string()),
                             limit: Some(3),
                             ..Default::default()
-                        })
-                        .await
-                        .map_err(|e| format!("Similarity search failed: {}", e))?;
+                        },
+                    )
+                    .await
+                    .map_err(|e| format!("Similarity search failed: {}", e))?;
 
-                        Ok((concept, similar))
-                    }
-                })
-                .buffer_unordered(BATCH_SIZE)
-                .collect()
-                .await;
+                    Ok((concept, similar))
+                }
+            })
+            .buffer_unordered(BATCH_SIZE)
+            .collect()
+            .await;
 
         results.into_iter().collect()
     }
@@ -85,18 +94,23 @@ impl TraitGeneratorStep {
 
         for (concept, similar_nodes) in similarity_results {
             let top_score = similar_nodes.first().map(|n| n.score);
-            emit_event(&self.event_tx, SseEvent::SimilarityCheck {
-                agent: AgentType::TraitGenerator,
-                concept: concept.clone(),
-                similar_found: similar_nodes.len(),
-                top_score,
-            }).await;
-
-            // For traits, we use a slightly lower threshold since they're generic
+            emit_event(
+                &self.event_tx,
+                SseEvent::SimilarityCheck {
+                    agent: AgentType::TraitGenerator,
+                    concept: concept.clone(),
+                    similar_found: similar_nodes.len(),
+                    top_score,
+                },
+            )
+            .await;
+
+            // Trait thresholds are lower by default, since traits are generic
             // and we want to maximize reuse
-            if similar_nodes.is_empty() || similar_nodes[0].score < 0.65 {
+            let thresholds = context.thresholds.for_label("Trait");
+            if similar_nodes.is_empty() || similar_nodes[0].score < thresholds.verify {
                 verified.push(VerifiedConcept::create_new(concept));
-            } else if similar_nodes[0].score >= 0.85 {
+            } else if similar_nodes[0].score >= thresholds.auto_reuse {
                 // High match - auto-use existing
                 verified.push(VerifiedConcept::use_existing(concept, &similar_nodes[0].id));
             } else {
@@ -113,11 +127,11 @@ impl TraitGeneratorStep {
                     stop_sequences: None,
                 };
 
-                let response = self.llm.generate(
-                    SystemPrompts::trait_analyst(),
-                    &prompt,
-                    &config,
-                ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+                let response = self
+                    .llm
+                    .generate(SystemPrompts::trait_analyst(), &prompt, &config)
+                    .await
+                    .map_err(|e| format!("LLM error: {:?}", e))?;
 
                 // For traits, we only support use_existing or create_new
                 let decision = self.parse_trait_verification(&response, concept, similar_nodes)?;
@@ -139,22 +153,25 @@ impl TraitGeneratorStep {
         let end = trimmed.rfind('}').ok_or("No closing brace found")?;
 
         let json_str = &trimmed[start..=end];
-        let parsed: Value = serde_json::from_str(json_str)
-            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        let parsed: Value =
+            serde_json::from_str(json_str).map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
-        let decision = parsed.get("decision")
+        let decision = parsed
+            .get("decision")
             .and_then(|v| v.as_str())
             .ok_or("Missing decision field")?;
 
         match decision {
             "use_existing" => {
-                let node_name = parsed.get("existing_node_name")
+                let node_name = parsed
+                    .get("existing_node_name")
                     .and_then(|v| v.as_str())
                     .filter(|s| !s.is_empty() && *s != "null");
 
                 if let Some(name) = node_name {
                     // Find the element ID
-                    let existing_id = similar_nodes.iter()
+                    let existing_id = similar_nodes
+                        .iter()
                         .find(|n| n.name == name)
                         .map(|n| n.id.clone())
                         .unwrap_or_else(|| similar_nodes[0].id.clone());
@@ -198,11 +215,10 @@ impl TraitGeneratorStep {
                         stop_sequences: None,
                     };
 
-                    let response = llm.generate(
-                        SystemPrompts::trait_analyst(),
-                        &prompt,
-                        &config,
-                    ).await.map_err(|e| format!("LLM error: {:?}", e))?;
+                    let response = llm
+                        .generate(SystemPrompts::trait_analyst(), &prompt, &config)
+                        .await
+                        .map_err(|e| format!("LLM error: {:?}", e))?;
 
                     Self::parse_trait_properties(&response, &concept_name)
                 }
@@ -214,19 +230,34 @@ impl TraitGeneratorStep {
         results.into_iter().collect()
     }
 
-    fn parse_trait_properties(response: &str, concept_name: &str) -> Result<TraitProperties, String> {
+    fn parse_trait_properties(
+        response: &str,
+        concept_name: &str,
+    ) -> Result<TraitProperties, String> {
         let trimmed = response.trim();
         let start = trimmed.find('{').ok_or("No JSON object found")?;
         let end = trimmed.rfind('}').ok_or("No closing brace found")?;
 
         let json_str = &trimmed[start..=end];
-        let parsed: Value = serde_json::from_str(json_str)
-            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        let parsed: Value =
+            serde_json::from_str(json_str).map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
         Ok(TraitProperties {
-            name: parsed.get("name").and_then(|v| v.as_str()).unwrap_or(concept_name).to_string(),
-            description: parsed.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-            measurement_criteria: parsed.get("measurement_criteria").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            name: parsed
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or(concept_name)
+                .to_string(),
+            description: parsed
+                .get("description")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            measurement_criteria: parsed
+                .get("measurement_criteria")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
         })
     }
 
@@ -253,14 +284,17 @@ impl TraitGeneratorStep {
                     let mut node_props: HashMap<String, Value> = HashMap::new();
                     node_props.insert("name".to_string(), json!(props.name));
                     node_props.insert("description".to_string(), json!(props.description));
-                    node_props.insert("measurement_criteria".to_string(), json!(props.measurement_criteria));
+                    node_props.insert(
+                        "measurement_criteria".to_string(),
+                        json!(props.measurement_criteria),
+                    );
 
                     let request = CreateNodeRequest {
                         labels: vec!["Trait".to_string()],
                         properties: node_props,
                     };
 
-                    let result = services::create_node(&self.graph, request, false)
+                    let result = services::create_node(&self.graph, request, None)
                         .await
                         .map_err(|e| e.to_string())?;
 
@@ -285,30 +319,44 @@ impl AgentStep for TraitGeneratorStep {
     }
 
     async fn execute(&self, context: &mut AgentContext) -> Result<(), String> {
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: "Identifying relevant traits...".to_string(),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: "Identifying relevant traits...".to_string(),
+            },
+        )
+        .await;
 
         let concepts = self.pass1_conceptualize(context).await?;
 
-        emit_event(&self.event_tx, SseEvent::StepProgress {
-            agent: self.agent_type(),
-            message: format!("Found {} trait concepts", concepts.len()),
-        }).await;
+        emit_event(
+            &self.event_tx,
+            SseEvent::StepProgress {
+                agent: self.agent_type(),
+                message: format!("Found {} trait concepts", concepts.len()),
+            },
+        )
+        .await;
 
         let similarity_results = self.pass2_similarity_search(&concepts).await?;
-        let verified = self.pass3_verify_matches(context, &similarity_results).await?;
+        let verified = self
+            .pass3_verify_matches(context, &similarity_results)
+            .await?;
         let properties = self.pass4_generate_properties(&verified).await?;
         let created = self.pass5_create_nodes(&verified, &properties).await?;
 
         for node in created {
-            emit_event(&self.event_tx, SseEvent::NodeCreated {
-                agent: self.agent_type(),
-                node_name: node.name.clone(),
-                label: "Trait".to_string(),
-                was_reused: node.was_reused,
-            }).await;
+            emit_event(
+                &self.event_tx,
+                SseEvent::NodeCreated {
+                    agent: self.agent_type(),
+                    node_name: node.name.clone(),
+                    label: "Trait".to_string(),
+                    was_reused: node.was_reused,
+                },
+            )
+            .await;
 
             context.add_trait(node);
         }
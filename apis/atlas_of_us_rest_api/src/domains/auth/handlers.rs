@@ -14,7 +14,7 @@ pub async fn login(
     State(graph): State<Graph>,
     Json(login_req): Json<LoginRequest>,
 ) -> Result<Json<AuthResponse>, (StatusCode, Json<ErrorResponse>)> {
-    if let Err(_) = login_req.validate() {
+    if login_req.validate().is_err() {
         return Err((
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
@@ -84,17 +84,15 @@ pub async fn login(
 
     // Verify password
     match verify_password(&login_req.password, &stored_password) {
-        Ok(true) => {
-            match generate_token(&login_req.username) {
-                Ok(token) => Ok(Json(AuthResponse { token })),
-                Err(_) => Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: "internal server error".to_string(),
-                    }),
-                )),
-            }
-        }
+        Ok(true) => match generate_token(&login_req.username) {
+            Ok(token) => Ok(Json(AuthResponse { token })),
+            Err(_) => Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "internal server error".to_string(),
+                }),
+            )),
+        },
         Ok(false) => Err((
             StatusCode::UNAUTHORIZED,
             Json(ErrorResponse {
@@ -114,7 +112,7 @@ pub async fn signup(
     State(graph): State<Graph>,
     Json(signup_req): Json<SignUpRequest>,
 ) -> Result<Json<AuthResponse>, (StatusCode, Json<ErrorResponse>)> {
-    if let Err(_) = signup_req.validate() {
+    if signup_req.validate().is_err() {
         return Err((
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
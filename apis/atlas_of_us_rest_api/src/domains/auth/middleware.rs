@@ -16,14 +16,8 @@ pub async fn jwt_auth_middleware(
         .and_then(|header| header.to_str().ok());
 
     let token = match auth_header {
-        Some(auth) => {
-            if auth.starts_with("Bearer ") {
-                auth.strip_prefix("Bearer ").unwrap_or("")
-            } else {
-                return Err(StatusCode::UNAUTHORIZED);
-            }
-        }
-        None => return Err(StatusCode::UNAUTHORIZED),
+        Some(auth) if auth.starts_with("Bearer ") => auth.strip_prefix("Bearer ").unwrap_or(""),
+        _ => return Err(StatusCode::UNAUTHORIZED),
     };
 
     let jwt_secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "your-secret-key".to_string());
@@ -34,9 +28,7 @@ pub async fn jwt_auth_middleware(
         &DecodingKey::from_secret(jwt_secret.as_bytes()),
         &validation,
     ) {
-        Ok(_token_data) => {
-            Ok(next.run(req).await)
-        }
+        Ok(_token_data) => Ok(next.run(req).await),
         Err(_) => Err(StatusCode::UNAUTHORIZED),
     }
 }
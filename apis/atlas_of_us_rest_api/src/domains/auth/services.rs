@@ -51,5 +51,5 @@ pub fn generate_random_key() -> String {
     use base64::Engine;
     let mut key = [0u8; 32]; // 256-bit key
     OsRng.fill_bytes(&mut key);
-    base64::engine::general_purpose::STANDARD.encode(&key)
+    base64::engine::general_purpose::STANDARD.encode(key)
 }
@@ -1,19 +1,27 @@
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
-    response::Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Json, Response},
 };
 use neo4rs::Graph;
-use serde_json::{json, Value};
+use serde_json::{Value, json};
 use std::collections::HashMap;
 
+use super::level_template::BUILTIN_TEMPLATES;
 use super::models::{
-    CreateDomainRequest, DeleteRelationshipRequest,
-    GetDomainParams, GetNodeWithRelationshipsBySearchTermParams, NodeQueryParams,
-    SearchNodesParams, ServiceError, UpdateDomainRequest, UpdateNodeRequest,
-    UpdateRelationshipRequest, ValidateDomainNameParams,
+    CreateCategoryRequest, CreateDomainRequest, CreateNodeParams, DeleteRelationshipRequest,
+    DomainDiffParams, DomainIntegrityParams, DomainProgressParams, DomainVersionsParams,
+    ExportGraphParams, ForkDomainRequest, GetDomainParams,
+    GetNodeWithRelationshipsBySearchTermParams, GraphDocument, ImportGraphParams,
+    ListDomainsParams, LoadCuratedDomainsParams, MigratePursuersRequest, MigrationReportParams,
+    NodeQueryParams, PathParams, PublishDomainRequest, SearchNodesParams, ServiceError,
+    SetDomainTaxonomyRequest, SimilarNodesRequest, SoftDeleteNodeRequest, StartLayoutRequest,
+    StartReembedRequest, SubgraphParams, TaxonomyListParams, TransferableComponentsParams,
+    UpdateDomainRequest, UpdateNodeRequest, UpdateRelationshipRequest, ValidateDomainNameParams,
 };
 use super::services;
+use crate::common::thresholds::similarity_thresholds;
+use crate::error::AppError;
 
 // Re-export types needed by agent domain and other modules
 pub use super::models::{
@@ -29,22 +37,46 @@ pub async fn get_nodes(
     let depth = params.depth.unwrap_or(1);
 
     // Parse labels
-    let labels: Option<Vec<&str>> = params.labels.as_ref().map(|labels_str| {
-        labels_str.split(',').map(|s| s.trim()).collect()
-    });
+    let labels: Option<Vec<&str>> = params
+        .labels
+        .as_ref()
+        .map(|labels_str| labels_str.split(',').map(|s| s.trim()).collect());
 
     // Parse properties
-    let properties: Option<HashMap<String, Value>> = if let Some(properties_str) = &params.properties {
-        match serde_json::from_str(properties_str) {
-            Ok(props) => Some(props),
-            Err(_) => return Err(StatusCode::BAD_REQUEST),
-        }
-    } else {
-        None
-    };
+    let properties: Option<HashMap<String, Value>> =
+        if let Some(properties_str) = &params.properties {
+            match serde_json::from_str(properties_str) {
+                Ok(props) => Some(props),
+                Err(_) => return Err(StatusCode::BAD_REQUEST),
+            }
+        } else {
+            None
+        };
+
+    let projection =
+        services::NodeProjection::new(params.fields.as_deref(), params.include_embedding).map_err(
+            |e| {
+                tracing::warn!("Invalid get_nodes fields: {}", e);
+                StatusCode::BAD_REQUEST
+            },
+        )?;
 
-    match services::get_nodes_with_relationships(&graph, labels, properties, depth).await {
-        Ok(data) => Ok(Json(data)),
+    match services::get_nodes_with_relationships(
+        &graph,
+        labels,
+        properties,
+        depth,
+        params.limit,
+        params.cursor.as_deref(),
+        &projection,
+    )
+    .await
+    {
+        Ok(page) => Ok(Json(json!(page))),
+        Err(ServiceError::ValidationError(e)) => {
+            tracing::warn!("Invalid get_nodes filter: {}", e);
+            Err(StatusCode::BAD_REQUEST)
+        }
         Err(e) => {
             tracing::error!("Error in get_nodes: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -52,25 +84,80 @@ pub async fn get_nodes(
     }
 }
 
+pub async fn get_subgraph(
+    Query(params): Query<SubgraphParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::get_subgraph(&graph, params).await {
+        Ok(subgraph) => Ok(Json(json!(subgraph))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error building subgraph: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn get_paths(
+    Query(params): Query<PathParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::find_paths(&graph, params).await {
+        Ok(paths) => Ok(Json(json!(paths))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error finding paths: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
 pub async fn get_node_with_relationships_by_search_term(
     Query(params): Query<GetNodeWithRelationshipsBySearchTermParams>,
     State(graph): State<Graph>,
 ) -> Result<Json<Value>, StatusCode> {
     let depth = params.depth.unwrap_or(1);
 
-    match services::get_nodes_by_search_term(&graph, &params.search_term, depth).await {
-        Ok(nodes) => {
-            let result: Vec<Value> = nodes
-                .into_iter()
-                .map(|n| {
-                    json!({
-                        "node": n.node,
-                        "relationships": n.relationships,
-                        "affiliatedNodes": n.affiliated_nodes
-                    })
-                })
-                .collect();
-            Ok(Json(json!(result)))
+    let projection =
+        services::NodeProjection::new(params.fields.as_deref(), params.include_embedding).map_err(
+            |e| {
+                tracing::warn!(
+                    "Invalid get_node_with_relationships_by_search_term fields: {}",
+                    e
+                );
+                StatusCode::BAD_REQUEST
+            },
+        )?;
+
+    match services::get_nodes_by_search_term(
+        &graph,
+        &params.search_term,
+        depth,
+        params.limit,
+        params.cursor.as_deref(),
+        &projection,
+    )
+    .await
+    {
+        Ok(page) => Ok(Json(json!(page))),
+        Err(ServiceError::ValidationError(e)) => {
+            tracing::warn!(
+                "Invalid get_node_with_relationships_by_search_term request: {}",
+                e
+            );
+            Err(StatusCode::BAD_REQUEST)
         }
         Err(ServiceError::EmbeddingFailed(e)) => {
             tracing::error!("Failed to generate embedding: {}", e);
@@ -84,10 +171,23 @@ pub async fn get_node_with_relationships_by_search_term(
 }
 
 pub async fn create_node(
+    Query(params): Query<CreateNodeParams>,
     State(graph): State<Graph>,
     Json(request): Json<CreateNodeRequest>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    match services::create_node(&graph, request, true).await {
+    let threshold = params.similarity_threshold.unwrap_or_else(|| {
+        similarity_thresholds()
+            .for_labels(&request.labels)
+            .duplicate
+    });
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "similarityThreshold must be between 0 and 1"})),
+        ));
+    }
+
+    match services::create_node(&graph, request, Some(threshold)).await {
         Ok(result) => Ok(Json(json!([{
             "elementId": result.element_id,
             "labels": result.labels,
@@ -107,6 +207,14 @@ pub async fn create_node(
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(json!({"error": format!("failed to generate embedding: {}", e)})),
         )),
+        Err(e @ ServiceError::InvalidFields(_)) => Err(AppError::from(e).into_json()),
+        Err(ServiceError::ValidationError(e)) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "invalid request body",
+                "details": e
+            })),
+        )),
         Err(e) => {
             tracing::error!("Error creating node: {}", e);
             Err((
@@ -128,6 +236,15 @@ pub async fn create_relationship(
             "startElementId": result.source_id,
             "endElementId": result.target_id
         }]))),
+        Err(e @ ServiceError::InvalidFields(_)) => Err(AppError::from(e).into_json()),
+        Err(ServiceError::ValidationError(e)) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "invalid request body",
+                "details": e
+            })),
+        )),
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
         Err(e) => {
             tracing::error!("Error creating relationship: {}", e);
             Err((
@@ -142,9 +259,16 @@ pub async fn update_node(
     State(graph): State<Graph>,
     Json(request): Json<UpdateNodeRequest>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    match services::update_node(&graph, &request.target_id, request.labels, request.properties).await
+    match services::update_node(
+        &graph,
+        &request.target_id,
+        request.labels,
+        request.properties,
+    )
+    .await
     {
         Ok(nodes) => Ok(Json(json!(nodes))),
+        Err(e @ ServiceError::InvalidFields(_)) => Err(AppError::from(e).into_json()),
         Err(ServiceError::ValidationError(e)) => Err((
             StatusCode::BAD_REQUEST,
             Json(json!({
@@ -152,6 +276,7 @@ pub async fn update_node(
                 "details": e
             })),
         )),
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
         Err(e) => {
             tracing::error!("Error updating node: {}", e);
             Err((
@@ -162,6 +287,26 @@ pub async fn update_node(
     }
 }
 
+pub async fn soft_delete_node(
+    State(graph): State<Graph>,
+    Json(request): Json<SoftDeleteNodeRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::set_node_deleted(&graph, &request.target_id, !request.restore).await {
+        Ok(deleted_at) => Ok(Json(json!({
+            "targetId": request.target_id,
+            "deletedAt": deleted_at
+        }))),
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error soft-deleting node: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
 pub async fn update_relationship(
     State(graph): State<Graph>,
     Json(request): Json<UpdateRelationshipRequest>,
@@ -175,6 +320,15 @@ pub async fn update_relationship(
     .await
     {
         Ok(relationships) => Ok(Json(json!(relationships))),
+        Err(e @ ServiceError::InvalidFields(_)) => Err(AppError::from(e).into_json()),
+        Err(ServiceError::ValidationError(e)) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "invalid request body",
+                "details": e
+            })),
+        )),
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
         Err(e) => {
             tracing::error!("Error updating relationship: {}", e);
             Err((
@@ -187,12 +341,10 @@ pub async fn update_relationship(
 
 pub async fn get_similar_nodes(
     State(graph): State<Graph>,
-    Json(request): Json<crate::common::similarity::FindSimilarNodesRequest>,
+    Json(request): Json<SimilarNodesRequest>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    match services::find_similar_nodes(&graph, request.node_id, request.embedding, request.limit)
-        .await
-    {
-        Ok(result) => Ok(Json(result)),
+    match services::find_similar_nodes(&graph, request).await {
+        Ok(page) => Ok(Json(json!(page))),
         Err(ServiceError::ValidationError(e)) => Err((
             StatusCode::BAD_REQUEST,
             Json(json!({
@@ -214,7 +366,7 @@ pub async fn get_domain(
     Query(params): Query<GetDomainParams>,
     State(graph): State<Graph>,
 ) -> Result<Json<Value>, StatusCode> {
-    match services::get_domain(&graph, &params.name).await {
+    match services::get_domain(&graph, &params.name, params.version).await {
         Ok(Some(domain_data)) => Ok(Json(domain_data)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(e) => {
@@ -246,12 +398,34 @@ pub async fn search_nodes(
 ) -> Result<Json<Value>, StatusCode> {
     let limit = params.limit.unwrap_or(20);
 
-    let labels: Option<Vec<&str>> = params.labels.as_ref().map(|labels_str| {
-        labels_str.split(',').map(|s| s.trim()).collect()
-    });
+    let labels: Option<Vec<&str>> = params
+        .labels
+        .as_ref()
+        .map(|labels_str| labels_str.split(',').map(|s| s.trim()).collect());
+
+    let projection =
+        services::NodeProjection::new(params.fields.as_deref(), params.include_embedding).map_err(
+            |e| {
+                tracing::warn!("Invalid search_nodes fields: {}", e);
+                StatusCode::BAD_REQUEST
+            },
+        )?;
 
-    match services::search_nodes(&graph, &params.query, labels, limit).await {
-        Ok(nodes) => Ok(Json(json!({ "nodes": nodes }))),
+    match services::search_nodes(
+        &graph,
+        &params.query,
+        labels,
+        limit,
+        params.cursor.as_deref(),
+        &projection,
+    )
+    .await
+    {
+        Ok(page) => Ok(Json(json!(page))),
+        Err(ServiceError::ValidationError(e)) => {
+            tracing::warn!("Invalid search_nodes filter: {}", e);
+            Err(StatusCode::BAD_REQUEST)
+        }
         Err(e) => {
             tracing::error!("Error in search_nodes: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -294,10 +468,9 @@ pub async fn create_domain(
             },
             "createdNodes": result.created_nodes
         }))),
-        Err(ServiceError::ValidationError(e)) => Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": e})),
-        )),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
         Err(e) => {
             tracing::error!("Error creating domain: {}", e);
             Err((
@@ -320,16 +493,13 @@ pub async fn update_domain(
                 "name": result.domain_name
             },
             "createdNodes": result.created_nodes,
+            "draftVersion": result.draft_version,
             "affectedUserProgressCount": result.affected_user_progress_count
         }))),
-        Err(ServiceError::ValidationError(e)) => Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": e})),
-        )),
-        Err(ServiceError::NotFound(e)) => Err((
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": e})),
-        )),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
         Err(e) => {
             tracing::error!("Error updating domain: {}", e);
             Err((
@@ -340,6 +510,309 @@ pub async fn update_domain(
     }
 }
 
+pub async fn list_domain_versions(
+    Query(params): Query<DomainVersionsParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::list_domain_versions(&graph, &params).await {
+        Ok(versions) => Ok(Json(json!(versions))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error listing domain versions: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn publish_domain(
+    State(graph): State<Graph>,
+    Json(request): Json<PublishDomainRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::publish_domain(&graph, &request.domain_element_id).await {
+        Ok(result) => Ok(Json(json!(result))),
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error publishing domain: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn domain_migration_report(
+    Query(params): Query<MigrationReportParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::migration_report(&graph, &params.name, params.to_version).await {
+        Ok(report) => Ok(Json(json!(report))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error building migration report: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn get_domain_progress(
+    Query(params): Query<DomainProgressParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::domain_progress(&graph, &params.name, &params.username).await {
+        Ok(progress) => Ok(Json(json!(progress))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error computing domain progress: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn migrate_domain_pursuers(
+    State(graph): State<Graph>,
+    Json(request): Json<MigratePursuersRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::migrate_pursuers(&graph, &request).await {
+        Ok(report) => Ok(Json(json!(report))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error migrating domain pursuers: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn diff_domains(
+    Query(params): Query<DomainDiffParams>,
+    State(graph): State<Graph>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let format = params.format.as_deref().unwrap_or("json");
+    if format != "json" && format != "text" {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "format must be 'json' or 'text'"})),
+        ));
+    }
+
+    let result = services::diff_domains(
+        &graph,
+        &params.name,
+        params.from_version,
+        params.other_name.as_deref(),
+        params.to_version,
+    )
+    .await;
+
+    match result {
+        Ok(diff) if format == "text" => Ok((
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            diff.summary.join("\n"),
+        )
+            .into_response()),
+        Ok(diff) => Ok(Json(json!(diff)).into_response()),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error diffing domains: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn fork_domain(
+    State(graph): State<Graph>,
+    Json(request): Json<ForkDomainRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::fork_domain(&graph, request).await {
+        Ok(domain) => Ok(Json(domain)),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error forking domain: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn domain_integrity(
+    Query(params): Query<DomainIntegrityParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::validate_domain(&graph, &params.name, params.version).await {
+        Ok(report) => Ok(Json(json!(report))),
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error validating domain: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn integrity_report(
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::validate_all_domains(&graph).await {
+        Ok(report) => Ok(Json(json!(report))),
+        Err(e) => {
+            tracing::error!("Error building integrity report: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn list_categories(
+    Query(params): Query<TaxonomyListParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::list_categories(&graph, &params).await {
+        Ok(page) => Ok(Json(json!(page))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(e) => {
+            tracing::error!("Error listing categories: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn create_category(
+    State(graph): State<Graph>,
+    Json(request): Json<CreateCategoryRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::create_category(&graph, request).await {
+        Ok(category) => Ok(Json(json!(category))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error creating category: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn set_domain_taxonomy(
+    State(graph): State<Graph>,
+    Json(request): Json<SetDomainTaxonomyRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::set_domain_taxonomy(&graph, request).await {
+        Ok(domain) => Ok(Json(json!(domain))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error setting domain taxonomy: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn list_domains(
+    Query(params): Query<ListDomainsParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::list_domains(&graph, params).await {
+        Ok(page) => Ok(Json(json!(page))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(e) => {
+            tracing::error!("Error listing domains: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn list_tags(
+    Query(params): Query<TaxonomyListParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::list_tags(&graph, &params).await {
+        Ok(page) => Ok(Json(json!(page))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(e) => {
+            tracing::error!("Error listing tags: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn list_level_templates() -> Json<Value> {
+    let templates: Vec<Value> = BUILTIN_TEMPLATES
+        .iter()
+        .map(|builtin| {
+            json!({
+                "key": builtin.key,
+                "summary": builtin.summary,
+                "template": builtin.template(),
+            })
+        })
+        .collect();
+
+    Json(json!({ "templates": templates }))
+}
+
 // ========== Internal Functions (for agent orchestrator) ==========
 // These are thin wrappers around services for backwards compatibility
 
@@ -348,7 +821,7 @@ pub async fn create_node_internal(
     graph: &Graph,
     request: CreateNodeRequest,
 ) -> Result<CreateNodeResult, String> {
-    services::create_node(graph, request, false)
+    services::create_node(graph, request, None)
         .await
         .map_err(|e| e.to_string())
 }
@@ -373,3 +846,253 @@ pub async fn find_node_by_name(
         .await
         .map_err(|e| e.to_string())
 }
+
+pub async fn export_graph(
+    Query(params): Query<ExportGraphParams>,
+    State(graph): State<Graph>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let selection = if let Some(domain) = params.domain {
+        services::ExportSelection::Domain(domain)
+    } else {
+        let labels: Vec<String> = params
+            .labels
+            .as_deref()
+            .map(|labels_str| {
+                labels_str
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let properties: HashMap<String, Value> = match params.properties.as_deref() {
+            Some(properties_str) => serde_json::from_str(properties_str).map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({"error": "invalid properties filter", "details": e.to_string()})),
+                )
+            })?,
+            None => HashMap::new(),
+        };
+        services::ExportSelection::Filter { labels, properties }
+    };
+
+    let format = params.format.as_deref().unwrap_or("json");
+    if format != "json" && format != "graphml" {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "format must be 'json' or 'graphml'"})),
+        ));
+    }
+
+    match services::export_graph(
+        &graph,
+        &selection,
+        params.include_embeddings.unwrap_or(false),
+    )
+    .await
+    {
+        Ok(document) if format == "graphml" => Ok((
+            [(header::CONTENT_TYPE, "application/graphml+xml")],
+            services::to_graphml(&document),
+        )
+            .into_response()),
+        Ok(document) => Ok(Json(document).into_response()),
+        Err(ServiceError::ValidationError(e)) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "invalid export selection", "details": e})),
+        )),
+        Err(ServiceError::NotFound(e)) => Err((StatusCode::NOT_FOUND, Json(json!({"error": e})))),
+        Err(e) => {
+            tracing::error!("Error exporting graph: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn import_graph(
+    Query(params): Query<ImportGraphParams>,
+    State(graph): State<Graph>,
+    Json(document): Json<GraphDocument>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::import_graph(&graph, document, params.dry_run.unwrap_or(false)).await {
+        Ok(report) if report.dry_run || report.applied => Ok(Json(json!(report))),
+        Ok(report) => Err((StatusCode::CONFLICT, Json(json!(report)))),
+        Err(ServiceError::ValidationError(e)) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "invalid graph document", "details": e})),
+        )),
+        Err(e) => {
+            tracing::error!("Error importing graph: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn start_reembed(
+    State(graph): State<Graph>,
+    request: Option<Json<StartReembedRequest>>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    match services::start_reembed(&graph, request).await {
+        Ok(job) => Ok(Json(json!(job))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::CONFLICT, Json(json!({"error": e}))))
+        }
+        Err(e) => {
+            tracing::error!("Error starting re-embed job: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn reembed_status(
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::reembed_status(&graph).await {
+        Ok(Some(job)) => Ok(Json(json!(job))),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "no re-embed job has been started"})),
+        )),
+        Err(e) => {
+            tracing::error!("Error reading re-embed job: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn start_layout(
+    State(graph): State<Graph>,
+    request: Option<Json<StartLayoutRequest>>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    match services::start_layout(&graph, request).await {
+        Ok(job) => Ok(Json(json!(job))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::CONFLICT, Json(json!({"error": e}))))
+        }
+        Err(e) => {
+            tracing::error!("Error starting layout job: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn layout_status(
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::layout_status(&graph).await {
+        Ok(Some(job)) => Ok(Json(json!(job))),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "no layout has been computed"})),
+        )),
+        Err(e) => {
+            tracing::error!("Error reading layout job: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn start_analytics(
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::start_analytics(&graph).await {
+        Ok(job) => Ok(Json(json!(job))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::CONFLICT, Json(json!({"error": e}))))
+        }
+        Err(e) => {
+            tracing::error!("Error starting analytics job: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn analytics_status(
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::analytics_status(&graph).await {
+        Ok(Some(job)) => Ok(Json(json!(job))),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "no analytics have been computed"})),
+        )),
+        Err(e) => {
+            tracing::error!("Error reading analytics job: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn get_transferable_components(
+    Query(params): Query<TransferableComponentsParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    match services::most_transferable_components(&graph, params).await {
+        Ok(components) => Ok(Json(json!({"components": components}))),
+        Err(ServiceError::ValidationError(e)) => {
+            Err((StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
+        }
+        Err(e) => {
+            tracing::error!("Error ranking components: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn load_curated_domains(
+    Query(params): Query<LoadCuratedDomainsParams>,
+    State(graph): State<Graph>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let dir = std::env::var("CURATED_DOMAINS_DIR")
+        .unwrap_or_else(|_| services::curated::DEFAULT_CURATED_DOMAINS_DIR.to_string());
+
+    match services::load_curated_domains(
+        &graph,
+        std::path::Path::new(&dir),
+        params.dry_run.unwrap_or(false),
+    )
+    .await
+    {
+        Ok(report) => Ok(Json(json!(report))),
+        Err(ServiceError::ValidationError(e)) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "invalid curated domain scripts", "details": e})),
+        )),
+        Err(e) => {
+            tracing::error!("Error loading curated domains: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "internal server error"})),
+            ))
+        }
+    }
+}
@@ -0,0 +1,377 @@
+//! Level templates.
+//!
+//! A template decides how many levels a domain has, what each tier is called
+//! and how many points it requires. Domain generation picks one of the
+//! built-in templates (or takes a custom one); the resulting levels can be
+//! edited freely afterwards through the draft/publish flow like any others.
+
+use serde::{Deserialize, Serialize};
+
+use super::models::ServiceError;
+
+/// Fewest levels a template may define
+pub const MIN_LEVELS: usize = 2;
+
+/// Most levels a template may define; level numbers must fit in a `u8`
+pub const MAX_LEVELS: usize = 12;
+
+/// Template used when generation doesn't ask for one
+pub const DEFAULT_TEMPLATE: &str = "standard";
+
+/// Placeholder replaced by the domain name in tier descriptions
+const DOMAIN_PLACEHOLDER: &str = "{domain}";
+
+/// Points required per level, lowest level first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PointCurve {
+    /// One explicit value per tier
+    Explicit { points: Vec<i64> },
+    /// `start`, `start + step`, `start + 2 * step`, ...
+    Linear { start: i64, step: i64 },
+    /// `start`, `start * ratio`, `start * ratio^2`, ... rounded to the nearest 10
+    Geometric { start: i64, ratio: f64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelTier {
+    /// Tier name; the level is named "<domain> <tier>"
+    pub name: String,
+    /// Optional description; `{domain}` is replaced by the domain name
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelTemplate {
+    /// Tiers from lowest to highest
+    pub tiers: Vec<LevelTier>,
+    #[serde(rename = "pointCurve")]
+    pub point_curve: PointCurve,
+}
+
+/// A template by built-in name, or a custom one supplied inline
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum LevelTemplateChoice {
+    Builtin(String),
+    Custom(LevelTemplate),
+}
+
+impl LevelTemplateChoice {
+    /// Name recorded on the Domain node (`custom` for inline templates)
+    pub fn key(&self) -> &str {
+        match self {
+            LevelTemplateChoice::Builtin(key) => key,
+            LevelTemplateChoice::Custom(_) => "custom",
+        }
+    }
+
+    /// Resolve to a validated template
+    pub fn resolve(&self) -> Result<LevelTemplate, ServiceError> {
+        let template = match self {
+            LevelTemplateChoice::Builtin(key) => builtin_template(key).ok_or_else(|| {
+                ServiceError::ValidationError(format!(
+                    "unknown level template '{}' (expected one of {})",
+                    key,
+                    BUILTIN_TEMPLATES
+                        .iter()
+                        .map(|t| t.key)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })?,
+            LevelTemplateChoice::Custom(template) => template.clone(),
+        };
+        template.validate()?;
+        Ok(template)
+    }
+}
+
+impl Default for LevelTemplateChoice {
+    fn default() -> Self {
+        LevelTemplateChoice::Builtin(DEFAULT_TEMPLATE.to_string())
+    }
+}
+
+/// A concrete level produced by a template for a given domain
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateLevel {
+    pub level: u8,
+    pub tier: String,
+    pub name: String,
+    pub description: String,
+    pub points_required: i64,
+}
+
+impl LevelTemplate {
+    pub fn validate(&self) -> Result<(), ServiceError> {
+        let count = self.tiers.len();
+        if !(MIN_LEVELS..=MAX_LEVELS).contains(&count) {
+            return Err(ServiceError::ValidationError(format!(
+                "level templates need between {} and {} tiers, got {}",
+                MIN_LEVELS, MAX_LEVELS, count
+            )));
+        }
+        if self.tiers.iter().any(|t| t.name.trim().is_empty()) {
+            return Err(ServiceError::ValidationError(
+                "level tier names must not be empty".to_string(),
+            ));
+        }
+
+        match &self.point_curve {
+            PointCurve::Explicit { points } if points.len() != count => {
+                return Err(ServiceError::ValidationError(format!(
+                    "explicit point curve has {} values for {} tiers",
+                    points.len(),
+                    count
+                )));
+            }
+            PointCurve::Linear { step, .. } if *step <= 0 => {
+                return Err(ServiceError::ValidationError(
+                    "linear point curve step must be positive".to_string(),
+                ));
+            }
+            PointCurve::Geometric { ratio, .. } if !ratio.is_finite() || *ratio <= 1.0 => {
+                return Err(ServiceError::ValidationError(
+                    "geometric point curve ratio must be greater than 1".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        let Some(points) = self.points() else {
+            return Err(ServiceError::ValidationError(
+                "linear point curve overflows the points range".to_string(),
+            ));
+        };
+        if points[0] <= 0 || points.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(ServiceError::ValidationError(
+                "points required must be positive and increase with each level".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Points required for each tier, lowest first; `None` if a linear curve
+    /// overflows `i64`
+    pub fn points(&self) -> Option<Vec<i64>> {
+        let count = self.tiers.len();
+        match &self.point_curve {
+            PointCurve::Explicit { points } => Some(points.clone()),
+            PointCurve::Linear { start, step } => (0..count as i64)
+                .map(|i| {
+                    i.checked_mul(*step)
+                        .and_then(|offset| start.checked_add(offset))
+                })
+                .collect(),
+            PointCurve::Geometric { start, ratio } => Some(
+                (0..count as i32)
+                    .map(|i| ((*start as f64 * ratio.powi(i) / 10.0).round() * 10.0) as i64)
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The levels this template produces for a domain
+    pub fn levels_for(&self, domain_name: &str) -> Vec<TemplateLevel> {
+        self.tiers
+            .iter()
+            // `validate` rejects templates whose points overflow
+            .zip(self.points().unwrap_or_default())
+            .enumerate()
+            .map(|(i, (tier, points_required))| TemplateLevel {
+                level: (i + 1) as u8,
+                tier: tier.name.clone(),
+                name: format!("{} {}", domain_name, tier.name),
+                description: match &tier.description {
+                    Some(description) => description.replace(DOMAIN_PLACEHOLDER, domain_name),
+                    None => format!("{} level in {}", tier.name, domain_name),
+                },
+                points_required,
+            })
+            .collect()
+    }
+}
+
+/// Check that hand-edited levels are numbered 1..=n without gaps or
+/// duplicates, and that there are no more than `MAX_LEVELS` of them.
+pub fn validate_level_numbers(levels: &[i64]) -> Result<(), ServiceError> {
+    if levels.len() > MAX_LEVELS {
+        return Err(ServiceError::ValidationError(format!(
+            "domains can have at most {} levels, got {}",
+            MAX_LEVELS,
+            levels.len()
+        )));
+    }
+
+    let mut sorted = levels.to_vec();
+    sorted.sort_unstable();
+    if sorted
+        .iter()
+        .zip(1..)
+        .any(|(level, expected)| *level != expected)
+    {
+        return Err(ServiceError::ValidationError(
+            "levels must be numbered 1 to n without gaps or duplicates".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// ========== Built-in templates ==========
+
+pub struct BuiltinTemplate {
+    pub key: &'static str,
+    pub summary: &'static str,
+    tiers: &'static [(&'static str, &'static str)],
+    points: &'static [i64],
+}
+
+impl BuiltinTemplate {
+    pub fn template(&self) -> LevelTemplate {
+        LevelTemplate {
+            tiers: self
+                .tiers
+                .iter()
+                .map(|(name, description)| LevelTier {
+                    name: name.to_string(),
+                    description: Some(description.to_string()),
+                })
+                .collect(),
+            point_curve: PointCurve::Explicit {
+                points: self.points.to_vec(),
+            },
+        }
+    }
+}
+
+pub const BUILTIN_TEMPLATES: &[BuiltinTemplate] = &[
+    BuiltinTemplate {
+        key: "standard",
+        summary: "Five tiers from Novice to Master",
+        tiers: &[
+            ("Novice", "Beginning your journey in {domain}"),
+            ("Developing", "Building foundational skills in {domain}"),
+            ("Competent", "Demonstrating solid competence in {domain}"),
+            ("Advanced", "Achieving advanced mastery in {domain}"),
+            ("Master", "Expert-level mastery of {domain}"),
+        ],
+        points: &[100, 200, 400, 700, 1000],
+    },
+    BuiltinTemplate {
+        key: "compact",
+        summary: "Three tiers for narrow or casual domains",
+        tiers: &[
+            ("Beginner", "Getting started with {domain}"),
+            ("Practitioner", "Applying {domain} with confidence"),
+            ("Expert", "Deep expertise in {domain}"),
+        ],
+        points: &[100, 300, 700],
+    },
+    BuiltinTemplate {
+        key: "extended",
+        summary: "Seven tiers for broad domains with a long path to mastery",
+        tiers: &[
+            ("Novice", "Beginning your journey in {domain}"),
+            ("Beginner", "Learning the fundamentals of {domain}"),
+            ("Developing", "Building foundational skills in {domain}"),
+            ("Competent", "Demonstrating solid competence in {domain}"),
+            ("Proficient", "Working fluently in {domain}"),
+            ("Advanced", "Achieving advanced mastery in {domain}"),
+            ("Master", "Expert-level mastery of {domain}"),
+        ],
+        points: &[100, 200, 350, 550, 800, 1100, 1500],
+    },
+];
+
+/// Look up a built-in template by key
+pub fn builtin_template(key: &str) -> Option<LevelTemplate> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|t| t.key == key)
+        .map(BuiltinTemplate::template)
+}
+
+// ========== Proficiency guidelines ==========
+
+/// Bloom's taxonomy, lowest to highest
+pub const BLOOM_LEVELS: &[&str] = &[
+    "Remember",
+    "Understand",
+    "Apply",
+    "Analyze",
+    "Evaluate",
+    "Create",
+];
+/// Dreyfus skill acquisition stages, lowest to highest
+pub const DREYFUS_LEVELS: &[&str] = &[
+    "Novice",
+    "Advanced Beginner",
+    "Competent",
+    "Proficient",
+    "Expert",
+];
+
+/// Position of a named level on an ordered scale, ignoring case and `_` vs space
+pub fn scale_rank(scale: &[&str], value: Option<&str>) -> Option<usize> {
+    let value = value?.replace('_', " ");
+    scale.iter().position(|s| s.eq_ignore_ascii_case(&value))
+}
+
+/// Position of `level` (1-based) out of `count`, scaled onto `0..=max`
+fn scaled(level: usize, count: usize, max: usize) -> usize {
+    if count <= 1 {
+        return max;
+    }
+    (level - 1) * max / (count - 1)
+}
+
+/// Suggested proficiency requirements for one level, spreading the Bloom and
+/// Dreyfus scales and trait scores evenly over however many levels there are.
+pub fn proficiency_guideline(level: usize, count: usize) -> String {
+    let bloom = BLOOM_LEVELS[scaled(level, count, BLOOM_LEVELS.len() - 1)];
+    let dreyfus = DREYFUS_LEVELS[scaled(level, count, DREYFUS_LEVELS.len() - 1)];
+    let traits = if level == 1 {
+        "no trait requirements".to_string()
+    } else {
+        format!("traits ~{}", 40 + scaled(level - 1, count - 1, 45))
+    };
+    format!("Knowledge at {}, skills at {}, {}", bloom, dreyfus, traits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear_template(start: i64, step: i64) -> LevelTemplate {
+        LevelTemplate {
+            tiers: ["Novice", "Adept", "Expert"]
+                .iter()
+                .map(|name| LevelTier {
+                    name: name.to_string(),
+                    description: None,
+                })
+                .collect(),
+            point_curve: PointCurve::Linear { start, step },
+        }
+    }
+
+    #[test]
+    fn linear_curve_adds_the_step_per_level() {
+        let template = linear_template(100, 50);
+        assert!(template.validate().is_ok());
+        assert_eq!(template.points(), Some(vec![100, 150, 200]));
+    }
+
+    #[test]
+    fn overflowing_linear_curve_is_rejected() {
+        let template = linear_template(i64::MAX - 10, i64::MAX / 2);
+        assert_eq!(template.points(), None);
+        assert!(matches!(
+            template.validate(),
+            Err(ServiceError::ValidationError(_))
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod handlers;
+pub mod level_template;
 pub mod models;
+pub mod schema;
 pub mod services;
 
 // Re-export commonly used items
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::common::pagination::Page;
+use crate::common::similarity::FindSimilarNodesRequest;
+use crate::domains::graph::schema::SchemaViolation;
 
 // ========== Error Types ==========
 
@@ -8,9 +12,14 @@ use std::collections::HashMap;
 pub enum ServiceError {
     DatabaseError(String),
     EmbeddingFailed(String),
-    SimilarNodeExists { score: f64, details: String },
+    SimilarNodeExists {
+        score: f64,
+        details: String,
+    },
     NotFound(String),
     ValidationError(String),
+    /// Schema violations, one per offending field
+    InvalidFields(Vec<SchemaViolation>),
 }
 
 impl std::fmt::Display for ServiceError {
@@ -23,12 +32,28 @@ impl std::fmt::Display for ServiceError {
             }
             ServiceError::NotFound(msg) => write!(f, "Not found: {}", msg),
             ServiceError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            ServiceError::InvalidFields(fields) => {
+                let fields: Vec<String> = fields.iter().map(|v| v.to_string()).collect();
+                write!(f, "Validation error: {}", fields.join("; "))
+            }
         }
     }
 }
 
 impl std::error::Error for ServiceError {}
 
+impl From<neo4rs::Error> for ServiceError {
+    fn from(e: neo4rs::Error) -> Self {
+        ServiceError::DatabaseError(e.to_string())
+    }
+}
+
+impl From<crate::common::cypher::CypherError> for ServiceError {
+    fn from(e: crate::common::cypher::CypherError) -> Self {
+        ServiceError::ValidationError(e.to_string())
+    }
+}
+
 // ========== Query Parameter Types ==========
 
 #[derive(Debug, Deserialize)]
@@ -36,6 +61,19 @@ pub struct NodeQueryParams {
     pub labels: Option<String>,
     pub properties: Option<String>,
     pub depth: Option<i32>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    /// Comma-separated node properties to return; all when omitted
+    pub fields: Option<String>,
+    #[serde(rename = "includeEmbedding", default)]
+    pub include_embedding: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateNodeParams {
+    /// Overrides the configured `duplicate` threshold for this request
+    #[serde(rename = "similarityThreshold")]
+    pub similarity_threshold: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,11 +81,19 @@ pub struct GetNodeWithRelationshipsBySearchTermParams {
     #[serde(rename = "searchTerm")]
     pub search_term: String,
     pub depth: Option<i32>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    /// Comma-separated node properties to return; all when omitted
+    pub fields: Option<String>,
+    #[serde(rename = "includeEmbedding", default)]
+    pub include_embedding: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct GetDomainParams {
     pub name: String,
+    /// Specific version to load; defaults to the published one
+    pub version: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -55,6 +101,21 @@ pub struct SearchNodesParams {
     pub query: String,
     pub labels: Option<String>,
     pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    /// Comma-separated node properties to return; all when omitted
+    pub fields: Option<String>,
+    #[serde(rename = "includeEmbedding", default)]
+    pub include_embedding: bool,
+}
+
+/// Body of `similar-nodes`: the similarity search, paged over its nearest candidates
+#[derive(Debug, Deserialize)]
+pub struct SimilarNodesRequest {
+    #[serde(flatten)]
+    pub search: FindSimilarNodesRequest,
+    pub cursor: Option<String>,
+    /// Comma-separated result fields to return; all when omitted
+    pub fields: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -85,6 +146,15 @@ pub struct UpdateNodeRequest {
     pub properties: Option<HashMap<String, Value>>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SoftDeleteNodeRequest {
+    #[serde(rename = "targetId")]
+    pub target_id: String,
+    /// Clear the soft delete instead of setting it
+    #[serde(default)]
+    pub restore: bool,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct NodeData {
     pub element_id: String,
@@ -92,11 +162,13 @@ pub struct NodeData {
     pub properties: HashMap<String, Value>,
 }
 
+/// A node with its relationships and related nodes, all Bolt5-shaped
 #[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct NodeWithRelationships {
     pub node: Value,
-    pub relationships: Value,
-    pub affiliated_nodes: Value,
+    pub relationships: Vec<Value>,
+    pub affiliated_nodes: Vec<Value>,
 }
 
 // ========== Relationship Request/Response Types ==========
@@ -154,6 +226,8 @@ pub struct KnowledgeRequirement {
     #[serde(rename = "newNode")]
     pub new_node: Option<NewNodeData>,
     pub bloom_level: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -163,6 +237,8 @@ pub struct SkillRequirement {
     #[serde(rename = "newNode")]
     pub new_node: Option<NewNodeData>,
     pub dreyfus_level: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -172,6 +248,8 @@ pub struct TraitRequirement {
     #[serde(rename = "newNode")]
     pub new_node: Option<NewNodeData>,
     pub min_score: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -180,6 +258,43 @@ pub struct MilestoneRequirement {
     pub node_element_id: Option<String>,
     #[serde(rename = "newNode")]
     pub new_node: Option<NewNodeData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+}
+
+/// How a requirement group is satisfied
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RequirementGroupMode {
+    /// Every member must be met
+    AllOf,
+    /// At least `count` members must be met (default 1)
+    AnyOf,
+    /// At least `count` members must be met at `proficiency` or higher
+    AtLeast,
+}
+
+impl RequirementGroupMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequirementGroupMode::AllOf => "all_of",
+            RequirementGroupMode::AnyOf => "any_of",
+            RequirementGroupMode::AtLeast => "at_least",
+        }
+    }
+}
+
+/// A named group of a level's requirements, stored as a `Requirement_Group` node
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RequirementGroup {
+    /// Unique within the level; members refer to the group by this name
+    pub name: String,
+    pub mode: RequirementGroupMode,
+    /// Members that must be met for `any_of` and `at_least`
+    pub count: Option<i64>,
+    /// Floor for `at_least`: a Bloom level, Dreyfus level or trait score
+    pub proficiency: Option<String>,
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -197,6 +312,14 @@ pub struct DomainLevel {
     pub description: Option<String>,
     pub points_required: i64,
     pub requirements: LevelRequirements,
+    /// Requirements list the groups they belong to by name; requirements in no
+    /// group are required individually. Same shape as `get_domain` returns
+    #[serde(
+        rename = "requirementGroups",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub requirement_groups: Vec<RequirementGroup>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -233,7 +356,10 @@ pub struct UpdateDomainRequest {
     pub domain_element_id: String,
     pub domain: DomainInfo,
     pub levels: Vec<DomainLevel>,
-    #[serde(rename = "removedNodeElementIds")]
+    /// Components dropped by the editor. They are recorded on the draft, and
+    /// user progress on them is deleted when the draft is published, unless
+    /// a live level or a version someone is pinned to still requires them.
+    #[serde(rename = "removedNodeElementIds", default)]
     pub removed_node_element_ids: Vec<String>,
 }
 
@@ -243,11 +369,853 @@ pub struct UpdateDomainResult {
     pub domain_element_id: String,
     pub domain_name: String,
     pub created_nodes: Vec<CreatedNodeInfo>,
+    pub draft_version: i64,
+    /// User progress edges on the removed components
     pub affected_user_progress_count: i64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ForkDomainRequest {
+    #[serde(rename = "sourceName")]
+    pub source_name: String,
+    /// Version of the source to copy; defaults to the published one
+    #[serde(rename = "sourceVersion")]
+    pub source_version: Option<i64>,
+    pub name: String,
+    /// Description for the fork; defaults to the source's
+    pub description: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct DomainNameValidation {
     pub available: bool,
     pub existing_domain_element_id: Option<String>,
 }
+
+// ========== Import/Export Types ==========
+
+#[derive(Debug, Deserialize)]
+pub struct ExportGraphParams {
+    pub domain: Option<String>,
+    pub labels: Option<String>,
+    pub properties: Option<String>,
+    pub format: Option<String>,
+    #[serde(rename = "includeEmbeddings")]
+    pub include_embeddings: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportGraphParams {
+    #[serde(rename = "dryRun")]
+    pub dry_run: Option<bool>,
+}
+
+/// Natural key identifying a node across environments: primary label + name,
+/// plus the owning level's name for requirement groups, which are only unique
+/// within their level
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct NodeKey {
+    pub label: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+}
+
+impl std::fmt::Display for NodeKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.level {
+            Some(level) => write!(f, "{}:{}/{}", self.label, level, self.name),
+            None => write!(f, "{}:{}", self.label, self.name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedNode {
+    pub key: NodeKey,
+    pub labels: Vec<String>,
+    pub properties: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedRelationship {
+    #[serde(rename = "type")]
+    pub relationship_type: String,
+    pub from: NodeKey,
+    pub to: NodeKey,
+    #[serde(default)]
+    pub properties: BTreeMap<String, Value>,
+}
+
+/// Portable graph document produced by export and accepted by import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphDocument {
+    #[serde(rename = "formatVersion")]
+    pub format_version: u32,
+    pub nodes: Vec<ExportedNode>,
+    #[serde(default)]
+    pub relationships: Vec<ExportedRelationship>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportAction {
+    Create,
+    Update,
+    Unchanged,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportChange {
+    pub kind: &'static str,
+    pub action: ImportAction,
+    pub key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportConflict {
+    pub key: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ImportCounts {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+impl ImportCounts {
+    pub fn record(&mut self, action: ImportAction) {
+        match action {
+            ImportAction::Create => self.created += 1,
+            ImportAction::Update => self.updated += 1,
+            ImportAction::Unchanged => self.unchanged += 1,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    pub applied: bool,
+    pub nodes: ImportCounts,
+    pub relationships: ImportCounts,
+    pub changes: Vec<ImportChange>,
+    pub conflicts: Vec<ImportConflict>,
+}
+
+// ========== Curated Domain Loader Types ==========
+
+#[derive(Debug, Deserialize)]
+pub struct LoadCuratedDomainsParams {
+    #[serde(rename = "dryRun")]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CuratedDomainStatus {
+    New,
+    Changed,
+    Unchanged,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CuratedDomainResult {
+    pub file: String,
+    pub domain: String,
+    pub status: CuratedDomainStatus,
+    pub statements: usize,
+    /// Set when an existing domain was reloaded into its draft
+    #[serde(rename = "draftVersion", skip_serializing_if = "Option::is_none")]
+    pub draft_version: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CuratedLoadReport {
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    pub domains: Vec<CuratedDomainResult>,
+    #[serde(rename = "embeddingsBackfilled")]
+    pub embeddings_backfilled: usize,
+    #[serde(rename = "embeddingFailures")]
+    pub embedding_failures: usize,
+}
+
+// ========== Domain Versioning Types ==========
+
+#[derive(Debug, Deserialize)]
+pub struct DomainVersionsParams {
+    pub name: String,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    /// Comma-separated version fields to return; all when omitted
+    pub fields: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PublishDomainRequest {
+    #[serde(rename = "domainElementId")]
+    pub domain_element_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MigrationReportParams {
+    pub name: String,
+    /// Version to move pursuers to; defaults to the latest version (the draft, if any)
+    #[serde(rename = "toVersion")]
+    pub to_version: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MigratePursuersRequest {
+    #[serde(rename = "domainName")]
+    pub domain_name: String,
+    /// Published version to move pursuers to; defaults to the current one
+    #[serde(rename = "toVersion")]
+    pub to_version: Option<i64>,
+    /// Only move these users; all pursuers when omitted
+    pub usernames: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DomainVersionStatus {
+    Draft,
+    Published,
+    Archived,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainVersionInfo {
+    pub version: i64,
+    pub status: DomainVersionStatus,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: Option<String>,
+    pub published_at: Option<String>,
+    pub level_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainVersions {
+    pub domain_element_id: String,
+    pub domain_name: String,
+    pub published_version: Option<i64>,
+    /// `DomainVersionInfo` items, oldest first
+    #[serde(flatten)]
+    pub versions: Page<Value>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishDomainResult {
+    pub domain_element_id: String,
+    pub domain_name: String,
+    pub version: i64,
+    pub previous_version: i64,
+    /// PURSUING edges that had no version yet and were pinned to the previous one
+    pub pinned_pursuers: i64,
+    /// User progress edges deleted on components the draft removed
+    pub deleted_user_progress: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PursuerMigration {
+    pub username: String,
+    pub from_version: i64,
+    pub from_level: i64,
+    pub to_level: i64,
+    pub level_changed: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub domain_name: String,
+    pub to_version: i64,
+    pub applied: bool,
+    pub changed: usize,
+    pub pursuers: Vec<PursuerMigration>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DomainProgressParams {
+    pub name: String,
+    pub username: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelProgress {
+    pub level: i64,
+    pub met: bool,
+}
+
+/// A user's standing in a domain, evaluated on the version they are pinned to
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainProgress {
+    pub domain_name: String,
+    pub username: String,
+    pub version: i64,
+    /// Highest level met along with every level below it
+    pub current_level: i64,
+    pub levels: Vec<LevelProgress>,
+}
+
+// ========== Domain Diff Types ==========
+
+#[derive(Debug, Deserialize)]
+pub struct DomainDiffParams {
+    pub name: String,
+    /// Version of `name` to diff from; defaults to the published one
+    #[serde(rename = "fromVersion")]
+    pub from_version: Option<i64>,
+    /// Domain to diff against; defaults to `name` itself
+    #[serde(rename = "otherName")]
+    pub other_name: Option<String>,
+    /// Version to diff against; defaults to the latest version when comparing
+    /// a domain with itself, otherwise to the published one
+    #[serde(rename = "toVersion")]
+    pub to_version: Option<i64>,
+    /// `json` (default) or `text` for the human-readable summary only
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffSide {
+    pub name: String,
+    pub version: Option<i64>,
+}
+
+/// Component identity in a diff: its type and name
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct ComponentRef {
+    #[serde(rename = "type")]
+    pub component_type: String,
+    pub name: String,
+}
+
+impl std::fmt::Display for ComponentRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} '{}'", self.component_type, self.name)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PropertyChange {
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentMove {
+    #[serde(flatten)]
+    pub component: ComponentRef,
+    pub from_level: i64,
+    pub to_level: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequirementChange {
+    #[serde(flatten)]
+    pub component: ComponentRef,
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComponentChange {
+    #[serde(flatten)]
+    pub component: ComponentRef,
+    pub changes: Vec<PropertyChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LevelDiffStatus {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelDiff {
+    pub level: i64,
+    pub status: LevelDiffStatus,
+    pub property_changes: Vec<PropertyChange>,
+    pub added: Vec<ComponentRef>,
+    pub removed: Vec<ComponentRef>,
+    pub moved_in: Vec<ComponentMove>,
+    pub moved_out: Vec<ComponentMove>,
+    pub requirement_changes: Vec<RequirementChange>,
+    /// Names of requirement groups only on the `to` side
+    pub groups_added: Vec<String>,
+    /// Names of requirement groups only on the `from` side
+    pub groups_removed: Vec<String>,
+    pub group_changes: Vec<GroupChange>,
+}
+
+/// Change to a requirement group present on both sides: `mode`, `count`,
+/// `proficiency` or `description`
+#[derive(Debug, Serialize)]
+pub struct GroupChange {
+    pub group: String,
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainDiff {
+    pub from: DiffSide,
+    pub to: DiffSide,
+    pub identical: bool,
+    pub property_changes: Vec<PropertyChange>,
+    pub levels: Vec<LevelDiff>,
+    pub component_changes: Vec<ComponentChange>,
+    /// One human-readable line per change
+    pub summary: Vec<String>,
+}
+
+// ========== Domain Integrity Types ==========
+
+#[derive(Debug, Deserialize)]
+pub struct DomainIntegrityParams {
+    pub name: String,
+    /// Version to check; defaults to the published (live) domain
+    pub version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Warning,
+    Error,
+}
+
+/// Which integrity check produced an issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityCheck {
+    EmptyLevel,
+    ProficiencyRegression,
+    PrerequisiteCycle,
+    PointsNotIncreasing,
+    OrphanedLevel,
+    MissingEmbedding,
+    MissingProperty,
+    BrokenGeneralization,
+}
+
+impl IntegrityCheck {
+    pub fn severity(&self) -> IssueSeverity {
+        match self {
+            IntegrityCheck::EmptyLevel
+            | IntegrityCheck::ProficiencyRegression
+            | IntegrityCheck::OrphanedLevel
+            | IntegrityCheck::MissingEmbedding => IssueSeverity::Warning,
+            IntegrityCheck::PrerequisiteCycle
+            | IntegrityCheck::PointsNotIncreasing
+            | IntegrityCheck::MissingProperty
+            | IntegrityCheck::BrokenGeneralization => IssueSeverity::Error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityIssue {
+    pub check: IntegrityCheck,
+    pub severity: IssueSeverity,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<ComponentRef>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainIntegrityReport {
+    pub domain_name: String,
+    pub version: Option<i64>,
+    /// True when no error-level issues were found
+    pub valid: bool,
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub domains_checked: usize,
+    pub domains_with_errors: usize,
+    /// Domain_Level nodes that no Domain or Domain_Version owns
+    pub orphaned_levels: Vec<IntegrityIssue>,
+    /// Only domains with at least one issue
+    pub domains: Vec<DomainIntegrityReport>,
+}
+
+// ========== Taxonomy Types ==========
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCategoryRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// Parent category name; omitted for a top-level category
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetDomainTaxonomyRequest {
+    #[serde(rename = "domainName")]
+    pub domain_name: String,
+    /// Category to file the domain under; an empty string removes it from its category
+    pub category: Option<String>,
+    /// Replaces the domain's tags when given
+    pub tags: Option<Vec<String>>,
+}
+
+/// Paging and field selection for the taxonomy lists
+#[derive(Debug, Deserialize)]
+pub struct TaxonomyListParams {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    /// Comma-separated item fields to return; all when omitted
+    pub fields: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryNode {
+    pub name: String,
+    pub description: Option<String>,
+    /// Domains filed directly under this category
+    pub domain_count: i64,
+    /// Domains filed under this category or any of its subcategories
+    pub total_domain_count: i64,
+    pub children: Vec<CategoryNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainSummary {
+    pub element_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub avatar_url: Option<String>,
+    /// Category path from the root, e.g. `["Sports", "Combat Sports"]`
+    #[serde(default)]
+    pub category_path: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: i64,
+}
+
+// ========== Domain Listing Types ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DomainSort {
+    Name,
+    Created,
+    /// Number of people pursuing the domain
+    Popularity,
+    /// Semantic similarity to `semantic`; only valid when it is given
+    Relevance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListDomainsParams {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    /// Defaults to `relevance` when `semantic` is given, `name` otherwise
+    pub sort: Option<DomainSort>,
+    /// Defaults to ascending for `name`, descending for everything else
+    pub order: Option<SortOrder>,
+    /// Case-insensitive substring match on name and description
+    pub query: Option<String>,
+    /// Free text compared against domain embeddings
+    pub semantic: Option<String>,
+    /// Minimum similarity for `semantic` matches (default 0.6)
+    #[serde(rename = "minScore")]
+    pub min_score: Option<f64>,
+    /// Only domains filed under this category
+    pub category: Option<String>,
+    /// Comma-separated; domains must carry all of them
+    pub tags: Option<String>,
+    /// Include domains filed under subcategories of `category` (default true)
+    #[serde(rename = "includeSubcategories")]
+    pub include_subcategories: Option<bool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentCounts {
+    pub knowledge: i64,
+    pub skills: i64,
+    pub traits: i64,
+    pub milestones: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainListItem {
+    pub element_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub avatar_url: Option<String>,
+    pub created_date: Option<String>,
+    pub pursuer_count: i64,
+    pub level_count: i64,
+    /// Distinct components required across the published levels
+    pub components: ComponentCounts,
+    /// Category path from the root, e.g. `["Sports", "Combat Sports"]`
+    #[serde(default)]
+    pub category_path: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Similarity score; only present for `semantic` queries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relevance: Option<f64>,
+}
+
+// ========== Re-embedding Types ==========
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartReembedRequest {
+    /// Nodes embedded per batch (default 32)
+    pub batch_size: Option<i64>,
+    /// Pause between batches, to spare the embedding service (default 250)
+    pub delay_ms: Option<u64>,
+    /// Discard the checkpoint of an unfinished run and start over
+    #[serde(default)]
+    pub restart: bool,
+}
+
+/// Progress of the re-embed job, persisted on the `_ReembedJob` node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReembedJob {
+    /// Model and recipe version nodes are being moved to
+    pub model: String,
+    pub version: i64,
+    /// `running`, `completed` or `failed`
+    pub status: String,
+    /// Element id of the last node re-embedded; the walk resumes after it
+    pub checkpoint: Option<String>,
+    pub processed: i64,
+    /// Nodes that needed re-embedding when the run started
+    pub total: i64,
+    pub started_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub error: Option<String>,
+}
+
+// ========== Graph Layout Types ==========
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartLayoutRequest {
+    /// Recompute every position and cluster instead of placing only new nodes
+    #[serde(default)]
+    pub full: bool,
+}
+
+/// Last layout run, persisted on the `_GraphLayout` node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphLayoutJob {
+    /// `running`, `completed` or `failed`
+    pub status: String,
+    /// `full` or `incremental`
+    pub mode: String,
+    /// Nodes in the laid-out graph
+    pub nodes: i64,
+    /// Nodes given new coordinates by this run
+    pub placed: i64,
+    pub clusters: i64,
+    pub started_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub error: Option<String>,
+}
+
+// ========== Graph Analytics Types ==========
+
+/// Last component metrics run, persisted on the `_GraphAnalytics` node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphAnalyticsJob {
+    /// `running`, `completed` or `failed`
+    pub status: String,
+    /// Components given fresh metrics by the last completed run
+    pub components: i64,
+    pub started_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ComponentRanking {
+    /// Most domains requiring the component first
+    #[default]
+    Domains,
+    /// Highest PageRank first
+    Pagerank,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferableComponentsParams {
+    /// Only components with this label (`Knowledge`, `Skill`, `Trait` or `Milestone`)
+    pub label: Option<String>,
+    #[serde(default)]
+    pub order_by: ComponentRanking,
+    /// Number of components to return (default 20)
+    pub limit: Option<i64>,
+}
+
+/// Metrics stored on a component by the analytics job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentMetrics {
+    pub element_id: String,
+    pub label: String,
+    pub name: String,
+    /// Published domains requiring the component at some level
+    pub domain_count: i64,
+    /// PageRank over requirement and generalization links, scaled so the
+    /// average node scores 1
+    pub pagerank: f64,
+    /// Mean required proficiency across those domains, from 0 (lowest step of
+    /// the Bloom or Dreyfus scale, trait score 0) to 1; absent for milestones
+    pub avg_proficiency: Option<f64>,
+}
+
+// ========== Visualization Types ==========
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphParams {
+    /// Element id to expand from; without it every node with one of `labels` is a seed
+    pub root: Option<String>,
+    /// Comma-separated seed labels when no root is given (default `Domain`)
+    pub labels: Option<String>,
+    /// Comma-separated relationship types to follow; all types when omitted
+    pub relationship_types: Option<String>,
+    /// Hops from the seeds (default 2)
+    pub depth: Option<i64>,
+    /// Most nodes returned (default 300)
+    pub max_nodes: Option<i64>,
+    /// Include 2D coordinates for each node: those stored by the layout job
+    /// when every node has them, otherwise a radial layout around the seeds
+    #[serde(default)]
+    pub layout: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphNode {
+    pub id: String,
+    pub labels: Vec<String>,
+    pub properties: Value,
+    /// Hops from the nearest seed
+    pub depth: i64,
+    /// Relationships of the followed types in the whole graph, shown or not
+    pub degree: i64,
+    /// Some of this node's relationships are not in the response
+    pub truncated: bool,
+    /// Community assigned by the layout job
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphEdge {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub relationship_type: String,
+    pub source: String,
+    pub target: String,
+    pub properties: Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subgraph {
+    pub nodes: Vec<SubgraphNode>,
+    pub edges: Vec<SubgraphEdge>,
+    /// The node budget was reached before the expansion finished
+    pub truncated: bool,
+    pub max_nodes: i64,
+}
+
+// ========== Path Types ==========
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathParams {
+    /// Element id of the first node
+    pub from: String,
+    /// Element id of the second node
+    pub to: String,
+    /// Number of paths to return (default 3)
+    pub k: Option<usize>,
+    /// Longest path considered, in relationships (default 6)
+    pub max_hops: Option<usize>,
+    /// Comma-separated subset of the traversable relationship types
+    pub relationship_types: Option<String>,
+}
+
+/// One path, in the `node`/`relationships`/`affiliatedNodes` shape of `get-nodes`:
+/// the start node, the relationships in path order, and the following nodes
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphPath {
+    /// Sum of relationship weights; generalization links are cheaper
+    pub cost: f64,
+    pub hops: usize,
+    pub node: Value,
+    pub relationships: Vec<Value>,
+    pub affiliated_nodes: Vec<Value>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathsResult {
+    pub paths: Vec<GraphPath>,
+    /// The search area hit its node budget, so some paths may be missing
+    pub truncated: bool,
+}
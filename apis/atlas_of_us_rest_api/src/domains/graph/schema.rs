@@ -0,0 +1,795 @@
+//! Declarative schema registry for graph labels and relationships.
+//!
+//! Every label that can be written through the generic node endpoints is described
+//! here along with its typed properties, and every relationship type lists the
+//! label pairs it may connect. `create_node`, `update_node` and the relationship
+//! services validate against this registry before building any Cypher.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::models::ServiceError;
+
+// ========== Schema Types ==========
+
+/// Type of a node property value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyType {
+    String,
+    Integer,
+    Float,
+    Boolean,
+    StringList,
+    FloatList,
+}
+
+impl PropertyType {
+    /// Check whether a JSON value matches this type (null is never accepted)
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            PropertyType::String => value.is_string(),
+            PropertyType::Integer => value.is_i64() || value.is_u64(),
+            PropertyType::Float => value.is_number(),
+            PropertyType::Boolean => value.is_boolean(),
+            PropertyType::StringList => value
+                .as_array()
+                .map(|arr| arr.iter().all(|v| v.is_string()))
+                .unwrap_or(false),
+            PropertyType::FloatList => value
+                .as_array()
+                .map(|arr| arr.iter().all(|v| v.is_number()))
+                .unwrap_or(false),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PropertyType::String => "string",
+            PropertyType::Integer => "integer",
+            PropertyType::Float => "number",
+            PropertyType::Boolean => "boolean",
+            PropertyType::StringList => "list of strings",
+            PropertyType::FloatList => "list of numbers",
+        }
+    }
+}
+
+/// A single property allowed on a label
+#[derive(Debug, Clone, Copy)]
+pub struct PropertySchema {
+    pub name: &'static str,
+    pub property_type: PropertyType,
+    pub required: bool,
+}
+
+const fn required(name: &'static str, property_type: PropertyType) -> PropertySchema {
+    PropertySchema {
+        name,
+        property_type,
+        required: true,
+    }
+}
+
+const fn optional(name: &'static str, property_type: PropertyType) -> PropertySchema {
+    PropertySchema {
+        name,
+        property_type,
+        required: false,
+    }
+}
+
+/// Schema for a primary node label
+#[derive(Debug, Clone, Copy)]
+pub struct LabelSchema {
+    pub label: &'static str,
+    pub properties: &'static [PropertySchema],
+    /// Secondary labels that may be combined with this one (e.g. `Person:L3`)
+    pub extra_labels: &'static [&'static str],
+}
+
+impl LabelSchema {
+    pub fn property(&self, name: &str) -> Option<&PropertySchema> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+}
+
+/// Schema for a relationship type and the label pairs it may connect
+#[derive(Debug, Clone, Copy)]
+pub struct RelationshipSchema {
+    pub relationship_type: &'static str,
+    pub from: &'static [&'static str],
+    pub to: &'static [&'static str],
+}
+
+impl RelationshipSchema {
+    pub fn allows(&self, from_labels: &[String], to_labels: &[String]) -> bool {
+        from_labels.iter().any(|l| self.from.contains(&l.as_str()))
+            && to_labels.iter().any(|l| self.to.contains(&l.as_str()))
+    }
+}
+
+/// A single field-level schema violation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaViolation {
+    pub field: String,
+    pub message: String,
+}
+
+impl SchemaViolation {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Report violations as a single `ServiceError::InvalidFields`
+fn violations_to_error(violations: Vec<SchemaViolation>) -> Result<(), ServiceError> {
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    Err(ServiceError::InvalidFields(violations))
+}
+
+// ========== Registry ==========
+
+use PropertyType::{Boolean, FloatList, Integer, String as Str, StringList};
+
+/// Properties maintained by the API itself rather than supplied by clients
+pub const SYSTEM_PROPERTIES: &[&str] = &[
+    "embedding",
+    "embedding_model",
+    "embedding_version",
+    SOFT_DELETE_PROPERTY,
+];
+
+/// Layer-one label carried by every live graph node: components, domains and
+/// their published levels. The `nodeEmbeddings` vector index is defined over it
+/// and the graph traversals only follow it
+pub const EMBEDDED_LABEL: &str = "L1";
+
+/// Coordinates and community written by the layout job; derived data, so
+/// neither accepted from clients nor exported
+pub const LAYOUT_PROPERTIES: &[&str] = &["layout_x", "layout_y", "cluster_id"];
+
+/// Per-component metrics written by the analytics job; derived like the layout
+/// properties, and likewise left out of exports
+pub const ANALYTICS_PROPERTIES: &[&str] = &["domain_count", "pagerank", "avg_proficiency"];
+
+/// Nodes carrying this property are soft-deleted: kept in the graph but left
+/// out of similarity search unless asked for, and out of traversals and exports.
+/// Set and cleared only through the soft-delete endpoints
+pub const SOFT_DELETE_PROPERTY: &str = "deleted_at";
+
+/// Label of the singleton node recording which embedding model and text recipe
+/// version the `nodeEmbeddings` index currently holds
+pub const EMBEDDING_SPACE_LABEL: &str = "_EmbeddingSpace";
+
+const KNOWLEDGE_PROPERTIES: &[PropertySchema] = &[
+    required("name", Str),
+    required("description", Str),
+    optional("how_to_learn", Str),
+    optional("remember_level", Str),
+    optional("understand_level", Str),
+    optional("apply_level", Str),
+    optional("analyze_level", Str),
+    optional("evaluate_level", Str),
+    optional("create_level", Str),
+    optional("embedding", FloatList),
+    optional("embedding_model", Str),
+    optional("embedding_version", Integer),
+    optional(SOFT_DELETE_PROPERTY, Str),
+];
+
+const SKILL_PROPERTIES: &[PropertySchema] = &[
+    required("name", Str),
+    required("description", Str),
+    optional("how_to_develop", Str),
+    optional("novice_level", Str),
+    optional("advanced_beginner_level", Str),
+    optional("competent_level", Str),
+    optional("proficient_level", Str),
+    optional("expert_level", Str),
+    optional("embedding", FloatList),
+    optional("embedding_model", Str),
+    optional("embedding_version", Integer),
+    optional(SOFT_DELETE_PROPERTY, Str),
+];
+
+const TRAIT_PROPERTIES: &[PropertySchema] = &[
+    required("name", Str),
+    required("description", Str),
+    optional("measurement_criteria", Str),
+    optional("embedding", FloatList),
+    optional("embedding_model", Str),
+    optional("embedding_version", Integer),
+    optional(SOFT_DELETE_PROPERTY, Str),
+];
+
+const MILESTONE_PROPERTIES: &[PropertySchema] = &[
+    required("name", Str),
+    required("description", Str),
+    optional("how_to_achieve", Str),
+    optional("embedding", FloatList),
+    optional("embedding_model", Str),
+    optional("embedding_version", Integer),
+    optional(SOFT_DELETE_PROPERTY, Str),
+];
+
+const DOMAIN_PROPERTIES: &[PropertySchema] = &[
+    required("name", Str),
+    required("description", Str),
+    optional("level_count", Integer),
+    optional("created_date", Str),
+    optional("scope_included", StringList),
+    optional("scope_excluded", StringList),
+    optional("avatar_url", Str),
+    optional("published_version", Integer),
+    optional("level_template", Str),
+    optional("tags", StringList),
+    optional("embedding", FloatList),
+    optional("embedding_model", Str),
+    optional("embedding_version", Integer),
+    optional(SOFT_DELETE_PROPERTY, Str),
+];
+
+const DOMAIN_LEVEL_PROPERTIES: &[PropertySchema] = &[
+    required("name", Str),
+    required("level", Integer),
+    optional("description", Str),
+    optional("total_points_required", Integer),
+    optional("domain", Str),
+    optional("embedding", FloatList),
+    optional("embedding_model", Str),
+    optional("embedding_version", Integer),
+    optional(SOFT_DELETE_PROPERTY, Str),
+];
+
+/// `mode` is `all_of`, `any_of` or `at_least`; members name their groups
+/// through the `groups` property of their requirement relationships
+const REQUIREMENT_GROUP_PROPERTIES: &[PropertySchema] = &[
+    required("name", Str),
+    required("mode", Str),
+    optional("count", Integer),
+    optional("proficiency", Str),
+    optional("description", Str),
+];
+
+const CATEGORY_PROPERTIES: &[PropertySchema] =
+    &[required("name", Str), optional("description", Str)];
+
+const PERSON_PROPERTIES: &[PropertySchema] = &[
+    required("username", Str),
+    optional("name", Str),
+    optional("phone", Str),
+    optional("photo", Str),
+    optional("avatar_url", Str),
+    optional("is_public", Boolean),
+    optional("embedding", FloatList),
+    optional("embedding_model", Str),
+    optional("embedding_version", Integer),
+];
+
+/// All labels that may be written through the generic node services
+pub const LABEL_SCHEMAS: &[LabelSchema] = &[
+    LabelSchema {
+        label: "Knowledge",
+        properties: KNOWLEDGE_PROPERTIES,
+        extra_labels: &[EMBEDDED_LABEL],
+    },
+    LabelSchema {
+        label: "Skill",
+        properties: SKILL_PROPERTIES,
+        extra_labels: &[EMBEDDED_LABEL],
+    },
+    LabelSchema {
+        label: "Trait",
+        properties: TRAIT_PROPERTIES,
+        extra_labels: &[EMBEDDED_LABEL],
+    },
+    LabelSchema {
+        label: "Milestone",
+        properties: MILESTONE_PROPERTIES,
+        extra_labels: &[EMBEDDED_LABEL],
+    },
+    LabelSchema {
+        label: "Domain",
+        properties: DOMAIN_PROPERTIES,
+        extra_labels: &[EMBEDDED_LABEL],
+    },
+    LabelSchema {
+        label: "Domain_Level",
+        properties: DOMAIN_LEVEL_PROPERTIES,
+        extra_labels: &[EMBEDDED_LABEL],
+    },
+    LabelSchema {
+        label: "Requirement_Group",
+        properties: REQUIREMENT_GROUP_PROPERTIES,
+        extra_labels: &[],
+    },
+    LabelSchema {
+        label: "Category",
+        properties: CATEGORY_PROPERTIES,
+        extra_labels: &[],
+    },
+    LabelSchema {
+        label: "Person",
+        properties: PERSON_PROPERTIES,
+        extra_labels: &["L3"],
+    },
+];
+
+/// Labels of the components domain levels require
+pub const COMPONENT_LABELS: &[&str] = &["Knowledge", "Skill", "Trait", "Milestone"];
+
+/// All relationship types that may be written through the relationship services
+pub const RELATIONSHIP_SCHEMAS: &[RelationshipSchema] = &[
+    RelationshipSchema {
+        relationship_type: "HAS_DOMAIN_LEVEL",
+        from: &["Domain"],
+        to: &["Domain_Level"],
+    },
+    RelationshipSchema {
+        relationship_type: "HAS_REQUIREMENT_GROUP",
+        from: &["Domain_Level"],
+        to: &["Requirement_Group"],
+    },
+    RelationshipSchema {
+        relationship_type: "REQUIRES_KNOWLEDGE",
+        from: &["Domain_Level", "Knowledge", "Skill", "Milestone"],
+        to: &["Knowledge"],
+    },
+    RelationshipSchema {
+        relationship_type: "REQUIRES_SKILL",
+        from: &["Domain_Level", "Skill", "Milestone"],
+        to: &["Skill"],
+    },
+    RelationshipSchema {
+        relationship_type: "REQUIRES_TRAIT",
+        from: &["Domain_Level", "Skill", "Milestone"],
+        to: &["Trait"],
+    },
+    RelationshipSchema {
+        relationship_type: "REQUIRES_MILESTONE",
+        from: &["Domain_Level", "Milestone"],
+        to: &["Milestone"],
+    },
+    RelationshipSchema {
+        relationship_type: "GENERALIZES_TO",
+        from: COMPONENT_LABELS,
+        to: COMPONENT_LABELS,
+    },
+    RelationshipSchema {
+        relationship_type: "MILESTONE_STRENGTHENS_TRAIT",
+        from: &["Milestone"],
+        to: &["Trait"],
+    },
+    RelationshipSchema {
+        relationship_type: "IN_CATEGORY",
+        from: &["Domain"],
+        to: &["Category"],
+    },
+    RelationshipSchema {
+        relationship_type: "SUBCATEGORY_OF",
+        from: &["Category"],
+        to: &["Category"],
+    },
+    RelationshipSchema {
+        relationship_type: "FORKED_FROM",
+        from: &["Domain"],
+        to: &["Domain"],
+    },
+    RelationshipSchema {
+        relationship_type: "HAS_KNOWLEDGE",
+        from: &["Person"],
+        to: &["Knowledge"],
+    },
+    RelationshipSchema {
+        relationship_type: "HAS_SKILL",
+        from: &["Person"],
+        to: &["Skill"],
+    },
+    RelationshipSchema {
+        relationship_type: "HAS_TRAIT",
+        from: &["Person"],
+        to: &["Trait"],
+    },
+    RelationshipSchema {
+        relationship_type: "ACHIEVED",
+        from: &["Person"],
+        to: &["Milestone"],
+    },
+    RelationshipSchema {
+        relationship_type: "PURSUING",
+        from: &["Person"],
+        to: &["Domain"],
+    },
+];
+
+/// Look up the schema for a primary label
+pub fn label_schema(label: &str) -> Option<&'static LabelSchema> {
+    LABEL_SCHEMAS.iter().find(|s| s.label == label)
+}
+
+/// Look up the schema for a relationship type
+pub fn relationship_schema(relationship_type: &str) -> Option<&'static RelationshipSchema> {
+    RELATIONSHIP_SCHEMAS
+        .iter()
+        .find(|s| s.relationship_type == relationship_type)
+}
+
+/// True if the label is registered as a primary or secondary label
+pub fn is_known_label(label: &str) -> bool {
+    LABEL_SCHEMAS
+        .iter()
+        .any(|s| s.label == label || s.extra_labels.contains(&label))
+}
+
+// ========== Validation ==========
+
+/// Resolve the single primary label schema for a label set, collecting violations
+fn resolve_primary_schema(
+    labels: &[String],
+    violations: &mut Vec<SchemaViolation>,
+) -> Option<&'static LabelSchema> {
+    if labels.is_empty() {
+        violations.push(SchemaViolation::new(
+            "labels",
+            "at least one label is required",
+        ));
+        return None;
+    }
+
+    let primaries: Vec<&'static LabelSchema> =
+        labels.iter().filter_map(|l| label_schema(l)).collect();
+
+    for label in labels {
+        if !is_known_label(label) {
+            violations.push(SchemaViolation::new(
+                "labels",
+                format!("unknown label '{}'", label),
+            ));
+        }
+    }
+
+    match primaries.as_slice() {
+        [] => {
+            violations.push(SchemaViolation::new(
+                "labels",
+                format!(
+                    "no primary label provided (expected one of {})",
+                    LABEL_SCHEMAS
+                        .iter()
+                        .map(|s| s.label)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ));
+            None
+        }
+        [schema] => {
+            for label in labels {
+                if label != schema.label
+                    && !schema.extra_labels.contains(&label.as_str())
+                    && is_known_label(label)
+                {
+                    violations.push(SchemaViolation::new(
+                        "labels",
+                        format!(
+                            "label '{}' cannot be combined with '{}'",
+                            label, schema.label
+                        ),
+                    ));
+                }
+            }
+            Some(schema)
+        }
+        _ => {
+            violations.push(SchemaViolation::new(
+                "labels",
+                format!(
+                    "only one primary label is allowed, got {}",
+                    primaries
+                        .iter()
+                        .map(|s| s.label)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ));
+            None
+        }
+    }
+}
+
+/// Check each supplied property against the label schema
+fn check_properties(
+    schema: &LabelSchema,
+    properties: &HashMap<String, Value>,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    for (key, value) in properties {
+        let field = format!("properties.{}", key);
+        match schema.property(key) {
+            None => violations.push(SchemaViolation::new(
+                field,
+                format!("unknown property for label '{}'", schema.label),
+            )),
+            Some(_) if SYSTEM_PROPERTIES.contains(&key.as_str()) => violations.push(
+                SchemaViolation::new(field, "property is managed by the server"),
+            ),
+            Some(prop) if !prop.property_type.matches(value) => violations.push(
+                SchemaViolation::new(field, format!("expected {}", prop.property_type.name())),
+            ),
+            Some(_) => {}
+        }
+    }
+}
+
+/// Validate a node about to be created: labels must resolve to exactly one primary
+/// label, every required property must be present and every property must be typed
+/// as declared.
+pub fn validate_new_node(
+    labels: &[String],
+    properties: &HashMap<String, Value>,
+) -> Result<&'static LabelSchema, ServiceError> {
+    let mut violations = Vec::new();
+    let schema = resolve_primary_schema(labels, &mut violations);
+
+    if let Some(schema) = schema {
+        for prop in schema.properties.iter().filter(|p| p.required) {
+            if !properties.contains_key(prop.name) {
+                violations.push(SchemaViolation::new(
+                    format!("properties.{}", prop.name),
+                    "required property is missing",
+                ));
+            }
+        }
+        check_properties(schema, properties, &mut violations);
+    }
+
+    violations_to_error(violations)?;
+    Ok(schema.expect("schema resolved when there are no violations"))
+}
+
+/// Validate a partial update. Labels are added to the node's `existing_labels`, so the
+/// combined set must still resolve to a single primary label. Required properties may
+/// not be removed by setting them to null.
+pub fn validate_node_update(
+    existing_labels: &[String],
+    labels: Option<&[String]>,
+    properties: Option<&HashMap<String, Value>>,
+) -> Result<(), ServiceError> {
+    let mut violations = Vec::new();
+    let mut effective_labels = existing_labels.to_vec();
+    for label in labels.unwrap_or_default() {
+        if !effective_labels.contains(label) {
+            effective_labels.push(label.clone());
+        }
+    }
+    let schema = resolve_primary_schema(&effective_labels, &mut violations);
+
+    if let (Some(schema), Some(props)) = (schema, properties) {
+        for (key, value) in props {
+            if value.is_null() && SYSTEM_PROPERTIES.contains(&key.as_str()) {
+                violations.push(SchemaViolation::new(
+                    format!("properties.{}", key),
+                    "property is managed by the server",
+                ));
+            } else if value.is_null() && schema.property(key).map(|p| p.required).unwrap_or(false) {
+                violations.push(SchemaViolation::new(
+                    format!("properties.{}", key),
+                    "required property cannot be removed",
+                ));
+            }
+        }
+        let non_null: HashMap<String, Value> = props
+            .iter()
+            .filter(|(_, v)| !v.is_null())
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        check_properties(schema, &non_null, &mut violations);
+    }
+
+    violations_to_error(violations)
+}
+
+/// Validate that a relationship type is registered and may connect the given labels
+pub fn validate_relationship(
+    relationship_type: &str,
+    from_labels: &[String],
+    to_labels: &[String],
+) -> Result<(), ServiceError> {
+    let Some(schema) = relationship_schema(relationship_type) else {
+        return violations_to_error(vec![SchemaViolation::new(
+            "type",
+            format!("unknown relationship type '{}'", relationship_type),
+        )]);
+    };
+
+    if !schema.allows(from_labels, to_labels) {
+        return violations_to_error(vec![SchemaViolation::new(
+            "type",
+            format!(
+                "{} cannot connect ({}) to ({}); allowed: ({}) -> ({})",
+                relationship_type,
+                from_labels.join(":"),
+                to_labels.join(":"),
+                schema.from.join("|"),
+                schema.to.join("|")
+            ),
+        )]);
+    }
+
+    Ok(())
+}
+
+/// Validate relationship property names (values are free-form scalars)
+pub fn validate_relationship_properties(
+    properties: &HashMap<String, Value>,
+) -> Result<(), ServiceError> {
+    let violations: Vec<SchemaViolation> = properties
+        .iter()
+        .filter(|(_, v)| v.is_object())
+        .map(|(k, _)| {
+            SchemaViolation::new(
+                format!("properties.{}", k),
+                "nested objects are not supported",
+            )
+        })
+        .collect();
+
+    violations_to_error(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+    use serde_json::json;
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn props(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn violations(result: Result<(), ServiceError>) -> Vec<SchemaViolation> {
+        match result {
+            Err(ServiceError::InvalidFields(violations)) => violations,
+            other => panic!("expected InvalidFields, got {:?}", other),
+        }
+    }
+
+    fn new_node_violations(node_labels: &[&str], properties: Value) -> Vec<SchemaViolation> {
+        violations(validate_new_node(&labels(node_labels), &props(properties)).map(|_| ()))
+    }
+
+    #[test]
+    fn accepts_a_valid_node() {
+        let schema = validate_new_node(
+            &labels(&["Skill"]),
+            &props(json!({"name": "Welding", "description": "Joining metal"})),
+        )
+        .unwrap();
+        assert_eq!(schema.label, "Skill");
+    }
+
+    #[test]
+    fn rejects_an_unknown_label() {
+        let found = new_node_violations(
+            &["Skill", "Spaceship"],
+            json!({"name": "Welding", "description": "Joining metal"}),
+        );
+        assert_eq!(
+            found,
+            vec![SchemaViolation::new("labels", "unknown label 'Spaceship'")]
+        );
+    }
+
+    #[test]
+    fn rejects_a_missing_required_property() {
+        let found = new_node_violations(&["Knowledge"], json!({"name": "Algebra"}));
+        assert_eq!(
+            found,
+            vec![SchemaViolation::new(
+                "properties.description",
+                "required property is missing"
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_a_wrongly_typed_property() {
+        let found = new_node_violations(
+            &["Domain_Level"],
+            json!({"name": "Chess 1", "level": "one"}),
+        );
+        assert_eq!(
+            found,
+            vec![SchemaViolation::new("properties.level", "expected integer")]
+        );
+    }
+
+    #[test]
+    fn rejects_writes_to_system_properties() {
+        let found = new_node_violations(
+            &["Trait"],
+            json!({"name": "Patience", "description": "Waiting calmly", "embedding": [0.1, 0.2]}),
+        );
+        assert_eq!(
+            found,
+            vec![SchemaViolation::new(
+                "properties.embedding",
+                "property is managed by the server"
+            )]
+        );
+
+        let found = violations(validate_node_update(
+            &labels(&["Trait", EMBEDDED_LABEL]),
+            None,
+            Some(&props(json!({SOFT_DELETE_PROPERTY: null}))),
+        ));
+        assert_eq!(
+            found,
+            vec![SchemaViolation::new(
+                "properties.deleted_at",
+                "property is managed by the server"
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_a_relationship_between_disallowed_labels() {
+        assert!(
+            validate_relationship(
+                "REQUIRES_SKILL",
+                &labels(&["Domain_Level"]),
+                &labels(&["Skill"])
+            )
+            .is_ok()
+        );
+
+        let found = violations(validate_relationship(
+            "REQUIRES_SKILL",
+            &labels(&["Knowledge"]),
+            &labels(&["Skill"]),
+        ));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].field, "type");
+        assert!(
+            found[0]
+                .message
+                .starts_with("REQUIRES_SKILL cannot connect (Knowledge) to (Skill)")
+        );
+    }
+
+    #[test]
+    fn validation_errors_carry_field_level_violations() {
+        let error =
+            validate_new_node(&labels(&["Knowledge"]), &props(json!({"name": 7}))).unwrap_err();
+        let (status, body) = AppError::from(error).into_json();
+        assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
+
+        let mut fields = body.0["fields"].as_array().unwrap().clone();
+        fields.sort_by_key(|f| f["field"].as_str().unwrap().to_string());
+        assert_eq!(
+            fields,
+            vec![
+                json!({"field": "properties.description", "message": "required property is missing"}),
+                json!({"field": "properties.name", "message": "expected string"}),
+            ]
+        );
+        assert_eq!(body.0["code"], "VALIDATION_ERROR");
+    }
+}
@@ -0,0 +1,516 @@
+//! Centrality metrics showing which components are foundational across domains.
+//!
+//! For every live component (Knowledge, Skill, Trait, Milestone) the job stores:
+//! - `domain_count`: published domains requiring it at some level
+//! - `pagerank`: PageRank over the requirement links of published levels and
+//!   components and the `GENERALIZES_TO` links, which all point from the
+//!   dependent node to what it builds on. Scores are multiplied by the number of
+//!   nodes so that 1 is average and values compare across runs.
+//! - `avg_proficiency`: the proficiency each domain asks for at its highest
+//!   level requiring the component, mapped onto `0..=1` and averaged
+//!
+//! The scheduler started with the server recomputes everything on start-up and
+//! then every `ANALYTICS_REFRESH_SECS` seconds (default 3600, `0` disables it).
+
+use neo4rs::{BoltMap, BoltType, Graph, Query as Neo4jQuery};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::common::cypher;
+use crate::domains::graph::level_template::{BLOOM_LEVELS, DREYFUS_LEVELS, scale_rank};
+use crate::domains::graph::models::{
+    ComponentMetrics, ComponentRanking, GraphAnalyticsJob, ServiceError,
+    TransferableComponentsParams,
+};
+use crate::domains::graph::schema::{EMBEDDED_LABEL, SOFT_DELETE_PROPERTY};
+
+const ANALYTICS_NAME: &str = "centrality";
+const DEFAULT_REFRESH_SECS: u64 = 3600;
+const COMPONENT_LABELS: &[&str] = &["Knowledge", "Skill", "Trait", "Milestone"];
+const REQUIREMENT_TYPES: &str =
+    "REQUIRES_KNOWLEDGE|REQUIRES_SKILL|REQUIRES_TRAIT|REQUIRES_MILESTONE";
+const WRITE_BATCH_SIZE: usize = 1000;
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 100;
+/// Total change in scores below which PageRank has converged
+const TOLERANCE: f64 = 1e-9;
+
+/// Set while a run is in progress in this process
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Metrics computed for one component
+struct Metrics {
+    domain_count: i64,
+    pagerank: f64,
+    avg_proficiency: Option<f64>,
+}
+
+/// The last recorded run, if any
+pub async fn analytics_status(graph: &Graph) -> Result<Option<GraphAnalyticsJob>, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (a:_GraphAnalytics {name: $name})
+        RETURN {
+            status: a.status,
+            components: coalesce(a.components, 0),
+            startedAt: a.started_at,
+            updatedAt: a.updated_at,
+            error: a.error
+        } AS job
+        "#
+        .to_string(),
+    )
+    .param("name", ANALYTICS_NAME);
+    let mut result = graph.execute(query).await?;
+    match result.next().await? {
+        Some(row) => row.get("job").map(Some).map_err(|e| {
+            ServiceError::DatabaseError(format!("Failed to read analytics job: {}", e))
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Start a run in the background and return the job as recorded before it.
+/// Starting while a run is in progress is a `ValidationError`.
+pub async fn start_analytics(graph: &Graph) -> Result<GraphAnalyticsJob, ServiceError> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(ServiceError::ValidationError(
+            "an analytics job is already running".to_string(),
+        ));
+    }
+    let job = match record_start(graph).await {
+        Ok(job) => job,
+        Err(e) => {
+            RUNNING.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+
+    let graph = graph.clone();
+    tokio::spawn(async move {
+        run_and_record(&graph).await;
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+
+    Ok(job)
+}
+
+/// Recompute the metrics in the background for the life of the process
+pub fn spawn_analytics_scheduler(graph: Graph) {
+    let interval = std::env::var("ANALYTICS_REFRESH_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_REFRESH_SECS);
+    if interval == 0 {
+        tracing::info!("Analytics scheduler disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        loop {
+            if !RUNNING.swap(true, Ordering::SeqCst) {
+                match record_start(&graph).await {
+                    Ok(_) => run_and_record(&graph).await,
+                    Err(e) => tracing::error!("Failed to start analytics run: {}", e),
+                }
+                RUNNING.store(false, Ordering::SeqCst);
+            }
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+        }
+    });
+}
+
+/// Components ranked by a stored metric; components the job has not reached
+/// yet are left out
+pub async fn most_transferable_components(
+    graph: &Graph,
+    params: TransferableComponentsParams,
+) -> Result<Vec<ComponentMetrics>, ServiceError> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ServiceError::ValidationError(format!(
+            "limit must be between 1 and {}",
+            MAX_LIMIT
+        )));
+    }
+    let labels: Vec<&str> = match params.label.as_deref() {
+        Some(label) if COMPONENT_LABELS.contains(&label) => vec![label],
+        Some(label) => {
+            return Err(ServiceError::ValidationError(format!(
+                "label must be one of {}, got '{}'",
+                COMPONENT_LABELS.join(", "),
+                label
+            )));
+        }
+        None => COMPONENT_LABELS.to_vec(),
+    };
+    let order = match params.order_by {
+        ComponentRanking::Domains => "c.domain_count DESC, c.pagerank DESC",
+        ComponentRanking::Pagerank => "c.pagerank DESC, c.domain_count DESC",
+    };
+
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (c:{})
+        WHERE {} AND c.{} IS NULL AND c.domain_count IS NOT NULL
+        RETURN {{
+            elementId: elementId(c),
+            label: [l IN labels(c) WHERE l IN $componentLabels][0],
+            name: coalesce(c.name, ''),
+            domainCount: c.domain_count,
+            pagerank: c.pagerank,
+            avgProficiency: c.avg_proficiency
+        }} AS component
+        ORDER BY {}, c.name
+        LIMIT $limit
+        "#,
+        EMBEDDED_LABEL,
+        cypher::label_predicate("c", &labels)?,
+        SOFT_DELETE_PROPERTY,
+        order
+    ))
+    .param("componentLabels", COMPONENT_LABELS.to_vec())
+    .param("limit", limit);
+
+    let mut result = graph.execute(query).await?;
+    let mut components = Vec::new();
+    while let Some(row) = result.next().await? {
+        components.push(row.get("component").map_err(|e| {
+            ServiceError::DatabaseError(format!("Failed to read component metrics: {}", e))
+        })?);
+    }
+    Ok(components)
+}
+
+async fn record_start(graph: &Graph) -> Result<GraphAnalyticsJob, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MERGE (a:_GraphAnalytics {name: $name})
+        SET a.status = 'running', a.error = null,
+            a.started_at = toString(datetime()), a.updated_at = toString(datetime())
+        "#
+        .to_string(),
+    )
+    .param("name", ANALYTICS_NAME);
+    graph.run(query).await?;
+
+    analytics_status(graph)
+        .await?
+        .ok_or_else(|| ServiceError::DatabaseError("Analytics job was not recorded".to_string()))
+}
+
+async fn run_and_record(graph: &Graph) {
+    if let Err(e) = run(graph).await {
+        tracing::error!("Analytics job failed: {}", e);
+        let query = Neo4jQuery::new(
+            r#"
+            MATCH (a:_GraphAnalytics {name: $name})
+            SET a.status = 'failed', a.error = $error, a.updated_at = toString(datetime())
+            "#
+            .to_string(),
+        )
+        .param("name", ANALYTICS_NAME)
+        .param("error", e.to_string());
+        if let Err(e) = graph.run(query).await {
+            tracing::error!("Failed to record analytics failure: {}", e);
+        }
+    }
+}
+
+async fn run(graph: &Graph) -> Result<(), ServiceError> {
+    let metrics = compute(graph).await?;
+
+    let rows: Vec<BoltType> = metrics
+        .iter()
+        .map(|(id, m)| {
+            let mut row = BoltMap::new();
+            row.put("id".into(), id.as_str().into());
+            row.put("domainCount".into(), m.domain_count.into());
+            row.put("pagerank".into(), m.pagerank.into());
+            row.put(
+                "avgProficiency".into(),
+                m.avg_proficiency
+                    .map_or(BoltType::Null(Default::default()), Into::into),
+            );
+            BoltType::Map(row)
+        })
+        .collect();
+    for batch in rows.chunks(WRITE_BATCH_SIZE) {
+        let query = Neo4jQuery::new(
+            r#"
+            UNWIND $rows AS row
+            MATCH (c) WHERE elementId(c) = row.id
+            SET c.domain_count = row.domainCount, c.pagerank = row.pagerank,
+                c.avg_proficiency = row.avgProficiency
+            "#
+            .to_string(),
+        )
+        .param("rows", batch.to_vec());
+        graph.run(query).await?;
+    }
+
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (a:_GraphAnalytics {name: $name})
+        SET a.status = 'completed', a.components = $components, a.updated_at = toString(datetime())
+        "#
+        .to_string(),
+    )
+    .param("name", ANALYTICS_NAME)
+    .param("components", metrics.len() as i64);
+    graph.run(query).await?;
+
+    tracing::info!(
+        "Computed centrality metrics for {} components",
+        metrics.len()
+    );
+    Ok(())
+}
+
+/// Metrics of every live component, by element id
+async fn compute(graph: &Graph) -> Result<HashMap<String, Metrics>, ServiceError> {
+    // PageRank nodes: components first, then the published levels requiring them
+    let mut ids: Vec<String> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    let component_query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (c:{})
+        WHERE {} AND c.{} IS NULL
+        RETURN elementId(c) AS id
+        ORDER BY id
+        "#,
+        EMBEDDED_LABEL,
+        cypher::label_predicate("c", COMPONENT_LABELS)?,
+        SOFT_DELETE_PROPERTY
+    ));
+    let mut result = graph.execute(component_query).await?;
+    while let Some(row) = result.next().await? {
+        let id: String = row.get("id").unwrap_or_default();
+        index.insert(id.clone(), ids.len());
+        ids.push(id);
+    }
+    let components = ids.len();
+
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    // Highest proficiency (0..=1) each domain requires of each component
+    let mut required: Vec<HashMap<String, Option<f64>>> = vec![HashMap::new(); components];
+
+    let requirement_query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (d:Domain)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)-[r:{}]->(c:{})
+        WHERE d.{} IS NULL AND c.{} IS NULL
+        RETURN elementId(d) AS domain, elementId(l) AS level, elementId(c) AS component,
+               type(r) AS type, r.bloom_level AS bloom, r.dreyfus_level AS dreyfus,
+               r.min_score AS minScore
+        "#,
+        REQUIREMENT_TYPES, EMBEDDED_LABEL, SOFT_DELETE_PROPERTY, SOFT_DELETE_PROPERTY
+    ));
+    let mut result = graph.execute(requirement_query).await?;
+    while let Some(row) = result.next().await? {
+        let component: String = row.get("component").unwrap_or_default();
+        let Some(&target) = index.get(&component) else {
+            continue;
+        };
+        let level: String = row.get("level").unwrap_or_default();
+        let source = *index.entry(level.clone()).or_insert_with(|| {
+            ids.push(level);
+            ids.len() - 1
+        });
+        edges.push((source, target));
+
+        let relationship_type: String = row.get("type").unwrap_or_default();
+        let proficiency = normalized_proficiency(
+            &relationship_type,
+            row.get::<String>("bloom").ok().as_deref(),
+            row.get::<String>("dreyfus").ok().as_deref(),
+            row.get::<i64>("minScore").ok(),
+        );
+        let highest = required[target]
+            .entry(row.get("domain").unwrap_or_default())
+            .or_insert(None);
+        if proficiency > *highest {
+            *highest = proficiency;
+        }
+    }
+
+    let link_query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (a:{})-[:{}|GENERALIZES_TO]->(b:{})
+        WHERE elementId(a) IN $ids AND elementId(b) IN $ids
+        RETURN elementId(a) AS source, elementId(b) AS target
+        "#,
+        EMBEDDED_LABEL, REQUIREMENT_TYPES, EMBEDDED_LABEL
+    ))
+    .param("ids", ids[..components].to_vec());
+    let mut result = graph.execute(link_query).await?;
+    while let Some(row) = result.next().await? {
+        let source: String = row.get("source").unwrap_or_default();
+        let target: String = row.get("target").unwrap_or_default();
+        if let (Some(&a), Some(&b)) = (index.get(&source), index.get(&target))
+            && a != b
+        {
+            edges.push((a, b));
+        }
+    }
+
+    let node_count = ids.len();
+    let ranks = tokio::task::spawn_blocking(move || pagerank(node_count, &edges))
+        .await
+        .map_err(|e| {
+            ServiceError::DatabaseError(format!("PageRank computation panicked: {}", e))
+        })?;
+
+    Ok(ids
+        .into_iter()
+        .take(components)
+        .zip(required)
+        .zip(ranks)
+        .map(|((id, domains), rank)| {
+            let levels: Vec<f64> = domains.values().flatten().copied().collect();
+            let avg_proficiency = if levels.is_empty() {
+                None
+            } else {
+                Some(levels.iter().sum::<f64>() / levels.len() as f64)
+            };
+            let metrics = Metrics {
+                domain_count: domains.len() as i64,
+                pagerank: rank * node_count as f64,
+                avg_proficiency,
+            };
+            (id, metrics)
+        })
+        .collect())
+}
+
+/// Proficiency a requirement asks for on its own scale, mapped onto `0..=1`
+fn normalized_proficiency(
+    relationship_type: &str,
+    bloom: Option<&str>,
+    dreyfus: Option<&str>,
+    min_score: Option<i64>,
+) -> Option<f64> {
+    let on_scale = |scale: &[&str], value| {
+        scale_rank(scale, value).map(|rank| rank as f64 / (scale.len() - 1) as f64)
+    };
+    match relationship_type {
+        "REQUIRES_KNOWLEDGE" => on_scale(BLOOM_LEVELS, bloom),
+        "REQUIRES_SKILL" => on_scale(DREYFUS_LEVELS, dreyfus),
+        "REQUIRES_TRAIT" => min_score.map(|score| score.clamp(0, 100) as f64 / 100.0),
+        _ => None,
+    }
+}
+
+/// Power iteration; the rank of nodes without outgoing links is spread evenly
+fn pagerank(n: usize, edges: &[(usize, usize)]) -> Vec<f64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut out_degree = vec![0usize; n];
+    for &(source, _) in edges {
+        out_degree[source] += 1;
+    }
+    let uniform = 1.0 / n as f64;
+    let mut ranks = vec![uniform; n];
+
+    for _ in 0..MAX_ITERATIONS {
+        let dangling: f64 = (0..n)
+            .filter(|&i| out_degree[i] == 0)
+            .map(|i| ranks[i])
+            .sum();
+        let base = (1.0 - DAMPING) * uniform + DAMPING * dangling * uniform;
+        let mut next = vec![base; n];
+        for &(source, target) in edges {
+            next[target] += DAMPING * ranks[source] / out_degree[source] as f64;
+        }
+        let change: f64 = next.iter().zip(&ranks).map(|(a, b)| (a - b).abs()).sum();
+        ranks = next;
+        if change < TOLERANCE {
+            break;
+        }
+    }
+
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn pagerank_of_empty_graph_is_empty() {
+        assert!(pagerank(0, &[]).is_empty());
+    }
+
+    #[test]
+    fn pagerank_is_uniform_on_a_cycle() {
+        let ranks = pagerank(3, &[(0, 1), (1, 2), (2, 0)]);
+        for rank in ranks {
+            assert_close(rank, 1.0 / 3.0);
+        }
+    }
+
+    #[test]
+    fn pagerank_favours_the_shared_target_and_sums_to_one() {
+        // Two levels requiring the same component, one of them also requiring another
+        let ranks = pagerank(4, &[(0, 2), (1, 2), (1, 3)]);
+        assert_close(ranks.iter().sum(), 1.0);
+        assert!(ranks[2] > ranks[3]);
+        assert!(ranks[3] > ranks[0]);
+        assert_close(ranks[0], ranks[1]);
+    }
+
+    #[test]
+    fn proficiency_is_normalized_per_scale() {
+        assert_close(
+            normalized_proficiency("REQUIRES_KNOWLEDGE", Some("Remember"), None, None).unwrap(),
+            0.0,
+        );
+        assert_close(
+            normalized_proficiency("REQUIRES_KNOWLEDGE", Some("create"), None, None).unwrap(),
+            1.0,
+        );
+        assert_close(
+            normalized_proficiency("REQUIRES_SKILL", None, Some("Advanced_Beginner"), None)
+                .unwrap(),
+            0.25,
+        );
+        assert_close(
+            normalized_proficiency("REQUIRES_TRAIT", None, None, Some(70)).unwrap(),
+            0.7,
+        );
+        assert_close(
+            normalized_proficiency("REQUIRES_TRAIT", None, None, Some(150)).unwrap(),
+            1.0,
+        );
+    }
+
+    #[test]
+    fn proficiency_is_missing_without_a_matching_level() {
+        assert_eq!(
+            normalized_proficiency("REQUIRES_KNOWLEDGE", None, Some("Expert"), None),
+            None
+        );
+        assert_eq!(
+            normalized_proficiency("REQUIRES_SKILL", None, Some("Master"), None),
+            None
+        );
+        assert_eq!(
+            normalized_proficiency("REQUIRES_MILESTONE", Some("Apply"), None, Some(50)),
+            None
+        );
+    }
+}
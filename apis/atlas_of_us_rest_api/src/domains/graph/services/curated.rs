@@ -0,0 +1,609 @@
+//! Loader for the curated `database/domains/*.cypher` scripts.
+//!
+//! The scripts are written for one-off manual runs: Domain and Domain_Level
+//! nodes and every relationship use bare `CREATE`, levels are matched by name
+//! alone, and components use `MERGE ... ON CREATE SET`. To make them safe to
+//! re-run, the loader
+//! - upserts the Domain node by name instead of creating it,
+//! - creates levels under an owner (the new Domain, or the draft version of an
+//!   existing one) and scopes every level `MATCH` to that owner, so levels of
+//!   other domains or versions with the same name are never touched,
+//! - turns relationship `CREATE`s into `MERGE`s so shared component prerequisites aren't duplicated,
+//! - turns the scripts' `REQUIRES_MILESTONE {any_of: true}` flags into an `any_of` requirement group.
+//!
+//! A new domain's levels become its published version 1. Reloading an existing
+//! domain writes the levels into its draft (replacing any earlier draft) and
+//! leaves the published version alone until the draft is published (see `version`).
+//!
+//! Each file's content hash is recorded on a `_DomainSource` node, so unchanged
+//! files are skipped. Component properties are only written when a component is
+//! first created, as the scripts use `ON CREATE SET`.
+
+use neo4rs::{BoltMap, BoltType, Graph, Query as Neo4jQuery, Txn};
+use std::path::Path;
+
+use crate::common::{
+    embedding::{EMBEDDING_VERSION, configured_model, generate_embeddings, node_embedding_text},
+    neo4j_utils::with_transaction,
+};
+use crate::domains::graph::models::{
+    CuratedDomainResult, CuratedDomainStatus, CuratedLoadReport, ServiceError,
+};
+use crate::domains::graph::schema;
+
+use super::layout;
+use super::version::{open_draft, sync_published_levels};
+
+/// Default location of the curated scripts, relative to the REST API crate
+pub const DEFAULT_CURATED_DOMAINS_DIR: &str = "../../database/domains";
+
+/// Labels whose nodes get an embedding backfilled after a load
+const EMBEDDED_LABELS: &[&str] = &[
+    "Domain",
+    "Domain_Level",
+    "Knowledge",
+    "Skill",
+    "Trait",
+    "Milestone",
+];
+
+/// Nodes embedded per request while backfilling
+const BACKFILL_BATCH_SIZE: usize = 32;
+
+/// Variable bound to the node that owns the levels being loaded
+const OWNER: &str = "curated_owner";
+
+/// Requirement group collecting a level's `any_of` milestones
+pub const MILESTONE_OPTIONS_GROUP: &str = "Milestone options";
+
+struct CuratedScript {
+    file: String,
+    domain: String,
+    hash: String,
+    /// The rewritten `CREATE (d:Domain {...})` statement
+    domain_statement: String,
+    statements: Vec<String>,
+}
+
+impl CuratedScript {
+    fn statement_count(&self) -> usize {
+        self.statements.len() + 1
+    }
+}
+
+/// Load every `.cypher` file in `dir`, then backfill missing embeddings.
+/// With `dry_run` nothing is written; the report shows what would be loaded.
+pub async fn load_curated_domains(
+    graph: &Graph,
+    dir: &Path,
+    dry_run: bool,
+) -> Result<CuratedLoadReport, ServiceError> {
+    let scripts = read_scripts(dir).await?;
+    let mut domains = Vec::new();
+
+    for script in scripts {
+        let status = script_status(graph, &script).await?;
+
+        let mut draft_version = None;
+        if !dry_run && status != CuratedDomainStatus::Unchanged {
+            draft_version = with_transaction(graph, async |txn: &mut Txn| {
+                load_script(txn, &script, status).await
+            })
+            .await
+            .inspect_err(|e| tracing::error!("Failed to load {}: {}", script.file, e))?;
+            tracing::info!(
+                file = %script.file,
+                domain = %script.domain,
+                statements = script.statement_count(),
+                draft_version,
+                "Loaded curated domain"
+            );
+        }
+
+        domains.push(CuratedDomainResult {
+            statements: script.statement_count(),
+            file: script.file,
+            domain: script.domain,
+            status,
+            draft_version,
+        });
+    }
+
+    let (embeddings_backfilled, embedding_failures) = backfill_embeddings(graph, dry_run).await?;
+    if !dry_run {
+        layout::request_refresh();
+    }
+
+    Ok(CuratedLoadReport {
+        dry_run,
+        domains,
+        embeddings_backfilled,
+        embedding_failures,
+    })
+}
+
+async fn read_scripts(dir: &Path) -> Result<Vec<CuratedScript>, ServiceError> {
+    let io_error = |e: std::io::Error| {
+        ServiceError::ValidationError(format!("cannot read {}: {}", dir.display(), e))
+    };
+
+    let mut entries = tokio::fs::read_dir(dir).await.map_err(io_error)?;
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "cypher") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut scripts = Vec::new();
+    for path in paths {
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let source = tokio::fs::read_to_string(&path).await.map_err(io_error)?;
+        let statements = split_statements(&source);
+
+        let Some((index, domain)) = statements
+            .iter()
+            .enumerate()
+            .find_map(|(i, s)| domain_name(s).map(|name| (i, name)))
+        else {
+            return Err(ServiceError::ValidationError(format!(
+                "{}: no Domain node found",
+                file
+            )));
+        };
+
+        scripts.push(CuratedScript {
+            file,
+            domain,
+            hash: content_hash(&source),
+            domain_statement: upsert_domain(&statements[index]),
+            statements: statements
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != index)
+                .filter_map(|(_, s)| make_idempotent(s))
+                .collect(),
+        });
+    }
+
+    Ok(scripts)
+}
+
+async fn script_status(
+    graph: &Graph,
+    script: &CuratedScript,
+) -> Result<CuratedDomainStatus, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        OPTIONAL MATCH (s:_DomainSource {file: $file})
+        OPTIONAL MATCH (d:Domain {name: $domain})
+        RETURN s.hash AS hash, count(d) > 0 AS domainExists
+        "#
+        .to_string(),
+    )
+    .param("file", script.file.clone())
+    .param("domain", script.domain.clone());
+
+    let mut result = graph.execute(query).await?;
+    let (hash, domain_exists) = match result.next().await? {
+        Some(row) => (
+            row.get::<Option<String>>("hash").unwrap_or_default(),
+            row.get::<bool>("domainExists").unwrap_or(false),
+        ),
+        None => (None, false),
+    };
+
+    Ok(if !domain_exists {
+        CuratedDomainStatus::New
+    } else if hash.as_deref() == Some(script.hash.as_str()) {
+        CuratedDomainStatus::Unchanged
+    } else {
+        CuratedDomainStatus::Changed
+    })
+}
+
+/// Load a script in one transaction. Returns the draft version written when
+/// an existing domain was reloaded.
+async fn load_script(
+    txn: &mut Txn,
+    script: &CuratedScript,
+    status: CuratedDomainStatus,
+) -> Result<Option<i64>, ServiceError> {
+    let upsert =
+        Neo4jQuery::new(script.domain_statement.clone()).param("domainName", script.domain.clone());
+    let mut result = txn.execute(upsert).await?;
+    let Some(row) = result.next(txn.handle()).await? else {
+        return Err(ServiceError::DatabaseError(format!(
+            "Failed to upsert domain {}",
+            script.domain
+        )));
+    };
+    let domain_element_id: String = row.get("elementId").unwrap_or_default();
+    let description: String = row.get("description").unwrap_or_default();
+
+    // A new domain owns its levels directly; an existing one gets them in its draft
+    let (owner_id, draft_version) = if status == CuratedDomainStatus::New {
+        (domain_element_id.clone(), None)
+    } else {
+        let (draft_id, version) =
+            open_draft(txn, &domain_element_id, &script.domain, &description).await?;
+        (draft_id, Some(version))
+    };
+
+    for statement in &script.statements {
+        let query = Neo4jQuery::new(statement.clone())
+            .param("domainName", script.domain.clone())
+            .param("ownerId", owner_id.clone());
+        txn.run(query).await?;
+    }
+
+    let any_of_milestones = Neo4jQuery::new(any_of_milestones_statement(&format!(
+        "MATCH ({0})-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level) WHERE elementId({0}) = $ownerId",
+        OWNER
+    )))
+    .param("ownerId", owner_id.clone());
+    txn.run(any_of_milestones).await?;
+
+    // Scripts don't carry the L1 label; add it now rather than waiting for the
+    // embeddings. Levels only get it when they are live.
+    let label_graph_nodes = Neo4jQuery::new(format!(
+        r#"
+        MATCH (d:Domain) WHERE elementId(d) = $domainId
+        SET d:{0}
+        WITH d
+        MATCH (owner)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+        WHERE elementId(owner) = $ownerId
+        FOREACH (_ IN CASE WHEN owner = d THEN [1] ELSE [] END | SET l:{0})
+        WITH l
+        MATCH (l)-[]->(c)
+        WHERE (c:Knowledge OR c:Skill OR c:Trait OR c:Milestone) AND NOT c:{0}
+        SET c:{0}
+        "#,
+        schema::EMBEDDED_LABEL
+    ))
+    .param("domainId", domain_element_id.clone())
+    .param("ownerId", owner_id);
+    txn.run(label_graph_nodes).await?;
+
+    if draft_version.is_none() {
+        sync_published_levels(txn, &domain_element_id).await?;
+    }
+
+    let record_source = Neo4jQuery::new(
+        r#"
+        MERGE (s:_DomainSource {file: $file})
+        SET s.domain = $domain, s.hash = $hash, s.loaded_at = datetime()
+        "#
+        .to_string(),
+    )
+    .param("file", script.file.clone())
+    .param("domain", script.domain.clone())
+    .param("hash", script.hash.clone());
+    txn.run(record_source).await?;
+
+    Ok(draft_version)
+}
+
+/// Generate embeddings for domain nodes that don't have one yet.
+/// Returns (backfilled, failed); in a dry run, the number that would be backfilled.
+async fn backfill_embeddings(graph: &Graph, dry_run: bool) -> Result<(usize, usize), ServiceError> {
+    let label_filter = EMBEDDED_LABELS
+        .iter()
+        .map(|l| format!("n:{}", l))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (n)
+        WHERE ({}) AND n.embedding IS NULL
+        RETURN elementId(n) AS elementId, n.name AS name, coalesce(n.description, '') AS description
+        "#,
+        label_filter
+    ));
+
+    let mut result = graph.execute(query).await?;
+    let mut pending: Vec<(String, String)> = Vec::new();
+    while let Some(row) = result.next().await? {
+        let element_id: String = row.get("elementId").unwrap_or_default();
+        let name: String = row.get("name").unwrap_or_default();
+        let description: String = row.get("description").unwrap_or_default();
+        pending.push((element_id, node_embedding_text(&name, &description)));
+    }
+
+    if dry_run {
+        return Ok((pending.len(), 0));
+    }
+
+    let model = configured_model().map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+    let mut backfilled = 0;
+    let mut failed = 0;
+    for chunk in pending.chunks(BACKFILL_BATCH_SIZE) {
+        let texts: Vec<String> = chunk.iter().map(|(_, text)| text.clone()).collect();
+        let embeddings = match generate_embeddings(&texts).await {
+            Ok(embeddings) => embeddings,
+            Err(e) => {
+                tracing::warn!("Failed to embed {} nodes: {}", chunk.len(), e);
+                failed += chunk.len();
+                continue;
+            }
+        };
+
+        let rows: Vec<BoltType> = chunk
+            .iter()
+            .zip(embeddings)
+            .map(|((element_id, _), embedding)| {
+                let mut row = BoltMap::new();
+                row.put("elementId".into(), element_id.as_str().into());
+                row.put("embedding".into(), embedding.into());
+                BoltType::Map(row)
+            })
+            .collect();
+        // The L1 label is set by the load itself; draft levels must not get it here
+        let update = Neo4jQuery::new(
+            r#"
+            UNWIND $rows AS row
+            MATCH (n) WHERE elementId(n) = row.elementId
+            SET n.embedding = row.embedding,
+                n.embedding_model = $model, n.embedding_version = $version
+            "#
+            .to_string(),
+        )
+        .param("rows", rows)
+        .param("model", model.as_str())
+        .param("version", EMBEDDING_VERSION);
+        graph.run(update).await?;
+        backfilled += chunk.len();
+    }
+
+    Ok((backfilled, failed))
+}
+
+/// Statement moving the `any_of` flags on the `REQUIRES_MILESTONE` edges of the
+/// levels bound to `l` by `level_match` into a `MILESTONE_OPTIONS_GROUP` group:
+/// flagged milestones become its members and one of them is enough. The flag
+/// is removed from every edge, `any_of: false` meaning a plain requirement.
+pub fn any_of_milestones_statement(level_match: &str) -> String {
+    format!(
+        r#"
+        {0}
+        MATCH (l)-[r:REQUIRES_MILESTONE]->(:Milestone)
+        WHERE r.any_of IS NOT NULL
+        WITH l, collect(r) AS edges
+        WITH l, edges, [e IN edges WHERE e.any_of = true] AS options
+        FOREACH (_ IN CASE WHEN size(options) > 0 THEN [1] ELSE [] END |
+            MERGE (l)-[:HAS_REQUIREMENT_GROUP]->(:Requirement_Group {{name: '{1}', mode: 'any_of', count: 1}}))
+        FOREACH (e IN options |
+            SET e.groups = [g IN coalesce(e.groups, []) WHERE g <> '{1}'] + '{1}')
+        FOREACH (e IN edges | REMOVE e.any_of)
+        "#,
+        level_match, MILESTONE_OPTIONS_GROUP
+    )
+}
+
+// ========== Script parsing ==========
+
+/// Split a script into statements on `;`, dropping `//` comments.
+/// Quotes are tracked so semicolons, slashes and escaped quotes inside string literals are kept.
+fn split_statements(source: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                current.push(c);
+                if c == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        current.push(escaped);
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '\'' | '"' => {
+                    quote = Some(c);
+                    current.push(c);
+                }
+                '/' if chars.peek() == Some(&'/') => {
+                    for skipped in chars.by_ref() {
+                        if skipped == '\n' {
+                            current.push('\n');
+                            break;
+                        }
+                    }
+                }
+                ';' => {
+                    let statement = current.trim();
+                    if !statement.is_empty() {
+                        statements.push(statement.to_string());
+                    }
+                    current.clear();
+                }
+                _ => current.push(c),
+            },
+        }
+    }
+
+    let statement = current.trim();
+    if !statement.is_empty() {
+        statements.push(statement.to_string());
+    }
+    statements
+}
+
+/// Name of the Domain created by a `CREATE (d:Domain { name: '...', ... })` statement
+fn domain_name(statement: &str) -> Option<String> {
+    if !is_node_create(statement, "Domain") {
+        return None;
+    }
+
+    let start = statement.find("name:")? + "name:".len();
+    let rest = statement[start..].trim_start().strip_prefix('\'')?;
+
+    let mut name = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => name.push(chars.next()?),
+            '\'' => return Some(name),
+            _ => name.push(c),
+        }
+    }
+    None
+}
+
+fn is_node_create(statement: &str, label: &str) -> bool {
+    statement
+        .strip_prefix("CREATE (")
+        .and_then(|rest| rest.split_once(':'))
+        .is_some_and(|(_, rest)| {
+            rest.strip_prefix(label)
+                .is_some_and(|after| after.trim_start().starts_with('{'))
+        })
+}
+
+/// Rewrite `CREATE (d:Domain {...})` into an upsert by name that returns the
+/// domain's element id and the script's description. Existing domains keep
+/// their properties; the description goes into the draft instead. The
+/// script's own `created_date` (a Cypher `date()`) is replaced by the ISO
+/// string `create_domain` writes.
+fn upsert_domain(statement: &str) -> String {
+    let variable = &statement["CREATE (".len()..statement.find(':').unwrap_or(0)];
+    let body_start = statement.find('{').unwrap_or(0);
+    let body_end = statement.rfind('}').unwrap_or(statement.len() - 1);
+    format!(
+        "WITH {1} AS props\nMERGE ({0}:Domain {{name: $domainName}})\nON CREATE SET {0} += props {{.*, created_date: toString(date())}}\nRETURN elementId({0}) AS elementId, props.description AS description",
+        variable,
+        &statement[body_start..=body_end]
+    )
+}
+
+/// Rewrite a statement so running it twice has the same effect as running it
+/// once, and so its levels are those of `$ownerId`. Returns `None` for the
+/// statements linking the Domain to its levels, which creating the levels
+/// under their owner already does.
+fn make_idempotent(statement: &str) -> Option<String> {
+    if statement.contains("-[:HAS_DOMAIN_LEVEL]->") {
+        return None;
+    }
+
+    let owner = format!("MATCH ({0}) WHERE elementId({0}) = $ownerId", OWNER);
+    if is_node_create(statement, "Domain_Level") {
+        // CREATE (l:Domain_Level {...}) -> CREATE (owner)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level {...})
+        return Some(format!(
+            "{}\nCREATE ({})-[:HAS_DOMAIN_LEVEL]->({}",
+            owner,
+            OWNER,
+            &statement["CREATE (".len()..]
+        ));
+    }
+
+    let mut scoped = false;
+    let lines = statement
+        .lines()
+        .map(|line| {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let trimmed = line.trim_start();
+            match trimmed.strip_prefix("CREATE (") {
+                Some(rest) if is_relationship_pattern(rest) => {
+                    return format!("{}MERGE ({}", indent, rest);
+                }
+                _ => {}
+            }
+            match trimmed.strip_prefix("MATCH (") {
+                // MATCH (l:Domain_Level {...}) -> MATCH (owner)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level {...})
+                Some(rest) if is_level_pattern(rest) => {
+                    scoped = true;
+                    format!("{}MATCH ({})-[:HAS_DOMAIN_LEVEL]->({}", indent, OWNER, rest)
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some(if scoped {
+        format!("{}\n{}", owner, lines)
+    } else {
+        lines
+    })
+}
+
+/// True for the tail of `MATCH (l:Domain_Level ...)` after the opening parenthesis
+fn is_level_pattern(rest: &str) -> bool {
+    rest.split_once(':').is_some_and(|(variable, label)| {
+        !variable.contains(['(', ')', ' ']) && label.starts_with("Domain_Level")
+    })
+}
+
+/// True for the tail of `CREATE (a)-[...]->(b)` after the opening parenthesis
+fn is_relationship_pattern(rest: &str) -> bool {
+    rest.split_once(')').is_some_and(|(variable, after)| {
+        !variable.contains(':') && (after.starts_with("-[") || after.starts_with("<-["))
+    })
+}
+
+/// Stable FNV-1a hash of a script's contents
+fn content_hash(source: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in source.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_statements_are_scoped_to_the_owner() {
+        let create = make_idempotent(
+            "CREATE (level1:Domain_Level {\n  level: 1,\n  name: 'Chess Novice'\n})",
+        )
+        .unwrap();
+        assert_eq!(
+            create,
+            "MATCH (curated_owner) WHERE elementId(curated_owner) = $ownerId\nCREATE (curated_owner)-[:HAS_DOMAIN_LEVEL]->(level1:Domain_Level {\n  level: 1,\n  name: 'Chess Novice'\n})"
+        );
+
+        let requirement = make_idempotent(
+            "MATCH (level1:Domain_Level {level: 1, name: 'Chess Novice'})\nMATCH (k:Knowledge {name: 'Chess Piece Movements'})\nCREATE (level1)-[:REQUIRES_KNOWLEDGE {bloom_level: 'Remember'}]->(k)",
+        )
+        .unwrap();
+        assert_eq!(
+            requirement,
+            "MATCH (curated_owner) WHERE elementId(curated_owner) = $ownerId\nMATCH (curated_owner)-[:HAS_DOMAIN_LEVEL]->(level1:Domain_Level {level: 1, name: 'Chess Novice'})\nMATCH (k:Knowledge {name: 'Chess Piece Movements'})\nMERGE (level1)-[:REQUIRES_KNOWLEDGE {bloom_level: 'Remember'}]->(k)"
+        );
+    }
+
+    #[test]
+    fn upserted_domains_record_an_iso_creation_date() {
+        let upsert =
+            upsert_domain("CREATE (d:Domain {\n  name: 'Chess',\n  created_date: date()\n})");
+        assert_eq!(
+            upsert,
+            "WITH {\n  name: 'Chess',\n  created_date: date()\n} AS props\nMERGE (d:Domain {name: $domainName})\nON CREATE SET d += props {.*, created_date: toString(date())}\nRETURN elementId(d) AS elementId, props.description AS description"
+        );
+    }
+
+    #[test]
+    fn domain_level_links_are_dropped() {
+        let link = "MATCH (d:Domain {name: 'Chess'})\nMATCH (level1:Domain_Level {name: 'Chess Novice'})\nCREATE (d)-[:HAS_DOMAIN_LEVEL]->(level1)";
+        assert_eq!(make_idempotent(link), None);
+
+        let component =
+            "MERGE (k:Knowledge {name: 'Chess Openings'})\nON CREATE SET k.description = 'x'";
+        assert_eq!(make_idempotent(component).as_deref(), Some(component));
+    }
+}
@@ -0,0 +1,783 @@
+//! Structural diff between two domains, or two versions of one domain.
+//!
+//! Both sides are compared in the shape returned by `get_domain`. Components
+//! are identified by type and name (names are unique per label), so the same
+//! shared component appearing on both sides is recognised even when it moved
+//! to another level.
+
+use neo4rs::Graph;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::domains::graph::models::{
+    ComponentChange, ComponentMove, ComponentRef, DiffSide, DomainDiff, GroupChange, LevelDiff,
+    LevelDiffStatus, PropertyChange, RequirementChange, ServiceError,
+};
+
+use super::{domain::get_domain, version::latest_version};
+
+/// Relationship properties describing how a component is required: the
+/// proficiency and the requirement groups it counts towards
+const REQUIREMENT_FIELDS: &[&str] = &["bloomLevel", "dreyfusLevel", "minScore", "groups"];
+
+/// Requirement group properties compared between sides
+const GROUP_FIELDS: &[&str] = &["mode", "count", "proficiency", "description"];
+
+/// Component fields that identify a node rather than describe it
+const IGNORED_FIELDS: &[&str] = &["elementId"];
+
+/// Text changes longer than this are summarised as "changed" instead of quoted
+const MAX_QUOTED_TEXT: usize = 60;
+
+#[derive(Debug, Deserialize)]
+struct DomainStructure {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    levels: Vec<LevelStructure>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LevelStructure {
+    level: i64,
+    name: Option<String>,
+    description: Option<String>,
+    points_required: Option<i64>,
+    #[serde(default)]
+    knowledge: Vec<ComponentEntry>,
+    #[serde(default)]
+    skills: Vec<ComponentEntry>,
+    #[serde(default)]
+    traits: Vec<ComponentEntry>,
+    #[serde(default)]
+    milestones: Vec<ComponentEntry>,
+    #[serde(default)]
+    requirement_groups: Vec<GroupEntry>,
+}
+
+impl LevelStructure {
+    fn components(&self) -> impl Iterator<Item = &ComponentEntry> {
+        self.knowledge
+            .iter()
+            .chain(&self.skills)
+            .chain(&self.traits)
+            .chain(&self.milestones)
+    }
+
+    fn label(&self) -> String {
+        match &self.name {
+            Some(name) => format!("Level {} ({})", self.level, name),
+            None => format!("Level {}", self.level),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupEntry {
+    name: String,
+    #[serde(flatten)]
+    properties: BTreeMap<String, Value>,
+}
+
+impl GroupEntry {
+    fn field(&self, field: &str) -> Value {
+        self.properties.get(field).cloned().unwrap_or(Value::Null)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ComponentEntry {
+    #[serde(rename = "type")]
+    component_type: String,
+    name: String,
+    #[serde(flatten)]
+    properties: BTreeMap<String, Value>,
+}
+
+impl ComponentEntry {
+    fn key(&self) -> ComponentRef {
+        ComponentRef {
+            component_type: self.component_type.clone(),
+            name: self.name.clone(),
+        }
+    }
+
+    /// Property value in comparable form; `generalizesTo` is compared by name
+    /// and `groups` regardless of order
+    fn field(&self, field: &str) -> Value {
+        match self.properties.get(field) {
+            Some(Value::Object(target)) if field == "generalizesTo" => {
+                target.get("name").cloned().unwrap_or(Value::Null)
+            }
+            Some(Value::Array(groups)) if field == "groups" => {
+                let mut groups = groups.clone();
+                groups.sort_by_key(|g| g.as_str().unwrap_or_default().to_string());
+                Value::Array(groups)
+            }
+            Some(value) => value.clone(),
+            None => Value::Null,
+        }
+    }
+}
+
+/// Load both sides with `get_domain` and diff them.
+/// Comparing a domain with itself defaults to published vs latest version (the draft, if any).
+pub async fn diff_domains(
+    graph: &Graph,
+    name: &str,
+    from_version: Option<i64>,
+    other_name: Option<&str>,
+    to_version: Option<i64>,
+) -> Result<DomainDiff, ServiceError> {
+    let to_name = other_name.unwrap_or(name);
+    let to_version = match (to_version, other_name) {
+        (Some(version), _) => Some(version),
+        (None, Some(_)) => None,
+        (None, None) => latest_version(graph, name).await?,
+    };
+
+    let from = load_domain(graph, name, from_version).await?;
+    let to = load_domain(graph, to_name, to_version).await?;
+
+    diff_structures(&from, &to)
+}
+
+async fn load_domain(
+    graph: &Graph,
+    name: &str,
+    version: Option<i64>,
+) -> Result<Value, ServiceError> {
+    get_domain(graph, name, version)
+        .await?
+        .ok_or_else(|| match version {
+            Some(version) => {
+                ServiceError::NotFound(format!("Domain '{}' version {} not found", name, version))
+            }
+            None => ServiceError::NotFound(format!("Domain '{}' not found", name)),
+        })
+}
+
+/// Diff two domain structures in `get_domain` format
+pub fn diff_structures(from: &Value, to: &Value) -> Result<DomainDiff, ServiceError> {
+    let parse = |value: &Value| {
+        DomainStructure::deserialize(value)
+            .map_err(|e| ServiceError::ValidationError(format!("invalid domain structure: {}", e)))
+    };
+    let from_domain = parse(from)?;
+    let to_domain = parse(to)?;
+
+    let mut diff = DomainDiff {
+        from: DiffSide {
+            name: from_domain.name.clone(),
+            version: from.get("version").and_then(Value::as_i64),
+        },
+        to: DiffSide {
+            name: to_domain.name.clone(),
+            version: to.get("version").and_then(Value::as_i64),
+        },
+        identical: false,
+        property_changes: Vec::new(),
+        levels: Vec::new(),
+        component_changes: Vec::new(),
+        summary: Vec::new(),
+    };
+
+    push_change(
+        &mut diff.property_changes,
+        "name",
+        json_text(&from_domain.name),
+        json_text(&to_domain.name),
+    );
+    push_change(
+        &mut diff.property_changes,
+        "description",
+        json_opt_text(&from_domain.description),
+        json_opt_text(&to_domain.description),
+    );
+
+    let from_levels: BTreeMap<i64, &LevelStructure> =
+        from_domain.levels.iter().map(|l| (l.level, l)).collect();
+    let to_levels: BTreeMap<i64, &LevelStructure> =
+        to_domain.levels.iter().map(|l| (l.level, l)).collect();
+
+    let mut level_diffs: BTreeMap<i64, LevelDiff> = from_levels
+        .keys()
+        .chain(to_levels.keys())
+        .map(|&level| {
+            let status = match (from_levels.get(&level), to_levels.get(&level)) {
+                (Some(_), None) => LevelDiffStatus::Removed,
+                (None, Some(_)) => LevelDiffStatus::Added,
+                _ => LevelDiffStatus::Unchanged,
+            };
+            (level, empty_level_diff(level, status))
+        })
+        .collect();
+
+    for (level, level_diff) in level_diffs.iter_mut() {
+        push_group_changes(
+            level_diff,
+            from_levels
+                .get(level)
+                .map_or(&[], |l| &l.requirement_groups),
+            to_levels.get(level).map_or(&[], |l| &l.requirement_groups),
+        );
+
+        if let (Some(old), Some(new)) = (from_levels.get(level), to_levels.get(level)) {
+            let changes = &mut level_diff.property_changes;
+            push_change(
+                changes,
+                "name",
+                json_opt_text(&old.name),
+                json_opt_text(&new.name),
+            );
+            push_change(
+                changes,
+                "description",
+                json_opt_text(&old.description),
+                json_opt_text(&new.description),
+            );
+            push_change(
+                changes,
+                "pointsRequired",
+                old.points_required.into(),
+                new.points_required.into(),
+            );
+        }
+    }
+
+    let from_placements = placements(&from_domain);
+    let to_placements = placements(&to_domain);
+    let components: BTreeSet<&ComponentRef> =
+        from_placements.keys().chain(to_placements.keys()).collect();
+    let no_placements = BTreeMap::new();
+
+    for component in components {
+        let old = from_placements.get(component).unwrap_or(&no_placements);
+        let new = to_placements.get(component).unwrap_or(&no_placements);
+
+        // Same level on both sides: only the required proficiency can differ
+        for (level, old_entry) in old {
+            if let Some(new_entry) = new.get(level) {
+                let level_diff = level_diffs.get_mut(level).expect("level exists");
+                push_requirement_changes(level_diff, component, old_entry, new_entry);
+            }
+        }
+
+        // Levels the component left and joined; pair them up as moves
+        let left: Vec<i64> = old
+            .keys()
+            .filter(|l| !new.contains_key(l))
+            .copied()
+            .collect();
+        let joined: Vec<i64> = new
+            .keys()
+            .filter(|l| !old.contains_key(l))
+            .copied()
+            .collect();
+
+        for (&from_level, &to_level) in left.iter().zip(&joined) {
+            let component_move = || ComponentMove {
+                component: component.clone(),
+                from_level,
+                to_level,
+            };
+            let target = level_diffs.get_mut(&to_level).expect("level exists");
+            target.moved_in.push(component_move());
+            push_requirement_changes(target, component, old[&from_level], new[&to_level]);
+            level_diffs
+                .get_mut(&from_level)
+                .expect("level exists")
+                .moved_out
+                .push(component_move());
+        }
+        for level in left.iter().skip(joined.len()) {
+            level_diffs
+                .get_mut(level)
+                .expect("level exists")
+                .removed
+                .push(component.clone());
+        }
+        for level in joined.iter().skip(left.len()) {
+            level_diffs
+                .get_mut(level)
+                .expect("level exists")
+                .added
+                .push(component.clone());
+        }
+
+        // Descriptive text of components present on both sides
+        if let (Some(old_entry), Some(new_entry)) = (old.values().next(), new.values().next()) {
+            let fields: BTreeSet<&String> = old_entry
+                .properties
+                .keys()
+                .chain(new_entry.properties.keys())
+                .filter(|f| {
+                    !REQUIREMENT_FIELDS.contains(&f.as_str())
+                        && !IGNORED_FIELDS.contains(&f.as_str())
+                })
+                .collect();
+            let mut changes = Vec::new();
+            for field in fields {
+                push_change(
+                    &mut changes,
+                    field,
+                    old_entry.field(field),
+                    new_entry.field(field),
+                );
+            }
+            if !changes.is_empty() {
+                diff.component_changes.push(ComponentChange {
+                    component: component.clone(),
+                    changes,
+                });
+            }
+        }
+    }
+
+    for level_diff in level_diffs.values_mut() {
+        if level_diff.status == LevelDiffStatus::Unchanged && !is_level_unchanged(level_diff) {
+            level_diff.status = LevelDiffStatus::Changed;
+        }
+    }
+    diff.levels = level_diffs.into_values().collect();
+    diff.identical = diff.property_changes.is_empty()
+        && diff.component_changes.is_empty()
+        && diff
+            .levels
+            .iter()
+            .all(|l| l.status == LevelDiffStatus::Unchanged);
+    diff.summary = summarize(&diff, &from_levels, &to_levels);
+
+    Ok(diff)
+}
+
+/// Where each component is required: component -> level -> requirement entry
+fn placements(domain: &DomainStructure) -> BTreeMap<ComponentRef, BTreeMap<i64, &ComponentEntry>> {
+    let mut placements: BTreeMap<ComponentRef, BTreeMap<i64, &ComponentEntry>> = BTreeMap::new();
+    for level in &domain.levels {
+        for entry in level.components() {
+            placements
+                .entry(entry.key())
+                .or_default()
+                .insert(level.level, entry);
+        }
+    }
+    placements
+}
+
+fn empty_level_diff(level: i64, status: LevelDiffStatus) -> LevelDiff {
+    LevelDiff {
+        level,
+        status,
+        property_changes: Vec::new(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        moved_in: Vec::new(),
+        moved_out: Vec::new(),
+        requirement_changes: Vec::new(),
+        groups_added: Vec::new(),
+        groups_removed: Vec::new(),
+        group_changes: Vec::new(),
+    }
+}
+
+fn is_level_unchanged(level_diff: &LevelDiff) -> bool {
+    level_diff.property_changes.is_empty()
+        && level_diff.added.is_empty()
+        && level_diff.removed.is_empty()
+        && level_diff.moved_in.is_empty()
+        && level_diff.moved_out.is_empty()
+        && level_diff.requirement_changes.is_empty()
+        && level_diff.groups_added.is_empty()
+        && level_diff.groups_removed.is_empty()
+        && level_diff.group_changes.is_empty()
+}
+
+/// Requirement groups are matched by name within a level
+fn push_group_changes(level_diff: &mut LevelDiff, old: &[GroupEntry], new: &[GroupEntry]) {
+    for group in old {
+        match new.iter().find(|g| g.name == group.name) {
+            None => level_diff.groups_removed.push(group.name.clone()),
+            Some(new_group) => {
+                for field in GROUP_FIELDS {
+                    let (from, to) = (group.field(field), new_group.field(field));
+                    if from != to {
+                        level_diff.group_changes.push(GroupChange {
+                            group: group.name.clone(),
+                            field: field.to_string(),
+                            from,
+                            to,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    for group in new {
+        if !old.iter().any(|g| g.name == group.name) {
+            level_diff.groups_added.push(group.name.clone());
+        }
+    }
+}
+
+fn push_change(changes: &mut Vec<PropertyChange>, field: &str, from: Value, to: Value) {
+    if from != to {
+        changes.push(PropertyChange {
+            field: field.to_string(),
+            from,
+            to,
+        });
+    }
+}
+
+fn push_requirement_changes(
+    level_diff: &mut LevelDiff,
+    component: &ComponentRef,
+    old: &ComponentEntry,
+    new: &ComponentEntry,
+) {
+    for field in REQUIREMENT_FIELDS {
+        let (from, to) = (old.field(field), new.field(field));
+        if from != to {
+            level_diff.requirement_changes.push(RequirementChange {
+                component: component.clone(),
+                field: field.to_string(),
+                from,
+                to,
+            });
+        }
+    }
+}
+
+fn json_text(text: &str) -> Value {
+    Value::String(text.to_string())
+}
+
+fn json_opt_text(text: &Option<String>) -> Value {
+    text.as_deref().map(json_text).unwrap_or(Value::Null)
+}
+
+// ========== Human-readable summary ==========
+
+fn summarize(
+    diff: &DomainDiff,
+    from_levels: &BTreeMap<i64, &LevelStructure>,
+    to_levels: &BTreeMap<i64, &LevelStructure>,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for change in &diff.property_changes {
+        lines.push(format!("Domain {}", describe_change(change)));
+    }
+
+    for level_diff in &diff.levels {
+        let label = to_levels
+            .get(&level_diff.level)
+            .or_else(|| from_levels.get(&level_diff.level))
+            .map(|l| l.label())
+            .unwrap_or_else(|| format!("Level {}", level_diff.level));
+
+        match level_diff.status {
+            LevelDiffStatus::Added => lines.push(format!("{}: level added", label)),
+            LevelDiffStatus::Removed => lines.push(format!("{}: level removed", label)),
+            _ => {}
+        }
+        for change in &level_diff.property_changes {
+            lines.push(format!("{}: {}", label, describe_change(change)));
+        }
+        for component in &level_diff.added {
+            lines.push(format!("{}: added {}", label, component));
+        }
+        for component in &level_diff.removed {
+            lines.push(format!("{}: removed {}", label, component));
+        }
+        for component_move in &level_diff.moved_in {
+            lines.push(format!(
+                "{}: moved {} here from level {}",
+                label, component_move.component, component_move.from_level
+            ));
+        }
+        for change in &level_diff.requirement_changes {
+            lines.push(format!(
+                "{}: {} {} {} -> {}",
+                label,
+                change.component,
+                change.field,
+                display_value(&change.from),
+                display_value(&change.to)
+            ));
+        }
+        for group in &level_diff.groups_added {
+            lines.push(format!("{}: added requirement group '{}'", label, group));
+        }
+        for group in &level_diff.groups_removed {
+            lines.push(format!("{}: removed requirement group '{}'", label, group));
+        }
+        for change in &level_diff.group_changes {
+            lines.push(format!(
+                "{}: requirement group '{}' {} {} -> {}",
+                label,
+                change.group,
+                change.field,
+                display_value(&change.from),
+                display_value(&change.to)
+            ));
+        }
+    }
+
+    for component_change in &diff.component_changes {
+        for change in &component_change.changes {
+            lines.push(format!(
+                "{} {}",
+                capitalize(&component_change.component.to_string()),
+                describe_change(change)
+            ));
+        }
+    }
+
+    lines
+}
+
+fn describe_change(change: &PropertyChange) -> String {
+    let is_long = |value: &Value| {
+        value
+            .as_str()
+            .is_some_and(|s| s.chars().count() > MAX_QUOTED_TEXT)
+    };
+    if is_long(&change.from) || is_long(&change.to) {
+        format!("{} changed", change.field)
+    } else {
+        format!(
+            "{} {} -> {}",
+            change.field,
+            display_value(&change.from),
+            display_value(&change.to)
+        )
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Null => "none".to_string(),
+        Value::String(text) => format!("'{}'", text),
+        other => other.to_string(),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn knowledge(name: &str, bloom_level: &str) -> Value {
+        json!({"type": "knowledge", "name": name, "description": "d", "bloomLevel": bloom_level, "groups": []})
+    }
+
+    fn skill(name: &str, dreyfus_level: &str) -> Value {
+        json!({"type": "skill", "name": name, "description": "d", "dreyfusLevel": dreyfus_level, "groups": []})
+    }
+
+    fn trait_entry(name: &str, min_score: i64) -> Value {
+        json!({"type": "trait", "name": name, "description": "d", "minScore": min_score, "groups": []})
+    }
+
+    fn domain(levels: Value) -> Value {
+        json!({"name": "Chess", "description": "The game", "levels": levels})
+    }
+
+    fn level(level: i64, points: i64, knowledge: Value) -> Value {
+        json!({
+            "level": level,
+            "name": format!("Chess {}", level),
+            "pointsRequired": points,
+            "knowledge": knowledge
+        })
+    }
+
+    fn component(component_type: &str, name: &str) -> ComponentRef {
+        ComponentRef {
+            component_type: component_type.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn identical_domains_have_no_changes() {
+        let chess = domain(json!([level(
+            1,
+            100,
+            json!([knowledge("Openings", "Remember")])
+        )]));
+        let diff = diff_structures(&chess, &chess).unwrap();
+        assert!(diff.identical);
+        assert!(diff.summary.is_empty());
+        assert_eq!(diff.levels[0].status, LevelDiffStatus::Unchanged);
+    }
+
+    #[test]
+    fn added_and_removed_components_are_reported_per_level() {
+        let from = domain(json!([level(
+            1,
+            100,
+            json!([knowledge("Openings", "Remember")])
+        )]));
+        let to = domain(json!([level(
+            1,
+            100,
+            json!([knowledge("Endgames", "Remember")])
+        )]));
+        let diff = diff_structures(&from, &to).unwrap();
+
+        let level = &diff.levels[0];
+        assert_eq!(level.status, LevelDiffStatus::Changed);
+        assert_eq!(level.added, vec![component("knowledge", "Endgames")]);
+        assert_eq!(level.removed, vec![component("knowledge", "Openings")]);
+        assert_eq!(
+            diff.summary,
+            vec![
+                "Level 1 (Chess 1): added knowledge 'Endgames'",
+                "Level 1 (Chess 1): removed knowledge 'Openings'",
+            ]
+        );
+    }
+
+    #[test]
+    fn a_component_changing_level_is_a_move() {
+        let from = domain(json!([
+            level(1, 100, json!([knowledge("Openings", "Remember")])),
+            level(2, 200, json!([])),
+        ]));
+        let to = domain(json!([
+            level(1, 100, json!([])),
+            level(2, 200, json!([knowledge("Openings", "Remember")])),
+        ]));
+        let diff = diff_structures(&from, &to).unwrap();
+
+        let openings = component("knowledge", "Openings");
+        assert_eq!(diff.levels[0].moved_out.len(), 1);
+        assert_eq!(diff.levels[1].moved_in[0].component, openings);
+        assert_eq!(diff.levels[1].moved_in[0].from_level, 1);
+        assert!(diff.levels[0].removed.is_empty() && diff.levels[1].added.is_empty());
+        assert!(diff.summary.contains(
+            &"Level 2 (Chess 2): moved knowledge 'Openings' here from level 1".to_string()
+        ));
+    }
+
+    #[test]
+    fn proficiency_changes_are_requirement_changes() {
+        let requirements = |bloom: &str, dreyfus: &str, min_score: i64| {
+            let mut level = level(1, 100, json!([knowledge("Openings", bloom)]));
+            level["skills"] = json!([skill("Tactics", dreyfus)]);
+            level["traits"] = json!([trait_entry("Patience", min_score)]);
+            domain(json!([level]))
+        };
+        let from = requirements("Remember", "Novice", 50);
+        let to = requirements("Apply", "Competent", 70);
+        let diff = diff_structures(&from, &to).unwrap();
+
+        let changes: Vec<(&str, Value, Value)> = diff.levels[0]
+            .requirement_changes
+            .iter()
+            .map(|c| (c.field.as_str(), c.from.clone(), c.to.clone()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("bloomLevel", json!("Remember"), json!("Apply")),
+                ("dreyfusLevel", json!("Novice"), json!("Competent")),
+                ("minScore", json!(50), json!(70)),
+            ]
+        );
+        assert!(diff.component_changes.is_empty());
+    }
+
+    #[test]
+    fn points_required_change_is_a_level_property_change() {
+        let from = domain(json!([level(1, 100, json!([]))]));
+        let to = domain(json!([level(1, 150, json!([]))]));
+        let diff = diff_structures(&from, &to).unwrap();
+
+        let change = &diff.levels[0].property_changes[0];
+        assert_eq!(change.field, "pointsRequired");
+        assert_eq!(
+            (change.from.clone(), change.to.clone()),
+            (json!(100), json!(150))
+        );
+        assert_eq!(
+            diff.summary,
+            vec!["Level 1 (Chess 1): pointsRequired 100 -> 150"]
+        );
+    }
+
+    #[test]
+    fn descriptive_text_changes_are_component_changes() {
+        let mut renamed = knowledge("Openings", "Remember");
+        renamed["description"] =
+            json!("A much longer description of the opening repertoire than the summary quotes");
+        let from = domain(json!([level(
+            1,
+            100,
+            json!([knowledge("Openings", "Remember")])
+        )]));
+        let to = domain(json!([level(1, 100, json!([renamed]))]));
+        let diff = diff_structures(&from, &to).unwrap();
+
+        assert_eq!(diff.component_changes[0].changes[0].field, "description");
+        assert!(diff.levels[0].requirement_changes.is_empty());
+        assert_eq!(
+            diff.summary,
+            vec!["Knowledge 'Openings' description changed"]
+        );
+    }
+
+    #[test]
+    fn group_membership_and_group_settings_are_diffed() {
+        let grouped = |mode: &str, groups: Value| {
+            let mut openings = knowledge("Openings", "Remember");
+            openings["groups"] = groups;
+            let mut level = level(1, 100, json!([openings, knowledge("Endgames", "Remember")]));
+            level["requirementGroups"] = json!([{"name": "Theory", "mode": mode, "count": 1}]);
+            domain(json!([level]))
+        };
+        let from = grouped("any_of", json!([]));
+        let to = grouped("all_of", json!(["Theory"]));
+        let diff = diff_structures(&from, &to).unwrap();
+
+        let level_diff = &diff.levels[0];
+        assert_eq!(level_diff.requirement_changes[0].field, "groups");
+        assert_eq!(level_diff.group_changes[0].field, "mode");
+        assert!(diff.component_changes.is_empty());
+        assert!(diff.summary.contains(
+            &"Level 1 (Chess 1): requirement group 'Theory' mode 'any_of' -> 'all_of'".to_string()
+        ));
+
+        let ungrouped = domain(json!([level(
+            1,
+            100,
+            json!([knowledge("Openings", "Remember")])
+        )]));
+        let diff = diff_structures(&ungrouped, &from).unwrap();
+        assert_eq!(diff.levels[0].groups_added, vec!["Theory"]);
+        let diff = diff_structures(&from, &ungrouped).unwrap();
+        assert_eq!(diff.levels[0].groups_removed, vec!["Theory"]);
+    }
+
+    #[test]
+    fn added_levels_are_summarised() {
+        let from = domain(json!([level(1, 100, json!([]))]));
+        let to = domain(json!([level(1, 100, json!([])), level(2, 200, json!([]))]));
+        let diff = diff_structures(&from, &to).unwrap();
+        assert_eq!(diff.levels[1].status, LevelDiffStatus::Added);
+        assert_eq!(diff.summary, vec!["Level 2 (Chess 2): level added"]);
+    }
+}
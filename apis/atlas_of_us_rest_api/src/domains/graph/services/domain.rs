@@ -1,16 +1,42 @@
-use neo4rs::{Graph, Query as Neo4jQuery};
-use serde_json::{json, Value};
+use neo4rs::{BoltMap, BoltType, Graph, Query as Neo4jQuery, Txn};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+use crate::common::{
+    cypher,
+    embedding::{EMBEDDING_VERSION, configured_model, generate_embeddings, node_embedding_text},
+    neo4j_utils::with_transaction,
+};
+use crate::domains::graph::level_template::{
+    BLOOM_LEVELS, DREYFUS_LEVELS, scale_rank, validate_level_numbers,
+};
+use crate::domains::graph::schema::EMBEDDED_LABEL;
 
 use crate::domains::graph::models::{
-    CreateDomainRequest, CreateDomainResult, CreatedNodeInfo, DomainNameValidation,
-    LevelRequirements, NewNodeData, ServiceError, UpdateDomainRequest, UpdateDomainResult,
+    CreateDomainRequest, CreateDomainResult, CreatedNodeInfo, DomainLevel, DomainNameValidation,
+    ForkDomainRequest, NewNodeData, RequirementGroupMode, ServiceError, UpdateDomainRequest,
+    UpdateDomainResult,
 };
 
-/// Get a domain with all its levels and requirements
-pub async fn get_domain(graph: &Graph, name: &str) -> Result<Option<Value>, ServiceError> {
-    let query_string = r#"
-        MATCH (domain:Domain {name: $name})
-        OPTIONAL MATCH (domain)-[:HAS_DOMAIN_LEVEL]->(level:Domain_Level)
+use super::{layout, version};
+
+/// Get a domain with all its levels and requirements.
+/// Without a version this is the published (live) domain.
+pub async fn get_domain(
+    graph: &Graph,
+    name: &str,
+    version: Option<i64>,
+) -> Result<Option<Value>, ServiceError> {
+    let source_match = if version.is_some() {
+        "MATCH (domain:Domain {name: $name})-[:HAS_VERSION]->(source:Domain_Version {version: $version})"
+    } else {
+        "MATCH (domain:Domain {name: $name}) WITH domain, domain AS source"
+    };
+
+    let query_string = format!(
+        r#"
+        {}
+        OPTIONAL MATCH (source)-[:HAS_DOMAIN_LEVEL]->(level:Domain_Level)
 
         // Get knowledge requirements for each level
         OPTIONAL MATCH (level)-[kr:REQUIRES_KNOWLEDGE]->(k:Knowledge)
@@ -28,51 +54,55 @@ pub async fn get_domain(graph: &Graph, name: &str) -> Result<Option<Value>, Serv
         OPTIONAL MATCH (s)-[:GENERALIZES_TO]->(generalSkill:Skill)
         OPTIONAL MATCH (k)-[:GENERALIZES_TO]->(generalKnowledge:Knowledge)
 
-        WITH domain, level,
-             collect(DISTINCT CASE WHEN k IS NOT NULL THEN {
+        WITH domain, source, level,
+             collect(DISTINCT CASE WHEN k IS NOT NULL THEN {{
                  elementId: elementId(k),
                  type: 'knowledge',
                  name: k.name,
                  description: k.description,
                  howToLearn: k.how_to_learn,
                  bloomLevel: kr.bloom_level,
-                 generalizesTo: CASE WHEN generalKnowledge IS NOT NULL THEN {
+                 groups: coalesce(kr.groups, []),
+                 generalizesTo: CASE WHEN generalKnowledge IS NOT NULL THEN {{
                      elementId: elementId(generalKnowledge),
                      name: generalKnowledge.name
-                 } ELSE null END
-             } ELSE NULL END) AS knowledge,
-             collect(DISTINCT CASE WHEN s IS NOT NULL THEN {
+                 }} ELSE null END
+             }} ELSE NULL END) AS knowledge,
+             collect(DISTINCT CASE WHEN s IS NOT NULL THEN {{
                  elementId: elementId(s),
                  type: 'skill',
                  name: s.name,
                  description: s.description,
                  howToDevelop: s.how_to_develop,
                  dreyfusLevel: sr.dreyfus_level,
-                 generalizesTo: CASE WHEN generalSkill IS NOT NULL THEN {
+                 groups: coalesce(sr.groups, []),
+                 generalizesTo: CASE WHEN generalSkill IS NOT NULL THEN {{
                      elementId: elementId(generalSkill),
                      name: generalSkill.name
-                 } ELSE null END
-             } ELSE NULL END) AS skills,
-             collect(DISTINCT CASE WHEN t IS NOT NULL THEN {
+                 }} ELSE null END
+             }} ELSE NULL END) AS skills,
+             collect(DISTINCT CASE WHEN t IS NOT NULL THEN {{
                  elementId: elementId(t),
                  type: 'trait',
                  name: t.name,
                  description: t.description,
                  measurementCriteria: t.measurement_criteria,
-                 minScore: tr.min_score
-             } ELSE NULL END) AS traits,
-             collect(DISTINCT CASE WHEN m IS NOT NULL THEN {
+                 minScore: tr.min_score,
+                 groups: coalesce(tr.groups, [])
+             }} ELSE NULL END) AS traits,
+             collect(DISTINCT CASE WHEN m IS NOT NULL THEN {{
                  elementId: elementId(m),
                  type: 'milestone',
                  name: m.name,
                  description: m.description,
-                 howToAchieve: m.how_to_achieve
-             } ELSE NULL END) AS milestones
+                 howToAchieve: m.how_to_achieve,
+                 groups: coalesce(mr.groups, [])
+             }} ELSE NULL END) AS milestones
 
         ORDER BY level.level
 
-        WITH domain,
-             collect(CASE WHEN level IS NOT NULL THEN {
+        WITH domain, source,
+             collect(CASE WHEN level IS NOT NULL THEN {{
                  elementId: elementId(level),
                  level: level.level,
                  name: level.name,
@@ -81,19 +111,33 @@ pub async fn get_domain(graph: &Graph, name: &str) -> Result<Option<Value>, Serv
                  knowledge: [item IN knowledge WHERE item IS NOT NULL],
                  skills: [item IN skills WHERE item IS NOT NULL],
                  traits: [item IN traits WHERE item IS NOT NULL],
-                 milestones: [item IN milestones WHERE item IS NOT NULL]
-             } ELSE NULL END) AS levels
-
-        RETURN {
+                 milestones: [item IN milestones WHERE item IS NOT NULL],
+                 requirementGroups: [(level)-[:HAS_REQUIREMENT_GROUP]->(g:Requirement_Group) | {{
+                     name: g.name,
+                     mode: g.mode,
+                     count: g.count,
+                     proficiency: g.proficiency,
+                     description: g.description
+                 }}]
+             }} ELSE NULL END) AS levels
+
+        RETURN {{
             elementId: elementId(domain),
-            name: domain.name,
-            description: domain.description,
+            name: source.name,
+            description: source.description,
+            version: coalesce(source.version, domain.published_version),
+            status: coalesce(source.status, 'published'),
             levels: [l IN levels WHERE l IS NOT NULL]
-        } AS result
-    "#;
+        }} AS result
+    "#,
+        source_match
+    );
 
-    let mut query = Neo4jQuery::new(query_string.to_string());
+    let mut query = Neo4jQuery::new(query_string);
     query = query.param("name", name);
+    if let Some(version) = version {
+        query = query.param("version", version);
+    }
 
     match graph.execute(query).await {
         Ok(mut result) => {
@@ -111,7 +155,10 @@ pub async fn get_domain(graph: &Graph, name: &str) -> Result<Option<Value>, Serv
         }
         Err(e) => {
             tracing::error!("Error in get_domain: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
+            Err(ServiceError::DatabaseError(format!(
+                "Database error: {}",
+                e
+            )))
         }
     }
 }
@@ -147,17 +194,79 @@ pub async fn validate_domain_name(
         }
         Err(e) => {
             tracing::error!("Error in validate_domain_name: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
+            Err(ServiceError::DatabaseError(format!(
+                "Database error: {}",
+                e
+            )))
         }
     }
 }
 
-/// Create a component node (Knowledge, Skill, Trait, Milestone) - helper for domain operations
+/// Embeddings for the components a request creates, keyed by embedding text
+pub(super) struct ComponentEmbeddings {
+    model: String,
+    by_text: HashMap<String, Vec<f64>>,
+}
+
+/// Embed every new component of `levels` in one batch, before the caller opens
+/// its transaction, so no transaction waits on the provider
+pub(super) async fn embed_new_components(
+    levels: &[DomainLevel],
+) -> Result<ComponentEmbeddings, ServiceError> {
+    let mut texts: Vec<String> = levels
+        .iter()
+        .flat_map(|level| {
+            let requirements = &level.requirements;
+            requirements
+                .knowledge
+                .iter()
+                .map(|r| &r.new_node)
+                .chain(requirements.skills.iter().map(|r| &r.new_node))
+                .chain(requirements.traits.iter().map(|r| &r.new_node))
+                .chain(requirements.milestones.iter().map(|r| &r.new_node))
+        })
+        .flatten()
+        .map(|node| node_embedding_text(&node.name, &node.description))
+        .collect();
+    texts.sort();
+    texts.dedup();
+
+    if texts.is_empty() {
+        return Ok(ComponentEmbeddings {
+            model: String::new(),
+            by_text: HashMap::new(),
+        });
+    }
+
+    let embeddings = generate_embeddings(&texts)
+        .await
+        .map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+    let model = configured_model().map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+
+    Ok(ComponentEmbeddings {
+        model,
+        by_text: texts.into_iter().zip(embeddings).collect(),
+    })
+}
+
+/// Create a component node (Knowledge, Skill, Trait, Milestone) - helper for domain operations.
+/// New components are embedded and labelled as graph nodes, like the worker's.
 async fn create_component_node(
-    graph: &Graph,
+    txn: &mut Txn,
     label: &str,
     node_data: &NewNodeData,
+    embeddings: &ComponentEmbeddings,
 ) -> Result<String, ServiceError> {
+    let embedding = embeddings
+        .by_text
+        .get(&node_embedding_text(
+            &node_data.name,
+            &node_data.description,
+        ))
+        .ok_or_else(|| {
+            ServiceError::EmbeddingFailed(format!("No embedding for new node '{}'", node_data.name))
+        })?;
+
     // Build properties based on node type
     let mut props = vec![
         ("name".to_string(), node_data.name.clone()),
@@ -177,7 +286,10 @@ async fn create_component_node(
         }
         "Trait" => {
             if let Some(measurement_criteria) = &node_data.measurement_criteria {
-                props.push(("measurement_criteria".to_string(), measurement_criteria.clone()));
+                props.push((
+                    "measurement_criteria".to_string(),
+                    measurement_criteria.clone(),
+                ));
             }
         }
         "Milestone" => {
@@ -189,72 +301,97 @@ async fn create_component_node(
     }
 
     // Build SET clause
-    let set_clauses: Vec<String> = props
+    let set_clauses = props
         .iter()
         .enumerate()
-        .map(|(i, (key, _))| format!("n.{} = $prop{}", key, i))
-        .collect();
+        .map(|(i, (key, _))| cypher::property_key(key).map(|key| format!("n.{} = $prop{}", key, i)))
+        .collect::<Result<Vec<_>, _>>()?;
 
     let query_string = format!(
         r#"
         MERGE (n:{} {{name: $name}})
-        ON CREATE SET {}
+        ON CREATE SET {},
+            n.embedding = $embedding,
+            n.embedding_model = $embeddingModel,
+            n.embedding_version = $embeddingVersion,
+            n:{}
         RETURN elementId(n) AS elementId
         "#,
-        label,
-        set_clauses.join(", ")
+        cypher::label(label)?,
+        set_clauses.join(", "),
+        EMBEDDED_LABEL
     );
 
     let mut query = Neo4jQuery::new(query_string);
     query = query.param("name", node_data.name.clone());
+    query = query.param("embedding", embedding.clone());
+    query = query.param("embeddingModel", embeddings.model.clone());
+    query = query.param("embeddingVersion", EMBEDDING_VERSION);
 
     for (i, (_, value)) in props.iter().enumerate() {
         query = query.param(&format!("prop{}", i), value.clone());
     }
 
-    match graph.execute(query).await {
-        Ok(mut result) => {
-            if let Ok(Some(row)) = result.next().await {
-                let element_id: String = row.get("elementId").unwrap_or_default();
-                Ok(element_id)
-            } else {
-                Err(ServiceError::DatabaseError(
-                    "Failed to get element ID after creation".to_string(),
-                ))
-            }
+    let mut result = txn.execute(query).await?;
+    match result.next(txn.handle()).await? {
+        Some(row) => {
+            let element_id: String = row.get("elementId").unwrap_or_default();
+            Ok(element_id)
         }
-        Err(e) => Err(ServiceError::DatabaseError(format!("Database error: {}", e))),
+        None => Err(ServiceError::DatabaseError(
+            "Failed to get element ID after creation".to_string(),
+        )),
     }
 }
 
 /// Process level requirements - creates nodes and relationships
 /// This eliminates the 8x duplication in create_domain/update_domain
 async fn process_level_requirements(
-    graph: &Graph,
+    txn: &mut Txn,
     level_element_id: &str,
-    requirements: &LevelRequirements,
+    level: &DomainLevel,
+    embeddings: &ComponentEmbeddings,
 ) -> Result<Vec<CreatedNodeInfo>, ServiceError> {
     let mut created_nodes: Vec<CreatedNodeInfo> = Vec::new();
+    let requirements = &level.requirements;
+
+    // Requirement groups; members point at them by name through the `groups` edge property
+    for group in &level.requirement_groups {
+        let group_query = Neo4jQuery::new(
+            r#"
+            MATCH (l:Domain_Level) WHERE elementId(l) = $levelId
+            CREATE (l)-[:HAS_REQUIREMENT_GROUP]->(:Requirement_Group {
+                name: $name,
+                mode: $mode,
+                count: $count,
+                proficiency: $proficiency,
+                description: $description
+            })
+            "#
+            .to_string(),
+        )
+        .param("levelId", level_element_id)
+        .param("name", group.name.clone())
+        .param("mode", group.mode.as_str())
+        .param("count", group.count)
+        .param("proficiency", group.proficiency.clone())
+        .param("description", group.description.clone());
+
+        txn.run(group_query).await?;
+    }
 
     // Process Knowledge requirements
     for knowledge_req in &requirements.knowledge {
         let node_id = if let Some(existing_id) = &knowledge_req.node_element_id {
             existing_id.clone()
         } else if let Some(new_node) = &knowledge_req.new_node {
-            match create_component_node(graph, "Knowledge", new_node).await {
-                Ok(id) => {
-                    created_nodes.push(CreatedNodeInfo {
-                        element_id: id.clone(),
-                        name: new_node.name.clone(),
-                        labels: vec!["Knowledge".to_string()],
-                    });
-                    id
-                }
-                Err(e) => {
-                    tracing::error!("Failed to create knowledge node: {}", e);
-                    continue;
-                }
-            }
+            let id = create_component_node(txn, "Knowledge", new_node, embeddings).await?;
+            created_nodes.push(CreatedNodeInfo {
+                element_id: id.clone(),
+                name: new_node.name.clone(),
+                labels: vec!["Knowledge".to_string()],
+            });
+            id
         } else {
             continue;
         };
@@ -263,17 +400,16 @@ async fn process_level_requirements(
             r#"
             MATCH (l:Domain_Level), (k:Knowledge)
             WHERE elementId(l) = $levelId AND elementId(k) = $nodeId
-            CREATE (l)-[:REQUIRES_KNOWLEDGE {bloom_level: $bloomLevel}]->(k)
+            CREATE (l)-[:REQUIRES_KNOWLEDGE {bloom_level: $bloomLevel, groups: $groups}]->(k)
             "#
             .to_string(),
         )
         .param("levelId", level_element_id)
         .param("nodeId", node_id)
-        .param("bloomLevel", knowledge_req.bloom_level.clone());
+        .param("bloomLevel", knowledge_req.bloom_level.clone())
+        .param("groups", knowledge_req.groups.clone());
 
-        if let Err(e) = graph.run(rel_query).await {
-            tracing::error!("Error creating knowledge requirement: {}", e);
-        }
+        txn.run(rel_query).await?;
     }
 
     // Process Skill requirements
@@ -281,20 +417,13 @@ async fn process_level_requirements(
         let node_id = if let Some(existing_id) = &skill_req.node_element_id {
             existing_id.clone()
         } else if let Some(new_node) = &skill_req.new_node {
-            match create_component_node(graph, "Skill", new_node).await {
-                Ok(id) => {
-                    created_nodes.push(CreatedNodeInfo {
-                        element_id: id.clone(),
-                        name: new_node.name.clone(),
-                        labels: vec!["Skill".to_string()],
-                    });
-                    id
-                }
-                Err(e) => {
-                    tracing::error!("Failed to create skill node: {}", e);
-                    continue;
-                }
-            }
+            let id = create_component_node(txn, "Skill", new_node, embeddings).await?;
+            created_nodes.push(CreatedNodeInfo {
+                element_id: id.clone(),
+                name: new_node.name.clone(),
+                labels: vec!["Skill".to_string()],
+            });
+            id
         } else {
             continue;
         };
@@ -303,17 +432,16 @@ async fn process_level_requirements(
             r#"
             MATCH (l:Domain_Level), (s:Skill)
             WHERE elementId(l) = $levelId AND elementId(s) = $nodeId
-            CREATE (l)-[:REQUIRES_SKILL {dreyfus_level: $dreyfusLevel}]->(s)
+            CREATE (l)-[:REQUIRES_SKILL {dreyfus_level: $dreyfusLevel, groups: $groups}]->(s)
             "#
             .to_string(),
         )
         .param("levelId", level_element_id)
         .param("nodeId", node_id)
-        .param("dreyfusLevel", skill_req.dreyfus_level.clone());
+        .param("dreyfusLevel", skill_req.dreyfus_level.clone())
+        .param("groups", skill_req.groups.clone());
 
-        if let Err(e) = graph.run(rel_query).await {
-            tracing::error!("Error creating skill requirement: {}", e);
-        }
+        txn.run(rel_query).await?;
     }
 
     // Process Trait requirements
@@ -321,20 +449,13 @@ async fn process_level_requirements(
         let node_id = if let Some(existing_id) = &trait_req.node_element_id {
             existing_id.clone()
         } else if let Some(new_node) = &trait_req.new_node {
-            match create_component_node(graph, "Trait", new_node).await {
-                Ok(id) => {
-                    created_nodes.push(CreatedNodeInfo {
-                        element_id: id.clone(),
-                        name: new_node.name.clone(),
-                        labels: vec!["Trait".to_string()],
-                    });
-                    id
-                }
-                Err(e) => {
-                    tracing::error!("Failed to create trait node: {}", e);
-                    continue;
-                }
-            }
+            let id = create_component_node(txn, "Trait", new_node, embeddings).await?;
+            created_nodes.push(CreatedNodeInfo {
+                element_id: id.clone(),
+                name: new_node.name.clone(),
+                labels: vec!["Trait".to_string()],
+            });
+            id
         } else {
             continue;
         };
@@ -343,17 +464,16 @@ async fn process_level_requirements(
             r#"
             MATCH (l:Domain_Level), (t:Trait)
             WHERE elementId(l) = $levelId AND elementId(t) = $nodeId
-            CREATE (l)-[:REQUIRES_TRAIT {min_score: $minScore}]->(t)
+            CREATE (l)-[:REQUIRES_TRAIT {min_score: $minScore, groups: $groups}]->(t)
             "#
             .to_string(),
         )
         .param("levelId", level_element_id)
         .param("nodeId", node_id)
-        .param("minScore", trait_req.min_score);
+        .param("minScore", trait_req.min_score)
+        .param("groups", trait_req.groups.clone());
 
-        if let Err(e) = graph.run(rel_query).await {
-            tracing::error!("Error creating trait requirement: {}", e);
-        }
+        txn.run(rel_query).await?;
     }
 
     // Process Milestone requirements
@@ -361,20 +481,13 @@ async fn process_level_requirements(
         let node_id = if let Some(existing_id) = &milestone_req.node_element_id {
             existing_id.clone()
         } else if let Some(new_node) = &milestone_req.new_node {
-            match create_component_node(graph, "Milestone", new_node).await {
-                Ok(id) => {
-                    created_nodes.push(CreatedNodeInfo {
-                        element_id: id.clone(),
-                        name: new_node.name.clone(),
-                        labels: vec!["Milestone".to_string()],
-                    });
-                    id
-                }
-                Err(e) => {
-                    tracing::error!("Failed to create milestone node: {}", e);
-                    continue;
-                }
-            }
+            let id = create_component_node(txn, "Milestone", new_node, embeddings).await?;
+            created_nodes.push(CreatedNodeInfo {
+                element_id: id.clone(),
+                name: new_node.name.clone(),
+                labels: vec!["Milestone".to_string()],
+            });
+            id
         } else {
             continue;
         };
@@ -383,22 +496,197 @@ async fn process_level_requirements(
             r#"
             MATCH (l:Domain_Level), (m:Milestone)
             WHERE elementId(l) = $levelId AND elementId(m) = $nodeId
-            CREATE (l)-[:REQUIRES_MILESTONE]->(m)
+            CREATE (l)-[:REQUIRES_MILESTONE {groups: $groups}]->(m)
             "#
             .to_string(),
         )
         .param("levelId", level_element_id)
-        .param("nodeId", node_id);
+        .param("nodeId", node_id)
+        .param("groups", milestone_req.groups.clone());
 
-        if let Err(e) = graph.run(rel_query).await {
-            tracing::error!("Error creating milestone requirement: {}", e);
-        }
+        txn.run(rel_query).await?;
+    }
+
+    Ok(created_nodes)
+}
+
+/// Create Domain_Level nodes under a Domain or Domain_Version and process their requirements.
+/// Shared by create_domain and the draft versions; runs inside the caller's transaction.
+pub(super) async fn create_levels_with_requirements(
+    txn: &mut Txn,
+    owner_element_id: &str,
+    levels: &[DomainLevel],
+    embeddings: &ComponentEmbeddings,
+) -> Result<Vec<CreatedNodeInfo>, ServiceError> {
+    let mut created_nodes: Vec<CreatedNodeInfo> = Vec::new();
+
+    for level in levels {
+        let level_description = level.description.clone().unwrap_or_default();
+
+        // Only live levels carry the embedded label; draft levels get it when published
+        let create_level_query = Neo4jQuery::new(format!(
+            r#"
+            MATCH (owner) WHERE elementId(owner) = $ownerId AND (owner:Domain OR owner:Domain_Version)
+            CREATE (l:Domain_Level {{
+                level: $level,
+                name: $name,
+                description: $description,
+                total_points_required: $points
+            }})
+            CREATE (owner)-[:HAS_DOMAIN_LEVEL]->(l)
+            FOREACH (_ IN CASE WHEN owner:Domain THEN [1] ELSE [] END | SET l:{})
+            RETURN elementId(l) AS elementId
+            "#,
+            EMBEDDED_LABEL
+        ))
+        .param("ownerId", owner_element_id)
+        .param("level", level.level)
+        .param("name", level.name.clone())
+        .param("description", level_description)
+        .param("points", level.points_required);
+
+        let mut result = txn.execute(create_level_query).await?;
+        let level_element_id: String = match result.next(txn.handle()).await? {
+            Some(row) => row.get("elementId").unwrap_or_default(),
+            None => {
+                return Err(ServiceError::DatabaseError(format!(
+                    "Failed to create domain level {}",
+                    level.level
+                )));
+            }
+        };
+
+        let mut nodes =
+            process_level_requirements(txn, &level_element_id, level, embeddings).await?;
+        created_nodes.append(&mut nodes);
     }
 
     Ok(created_nodes)
 }
 
-/// Create a complete domain with levels and requirements
+/// Whether a group's proficiency floor is meaningful for a member of this requirement type
+fn proficiency_applies(relationship_type: &str, proficiency: &str) -> bool {
+    match relationship_type {
+        "REQUIRES_KNOWLEDGE" => scale_rank(BLOOM_LEVELS, Some(proficiency)).is_some(),
+        "REQUIRES_SKILL" => scale_rank(DREYFUS_LEVELS, Some(proficiency)).is_some(),
+        "REQUIRES_TRAIT" => proficiency
+            .parse::<i64>()
+            .is_ok_and(|score| (0..=100).contains(&score)),
+        // Milestones are simply achieved or not
+        _ => true,
+    }
+}
+
+/// Check each level's requirement groups: names are unique, every group has
+/// members, members only name groups that exist, and counts and proficiency
+/// floors make sense for the group's members.
+fn validate_requirement_groups(levels: &[DomainLevel]) -> Result<(), ServiceError> {
+    for level in levels {
+        let requirements = &level.requirements;
+        let invalid = |message: String| {
+            ServiceError::ValidationError(format!("Level {}: {}", level.level, message))
+        };
+
+        // (group names, relationship type) for every item
+        let members: Vec<(&Vec<String>, &str)> = requirements
+            .knowledge
+            .iter()
+            .map(|r| (&r.groups, "REQUIRES_KNOWLEDGE"))
+            .chain(
+                requirements
+                    .skills
+                    .iter()
+                    .map(|r| (&r.groups, "REQUIRES_SKILL")),
+            )
+            .chain(
+                requirements
+                    .traits
+                    .iter()
+                    .map(|r| (&r.groups, "REQUIRES_TRAIT")),
+            )
+            .chain(
+                requirements
+                    .milestones
+                    .iter()
+                    .map(|r| (&r.groups, "REQUIRES_MILESTONE")),
+            )
+            .collect();
+
+        let groups = &level.requirement_groups;
+        for (i, group) in groups.iter().enumerate() {
+            if group.name.trim().is_empty() {
+                return Err(invalid(
+                    "requirement group names must not be empty".to_string(),
+                ));
+            }
+            if groups[..i].iter().any(|g| g.name == group.name) {
+                return Err(invalid(format!(
+                    "duplicate requirement group '{}'",
+                    group.name
+                )));
+            }
+
+            let group_members: Vec<_> = members
+                .iter()
+                .filter(|(groups, _)| groups.contains(&group.name))
+                .collect();
+            if group_members.is_empty() {
+                return Err(invalid(format!(
+                    "requirement group '{}' has no members",
+                    group.name
+                )));
+            }
+
+            let count = match group.mode {
+                RequirementGroupMode::AllOf => None,
+                RequirementGroupMode::AnyOf => Some(group.count.unwrap_or(1)),
+                RequirementGroupMode::AtLeast => Some(group.count.ok_or_else(|| {
+                    invalid(format!("at_least group '{}' needs a count", group.name))
+                })?),
+            };
+            if let Some(count) = count
+                && !(1..=group_members.len() as i64).contains(&count)
+            {
+                return Err(invalid(format!(
+                    "requirement group '{}' needs a count between 1 and its {} members",
+                    group.name,
+                    group_members.len()
+                )));
+            }
+
+            if group.mode == RequirementGroupMode::AtLeast {
+                let proficiency = group.proficiency.as_deref().ok_or_else(|| {
+                    invalid(format!(
+                        "at_least group '{}' needs a proficiency",
+                        group.name
+                    ))
+                })?;
+                if !group_members.iter().all(|(_, relationship_type)| {
+                    proficiency_applies(relationship_type, proficiency)
+                }) {
+                    return Err(invalid(format!(
+                        "proficiency '{}' of group '{}' doesn't apply to all of its members",
+                        proficiency, group.name
+                    )));
+                }
+            }
+        }
+
+        for (member_groups, _) in &members {
+            if let Some(unknown) = member_groups
+                .iter()
+                .find(|name| !groups.iter().any(|g| &g.name == *name))
+            {
+                return Err(invalid(format!("unknown requirement group '{}'", unknown)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Create a complete domain with levels and requirements.
+/// All writes happen in one transaction, so a failure leaves no partial domain behind.
 pub async fn create_domain(
     graph: &Graph,
     request: CreateDomainRequest,
@@ -416,111 +704,63 @@ pub async fn create_domain(
         ));
     }
 
-    // Check if domain already exists
-    let check_query = Neo4jQuery::new(
-        "MATCH (d:Domain {name: $name}) RETURN elementId(d) AS id LIMIT 1".to_string(),
-    )
-    .param("name", request.domain.name.clone());
+    let level_numbers: Vec<i64> = request.levels.iter().map(|l| l.level).collect();
+    validate_level_numbers(&level_numbers)?;
+    validate_requirement_groups(&request.levels)?;
+    let embeddings = embed_new_components(&request.levels).await?;
 
-    match graph.execute(check_query).await {
-        Ok(mut result) => {
-            if let Ok(Some(_)) = result.next().await {
-                return Err(ServiceError::ValidationError(
-                    "Domain with this name already exists".to_string(),
-                ));
-            }
-        }
-        Err(e) => {
-            tracing::error!("Error checking domain existence: {}", e);
-            return Err(ServiceError::DatabaseError(
-                "Failed to check domain existence".to_string(),
+    let result = with_transaction(graph, async |txn: &mut Txn| {
+        // Check if domain already exists
+        let check_query = Neo4jQuery::new(
+            "MATCH (d:Domain {name: $name}) RETURN elementId(d) AS id LIMIT 1".to_string(),
+        )
+        .param("name", request.domain.name.clone());
+
+        let mut existing = txn.execute(check_query).await?;
+        if existing.next(txn.handle()).await?.is_some() {
+            return Err(ServiceError::ValidationError(
+                "Domain with this name already exists".to_string(),
             ));
         }
-    }
 
-    let mut created_nodes: Vec<CreatedNodeInfo> = Vec::new();
-
-    // Create Domain node
-    let create_domain_query = Neo4jQuery::new(
-        r#"
-        CREATE (d:Domain {name: $name, description: $description})
-        RETURN elementId(d) AS elementId
-        "#
-        .to_string(),
-    )
-    .param("name", request.domain.name.clone())
-    .param("description", request.domain.description.clone());
-
-    let domain_element_id = match graph.execute(create_domain_query).await {
-        Ok(mut result) => {
-            if let Ok(Some(row)) = result.next().await {
-                let id: String = row.get("elementId").unwrap_or_default();
-                id
-            } else {
+        // Create Domain node
+        let create_domain_query = Neo4jQuery::new(format!(
+            r#"
+            CREATE (d:Domain:{} {{name: $name, description: $description, created_date: toString(date())}})
+            RETURN elementId(d) AS elementId
+            "#,
+            EMBEDDED_LABEL
+        ))
+        .param("name", request.domain.name.clone())
+        .param("description", request.domain.description.clone());
+
+        let mut created = txn.execute(create_domain_query).await?;
+        let domain_element_id: String = match created.next(txn.handle()).await? {
+            Some(row) => row.get("elementId").unwrap_or_default(),
+            None => {
                 return Err(ServiceError::DatabaseError(
                     "Failed to create domain node".to_string(),
                 ));
             }
-        }
-        Err(e) => {
-            tracing::error!("Error creating domain: {}", e);
-            return Err(ServiceError::DatabaseError("Failed to create domain".to_string()));
-        }
-    };
+        };
 
-    // Create Domain Levels and link to domain
-    let mut level_element_ids: Vec<String> = Vec::new();
+        // Create Domain Levels, link them to the domain and process their requirements
+        let created_nodes =
+            create_levels_with_requirements(txn, &domain_element_id, &request.levels, &embeddings)
+                .await?;
 
-    for level in &request.levels {
-        let level_description = level.description.clone().unwrap_or_default();
+        // The initial levels become the published version 1
+        version::sync_published_levels(txn, &domain_element_id).await?;
 
-        let create_level_query = Neo4jQuery::new(
-            r#"
-            MATCH (d:Domain) WHERE elementId(d) = $domainId
-            CREATE (l:Domain_Level {
-                level: $level,
-                name: $name,
-                description: $description,
-                total_points_required: $points
-            })
-            CREATE (d)-[:HAS_DOMAIN_LEVEL]->(l)
-            RETURN elementId(l) AS elementId
-            "#
-            .to_string(),
-        )
-        .param("domainId", domain_element_id.clone())
-        .param("level", level.level)
-        .param("name", level.name.clone())
-        .param("description", level_description)
-        .param("points", level.points_required);
-
-        match graph.execute(create_level_query).await {
-            Ok(mut result) => {
-                if let Ok(Some(row)) = result.next().await {
-                    let id: String = row.get("elementId").unwrap_or_default();
-                    level_element_ids.push(id);
-                }
-            }
-            Err(e) => {
-                tracing::error!("Error creating domain level: {}", e);
-            }
-        }
-    }
+        Ok((domain_element_id, created_nodes))
+    })
+    .await;
 
-    // Process requirements for each level
-    for (level_idx, level) in request.levels.iter().enumerate() {
-        if level_idx >= level_element_ids.len() {
-            continue;
-        }
-        let level_element_id = &level_element_ids[level_idx];
+    let (domain_element_id, created_nodes) = result.inspect_err(|e| {
+        tracing::error!("Error creating domain '{}': {}", request.domain.name, e);
+    })?;
 
-        match process_level_requirements(graph, level_element_id, &level.requirements).await {
-            Ok(mut nodes) => created_nodes.append(&mut nodes),
-            Err(e) => {
-                tracing::error!("Error processing level requirements: {}", e);
-            }
-        }
-    }
+    layout::request_refresh();
 
     Ok(CreateDomainResult {
         success: true,
@@ -530,7 +770,8 @@ pub async fn create_domain(
     })
 }
 
-/// Update a domain with new levels and requirements
+/// Save edits to a domain as its draft version.
+/// The published version, and the users pursuing it, are untouched until the draft is published.
 pub async fn update_domain(
     graph: &Graph,
     request: UpdateDomainRequest,
@@ -548,152 +789,453 @@ pub async fn update_domain(
         ));
     }
 
-    // Verify domain exists
-    let verify_query = Neo4jQuery::new(
-        "MATCH (d:Domain) WHERE elementId(d) = $domainId RETURN d.name AS name".to_string(),
-    )
-    .param("domainId", request.domain_element_id.clone());
-
-    match graph.execute(verify_query).await {
-        Ok(mut result) => {
-            if result.next().await.ok().flatten().is_none() {
-                return Err(ServiceError::NotFound("Domain not found".to_string()));
-            }
-        }
-        Err(e) => {
-            tracing::error!("Error verifying domain: {}", e);
-            return Err(ServiceError::DatabaseError(
-                "Failed to verify domain".to_string(),
-            ));
-        }
-    }
+    let level_numbers: Vec<i64> = request.levels.iter().map(|l| l.level).collect();
+    validate_level_numbers(&level_numbers)?;
+    validate_requirement_groups(&request.levels)?;
+    validate_removals(&request.levels, &request.removed_node_element_ids)?;
+    let embeddings = embed_new_components(&request.levels).await?;
+
+    let result = with_transaction(graph, async |txn: &mut Txn| {
+        // Write the domain info and levels into the draft, creating it if needed
+        let (draft_version, created_nodes) = version::save_draft(
+            txn,
+            &request.domain_element_id,
+            &request.domain,
+            &request.levels,
+            &embeddings,
+        )
+        .await?;
 
-    // Delete user progress for removed nodes
-    let mut affected_user_count: i64 = 0;
-    if !request.removed_node_element_ids.is_empty() {
-        let delete_progress_query = Neo4jQuery::new(
-            r#"
-            UNWIND $nodeIds AS nodeId
-            MATCH (u:User)-[r:HAS_KNOWLEDGE|HAS_SKILL|HAS_TRAIT|ACHIEVED]->(n)
-            WHERE elementId(n) = nodeId
-            DELETE r
-            RETURN count(r) AS deletedCount
-            "#
-            .to_string(),
+        let affected_user_count = version::record_removals(
+            txn,
+            &request.domain_element_id,
+            &request.removed_node_element_ids,
         )
-        .param("nodeIds", request.removed_node_element_ids.clone());
+        .await?;
 
-        match graph.execute(delete_progress_query).await {
-            Ok(mut result) => {
-                if let Ok(Some(row)) = result.next().await {
-                    affected_user_count = row.get("deletedCount").unwrap_or(0);
-                }
-            }
-            Err(e) => {
-                tracing::error!("Error deleting user progress: {}", e);
+        Ok::<_, ServiceError>((draft_version, created_nodes, affected_user_count))
+    })
+    .await;
+
+    let (draft_version, created_nodes, affected_user_count) = result.inspect_err(|e| {
+        tracing::error!("Error updating domain {}: {}", request.domain_element_id, e);
+    })?;
+
+    layout::request_refresh();
+
+    Ok(UpdateDomainResult {
+        success: true,
+        domain_element_id: request.domain_element_id,
+        domain_name: request.domain.name,
+        created_nodes,
+        draft_version,
+        affected_user_progress_count: affected_user_count,
+    })
+}
+
+/// A removed component must not also be required by one of the levels
+fn validate_removals(levels: &[DomainLevel], removed: &[String]) -> Result<(), ServiceError> {
+    for level in levels {
+        let requirements = &level.requirements;
+        let required = requirements
+            .knowledge
+            .iter()
+            .map(|r| &r.node_element_id)
+            .chain(requirements.skills.iter().map(|r| &r.node_element_id))
+            .chain(requirements.traits.iter().map(|r| &r.node_element_id))
+            .chain(requirements.milestones.iter().map(|r| &r.node_element_id))
+            .flatten();
+        for element_id in required {
+            if removed.contains(element_id) {
+                return Err(ServiceError::ValidationError(format!(
+                    "Level {}: node {} is both required and removed",
+                    level.level, element_id
+                )));
             }
         }
     }
+    Ok(())
+}
 
-    // Update domain properties
-    let update_domain_query = Neo4jQuery::new(
-        r#"
-        MATCH (d:Domain) WHERE elementId(d) = $domainId
-        SET d.name = $name, d.description = $description
-        RETURN elementId(d) AS elementId
-        "#
-        .to_string(),
-    )
-    .param("domainId", request.domain_element_id.clone())
-    .param("name", request.domain.name.clone())
-    .param("description", request.domain.description.clone());
-
-    if let Err(e) = graph.run(update_domain_query).await {
-        tracing::error!("Error updating domain: {}", e);
-        return Err(ServiceError::DatabaseError(
-            "Failed to update domain".to_string(),
-        ));
-    }
+/// Requirement relationships copied when a domain is forked
+const REQUIREMENT_RELATIONSHIPS: &[&str] = &[
+    "REQUIRES_KNOWLEDGE",
+    "REQUIRES_SKILL",
+    "REQUIRES_TRAIT",
+    "REQUIRES_MILESTONE",
+];
+
+/// A level of the domain being forked
+struct SourceLevel {
+    element_id: String,
+    name: String,
+    description: String,
+}
 
-    // Delete old Domain_Level nodes and their relationships
-    let delete_levels_query = Neo4jQuery::new(
-        r#"
-        MATCH (d:Domain)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
-        WHERE elementId(d) = $domainId
-        DETACH DELETE l
-        "#
-        .to_string(),
-    )
-    .param("domainId", request.domain_element_id.clone());
-
-    if let Err(e) = graph.run(delete_levels_query).await {
-        tracing::error!("Error deleting old levels: {}", e);
-        return Err(ServiceError::DatabaseError(
-            "Failed to delete old levels".to_string(),
+/// Fork a domain under a new name.
+/// The Domain and its levels are copied; the copied levels are renamed after the
+/// fork and require the same shared component nodes with identical requirement
+/// properties. Returns the new domain in `get_domain` format.
+pub async fn fork_domain(graph: &Graph, request: ForkDomainRequest) -> Result<Value, ServiceError> {
+    if request.name.is_empty() {
+        return Err(ServiceError::ValidationError(
+            "Domain name is required".to_string(),
         ));
     }
 
-    let mut created_nodes: Vec<CreatedNodeInfo> = Vec::new();
+    // Embed the new Domain and levels up front, so no transaction waits on the provider
+    let (source_description, source_levels) = load_fork_source(graph, &request).await?;
+    let description = request.description.clone().unwrap_or(source_description);
+    let level_names: Vec<String> = source_levels
+        .iter()
+        .map(|level| fork_level_name(&level.name, &request.source_name, &request.name))
+        .collect();
 
-    // Create new Domain Levels and link to domain
-    let mut level_element_ids: Vec<String> = Vec::new();
+    let mut texts = vec![node_embedding_text(&request.name, &description)];
+    texts.extend(
+        source_levels
+            .iter()
+            .zip(&level_names)
+            .map(|(level, name)| node_embedding_text(name, &level.description)),
+    );
+    let mut embeddings = generate_embeddings(&texts)
+        .await
+        .map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?
+        .into_iter();
+    let domain_embedding = embeddings.next().unwrap_or_default();
+    let level_rows: Vec<BoltType> = source_levels
+        .iter()
+        .zip(level_names)
+        .zip(embeddings)
+        .map(|((level, name), embedding)| {
+            let mut row = BoltMap::new();
+            row.put("sourceId".into(), level.element_id.as_str().into());
+            row.put("name".into(), name.into());
+            row.put("embedding".into(), embedding.into());
+            BoltType::Map(row)
+        })
+        .collect();
+    let model = configured_model().map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
 
-    for level in &request.levels {
-        let level_description = level.description.clone().unwrap_or_default();
+    let result = with_transaction(graph, async |txn: &mut Txn| {
+        let check_query = Neo4jQuery::new(
+            "MATCH (d:Domain {name: $name}) RETURN elementId(d) AS id LIMIT 1".to_string(),
+        )
+        .param("name", request.name.clone());
 
-        let create_level_query = Neo4jQuery::new(
+        let mut existing = txn.execute(check_query).await?;
+        if existing.next(txn.handle()).await?.is_some() {
+            return Err(ServiceError::ValidationError(
+                "Domain with this name already exists".to_string(),
+            ));
+        }
+
+        // Copy the Domain node itself; version bookkeeping and layout start fresh
+        let create_fork_query = Neo4jQuery::new(format!(
             r#"
+            MATCH (source:Domain {{name: $sourceName}})
+            CREATE (d:Domain:{})
+            SET d = properties(source),
+                d.name = $name,
+                d.description = $description,
+                d.created_date = toString(date()),
+                d.embedding = $embedding,
+                d.embedding_model = $model,
+                d.embedding_version = $embeddingVersion
+            REMOVE d.published_version, d.layout_x, d.layout_y, d.cluster_id
+            CREATE (d)-[:FORKED_FROM {{
+                version: coalesce($sourceVersion, source.published_version),
+                forked_at: datetime()
+            }}]->(source)
+            RETURN elementId(d) AS elementId
+            "#,
+            EMBEDDED_LABEL
+        ))
+        .param("sourceName", request.source_name.clone())
+        .param("name", request.name.clone())
+        .param("description", description.clone())
+        .param("embedding", domain_embedding.clone())
+        .param("model", model.as_str())
+        .param("embeddingVersion", EMBEDDING_VERSION)
+        .param("sourceVersion", request.source_version);
+
+        let mut created = txn.execute(create_fork_query).await?;
+        let domain_element_id: String = match created.next(txn.handle()).await? {
+            Some(row) => row.get("elementId").unwrap_or_default(),
+            None => {
+                return Err(ServiceError::NotFound(format!(
+                    "Domain '{}' not found",
+                    request.source_name
+                )));
+            }
+        };
+
+        // Copy the levels under their new names, remembering which copy belongs to which original
+        let copy_levels_query = Neo4jQuery::new(format!(
+            r#"
+            UNWIND $levels AS row
+            MATCH (level:Domain_Level) WHERE elementId(level) = row.sourceId
             MATCH (d:Domain) WHERE elementId(d) = $domainId
-            CREATE (l:Domain_Level {
-                level: $level,
-                name: $name,
-                description: $description,
-                total_points_required: $points
-            })
-            CREATE (d)-[:HAS_DOMAIN_LEVEL]->(l)
-            RETURN elementId(l) AS elementId
+            CREATE (d)-[:HAS_DOMAIN_LEVEL]->(copy:Domain_Level:{})
+            SET copy = properties(level),
+                copy.name = row.name,
+                copy.domain = CASE WHEN level.domain IS NULL THEN NULL ELSE d.name END,
+                copy.embedding = row.embedding,
+                copy.embedding_model = $model,
+                copy.embedding_version = $embeddingVersion
+            REMOVE copy.layout_x, copy.layout_y, copy.cluster_id
+            RETURN elementId(level) AS sourceId, elementId(copy) AS copyId
+            "#,
+            EMBEDDED_LABEL
+        ))
+        .param("levels", level_rows.clone())
+        .param("model", model.as_str())
+        .param("embeddingVersion", EMBEDDING_VERSION)
+        .param("domainId", domain_element_id.clone());
+
+        let mut copied = txn.execute(copy_levels_query).await?;
+        let mut source_ids: Vec<String> = Vec::new();
+        let mut copy_ids: Vec<String> = Vec::new();
+        while let Some(row) = copied.next(txn.handle()).await? {
+            source_ids.push(row.get("sourceId").unwrap_or_default());
+            copy_ids.push(row.get("copyId").unwrap_or_default());
+        }
+
+        if source_ids.len() != level_rows.len() {
+            return Err(ServiceError::DatabaseError(format!(
+                "Levels of '{}' changed while forking",
+                request.source_name
+            )));
+        }
+
+        // Point the copied levels at the same components, with the same requirement properties
+        for relationship in REQUIREMENT_RELATIONSHIPS {
+            let copy_requirements_query = Neo4jQuery::new(format!(
+                r#"
+                UNWIND range(0, size($sourceIds) - 1) AS i
+                MATCH (level:Domain_Level) WHERE elementId(level) = $sourceIds[i]
+                MATCH (copy:Domain_Level) WHERE elementId(copy) = $copyIds[i]
+                MATCH (level)-[r:{0}]->(component)
+                CREATE (copy)-[copied:{0}]->(component)
+                SET copied = properties(r)
+                "#,
+                cypher::relationship_type(relationship)?
+            ))
+            .param("sourceIds", source_ids.clone())
+            .param("copyIds", copy_ids.clone());
+
+            txn.run(copy_requirements_query).await?;
+        }
+
+        // Requirement groups belong to a single level, so they are copied rather than shared
+        let copy_groups_query = Neo4jQuery::new(
+            r#"
+            UNWIND range(0, size($sourceIds) - 1) AS i
+            MATCH (level:Domain_Level) WHERE elementId(level) = $sourceIds[i]
+            MATCH (copy:Domain_Level) WHERE elementId(copy) = $copyIds[i]
+            MATCH (level)-[:HAS_REQUIREMENT_GROUP]->(group:Requirement_Group)
+            CREATE (copy)-[:HAS_REQUIREMENT_GROUP]->(copiedGroup:Requirement_Group)
+            SET copiedGroup = properties(group)
             "#
             .to_string(),
         )
-        .param("domainId", request.domain_element_id.clone())
-        .param("level", level.level)
-        .param("name", level.name.clone())
-        .param("description", level_description)
-        .param("points", level.points_required);
+        .param("sourceIds", source_ids.clone())
+        .param("copyIds", copy_ids.clone());
+        txn.run(copy_groups_query).await?;
 
-        match graph.execute(create_level_query).await {
-            Ok(mut result) => {
-                if let Ok(Some(row)) = result.next().await {
-                    let id: String = row.get("elementId").unwrap_or_default();
-                    level_element_ids.push(id);
-                }
-            }
-            Err(e) => {
-                tracing::error!("Error creating domain level: {}", e);
-            }
-        }
+        // The copied levels become the fork's published version 1
+        version::sync_published_levels(txn, &domain_element_id).await?;
+
+        Ok(())
+    })
+    .await;
+
+    result.inspect_err(|e| {
+        tracing::error!(
+            "Error forking domain '{}' into '{}': {}",
+            request.source_name,
+            request.name,
+            e
+        );
+    })?;
+    layout::request_refresh();
+
+    get_domain(graph, &request.name, None)
+        .await?
+        .ok_or_else(|| ServiceError::DatabaseError("Forked domain not found".to_string()))
+}
+
+/// Description and levels of the domain version being forked
+async fn load_fork_source(
+    graph: &Graph,
+    request: &ForkDomainRequest,
+) -> Result<(String, Vec<SourceLevel>), ServiceError> {
+    let source_levels = if request.source_version.is_some() {
+        "(source)-[:HAS_VERSION]->(:Domain_Version {version: $sourceVersion})-[:HAS_DOMAIN_LEVEL]->(level:Domain_Level)"
+    } else {
+        "(source)-[:HAS_DOMAIN_LEVEL]->(level:Domain_Level)"
+    };
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (source:Domain {{name: $sourceName}})
+        OPTIONAL MATCH {}
+        WITH source, level
+        ORDER BY level.level
+        RETURN coalesce(source.description, '') AS description,
+               collect(CASE WHEN level IS NULL THEN NULL ELSE {{
+                   elementId: elementId(level),
+                   name: level.name,
+                   description: coalesce(level.description, '')
+               }} END) AS levels
+        "#,
+        source_levels
+    ))
+    .param("sourceName", request.source_name.clone())
+    .param("sourceVersion", request.source_version);
+
+    let mut result = graph.execute(query).await?;
+    let Some(row) = result.next().await? else {
+        return Err(ServiceError::NotFound(format!(
+            "Domain '{}' not found",
+            request.source_name
+        )));
+    };
+
+    let levels: Vec<Value> = row.get("levels").unwrap_or_default();
+    let levels: Vec<SourceLevel> = levels
+        .iter()
+        .map(|level| SourceLevel {
+            element_id: level["elementId"].as_str().unwrap_or_default().to_string(),
+            name: level["name"].as_str().unwrap_or_default().to_string(),
+            description: level["description"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        })
+        .collect();
+    if levels.is_empty() {
+        return Err(ServiceError::NotFound(match request.source_version {
+            Some(version) => format!(
+                "Domain '{}' has no levels in version {}",
+                request.source_name, version
+            ),
+            None => format!("Domain '{}' has no levels", request.source_name),
+        }));
     }
 
-    // Process requirements for each level
-    for (level_idx, level) in request.levels.iter().enumerate() {
-        if level_idx >= level_element_ids.len() {
-            continue;
-        }
-        let level_element_id = &level_element_ids[level_idx];
+    Ok((row.get("description").unwrap_or_default(), levels))
+}
 
-        match process_level_requirements(graph, level_element_id, &level.requirements).await {
-            Ok(mut nodes) => created_nodes.append(&mut nodes),
-            Err(e) => {
-                tracing::error!("Error processing level requirements: {}", e);
-            }
-        }
+/// Name of a forked level: the source domain's name prefix is swapped for the
+/// fork's, otherwise the fork's name is prepended ("Chess Novice" -> "Blitz Novice")
+fn fork_level_name(level_name: &str, source_name: &str, name: &str) -> String {
+    match level_name.strip_prefix(source_name) {
+        Some(rest) if rest.is_empty() || rest.starts_with(' ') => format!("{}{}", name, rest),
+        _ => format!("{} {}", name, level_name),
     }
+}
 
-    Ok(UpdateDomainResult {
-        success: true,
-        domain_element_id: request.domain_element_id,
-        domain_name: request.domain.name,
-        created_nodes,
-        affected_user_progress_count: affected_user_count,
-    })
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forked_levels_take_the_fork_name() {
+        assert_eq!(
+            fork_level_name("Chess Novice", "Chess", "Blitz"),
+            "Blitz Novice"
+        );
+        assert_eq!(fork_level_name("Chess", "Chess", "Blitz"), "Blitz");
+        assert_eq!(
+            fork_level_name("Chessboard Basics", "Chess", "Blitz"),
+            "Blitz Chessboard Basics"
+        );
+        assert_eq!(fork_level_name("Novice", "Chess", "Blitz"), "Blitz Novice");
+    }
+
+    /// Needs a migrated Neo4j (`NEO4J_URI`, `NEO4J_USER`, `NEO4J_PASSWORD`) and
+    /// `EMBEDDING_PROVIDER=hash`; run with `cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn created_components_are_graph_nodes() {
+        use crate::common::embedding::generate_embedding;
+        use crate::common::similarity::FindSimilarNodesRequest;
+        use crate::domains::graph::models::{SimilarNodesRequest, SubgraphParams};
+        use crate::domains::graph::services::{find_similar_nodes, get_subgraph};
+
+        let env = |name: &str, default: &str| std::env::var(name).unwrap_or(default.to_string());
+        let graph = Graph::new(
+            env("NEO4J_URI", "127.0.0.1:7687"),
+            env("NEO4J_USER", "neo4j"),
+            env("NEO4J_PASSWORD", "neo4j"),
+        )
+        .expect("Neo4j connection");
+
+        let suffix = std::process::id();
+        let domain_name = format!("Component Test Domain {}", suffix);
+        let skill_name = format!("Component Test Skill {}", suffix);
+        let request: CreateDomainRequest = serde_json::from_value(json!({
+            "domain": {"name": domain_name, "description": "Test domain"},
+            "levels": [{
+                "level": 1,
+                "name": format!("{} Novice", domain_name),
+                "description": "First level",
+                "points_required": 100,
+                "requirements": {
+                    "knowledge": [],
+                    "skills": [{
+                        "newNode": {"name": skill_name, "description": "A test skill"},
+                        "dreyfus_level": "Novice"
+                    }],
+                    "traits": [],
+                    "milestones": []
+                }
+            }]
+        }))
+        .unwrap();
+
+        let created = create_domain(&graph, request).await.unwrap();
+        let skill_id = created.created_nodes[0].element_id.clone();
+
+        let params: SubgraphParams =
+            serde_json::from_value(json!({"root": created.domain_element_id, "depth": 2})).unwrap();
+        let subgraph = get_subgraph(&graph, params).await.unwrap();
+        let in_subgraph = subgraph.nodes.iter().any(|n| n.id == skill_id);
+
+        let embedding = generate_embedding(&node_embedding_text(&skill_name, "A test skill"))
+            .await
+            .unwrap();
+        let similar = find_similar_nodes(
+            &graph,
+            SimilarNodesRequest {
+                search: FindSimilarNodesRequest {
+                    embedding: Some(embedding),
+                    label: Some("Skill".to_string()),
+                    limit: Some(5),
+                    ..Default::default()
+                },
+                cursor: None,
+                fields: None,
+            },
+        )
+        .await
+        .unwrap();
+        let in_similar = similar.items.iter().any(|n| n["name"] == json!(skill_name));
+
+        let cleanup = Neo4jQuery::new(
+            r#"
+            MATCH (d:Domain {name: $domain})
+            OPTIONAL MATCH (d)-[:HAS_DOMAIN_LEVEL|HAS_VERSION*1..2]->(owned)
+            OPTIONAL MATCH (s:Skill {name: $skill})
+            DETACH DELETE d, owned, s
+            "#
+            .to_string(),
+        )
+        .param("domain", domain_name)
+        .param("skill", skill_name);
+        graph.run(cleanup).await.unwrap();
+
+        assert!(in_subgraph, "component missing from the subgraph");
+        assert!(in_similar, "component missing from similar nodes");
+    }
 }
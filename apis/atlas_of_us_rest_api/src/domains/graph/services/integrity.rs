@@ -0,0 +1,490 @@
+//! Domain integrity validator.
+//!
+//! Checks that a domain is well formed: every level has requirements, points
+//! grow with the level, proficiency never drops from one level to the next,
+//! prerequisites don't loop, components carry their key properties and an
+//! embedding, and `GENERALIZES_TO` points at a component of the same kind.
+//! The all-domains report additionally lists `Domain_Level` nodes no domain owns.
+
+use neo4rs::{Graph, Query as Neo4jQuery};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::domains::graph::level_template::{BLOOM_LEVELS, DREYFUS_LEVELS, scale_rank};
+use crate::domains::graph::models::{
+    ComponentRef, DomainIntegrityReport, IntegrityCheck, IntegrityIssue, IntegrityReport,
+    IssueSeverity, ServiceError,
+};
+use crate::domains::graph::schema;
+
+const COMPONENT_LABELS: &[&str] = &["Knowledge", "Skill", "Trait", "Milestone"];
+
+/// Longest prerequisite chain searched for cycles
+const MAX_CYCLE_LENGTH: usize = 8;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LevelRow {
+    level: i64,
+    points: Option<i64>,
+    #[serde(default)]
+    requirements: Vec<RequirementRow>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RequirementRow {
+    component_id: String,
+    label: String,
+    name: String,
+    bloom_level: Option<String>,
+    dreyfus_level: Option<String>,
+    min_score: Option<i64>,
+}
+
+impl RequirementRow {
+    fn component(&self) -> ComponentRef {
+        ComponentRef {
+            component_type: self.label.to_lowercase(),
+            name: self.name.clone(),
+        }
+    }
+
+    /// Comparable proficiency of the requirement; milestones have none
+    fn proficiency(&self) -> Option<i64> {
+        match self.label.as_str() {
+            "Knowledge" => scale_rank(BLOOM_LEVELS, self.bloom_level.as_deref()).map(|r| r as i64),
+            "Skill" => scale_rank(DREYFUS_LEVELS, self.dreyfus_level.as_deref()).map(|r| r as i64),
+            "Trait" => self.min_score,
+            _ => None,
+        }
+    }
+
+    fn proficiency_label(&self) -> String {
+        match self.label.as_str() {
+            "Knowledge" => self.bloom_level.clone().unwrap_or_default(),
+            "Skill" => self.dreyfus_level.clone().unwrap_or_default(),
+            _ => self.min_score.map(|s| s.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+struct ComponentRow {
+    label: String,
+    name: Option<String>,
+    properties: HashMap<String, Value>,
+    has_embedding: bool,
+    generalizations: Vec<GeneralizationRow>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeneralizationRow {
+    labels: Vec<String>,
+    name: Option<String>,
+    is_self: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CycleNode {
+    element_id: String,
+    label: String,
+    name: String,
+}
+
+fn issue(
+    check: IntegrityCheck,
+    message: String,
+    level: Option<i64>,
+    component: Option<ComponentRef>,
+) -> IntegrityIssue {
+    IntegrityIssue {
+        check,
+        severity: check.severity(),
+        message,
+        level,
+        component,
+    }
+}
+
+/// Validate one domain; without a version this is the published (live) domain
+pub async fn validate_domain(
+    graph: &Graph,
+    name: &str,
+    version: Option<i64>,
+) -> Result<DomainIntegrityReport, ServiceError> {
+    let levels = load_levels(graph, name, version).await?;
+
+    let component_ids: Vec<String> = levels
+        .iter()
+        .flat_map(|l| l.requirements.iter().map(|r| r.component_id.clone()))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut issues = Vec::new();
+    check_levels(&levels, &mut issues);
+    check_components(graph, &component_ids, &mut issues).await?;
+    check_prerequisite_cycles(graph, &component_ids, &mut issues).await?;
+
+    issues.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then(a.level.cmp(&b.level))
+            .then(a.component.cmp(&b.component))
+    });
+
+    Ok(domain_report(name, version, issues))
+}
+
+/// Validate every domain's live structure and look for orphaned levels
+pub async fn validate_all_domains(graph: &Graph) -> Result<IntegrityReport, ServiceError> {
+    let mut result = graph
+        .execute(Neo4jQuery::new(
+            "MATCH (d:Domain) RETURN d.name AS name ORDER BY name".to_string(),
+        ))
+        .await?;
+    let mut names: Vec<String> = Vec::new();
+    while let Some(row) = result.next().await? {
+        names.push(row.get("name").unwrap_or_default());
+    }
+
+    let mut domains = Vec::new();
+    for name in &names {
+        let report = validate_domain(graph, name, None).await?;
+        if !report.issues.is_empty() {
+            domains.push(report);
+        }
+    }
+
+    Ok(IntegrityReport {
+        domains_checked: names.len(),
+        domains_with_errors: domains.iter().filter(|d| !d.valid).count(),
+        orphaned_levels: orphaned_levels(graph).await?,
+        domains,
+    })
+}
+
+fn domain_report(
+    name: &str,
+    version: Option<i64>,
+    issues: Vec<IntegrityIssue>,
+) -> DomainIntegrityReport {
+    let error_count = issues
+        .iter()
+        .filter(|i| i.severity == IssueSeverity::Error)
+        .count();
+
+    DomainIntegrityReport {
+        domain_name: name.to_string(),
+        version,
+        valid: error_count == 0,
+        error_count,
+        warning_count: issues.len() - error_count,
+        issues,
+    }
+}
+
+async fn load_levels(
+    graph: &Graph,
+    name: &str,
+    version: Option<i64>,
+) -> Result<Vec<LevelRow>, ServiceError> {
+    let source_match = if version.is_some() {
+        "MATCH (domain:Domain {name: $name})-[:HAS_VERSION]->(source:Domain_Version {version: $version})"
+    } else {
+        "MATCH (domain:Domain {name: $name}) WITH domain AS source"
+    };
+
+    let mut query = Neo4jQuery::new(format!(
+        r#"
+        {}
+        OPTIONAL MATCH (source)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+        OPTIONAL MATCH (l)-[r:REQUIRES_KNOWLEDGE|REQUIRES_SKILL|REQUIRES_TRAIT|REQUIRES_MILESTONE]->(c)
+        WITH source, l, collect(CASE WHEN c IS NOT NULL THEN {{
+            componentId: elementId(c),
+            label: coalesce([x IN labels(c) WHERE x IN $componentLabels][0], ''),
+            name: coalesce(c.name, ''),
+            bloomLevel: r.bloom_level,
+            dreyfusLevel: r.dreyfus_level,
+            minScore: r.min_score
+        }} ELSE NULL END) AS requirements
+        ORDER BY l.level
+        RETURN count(source) AS found,
+               collect(CASE WHEN l IS NOT NULL THEN {{
+                   level: l.level,
+                   points: l.total_points_required,
+                   requirements: requirements
+               }} ELSE NULL END) AS levels
+        "#,
+        source_match
+    ))
+    .param("name", name)
+    .param("componentLabels", COMPONENT_LABELS.to_vec());
+    if let Some(version) = version {
+        query = query.param("version", version);
+    }
+
+    let mut result = graph.execute(query).await?;
+    let row = result.next().await?;
+    let found: i64 = row.as_ref().and_then(|r| r.get("found").ok()).unwrap_or(0);
+    if found == 0 {
+        return Err(ServiceError::NotFound(match version {
+            Some(version) => format!("Domain '{}' has no version {}", name, version),
+            None => format!("Domain '{}' not found", name),
+        }));
+    }
+
+    Ok(row.and_then(|r| r.get("levels").ok()).unwrap_or_default())
+}
+
+/// Empty levels, non-increasing points and proficiency that drops at a higher level
+fn check_levels(levels: &[LevelRow], issues: &mut Vec<IntegrityIssue>) {
+    let mut previous_points: Option<(i64, i64)> = None;
+    for level in levels {
+        if level.requirements.is_empty() {
+            issues.push(issue(
+                IntegrityCheck::EmptyLevel,
+                format!("Level {} has no requirements", level.level),
+                Some(level.level),
+                None,
+            ));
+        }
+
+        if let Some(points) = level.points {
+            if let Some((previous_level, previous)) = previous_points
+                && points <= previous
+            {
+                issues.push(issue(
+                    IntegrityCheck::PointsNotIncreasing,
+                    format!(
+                        "Level {} requires {} points, not more than level {} ({})",
+                        level.level, points, previous_level, previous
+                    ),
+                    Some(level.level),
+                    None,
+                ));
+            }
+            previous_points = Some((level.level, points));
+        }
+    }
+
+    // Highest proficiency each component was required at so far, and where
+    let mut highest: HashMap<&str, (i64, &RequirementRow)> = HashMap::new();
+    for level in levels {
+        for requirement in &level.requirements {
+            let Some(proficiency) = requirement.proficiency() else {
+                continue;
+            };
+            match highest.get(requirement.component_id.as_str()) {
+                Some((lower_level, earlier)) if earlier.proficiency() > Some(proficiency) => {
+                    issues.push(issue(
+                        IntegrityCheck::ProficiencyRegression,
+                        format!(
+                            "{} is required at {} in level {} but at {} in level {}",
+                            requirement.component(),
+                            earlier.proficiency_label(),
+                            lower_level,
+                            requirement.proficiency_label(),
+                            level.level
+                        ),
+                        Some(level.level),
+                        Some(requirement.component()),
+                    ));
+                }
+                _ => {
+                    highest.insert(&requirement.component_id, (level.level, requirement));
+                }
+            }
+        }
+    }
+}
+
+/// Missing key properties or embeddings, and broken `GENERALIZES_TO` targets
+async fn check_components(
+    graph: &Graph,
+    component_ids: &[String],
+    issues: &mut Vec<IntegrityIssue>,
+) -> Result<(), ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (c) WHERE elementId(c) IN $ids
+        OPTIONAL MATCH (c)-[:GENERALIZES_TO]->(g)
+        WITH c, collect(CASE WHEN g IS NOT NULL THEN {
+            labels: labels(g),
+            name: g.name,
+            isSelf: g = c
+        } ELSE NULL END) AS generalizations
+        RETURN [x IN labels(c) WHERE x IN $componentLabels][0] AS label,
+               c.name AS name,
+               c { .*, embedding: null } AS properties,
+               c.embedding IS NOT NULL AS hasEmbedding,
+               generalizations
+        "#
+        .to_string(),
+    )
+    .param("ids", component_ids.to_vec())
+    .param("componentLabels", COMPONENT_LABELS.to_vec());
+
+    let mut result = graph.execute(query).await?;
+    while let Some(row) = result.next().await? {
+        let component = ComponentRow {
+            label: row.get("label").unwrap_or_default(),
+            name: row.get("name").unwrap_or_default(),
+            properties: row.get("properties").unwrap_or_default(),
+            has_embedding: row.get("hasEmbedding").unwrap_or_default(),
+            generalizations: row.get("generalizations").unwrap_or_default(),
+        };
+        let reference = ComponentRef {
+            component_type: component.label.to_lowercase(),
+            name: component.name.clone().unwrap_or_default(),
+        };
+
+        if let Some(label_schema) = schema::label_schema(&component.label) {
+            for property in label_schema.properties.iter().filter(|p| p.required) {
+                let present = match component.properties.get(property.name) {
+                    Some(Value::String(s)) => !s.trim().is_empty(),
+                    Some(value) => !value.is_null(),
+                    None => false,
+                };
+                if !present {
+                    issues.push(issue(
+                        IntegrityCheck::MissingProperty,
+                        format!("{} has no {}", reference, property.name),
+                        None,
+                        Some(reference.clone()),
+                    ));
+                }
+            }
+        }
+
+        if !component.has_embedding {
+            issues.push(issue(
+                IntegrityCheck::MissingEmbedding,
+                format!("{} has no embedding", reference),
+                None,
+                Some(reference.clone()),
+            ));
+        }
+
+        for target in &component.generalizations {
+            let problem = if target.is_self {
+                Some("generalizes to itself".to_string())
+            } else if !target.labels.contains(&component.label) {
+                Some(format!(
+                    "generalizes to a {} node",
+                    target
+                        .labels
+                        .first()
+                        .map(String::as_str)
+                        .unwrap_or("unlabelled")
+                ))
+            } else if target.name.as_deref().is_none_or(|n| n.trim().is_empty()) {
+                Some("generalizes to a node without a name".to_string())
+            } else {
+                None
+            };
+
+            if let Some(problem) = problem {
+                issues.push(issue(
+                    IntegrityCheck::BrokenGeneralization,
+                    format!("{} {}", reference, problem),
+                    None,
+                    Some(reference.clone()),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prerequisite cycles through any of the domain's components
+async fn check_prerequisite_cycles(
+    graph: &Graph,
+    component_ids: &[String],
+    issues: &mut Vec<IntegrityIssue>,
+) -> Result<(), ServiceError> {
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH path = (c)-[:REQUIRES_KNOWLEDGE|REQUIRES_SKILL|REQUIRES_TRAIT|REQUIRES_MILESTONE*1..{}]->(c)
+        WHERE elementId(c) IN $ids
+        RETURN [n IN nodes(path)[..-1] | {{
+            elementId: elementId(n),
+            label: coalesce([x IN labels(n) WHERE x IN $componentLabels][0], ''),
+            name: coalesce(n.name, '')
+        }}] AS cycle
+        "#,
+        MAX_CYCLE_LENGTH
+    ))
+    .param("ids", component_ids.to_vec())
+    .param("componentLabels", COMPONENT_LABELS.to_vec());
+
+    // The same cycle is found once from each of its members; keep one rotation
+    let mut cycles: BTreeMap<Vec<String>, Vec<CycleNode>> = BTreeMap::new();
+    let mut result = graph.execute(query).await?;
+    while let Some(row) = result.next().await? {
+        let mut cycle: Vec<CycleNode> = row.get("cycle").unwrap_or_default();
+        let Some(start) = cycle
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.element_id.cmp(&b.1.element_id))
+            .map(|(i, _)| i)
+        else {
+            continue;
+        };
+        cycle.rotate_left(start);
+        let key = cycle.iter().map(|n| n.element_id.clone()).collect();
+        cycles.entry(key).or_insert(cycle);
+    }
+
+    for cycle in cycles.into_values() {
+        let path: Vec<String> = cycle
+            .iter()
+            .chain(cycle.first())
+            .map(|n| n.name.clone())
+            .collect();
+        issues.push(issue(
+            IntegrityCheck::PrerequisiteCycle,
+            format!("Prerequisite cycle: {}", path.join(" -> ")),
+            None,
+            Some(ComponentRef {
+                component_type: cycle[0].label.to_lowercase(),
+                name: cycle[0].name.clone(),
+            }),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Domain_Level nodes that neither a Domain nor a Domain_Version owns
+async fn orphaned_levels(graph: &Graph) -> Result<Vec<IntegrityIssue>, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (l:Domain_Level)
+        WHERE NOT ()-[:HAS_DOMAIN_LEVEL]->(l)
+        RETURN l.name AS name, l.level AS level
+        ORDER BY name
+        "#
+        .to_string(),
+    );
+
+    let mut result = graph.execute(query).await?;
+    let mut issues = Vec::new();
+    while let Some(row) = result.next().await? {
+        let name: Option<String> = row.get("name").unwrap_or_default();
+        issues.push(issue(
+            IntegrityCheck::OrphanedLevel,
+            format!(
+                "Domain_Level '{}' belongs to no domain",
+                name.unwrap_or_default()
+            ),
+            row.get::<Option<i64>>("level").unwrap_or_default(),
+            None,
+        ));
+    }
+    Ok(issues)
+}
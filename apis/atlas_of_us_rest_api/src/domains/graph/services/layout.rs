@@ -0,0 +1,614 @@
+//! Precomputed 2D layout and communities for the Layer 1 constellation.
+//!
+//! The laid-out graph holds Domains, their live levels and the components, so
+//! draft and archived levels stay out of the picture. Communities come from
+//! label propagation; positions from a force-directed (Fruchterman-Reingold)
+//! layout in which every node is also pulled towards the centre of its
+//! community, so communities show up as clusters. Repulsion is only computed
+//! between nodes in neighbouring grid cells, which keeps an iteration roughly
+//! linear in the number of nodes.
+//!
+//! A full run recomputes everything, starting from the stored positions so the
+//! picture stays recognisable. An incremental run only places nodes without
+//! coordinates (new domains and components) among their fixed neighbours and
+//! gives them their neighbours' community; when more than a quarter of the
+//! graph is new it runs in full instead. Results are stored on the nodes as
+//! `layout_x`, `layout_y` and `cluster_id`, and served by the subgraph routes.
+//!
+//! The scheduler started with the server makes an incremental pass every
+//! `LAYOUT_REFRESH_SECS` seconds (default 300, `0` disables it), and shortly
+//! after a domain service reports a change through [`request_refresh`].
+
+use neo4rs::{BoltMap, BoltType, Graph, Query as Neo4jQuery};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::domains::graph::models::{GraphLayoutJob, ServiceError, StartLayoutRequest};
+use crate::domains::graph::schema::{EMBEDDED_LABEL, SOFT_DELETE_PROPERTY};
+
+const LAYOUT_NAME: &str = "constellation";
+const DEFAULT_REFRESH_SECS: u64 = 300;
+/// Wait after a change is reported, so a burst of edits triggers one run
+const REFRESH_DEBOUNCE: Duration = Duration::from_secs(5);
+/// Share of new nodes above which an incremental run becomes a full one
+const MAX_INCREMENTAL_SHARE: f64 = 0.25;
+const WRITE_BATCH_SIZE: usize = 1000;
+
+/// Ideal edge length
+const SPACING: f64 = 50.0;
+const FULL_ITERATIONS: usize = 200;
+const INCREMENTAL_ITERATIONS: usize = 100;
+/// Pull towards the community centre, per unit of distance
+const CLUSTER_GRAVITY: f64 = 0.1;
+/// Pull towards the origin, which keeps disconnected parts from drifting off
+const CENTER_GRAVITY: f64 = 0.01;
+const MAX_PROPAGATION_ROUNDS: usize = 20;
+
+/// Set while a run is in progress in this process
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static REFRESH: Notify = Notify::const_new();
+
+/// Predicate on `n` selecting the nodes taking part in the layout; draft and
+/// archived levels are already excluded by not carrying the embedded label
+fn member() -> String {
+    format!("n.{} IS NULL", SOFT_DELETE_PROPERTY)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Full,
+    Incremental,
+}
+
+impl Mode {
+    fn as_str(self) -> &'static str {
+        match self {
+            Mode::Full => "full",
+            Mode::Incremental => "incremental",
+        }
+    }
+}
+
+/// The laid-out graph as stored, nodes in element id order
+struct LayoutGraph {
+    ids: Vec<String>,
+    adjacency: Vec<Vec<usize>>,
+    edges: Vec<(usize, usize)>,
+    positions: Vec<Option<(f64, f64)>>,
+    clusters: Vec<Option<i64>>,
+}
+
+/// Positions and communities for every node, and which of them changed
+struct ComputedLayout {
+    positions: Vec<(f64, f64)>,
+    clusters: Vec<i64>,
+    changed: Vec<bool>,
+}
+
+/// The last recorded run, if any
+pub async fn layout_status(graph: &Graph) -> Result<Option<GraphLayoutJob>, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (l:_GraphLayout {name: $name})
+        RETURN {
+            status: l.status,
+            mode: l.mode,
+            nodes: coalesce(l.nodes, 0),
+            placed: coalesce(l.placed, 0),
+            clusters: coalesce(l.clusters, 0),
+            startedAt: l.started_at,
+            updatedAt: l.updated_at,
+            error: l.error
+        } AS job
+        "#
+        .to_string(),
+    )
+    .param("name", LAYOUT_NAME);
+    let mut result = graph.execute(query).await?;
+    match result.next().await? {
+        Some(row) => row
+            .get("job")
+            .map(Some)
+            .map_err(|e| ServiceError::DatabaseError(format!("Failed to read layout job: {}", e))),
+        None => Ok(None),
+    }
+}
+
+/// Start a layout run in the background and return the job as recorded
+/// before it. Starting while a run is in progress is a `ValidationError`.
+pub async fn start_layout(
+    graph: &Graph,
+    request: StartLayoutRequest,
+) -> Result<GraphLayoutJob, ServiceError> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(ServiceError::ValidationError(
+            "a layout job is already running".to_string(),
+        ));
+    }
+    let mode = if request.full {
+        Mode::Full
+    } else {
+        Mode::Incremental
+    };
+
+    let job = match record_start(graph, mode).await {
+        Ok(job) => job,
+        Err(e) => {
+            RUNNING.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+
+    let graph = graph.clone();
+    tokio::spawn(async move {
+        run_and_record(&graph, mode).await;
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+
+    Ok(job)
+}
+
+/// Have the scheduler place new nodes soon
+pub fn request_refresh() {
+    REFRESH.notify_one();
+}
+
+/// Run incremental passes in the background for the life of the process
+pub fn spawn_layout_scheduler(graph: Graph) {
+    let interval = std::env::var("LAYOUT_REFRESH_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_REFRESH_SECS);
+    if interval == 0 {
+        tracing::info!("Layout scheduler disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(interval)) => {}
+                _ = REFRESH.notified() => tokio::time::sleep(REFRESH_DEBOUNCE).await,
+            }
+            match unplaced_nodes(&graph).await {
+                Ok(0) => continue,
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("Failed to check for unplaced nodes: {}", e);
+                    continue;
+                }
+            }
+            if RUNNING.swap(true, Ordering::SeqCst) {
+                continue;
+            }
+            match record_start(&graph, Mode::Incremental).await {
+                Ok(_) => run_and_record(&graph, Mode::Incremental).await,
+                Err(e) => tracing::error!("Failed to start layout run: {}", e),
+            }
+            RUNNING.store(false, Ordering::SeqCst);
+        }
+    });
+}
+
+async fn unplaced_nodes(graph: &Graph) -> Result<i64, ServiceError> {
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (n:{})
+        WHERE {} AND (n.layout_x IS NULL OR n.cluster_id IS NULL)
+        RETURN count(n) AS unplaced
+        "#,
+        EMBEDDED_LABEL,
+        member()
+    ));
+    let mut result = graph.execute(query).await?;
+    Ok(match result.next().await? {
+        Some(row) => row.get("unplaced").unwrap_or_default(),
+        None => 0,
+    })
+}
+
+async fn record_start(graph: &Graph, mode: Mode) -> Result<GraphLayoutJob, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MERGE (l:_GraphLayout {name: $name})
+        SET l.status = 'running', l.mode = $mode, l.placed = 0, l.error = null,
+            l.started_at = toString(datetime()), l.updated_at = toString(datetime())
+        "#
+        .to_string(),
+    )
+    .param("name", LAYOUT_NAME)
+    .param("mode", mode.as_str());
+    graph.run(query).await?;
+
+    layout_status(graph)
+        .await?
+        .ok_or_else(|| ServiceError::DatabaseError("Layout job was not recorded".to_string()))
+}
+
+async fn run_and_record(graph: &Graph, mode: Mode) {
+    if let Err(e) = run(graph, mode).await {
+        tracing::error!("Layout job failed: {}", e);
+        let query = Neo4jQuery::new(
+            r#"
+            MATCH (l:_GraphLayout {name: $name})
+            SET l.status = 'failed', l.error = $error, l.updated_at = toString(datetime())
+            "#
+            .to_string(),
+        )
+        .param("name", LAYOUT_NAME)
+        .param("error", e.to_string());
+        if let Err(e) = graph.run(query).await {
+            tracing::error!("Failed to record layout failure: {}", e);
+        }
+    }
+}
+
+async fn run(graph: &Graph, requested: Mode) -> Result<(), ServiceError> {
+    let loaded = load_graph(graph).await?;
+    let unplaced = (0..loaded.ids.len())
+        .filter(|&i| loaded.positions[i].is_none() || loaded.clusters[i].is_none())
+        .count();
+    let mode = if requested == Mode::Incremental
+        && unplaced as f64 > MAX_INCREMENTAL_SHARE * loaded.ids.len() as f64
+    {
+        Mode::Full
+    } else {
+        requested
+    };
+
+    let (loaded, computed) = tokio::task::spawn_blocking(move || {
+        let computed = compute(&loaded, mode);
+        (loaded, computed)
+    })
+    .await
+    .map_err(|e| ServiceError::DatabaseError(format!("Layout computation panicked: {}", e)))?;
+
+    let rows: Vec<BoltType> = (0..loaded.ids.len())
+        .filter(|&i| computed.changed[i])
+        .map(|i| {
+            let mut row = BoltMap::new();
+            row.put("id".into(), loaded.ids[i].as_str().into());
+            row.put("x".into(), computed.positions[i].0.into());
+            row.put("y".into(), computed.positions[i].1.into());
+            row.put("cluster".into(), computed.clusters[i].into());
+            BoltType::Map(row)
+        })
+        .collect();
+    let placed = rows.len();
+    for batch in rows.chunks(WRITE_BATCH_SIZE) {
+        let query = Neo4jQuery::new(
+            r#"
+            UNWIND $rows AS row
+            MATCH (n) WHERE elementId(n) = row.id
+            SET n.layout_x = row.x, n.layout_y = row.y, n.cluster_id = row.cluster
+            "#
+            .to_string(),
+        )
+        .param("rows", batch.to_vec());
+        graph.run(query).await?;
+    }
+
+    let clusters = computed.clusters.iter().collect::<HashSet<_>>().len();
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (l:_GraphLayout {name: $name})
+        SET l.status = 'completed', l.mode = $mode, l.nodes = $nodes, l.placed = $placed,
+            l.clusters = $clusters, l.updated_at = toString(datetime())
+        "#
+        .to_string(),
+    )
+    .param("name", LAYOUT_NAME)
+    .param("mode", mode.as_str())
+    .param("nodes", loaded.ids.len() as i64)
+    .param("placed", placed as i64)
+    .param("clusters", clusters as i64);
+    graph.run(query).await?;
+
+    tracing::info!(
+        "{} layout placed {} of {} nodes in {} clusters",
+        mode.as_str(),
+        placed,
+        loaded.ids.len(),
+        clusters
+    );
+    Ok(())
+}
+
+async fn load_graph(graph: &Graph) -> Result<LayoutGraph, ServiceError> {
+    let node_query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (n:{})
+        WHERE {}
+        RETURN elementId(n) AS id, n.layout_x AS x, n.layout_y AS y, n.cluster_id AS cluster
+        ORDER BY id
+        "#,
+        EMBEDDED_LABEL,
+        member()
+    ));
+    let mut result = graph.execute(node_query).await?;
+    let mut ids = Vec::new();
+    let mut positions = Vec::new();
+    let mut clusters = Vec::new();
+    while let Some(row) = result.next().await? {
+        ids.push(row.get::<String>("id").unwrap_or_default());
+        positions.push(match (row.get::<f64>("x"), row.get::<f64>("y")) {
+            (Ok(x), Ok(y)) => Some((x, y)),
+            _ => None,
+        });
+        clusters.push(row.get::<i64>("cluster").ok());
+    }
+
+    let index: HashMap<&str, usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), i))
+        .collect();
+    let edge_query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (a)-[]->(b:{})
+        WHERE elementId(a) IN $ids AND elementId(b) IN $ids AND b.{} IS NULL
+        RETURN DISTINCT elementId(a) AS source, elementId(b) AS target
+        "#,
+        EMBEDDED_LABEL, SOFT_DELETE_PROPERTY
+    ))
+    .param("ids", ids.clone());
+    let mut result = graph.execute(edge_query).await?;
+    let mut seen = HashSet::new();
+    let mut edges = Vec::new();
+    let mut adjacency = vec![Vec::new(); ids.len()];
+    while let Some(row) = result.next().await? {
+        let source: String = row.get("source").unwrap_or_default();
+        let target: String = row.get("target").unwrap_or_default();
+        let (Some(&a), Some(&b)) = (index.get(source.as_str()), index.get(target.as_str())) else {
+            continue;
+        };
+        if a == b || !seen.insert((a.min(b), a.max(b))) {
+            continue;
+        }
+        edges.push((a, b));
+        adjacency[a].push(b);
+        adjacency[b].push(a);
+    }
+
+    Ok(LayoutGraph {
+        ids,
+        adjacency,
+        edges,
+        positions,
+        clusters,
+    })
+}
+
+fn compute(graph: &LayoutGraph, mode: Mode) -> ComputedLayout {
+    let n = graph.ids.len();
+    let changed: Vec<bool> = match mode {
+        Mode::Full => vec![true; n],
+        Mode::Incremental => (0..n)
+            .map(|i| graph.positions[i].is_none() || graph.clusters[i].is_none())
+            .collect(),
+    };
+
+    // Start every node in a community of its own, keeping fixed ones as stored
+    let first_fresh = match mode {
+        Mode::Full => 0,
+        Mode::Incremental => graph.clusters.iter().flatten().max().map_or(0, |m| m + 1),
+    };
+    let initial: Vec<i64> = (0..n)
+        .map(|i| match (mode, graph.clusters[i]) {
+            (Mode::Incremental, Some(cluster)) if !changed[i] => cluster,
+            _ => first_fresh + i as i64,
+        })
+        .collect();
+    let clusters = renumber(
+        propagate_labels(&graph.adjacency, initial, &changed),
+        first_fresh,
+    );
+
+    let side = (n as f64).sqrt() * SPACING;
+    let positions: Vec<(f64, f64)> = (0..n)
+        .map(|i| match (mode, graph.positions[i]) {
+            (Mode::Full, Some(position)) => position,
+            (Mode::Incremental, Some(position)) if !changed[i] => position,
+            _ => initial_position(graph, i, side),
+        })
+        .collect();
+    let iterations = match mode {
+        Mode::Full => FULL_ITERATIONS,
+        Mode::Incremental => INCREMENTAL_ITERATIONS,
+    };
+    let start_temperature = match mode {
+        Mode::Full => (side / 10.0).max(SPACING),
+        Mode::Incremental => 2.0 * SPACING,
+    };
+    let positions = force_layout(
+        positions,
+        &graph.edges,
+        &clusters,
+        &changed,
+        iterations,
+        start_temperature,
+    );
+
+    ComputedLayout {
+        positions,
+        clusters,
+        changed,
+    }
+}
+
+/// Give each movable node the most common label among its neighbours, until
+/// no label changes; ties go to the smallest label
+fn propagate_labels(adjacency: &[Vec<usize>], mut labels: Vec<i64>, movable: &[bool]) -> Vec<i64> {
+    for _ in 0..MAX_PROPAGATION_ROUNDS {
+        let mut changed = false;
+        for v in (0..labels.len()).filter(|&v| movable[v]) {
+            let mut counts: HashMap<i64, usize> = HashMap::new();
+            for &u in &adjacency[v] {
+                *counts.entry(labels[u]).or_default() += 1;
+            }
+            let best = counts
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+                .map(|(label, _)| label);
+            if let Some(best) = best
+                && best != labels[v]
+            {
+                labels[v] = best;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    labels
+}
+
+/// Number labels from `first` upwards, largest community first; labels below
+/// `first` belong to fixed communities and are kept
+fn renumber(labels: Vec<i64>, first: i64) -> Vec<i64> {
+    let mut sizes: HashMap<i64, usize> = HashMap::new();
+    for &label in labels.iter().filter(|&&l| l >= first) {
+        *sizes.entry(label).or_default() += 1;
+    }
+    let mut fresh: Vec<(i64, usize)> = sizes.into_iter().collect();
+    fresh.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let mapping: HashMap<i64, i64> = fresh
+        .iter()
+        .enumerate()
+        .map(|(i, (label, _))| (*label, first + i as i64))
+        .collect();
+    labels
+        .into_iter()
+        .map(|l| mapping.get(&l).copied().unwrap_or(l))
+        .collect()
+}
+
+/// Near the placed neighbours of a node if it has any, otherwise a
+/// deterministic spot in a square of the given side
+fn initial_position(graph: &LayoutGraph, i: usize, side: f64) -> (f64, f64) {
+    let (jx, jy) = jitter(&graph.ids[i]);
+    let placed: Vec<(f64, f64)> = graph.adjacency[i]
+        .iter()
+        .filter_map(|&u| graph.positions[u])
+        .collect();
+    if placed.is_empty() {
+        return (jx * side, jy * side);
+    }
+    let count = placed.len() as f64;
+    let (sx, sy) = placed
+        .iter()
+        .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+    (sx / count + jx * SPACING, sy / count + jy * SPACING)
+}
+
+/// Two values in `-0.5..0.5` derived from the element id
+fn jitter(id: &str) -> (f64, f64) {
+    let hash = id.bytes().fold(0xcbf29ce484222325_u64, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    });
+    let unit = |bits: u64| bits as f64 / u32::MAX as f64 - 0.5;
+    (unit(hash & 0xffff_ffff), unit(hash >> 32))
+}
+
+fn force_layout(
+    mut positions: Vec<(f64, f64)>,
+    edges: &[(usize, usize)],
+    clusters: &[i64],
+    movable: &[bool],
+    iterations: usize,
+    start_temperature: f64,
+) -> Vec<(f64, f64)> {
+    let n = positions.len();
+    let k = SPACING;
+    let cell_size = 2.0 * k;
+    let cell = |(x, y): (f64, f64)| {
+        (
+            (x / cell_size).floor() as i64,
+            (y / cell_size).floor() as i64,
+        )
+    };
+
+    for iteration in 0..iterations {
+        let temperature = start_temperature * (1.0 - iteration as f64 / iterations as f64);
+        let mut displacement = vec![(0.0, 0.0); n];
+
+        // Repulsion between nodes closer than one cell
+        let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (i, &position) in positions.iter().enumerate() {
+            grid.entry(cell(position)).or_default().push(i);
+        }
+        for i in (0..n).filter(|&i| movable[i]) {
+            let (cx, cy) = cell(positions[i]);
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for &j in grid.get(&(cx + dx, cy + dy)).into_iter().flatten() {
+                        if i == j {
+                            continue;
+                        }
+                        let (mut ddx, ddy) = (
+                            positions[i].0 - positions[j].0,
+                            positions[i].1 - positions[j].1,
+                        );
+                        if ddx == 0.0 && ddy == 0.0 {
+                            // Split coincident nodes apart in a stable direction
+                            ddx = if i < j { 0.01 } else { -0.01 };
+                        }
+                        let distance = ddx.hypot(ddy);
+                        if distance < cell_size {
+                            let force = k * k / distance;
+                            displacement[i].0 += ddx / distance * force;
+                            displacement[i].1 += ddy / distance * force;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Attraction along edges
+        for &(a, b) in edges {
+            let (ddx, ddy) = (
+                positions[a].0 - positions[b].0,
+                positions[a].1 - positions[b].1,
+            );
+            let distance = ddx.hypot(ddy).max(0.01);
+            let force = distance * distance / k;
+            let (fx, fy) = (ddx / distance * force, ddy / distance * force);
+            if movable[a] {
+                displacement[a].0 -= fx;
+                displacement[a].1 -= fy;
+            }
+            if movable[b] {
+                displacement[b].0 += fx;
+                displacement[b].1 += fy;
+            }
+        }
+
+        // Gravity towards community centres and the origin
+        let mut centres: HashMap<i64, (f64, f64, f64)> = HashMap::new();
+        for (i, &(x, y)) in positions.iter().enumerate() {
+            let centre = centres.entry(clusters[i]).or_default();
+            *centre = (centre.0 + x, centre.1 + y, centre.2 + 1.0);
+        }
+        for i in (0..n).filter(|&i| movable[i]) {
+            let (sx, sy, count) = centres[&clusters[i]];
+            let (x, y) = positions[i];
+            displacement[i].0 += (sx / count - x) * CLUSTER_GRAVITY - x * CENTER_GRAVITY;
+            displacement[i].1 += (sy / count - y) * CLUSTER_GRAVITY - y * CENTER_GRAVITY;
+        }
+
+        for i in (0..n).filter(|&i| movable[i]) {
+            let (dx, dy) = displacement[i];
+            let length = dx.hypot(dy);
+            if length > 0.0 {
+                let step = length.min(temperature);
+                positions[i].0 += dx / length * step;
+                positions[i].1 += dy / length * step;
+            }
+        }
+    }
+
+    positions
+}
@@ -0,0 +1,250 @@
+//! Paginated domain listing with sorting, text, semantic and taxonomy filters.
+//!
+//! Pages are keyset-paginated on `(sort key, name)`: domain names are unique,
+//! so the name breaks ties and the cursor stays stable while domains are
+//! added or removed between requests.
+
+use neo4rs::{Graph, Query as Neo4jQuery};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+use crate::common::neo4j_utils::json_value_to_bolt_type;
+use crate::common::pagination::{Page, decode_cursor, encode_cursor, page_size};
+use crate::common::similarity::{
+    FindSimilarNodesRequest, find_similar_nodes as similarity_find_similar_nodes,
+};
+use crate::domains::graph::models::{
+    DomainListItem, DomainSort, ListDomainsParams, ServiceError, SortOrder,
+};
+
+use super::taxonomy::{CATEGORY_PATH, normalize_tags};
+
+const DEFAULT_MIN_SCORE: f64 = 0.6;
+/// Vector search is top-k, so semantic listings are capped at this many domains
+const SEMANTIC_CANDIDATES: i32 = 100;
+
+/// Position of the last item on the previous page
+#[derive(Debug, Serialize, Deserialize)]
+struct DomainCursor {
+    sort: DomainSort,
+    order: SortOrder,
+    key: Value,
+    name: String,
+}
+
+pub async fn list_domains(
+    graph: &Graph,
+    params: ListDomainsParams,
+) -> Result<Page<DomainListItem>, ServiceError> {
+    let semantic = params
+        .semantic
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let sort = params.sort.unwrap_or(if semantic.is_some() {
+        DomainSort::Relevance
+    } else {
+        DomainSort::Name
+    });
+    if sort == DomainSort::Relevance && semantic.is_none() {
+        return Err(ServiceError::ValidationError(
+            "sort=relevance requires a semantic query".to_string(),
+        ));
+    }
+    let order = params.order.unwrap_or(match sort {
+        DomainSort::Name => SortOrder::Asc,
+        _ => SortOrder::Desc,
+    });
+
+    let cursor = match params.cursor.as_deref() {
+        Some(raw) => match decode_cursor::<DomainCursor>(raw) {
+            Some(c) if c.sort == sort && c.order == order => Some(c),
+            _ => return Err(ServiceError::ValidationError("invalid cursor".to_string())),
+        },
+        None => None,
+    };
+
+    let relevance = match semantic {
+        Some(text) => Some(semantic_scores(graph, text, params.min_score).await?),
+        None => None,
+    };
+    let query_text = params
+        .query
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(str::to_string);
+    let limit = page_size(params.limit);
+
+    let sort_key = match sort {
+        DomainSort::Name => "d.name",
+        DomainSort::Created => "coalesce(toString(d.created_date), '')",
+        DomainSort::Popularity => "size([(:Person)-[:PURSUING]->(d) | 1])",
+        DomainSort::Relevance => "$relevance[elementId(d)]",
+    };
+    let (direction, comparison) = match order {
+        SortOrder::Asc => ("ASC", ">"),
+        SortOrder::Desc => ("DESC", "<"),
+    };
+    let keyset = if cursor.is_some() {
+        format!(
+            "WHERE sortKey {0} $cursorKey OR (sortKey = $cursorKey AND d.name > $cursorName)",
+            comparison
+        )
+    } else {
+        String::new()
+    };
+
+    let category = params
+        .category
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string);
+    let tags: Vec<String> = params
+        .tags
+        .as_deref()
+        .map(|t| normalize_tags(&t.split(',').map(str::to_string).collect::<Vec<_>>()))
+        .unwrap_or_default();
+    let subcategories = if params.include_subcategories.unwrap_or(true) {
+        "*0.."
+    } else {
+        "*0..0"
+    };
+
+    let filters = format!(
+        r#"
+        MATCH (d:Domain)
+        WHERE ($query IS NULL
+               OR toLower(d.name) CONTAINS toLower($query)
+               OR toLower(coalesce(d.description, '')) CONTAINS toLower($query))
+          AND ($relevance IS NULL OR elementId(d) IN keys($relevance))
+          AND ($category IS NULL OR EXISTS {{
+              MATCH (d)-[:IN_CATEGORY]->(:Category)-[:SUBCATEGORY_OF{}]->(:Category {{name: $category}})
+          }})
+          AND all(tag IN $tags WHERE tag IN coalesce(d.tags, []))
+        "#,
+        subcategories
+    );
+
+    let page_query = Neo4jQuery::new(format!(
+        r#"
+        {filters}
+        WITH d, {sort_key} AS sortKey
+        {keyset}
+        WITH d, sortKey
+        ORDER BY sortKey {direction}, d.name ASC
+        LIMIT $limit
+        OPTIONAL MATCH (d)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+        OPTIONAL MATCH (l)-[r:REQUIRES_KNOWLEDGE|REQUIRES_SKILL|REQUIRES_TRAIT|REQUIRES_MILESTONE]->(c)
+        WITH d, sortKey,
+             count(DISTINCT l) AS levelCount,
+             count(DISTINCT CASE WHEN type(r) = 'REQUIRES_KNOWLEDGE' THEN c END) AS knowledge,
+             count(DISTINCT CASE WHEN type(r) = 'REQUIRES_SKILL' THEN c END) AS skills,
+             count(DISTINCT CASE WHEN type(r) = 'REQUIRES_TRAIT' THEN c END) AS traits,
+             count(DISTINCT CASE WHEN type(r) = 'REQUIRES_MILESTONE' THEN c END) AS milestones
+        RETURN {{
+            elementId: elementId(d),
+            name: d.name,
+            description: d.description,
+            avatarUrl: d.avatar_url,
+            createdDate: toString(d.created_date),
+            pursuerCount: size([(:Person)-[:PURSUING]->(d) | 1]),
+            levelCount: levelCount,
+            components: {{knowledge: knowledge, skills: skills, traits: traits, milestones: milestones}},
+            categoryPath: {category_path},
+            tags: coalesce(d.tags, []),
+            relevance: $relevance[elementId(d)]
+        }} AS domain
+        ORDER BY sortKey {direction}, d.name ASC
+        "#,
+        category_path = CATEGORY_PATH
+    ))
+    .param("query", query_text.clone())
+    .param("relevance", relevance.clone())
+    .param("category", category.clone())
+    .param("tags", tags.clone())
+    // One extra row tells us whether another page follows
+    .param("limit", limit + 1)
+    .param(
+        "cursorKey",
+        json_value_to_bolt_type(cursor.as_ref().map_or(&Value::Null, |c| &c.key)),
+    )
+    .param("cursorName", cursor.as_ref().map(|c| c.name.clone()));
+
+    let mut result = graph.execute(page_query).await?;
+    let mut items: Vec<DomainListItem> = Vec::new();
+    while let Some(row) = result.next().await? {
+        items.push(row.get("domain").map_err(|e| {
+            ServiceError::DatabaseError(format!("Failed to read domain listing: {}", e))
+        })?);
+    }
+
+    let next_cursor = if items.len() as i64 > limit {
+        items.truncate(limit as usize);
+        items.last().map(|last| {
+            encode_cursor(&DomainCursor {
+                sort,
+                order,
+                key: cursor_key(sort, last),
+                name: last.name.clone(),
+            })
+        })
+    } else {
+        None
+    };
+
+    let count_query = Neo4jQuery::new(format!("{filters} RETURN count(d) AS total"))
+        .param("query", query_text)
+        .param("relevance", relevance)
+        .param("category", category)
+        .param("tags", tags);
+    let mut result = graph.execute(count_query).await?;
+    let total = match result.next().await? {
+        Some(row) => row.get("total").unwrap_or_default(),
+        None => 0,
+    };
+
+    Ok(Page {
+        items,
+        next_cursor,
+        total,
+    })
+}
+
+/// Domain element ids mapped to their similarity with `text`, above the threshold
+async fn semantic_scores(
+    graph: &Graph,
+    text: &str,
+    min_score: Option<f64>,
+) -> Result<HashMap<String, f64>, ServiceError> {
+    let min_score = min_score.unwrap_or(DEFAULT_MIN_SCORE);
+    let matches = similarity_find_similar_nodes(
+        graph,
+        FindSimilarNodesRequest {
+            text: Some(text.to_string()),
+            label: Some("Domain".to_string()),
+            limit: Some(SEMANTIC_CANDIDATES),
+            ..Default::default()
+        },
+    )
+    .await
+    .map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+
+    Ok(matches
+        .into_iter()
+        .filter(|m| m.score >= min_score)
+        .map(|m| (m.id, m.score))
+        .collect())
+}
+
+/// The sort key of `item` as computed by the listing query
+fn cursor_key(sort: DomainSort, item: &DomainListItem) -> Value {
+    match sort {
+        DomainSort::Name => json!(item.name),
+        DomainSort::Created => json!(item.created_date.clone().unwrap_or_default()),
+        DomainSort::Popularity => json!(item.pursuer_count),
+        DomainSort::Relevance => json!(item.relevance),
+    }
+}
@@ -1,22 +1,66 @@
 //! Graph service operations split into logical modules.
 //!
 //! This module provides all graph-related database operations:
+//! - `analytics`: Centrality metrics of components across domains
 //! - `node`: Node creation, lookup, and updates
 //! - `relationship`: Relationship CRUD operations
 //! - `search`: Text and similarity-based search
 //! - `domain`: Domain management (levels, requirements)
+//! - `transfer`: Bulk JSON/GraphML export and natural-key import
+//! - `curated`: Loader for the curated `database/domains/*.cypher` scripts
+//! - `version`: Domain drafts, publishing and pursuer migration
+//! - `diff`: Structural diff between domains or domain versions
+//! - `integrity`: Well-formedness checks for domains
+//! - `taxonomy`: Domain categories and tags
+//! - `listing`: Paginated, sortable domain listing
+//! - `paths`: k shortest paths between two nodes
+//! - `layout`: Precomputed constellation layout and clustering
+//! - `reembed`: Background re-embedding after embedding model changes
+//! - `subgraph`: Bounded node/edge lists for constellation rendering
 
+pub mod analytics;
+pub mod curated;
+pub mod diff;
 pub mod domain;
+pub mod integrity;
+pub mod layout;
+pub mod listing;
 pub mod node;
+pub mod paths;
+pub mod reembed;
 pub mod relationship;
 pub mod search;
+pub mod subgraph;
+pub mod taxonomy;
+pub mod transfer;
+pub mod version;
 
 // Re-export all public functions for backward compatibility
 // This allows existing code to continue using `services::function_name()`
-pub use domain::{create_domain, get_domain, update_domain, validate_domain_name};
+pub use analytics::{analytics_status, most_transferable_components, start_analytics};
+pub use curated::load_curated_domains;
+pub use diff::{diff_domains, diff_structures};
+pub use domain::{create_domain, fork_domain, get_domain, update_domain, validate_domain_name};
+pub use integrity::{validate_all_domains, validate_domain};
+pub use layout::{layout_status, start_layout};
+pub use listing::list_domains;
 pub use node::{
-    create_node, find_node_by_name, get_nodes_by_search_term, get_nodes_with_relationships,
-    node_with_relationships_query_fragment, update_node,
+    NodeProjection, create_node, create_node_in_txn, find_node_by_name, get_node_labels,
+    get_node_labels_in_txn, get_nodes_by_search_term, get_nodes_with_relationships,
+    node_with_relationships_query_fragment, set_node_deleted, update_node,
+};
+pub use paths::find_paths;
+pub use reembed::{reembed_status, start_reembed};
+pub use relationship::{
+    create_relationship, create_relationship_in_txn, delete_relationship, update_relationship,
 };
-pub use relationship::{create_relationship, delete_relationship, update_relationship};
 pub use search::{find_similar_nodes, search_nodes};
+pub use subgraph::get_subgraph;
+pub use taxonomy::{
+    category_tree, create_category, file_domain_under_path, list_categories, list_tags,
+    set_domain_taxonomy,
+};
+pub use transfer::{ExportSelection, export_graph, import_graph, to_graphml};
+pub use version::{
+    domain_progress, list_domain_versions, migrate_pursuers, migration_report, publish_domain,
+};
@@ -1,16 +1,24 @@
-use neo4rs::{Graph, Query as Neo4jQuery};
-use serde_json::{json, Value};
+use neo4rs::{Graph, Query as Neo4jQuery, Txn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use std::collections::HashMap;
 
 use crate::common::{
-    embedding::generate_embedding,
-    neo4j_utils::{json_value_to_bolt_type, map_bolt4_to_bolt5},
-    similarity::{find_similar_nodes as similarity_find_similar_nodes, FindSimilarNodesRequest},
+    cypher,
+    embedding::{EMBEDDING_VERSION, configured_model, generate_embedding, node_embedding_text},
+    neo4j_utils::{
+        json_value_to_bolt_type, map_bolt4_to_bolt5_node, map_bolt4_to_bolt5_relationship,
+    },
+    pagination::{Page, decode_cursor, encode_cursor, page_of, page_size},
+    similarity::{FindSimilarNodesRequest, find_similar_nodes as similarity_find_similar_nodes},
 };
 
 use crate::domains::graph::models::{
     CreateNodeRequest, CreateNodeResult, NodeWithRelationships, ServiceError,
 };
+use crate::domains::graph::schema;
+
+use super::layout;
 
 /// Returns the Cypher fragment for collecting node metadata with relationships
 pub fn node_with_relationships_query_fragment(depth: i32) -> String {
@@ -52,43 +60,33 @@ pub fn node_with_relationships_query_fragment(depth: i32) -> String {
 }
 
 /// Create a node with optional similarity check
-/// When `check_similarity` is true, returns error if a similar node exists (score > 0.7)
+/// When `duplicate_threshold` is set, returns error if a similar node scores above it.
+/// Labels and properties are validated against the schema registry first.
 pub async fn create_node(
     graph: &Graph,
     request: CreateNodeRequest,
-    check_similarity: bool,
+    duplicate_threshold: Option<f64>,
 ) -> Result<CreateNodeResult, ServiceError> {
-    // Generate embedding from name + description
-    let name = request
-        .properties
-        .get("name")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let description = request
-        .properties
-        .get("description")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    let text_to_embed = format!("{}: {}", name, description);
+    let label_schema = schema::validate_new_node(&request.labels, &request.properties)?;
 
-    let embedding = generate_embedding(&text_to_embed)
-        .await
-        .map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+    // Requirement groups and categories are not embedded
+    let embedding = if label_schema.property("embedding").is_some() {
+        Some(embed_node_properties(&request.properties).await?)
+    } else {
+        None
+    };
 
     // Check for similar nodes if requested
-    if check_similarity {
+    if let (Some(threshold), Some(embedding)) = (duplicate_threshold, &embedding) {
         let similarity_request = FindSimilarNodesRequest {
-            node_id: None,
             embedding: Some(embedding.clone()),
-            text: None,
-            label: None,
             limit: Some(1),
+            ..Default::default()
         };
 
         match similarity_find_similar_nodes(graph, similarity_request).await {
             Ok(similar_nodes) => {
-                if !similar_nodes.is_empty() && similar_nodes[0].score > 0.7 {
+                if !similar_nodes.is_empty() && similar_nodes[0].score > threshold {
                     return Err(ServiceError::SimilarNodeExists {
                         score: similar_nodes[0].score,
                         details: format!(
@@ -108,27 +106,7 @@ pub async fn create_node(
         }
     }
 
-    // Add embedding to properties
-    let mut final_properties = request.properties.clone();
-    final_properties.insert("embedding".to_string(), json!(embedding));
-
-    // Build labels string and SET clauses
-    let label_string = format!(":{}", request.labels.join(":"));
-    let set_clauses: Vec<String> = final_properties
-        .keys()
-        .map(|key| format!("n.{} = ${}", key, key))
-        .collect();
-
-    let query_string = format!(
-        "CREATE (n{}) SET {} RETURN elementId(n) AS elementId, n.name AS name",
-        label_string,
-        set_clauses.join(", ")
-    );
-
-    let mut query = Neo4jQuery::new(query_string);
-    for (key, value) in final_properties {
-        query = query.param(&key, json_value_to_bolt_type(&value));
-    }
+    let query = build_create_node_query(&request.labels, &request.properties, embedding)?;
 
     match graph.execute(query).await {
         Ok(mut result) => {
@@ -149,11 +127,108 @@ pub async fn create_node(
         }
         Err(e) => {
             tracing::error!("Error creating node: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
+            Err(ServiceError::DatabaseError(format!(
+                "Database error: {}",
+                e
+            )))
         }
     }
 }
 
+/// Create a node inside an existing transaction.
+/// Same validation as `create_node`, without the similarity check. The caller
+/// generates `embedding` before opening the transaction, so the transaction
+/// never waits on the embedding provider.
+pub async fn create_node_in_txn(
+    txn: &mut Txn,
+    request: CreateNodeRequest,
+    embedding: Vec<f64>,
+) -> Result<CreateNodeResult, ServiceError> {
+    schema::validate_new_node(&request.labels, &request.properties)?;
+
+    let query = build_create_node_query(&request.labels, &request.properties, Some(embedding))?;
+
+    let mut result = txn.execute(query).await?;
+    match result.next(txn.handle()).await? {
+        Some(row) => Ok(CreateNodeResult {
+            element_id: row.get("elementId").unwrap_or_default(),
+            name: row.get("name").unwrap_or_default(),
+            labels: request.labels,
+        }),
+        None => Err(ServiceError::DatabaseError(
+            "Failed to get element ID after node creation".to_string(),
+        )),
+    }
+}
+
+/// Generate a node's embedding from its name + description
+pub async fn embed_node_properties(
+    properties: &HashMap<String, Value>,
+) -> Result<Vec<f64>, ServiceError> {
+    let name = properties
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let description = properties
+        .get("description")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+
+    generate_embedding(&node_embedding_text(name, description))
+        .await
+        .map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))
+}
+
+/// Add a freshly generated embedding to `properties`, stamped with the model
+/// and text recipe version that produced it
+pub fn insert_embedding(
+    properties: &mut HashMap<String, Value>,
+    embedding: Vec<f64>,
+) -> Result<(), ServiceError> {
+    let model = configured_model().map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+    properties.insert("embedding".to_string(), json!(embedding));
+    properties.insert("embedding_model".to_string(), json!(model));
+    properties.insert("embedding_version".to_string(), json!(EMBEDDING_VERSION));
+    Ok(())
+}
+
+/// Build the CREATE query for a validated node, adding its embedding, if any, to the properties
+fn build_create_node_query(
+    labels: &[String],
+    properties: &HashMap<String, Value>,
+    embedding: Option<Vec<f64>>,
+) -> Result<Neo4jQuery, ServiceError> {
+    let mut final_properties = properties.clone();
+    let mut labels = labels.to_vec();
+
+    // A node carrying an embedding also gets the label the vector index covers
+    if let Some(embedding) = embedding {
+        insert_embedding(&mut final_properties, embedding)?;
+        if !labels.iter().any(|l| l == schema::EMBEDDED_LABEL) {
+            labels.push(schema::EMBEDDED_LABEL.to_string());
+        }
+    }
+    let label_string = cypher::label_suffix(&labels)?;
+    let mut set_clauses = cypher::set_clauses("n", final_properties.keys())?;
+
+    // Domains record when they were created, for sorting domain listings
+    if labels.iter().any(|l| l == "Domain") && !final_properties.contains_key("created_date") {
+        set_clauses.push_str(", n.created_date = toString(date())");
+    }
+
+    let query_string = format!(
+        "CREATE (n{}) SET {} RETURN elementId(n) AS elementId, n.name AS name",
+        label_string, set_clauses
+    );
+
+    let mut query = Neo4jQuery::new(query_string);
+    for (key, value) in final_properties {
+        query = query.param(&key, json_value_to_bolt_type(&value));
+    }
+
+    Ok(query)
+}
+
 /// Find a node by name and optional label - returns element ID if found
 pub async fn find_node_by_name(
     graph: &Graph,
@@ -163,7 +238,7 @@ pub async fn find_node_by_name(
     let query_string = if let Some(lbl) = label {
         format!(
             "MATCH (n:{} {{name: $name}}) RETURN elementId(n) AS elementId LIMIT 1",
-            lbl
+            cypher::label(lbl)?
         )
     } else {
         "MATCH (n {name: $name}) RETURN elementId(n) AS elementId LIMIT 1".to_string()
@@ -180,139 +255,422 @@ pub async fn find_node_by_name(
                 Ok(None)
             }
         }
-        Err(e) => Err(ServiceError::DatabaseError(format!("Database error: {}", e))),
+        Err(e) => Err(ServiceError::DatabaseError(format!(
+            "Database error: {}",
+            e
+        ))),
+    }
+}
+
+/// Which node properties a response carries: every property, or only `fields`.
+/// The embedding is left out unless asked for, whatever `fields` says.
+#[derive(Debug, Clone, Default)]
+pub struct NodeProjection {
+    fields: Option<Vec<String>>,
+    include_embedding: bool,
+}
+
+impl NodeProjection {
+    /// From the comma-separated `fields` and `includeEmbedding` query parameters
+    pub fn new(fields: Option<&str>, include_embedding: bool) -> Result<Self, ServiceError> {
+        let fields = fields
+            .map(|fields| {
+                fields
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|f| !f.is_empty())
+                    .map(|f| cypher::property_key(f).map(|_| f.to_string()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        Ok(NodeProjection {
+            fields,
+            include_embedding,
+        })
+    }
+
+    /// Drop the properties this projection leaves out
+    pub fn apply(&self, props: &mut serde_json::Map<String, Value>) {
+        props.retain(|key, _| {
+            if key == "embedding" {
+                return self.include_embedding;
+            }
+            self.fields
+                .as_ref()
+                .is_none_or(|fields| fields.iter().any(|f| f == key))
+        });
+    }
+
+    /// Page through a list that is loaded whole, keeping only the selected
+    /// fields of each item
+    pub fn page_of<T: Serialize>(
+        &self,
+        items: Vec<T>,
+        limit: Option<i64>,
+        cursor: Option<&str>,
+    ) -> Result<Page<Value>, ServiceError> {
+        let page = page_of(items, limit, cursor)
+            .ok_or_else(|| ServiceError::ValidationError("invalid cursor".to_string()))?;
+        Ok(Page {
+            items: page
+                .items
+                .into_iter()
+                .map(|item| {
+                    let mut item = json!(item);
+                    if let Some(fields) = item.as_object_mut() {
+                        self.apply(fields);
+                    }
+                    item
+                })
+                .collect(),
+            next_cursor: page.next_cursor,
+            total: page.total,
+        })
+    }
+
+    /// Bolt5-shaped node (`{Id, ElementId, Labels, Props}`) from the
+    /// `{id, elementId, labels, props}` map built by the query fragment
+    fn node(&self, meta: &Value) -> Value {
+        let mut node = map_bolt4_to_bolt5_node(meta);
+        if let Some(props) = node.get_mut("Props").and_then(Value::as_object_mut) {
+            if self.include_embedding
+                && let Some(embedding) = meta.pointer("/props/embedding")
+            {
+                props.insert("embedding".to_string(), embedding.clone());
+            }
+            self.apply(props);
+        }
+        node
     }
 }
 
-/// Get nodes with optional filters (labels, properties) and related nodes up to depth.
-/// Returns Bolt5-shaped JSON (`[{Values:[node, relationships, affiliates], Keys:[...]}]`)
-/// matching what the web UI's `fetchNodes` expects.
+#[derive(Debug, Serialize, Deserialize)]
+struct NodeCursor {
+    /// Element id of the last node on the previous page
+    after: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchTermCursor {
+    offset: i64,
+}
+
+/// Nearest neighbours considered by the search-term lookup; its `total`
+/// counts matches among these
+const SEARCH_TERM_CANDIDATES: i64 = 100;
+
+/// Get nodes with optional filters (labels, properties) and related nodes up to depth,
+/// a page at a time in element id order.
 pub async fn get_nodes_with_relationships(
     graph: &Graph,
     labels: Option<Vec<&str>>,
     properties: Option<HashMap<String, Value>>,
     depth: i32,
-) -> Result<Value, ServiceError> {
+    limit: Option<i64>,
+    cursor: Option<&str>,
+    projection: &NodeProjection,
+) -> Result<Page<NodeWithRelationships>, ServiceError> {
     let mut match_clauses = Vec::new();
 
     // Handle labels
     if let Some(labels_vec) = labels {
         for label in labels_vec {
-            match_clauses.push(format!("MATCH (node:`{}`)", label));
+            match_clauses.push(format!("MATCH (node{})", cypher::label_suffix(&[label])?));
         }
     } else {
         match_clauses.push("MATCH (node)".to_string());
     }
 
-    // Handle properties
+    // Handle properties - values are always bound as parameters
     let mut where_clauses = Vec::new();
+    let mut where_params: Vec<(String, String)> = Vec::new();
     let props = properties.unwrap_or_default();
+    let mut by_element_id = false;
     for (key, value) in &props {
         let key = key.trim();
         if let Some(value_str) = value.as_str() {
-            let value_str = value_str.trim();
+            let param_name = format!("filter{}", where_params.len());
             if key == "elementId" {
-                where_clauses.push(format!("elementId(node) = '{}'", value_str));
+                by_element_id = true;
+                where_clauses.push(format!("elementId(node) = ${}", param_name));
             } else {
-                where_clauses.push(format!("node.{} = '{}'", key, value_str));
+                where_clauses.push(format!(
+                    "node.{} = ${}",
+                    cypher::property_key(key)?,
+                    param_name
+                ));
             }
+            where_params.push((param_name, value_str.trim().to_string()));
         }
     }
 
-    let mut query_string = match_clauses.join("\n");
+    let after = match cursor {
+        Some(raw) => Some(
+            decode_cursor::<NodeCursor>(raw)
+                .ok_or_else(|| ServiceError::ValidationError("invalid cursor".to_string()))?
+                .after,
+        ),
+        None => None,
+    };
+    let limit = page_size(limit);
+
+    let matches = match_clauses.join("\n");
+    let filter = |extra: Option<&str>| {
+        let clauses: Vec<&str> = where_clauses
+            .iter()
+            .map(String::as_str)
+            .chain(extra)
+            .collect();
+        if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        }
+    };
+    let with_filters = |mut query: Neo4jQuery| {
+        for (param_name, value) in &where_params {
+            query = query.param(param_name, value.clone());
+        }
+        query
+    };
 
-    if !where_clauses.is_empty() {
-        query_string += &format!("\nWHERE {}", where_clauses.join(" AND "));
+    let id_query = with_filters(Neo4jQuery::new(format!(
+        "{}\n{}\nRETURN elementId(node) AS id\nORDER BY id\nLIMIT $limit",
+        matches,
+        filter(after.as_ref().map(|_| "elementId(node) > $after"))
+    )))
+    .param("after", after.unwrap_or_default())
+    .param("limit", limit + 1);
+    let mut result = graph.execute(id_query).await?;
+    let mut ids: Vec<String> = Vec::new();
+    while let Some(row) = result.next().await? {
+        ids.push(row.get("id").unwrap_or_default());
     }
 
-    query_string += &node_with_relationships_query_fragment(depth);
+    let next_cursor = (ids.len() as i64 > limit).then(|| {
+        ids.truncate(limit as usize);
+        encode_cursor(&NodeCursor {
+            after: ids.last().cloned().unwrap_or_default(),
+        })
+    });
 
-    let query = Neo4jQuery::new(query_string);
-    match graph.execute(query).await {
-        Ok(mut result) => {
-            let mut nodes_data = Vec::new();
-
-            while let Ok(Some(row)) = result.next().await {
-                let node: Value = row.get("node").unwrap_or(json!({}));
-                let relationships: Value = row.get("relationships").unwrap_or(json!([]));
-                let affiliated_nodes: Value = row.get("affiliatedNodes").unwrap_or(json!([]));
-
-                nodes_data.push(json!({
-                    "node": node,
-                    "relationships": relationships,
-                    "affiliatedNodes": affiliated_nodes
-                }));
-            }
-
-            Ok(map_bolt4_to_bolt5(nodes_data))
-        }
-        Err(e) => {
-            tracing::error!("Error in get_nodes_with_relationships: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
+    // An element id matches at most one node, so the page already holds the total
+    let total = if by_element_id {
+        ids.len() as i64
+    } else {
+        let count_query = with_filters(Neo4jQuery::new(format!(
+            "{}\n{}\nRETURN count(node) AS total",
+            matches,
+            filter(None)
+        )));
+        let mut result = graph.execute(count_query).await?;
+        match result.next().await? {
+            Some(row) => row.get("total").unwrap_or_default(),
+            None => 0,
         }
-    }
+    };
+
+    Ok(Page {
+        items: fetch_with_relationships(graph, ids, depth, projection).await?,
+        next_cursor,
+        total,
+    })
 }
 
-/// Get nodes by embedding-based vector search
+/// Get the nodes nearest to a search term by embedding, with related nodes up
+/// to depth, a page at a time from the nearest
 pub async fn get_nodes_by_search_term(
     graph: &Graph,
     search_term: &str,
     depth: i32,
-) -> Result<Vec<NodeWithRelationships>, ServiceError> {
+    limit: Option<i64>,
+    cursor: Option<&str>,
+    projection: &NodeProjection,
+) -> Result<Page<NodeWithRelationships>, ServiceError> {
+    let offset = match cursor {
+        Some(raw) => {
+            decode_cursor::<SearchTermCursor>(raw)
+                .ok_or_else(|| ServiceError::ValidationError("invalid cursor".to_string()))?
+                .offset
+        }
+        None => 0,
+    };
+    let limit = page_size(limit);
+
     // Generate embedding for search term
     let embedding = generate_embedding(search_term)
         .await
         .map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
 
-    let query_string = format!(
+    let query = Neo4jQuery::new(
         r#"
-        CALL db.index.vector.queryNodes('nodeEmbeddings', 1, $embedding)
+        CALL db.index.vector.queryNodes('nodeEmbeddings', $candidates, $embedding)
         YIELD node, score
+        RETURN elementId(node) AS id
+        ORDER BY score DESC
+        "#
+        .to_string(),
+    )
+    .param("candidates", SEARCH_TERM_CANDIDATES)
+    .param("embedding", embedding);
+    let mut result = graph.execute(query).await?;
+    let mut candidates: Vec<String> = Vec::new();
+    while let Some(row) = result.next().await? {
+        candidates.push(row.get("id").unwrap_or_default());
+    }
+
+    let total = candidates.len() as i64;
+    let ids: Vec<String> = candidates
+        .into_iter()
+        .skip(offset.max(0) as usize)
+        .take(limit as usize)
+        .collect();
+    let next_offset = offset + ids.len() as i64;
+    let next_cursor = (ids.len() as i64 == limit && next_offset < total).then(|| {
+        encode_cursor(&SearchTermCursor {
+            offset: next_offset,
+        })
+    });
+
+    Ok(Page {
+        items: fetch_with_relationships(graph, ids, depth, projection).await?,
+        next_cursor,
+        total,
+    })
+}
+
+/// The nodes with `ids`, each with its relationships and related nodes up to
+/// depth, in the order of `ids`
+async fn fetch_with_relationships(
+    graph: &Graph,
+    ids: Vec<String>,
+    depth: i32,
+    projection: &NodeProjection,
+) -> Result<Vec<NodeWithRelationships>, ServiceError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (node)
+        WHERE elementId(node) IN $ids
         {}
         "#,
         node_with_relationships_query_fragment(depth)
-    );
+    ))
+    .param("ids", ids.clone());
+
+    let mut result = graph.execute(query).await?;
+    let mut by_id: HashMap<String, NodeWithRelationships> = HashMap::new();
+    while let Some(row) = result.next().await? {
+        let node: Value = row.get("node").unwrap_or(json!({}));
+        let relationships: Vec<Value> = row.get("relationships").unwrap_or_default();
+        let affiliated_nodes: Vec<Value> = row.get("affiliatedNodes").unwrap_or_default();
+        let element_id = node
+            .get("elementId")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        by_id.insert(
+            element_id,
+            NodeWithRelationships {
+                node: projection.node(&node),
+                relationships: relationships
+                    .iter()
+                    .map(map_bolt4_to_bolt5_relationship)
+                    .collect(),
+                affiliated_nodes: affiliated_nodes
+                    .iter()
+                    .map(|n| projection.node(n))
+                    .collect(),
+            },
+        );
+    }
 
-    let mut query = Neo4jQuery::new(query_string);
-    query = query.param("embedding", embedding);
+    Ok(ids.iter().filter_map(|id| by_id.remove(id)).collect())
+}
+
+fn node_labels_query(element_id: &str) -> Neo4jQuery {
+    Neo4jQuery::new(
+        "MATCH (n) WHERE elementId(n) = $elementId RETURN labels(n) AS labels".to_string(),
+    )
+    .param("elementId", element_id)
+}
+
+/// Get the labels of a node by element ID - returns None if the node doesn't exist
+pub async fn get_node_labels(
+    graph: &Graph,
+    element_id: &str,
+) -> Result<Option<Vec<String>>, ServiceError> {
+    let query = node_labels_query(element_id);
 
     match graph.execute(query).await {
         Ok(mut result) => {
-            let mut nodes_data = Vec::new();
-
-            while let Ok(Some(row)) = result.next().await {
-                let node: Value = row.get("node").unwrap_or(json!({}));
-                let relationships: Value = row.get("relationships").unwrap_or(json!([]));
-                let affiliated_nodes: Value = row.get("affiliatedNodes").unwrap_or(json!([]));
-
-                nodes_data.push(json!({
-                    "node": node,
-                    "relationships": relationships,
-                    "affiliatedNodes": affiliated_nodes
-                }));
+            if let Ok(Some(row)) = result.next().await {
+                let labels: Vec<String> = row.get("labels").unwrap_or_default();
+                Ok(Some(labels))
+            } else {
+                Ok(None)
             }
+        }
+        Err(e) => Err(ServiceError::DatabaseError(format!(
+            "Database error: {}",
+            e
+        ))),
+    }
+}
 
-            let bolt5_data = map_bolt4_to_bolt5(nodes_data);
+/// Get the labels of a node inside an existing transaction, so nodes created
+/// earlier in the same transaction are visible
+pub async fn get_node_labels_in_txn(
+    txn: &mut Txn,
+    element_id: &str,
+) -> Result<Option<Vec<String>>, ServiceError> {
+    let mut result = txn.execute(node_labels_query(element_id)).await?;
+    Ok(result
+        .next(txn.handle())
+        .await?
+        .map(|row| row.get("labels").unwrap_or_default()))
+}
 
-            let result = if let Value::Array(arr) = bolt5_data {
-                arr.into_iter()
-                    .map(|v| NodeWithRelationships {
-                        node: v.get("node").cloned().unwrap_or(json!({})),
-                        relationships: v.get("relationships").cloned().unwrap_or(json!([])),
-                        affiliated_nodes: v.get("affiliatedNodes").cloned().unwrap_or(json!([])),
-                    })
-                    .collect()
-            } else {
-                Vec::new()
-            };
+/// Soft-delete a layer-one node, or restore it with `deleted = false`.
+/// Returns the node's `deleted_at` timestamp, `None` once restored.
+pub async fn set_node_deleted(
+    graph: &Graph,
+    target_id: &str,
+    deleted: bool,
+) -> Result<Option<String>, ServiceError> {
+    let change = if deleted {
+        format!(
+            "SET n.{0} = coalesce(n.{0}, toString(datetime()))",
+            schema::SOFT_DELETE_PROPERTY
+        )
+    } else {
+        format!("REMOVE n.{}", schema::SOFT_DELETE_PROPERTY)
+    };
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (n:{0}) WHERE elementId(n) = $targetId
+        {1}
+        RETURN n.{2} AS deletedAt
+        "#,
+        schema::EMBEDDED_LABEL,
+        change,
+        schema::SOFT_DELETE_PROPERTY
+    ))
+    .param("targetId", target_id);
+
+    let mut result = graph.execute(query).await?;
+    let Some(row) = result.next().await? else {
+        return Err(ServiceError::NotFound(format!(
+            "Node {} not found",
+            target_id
+        )));
+    };
 
-            Ok(result)
-        }
-        Err(e) => {
-            tracing::error!("Error in get_nodes_by_search_term: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
-        }
-    }
+    layout::request_refresh();
+    Ok(row.get::<Option<String>>("deletedAt").unwrap_or_default())
 }
 
 /// Update a node's labels and/or properties
@@ -328,6 +686,12 @@ pub async fn update_node(
         ));
     }
 
+    let existing_labels = get_node_labels(graph, target_id)
+        .await?
+        .ok_or_else(|| ServiceError::NotFound(format!("Node {} not found", target_id)))?;
+
+    schema::validate_node_update(&existing_labels, labels.as_deref(), properties.as_ref())?;
+
     let mut query_parts = vec![
         "MATCH (n)".to_string(),
         "WHERE elementId(n) = $targetId".to_string(),
@@ -337,18 +701,14 @@ pub async fn update_node(
     if let Some(labels_vec) = &labels {
         query_parts.push("REMOVE n:_".to_string());
         for label in labels_vec {
-            query_parts.push(format!("SET n:`{}`", label));
+            query_parts.push(format!("SET n:{}", cypher::label(label)?));
         }
     }
 
     // Handle properties if provided
     let props = properties.clone().unwrap_or_default();
     if !props.is_empty() {
-        let set_clauses: Vec<String> = props
-            .keys()
-            .map(|key| format!("n.{} = ${}", key, key))
-            .collect();
-        query_parts.push(format!("SET {}", set_clauses.join(", ")));
+        query_parts.push(format!("SET {}", cypher::set_clauses("n", props.keys())?));
     }
 
     query_parts.push("RETURN n".to_string());
@@ -374,7 +734,10 @@ pub async fn update_node(
         }
         Err(e) => {
             tracing::error!("Error updating node: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
+            Err(ServiceError::DatabaseError(format!(
+                "Database error: {}",
+                e
+            )))
         }
     }
 }
@@ -0,0 +1,567 @@
+//! k shortest paths between two nodes, for exploring how they connect.
+//!
+//! Paths only follow the relationships that make up domains and their
+//! components (`REQUIRES_*`, `GENERALIZES_TO`, `HAS_DOMAIN_LEVEL`), in either
+//! direction, and only through live Layer 1 nodes. Each relationship costs its
+//! weight, with generalization links cheaper so that paths through transferable
+//! components rank first.
+//!
+//! The search area is a ball of `ceil(maxHops / 2)` hops around `from` joined
+//! with a ball of `floor(maxHops / 2)` hops around `to`, which holds every node
+//! of every path of at most `maxHops` relationships. It is loaded one hop per
+//! query, then Yen's algorithm runs over it in memory.
+
+use neo4rs::{BoltMap, BoltType, Graph, Query as Neo4jQuery};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::common::cypher;
+use crate::common::neo4j_utils::{map_bolt4_to_bolt5_node, map_bolt4_to_bolt5_relationship};
+use crate::domains::graph::models::{GraphPath, PathParams, PathsResult, ServiceError};
+use crate::domains::graph::schema::{EMBEDDED_LABEL, SOFT_DELETE_PROPERTY};
+
+/// Traversable relationship types and their cost
+const RELATIONSHIP_WEIGHTS: &[(&str, f64)] = &[
+    ("GENERALIZES_TO", 0.5),
+    ("REQUIRES_KNOWLEDGE", 1.0),
+    ("REQUIRES_SKILL", 1.0),
+    ("REQUIRES_TRAIT", 1.0),
+    ("REQUIRES_MILESTONE", 1.0),
+    ("HAS_DOMAIN_LEVEL", 1.0),
+];
+const DEFAULT_K: usize = 3;
+const MAX_K: usize = 10;
+const DEFAULT_MAX_HOPS: usize = 6;
+const MAX_HOPS: usize = 10;
+/// Most nodes loaded into the search area
+const NODE_BUDGET: usize = 20_000;
+/// Most paths Yen's algorithm deviates from before giving up on finding `k`
+/// short enough ones
+const MAX_DEVIATIONS: usize = 500;
+
+/// A relationship of the search area, walkable both ways
+struct Edge {
+    id: String,
+    start: usize,
+    end: usize,
+    weight: f64,
+}
+
+/// A loopless path as node and edge indexes
+#[derive(Clone)]
+struct Path {
+    cost: f64,
+    nodes: Vec<usize>,
+    edges: Vec<usize>,
+}
+
+pub async fn find_paths(graph: &Graph, params: PathParams) -> Result<PathsResult, ServiceError> {
+    let k = params.k.unwrap_or(DEFAULT_K);
+    if !(1..=MAX_K).contains(&k) {
+        return Err(ServiceError::ValidationError(format!(
+            "k must be between 1 and {}",
+            MAX_K
+        )));
+    }
+    let max_hops = params.max_hops.unwrap_or(DEFAULT_MAX_HOPS);
+    if !(1..=MAX_HOPS).contains(&max_hops) {
+        return Err(ServiceError::ValidationError(format!(
+            "maxHops must be between 1 and {}",
+            MAX_HOPS
+        )));
+    }
+    let weights = relationship_weights(params.relationship_types.as_deref())?;
+    let types = format!(
+        ":{}",
+        weights
+            .keys()
+            .map(|t| cypher::relationship_type(t))
+            .collect::<Result<Vec<_>, _>>()?
+            .join("|")
+    );
+
+    for id in [&params.from, &params.to] {
+        if !node_exists(graph, id).await? {
+            return Err(ServiceError::NotFound(format!("Node '{}' not found", id)));
+        }
+    }
+    if params.from == params.to {
+        return Err(ServiceError::ValidationError(
+            "from and to must be different nodes".to_string(),
+        ));
+    }
+
+    let mut known = vec![params.from.clone(), params.to.clone()];
+    let mut known_set: HashSet<String> = known.iter().cloned().collect();
+    let mut truncated = false;
+    for (start, depth) in [
+        (&params.from, max_hops.div_ceil(2)),
+        (&params.to, max_hops / 2),
+    ] {
+        truncated |= expand(graph, &types, start, depth, &mut known, &mut known_set).await?;
+    }
+
+    let index: HashMap<&str, usize> = known
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), i))
+        .collect();
+    let edges = fetch_edges(graph, &types, &known, &index, &weights).await?;
+    let mut adjacency: Vec<Vec<(usize, usize)>> = vec![Vec::new(); known.len()];
+    for (i, edge) in edges.iter().enumerate() {
+        adjacency[edge.start].push((edge.end, i));
+        if edge.end != edge.start {
+            adjacency[edge.end].push((edge.start, i));
+        }
+    }
+
+    let found = k_shortest_paths(
+        &adjacency,
+        &edges,
+        index[params.from.as_str()],
+        index[params.to.as_str()],
+        k,
+        max_hops,
+    );
+
+    let nodes = fetch_node_meta(graph, &found, &known).await?;
+    let relationships = fetch_relationship_meta(graph, &found, &edges, &known).await?;
+
+    // A node or relationship deleted between the traversal and the metadata
+    // fetch leaves its path incomplete, so such paths are dropped
+    let paths = found
+        .iter()
+        .filter_map(|path| {
+            let node = nodes.get(&known[path.nodes[0]])?;
+            let relationships = path
+                .edges
+                .iter()
+                .map(|&e| {
+                    relationships
+                        .get(&edges[e].id)
+                        .map(map_bolt4_to_bolt5_relationship)
+                })
+                .collect::<Option<Vec<_>>>()?;
+            let affiliated_nodes = path.nodes[1..]
+                .iter()
+                .map(|&n| nodes.get(&known[n]).map(map_bolt4_to_bolt5_node))
+                .collect::<Option<Vec<_>>>()?;
+            Some(GraphPath {
+                cost: path.cost,
+                hops: path.edges.len(),
+                node: map_bolt4_to_bolt5_node(node),
+                relationships,
+                affiliated_nodes,
+            })
+        })
+        .collect();
+
+    Ok(PathsResult { paths, truncated })
+}
+
+/// Weights of the requested types, defaulting to every traversable type
+fn relationship_weights(types: Option<&str>) -> Result<HashMap<String, f64>, ServiceError> {
+    let requested: Vec<&str> = types
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect();
+    if requested.is_empty() {
+        return Ok(RELATIONSHIP_WEIGHTS
+            .iter()
+            .map(|(t, w)| (t.to_string(), *w))
+            .collect());
+    }
+    requested
+        .into_iter()
+        .map(|t| {
+            RELATIONSHIP_WEIGHTS
+                .iter()
+                .find(|(allowed, _)| *allowed == t)
+                .map(|(allowed, w)| (allowed.to_string(), *w))
+                .ok_or_else(|| {
+                    ServiceError::ValidationError(format!(
+                        "relationship type '{}' cannot be traversed; allowed types are {}",
+                        t,
+                        RELATIONSHIP_WEIGHTS
+                            .iter()
+                            .map(|(allowed, _)| *allowed)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                })
+        })
+        .collect()
+}
+
+async fn node_exists(graph: &Graph, id: &str) -> Result<bool, ServiceError> {
+    let query = Neo4jQuery::new(format!(
+        "MATCH (n:{}) WHERE elementId(n) = $id AND n.{} IS NULL RETURN count(n) AS count",
+        EMBEDDED_LABEL, SOFT_DELETE_PROPERTY
+    ))
+    .param("id", id.to_string());
+
+    let mut result = graph.execute(query).await?;
+    let count: i64 = match result.next().await? {
+        Some(row) => row.get("count").unwrap_or_default(),
+        None => 0,
+    };
+    Ok(count > 0)
+}
+
+/// Add the nodes up to `depth` hops from `start` to `known`; returns whether
+/// the node budget cut the expansion short
+async fn expand(
+    graph: &Graph,
+    types: &str,
+    start: &str,
+    depth: usize,
+    known: &mut Vec<String>,
+    known_set: &mut HashSet<String>,
+) -> Result<bool, ServiceError> {
+    let mut frontier = vec![start.to_string()];
+    let mut seen: HashSet<String> = frontier.iter().cloned().collect();
+
+    for _ in 0..depth {
+        if frontier.is_empty() {
+            break;
+        }
+        let remaining = NODE_BUDGET.saturating_sub(known.len());
+        let query = Neo4jQuery::new(format!(
+            r#"
+            MATCH (n)-[{}]-(m:{})
+            WHERE elementId(n) IN $frontier AND NOT elementId(m) IN $seen AND m.{} IS NULL
+            RETURN DISTINCT elementId(m) AS id
+            LIMIT $limit
+            "#,
+            types, EMBEDDED_LABEL, SOFT_DELETE_PROPERTY
+        ))
+        .param("frontier", frontier.clone())
+        .param("seen", seen.iter().cloned().collect::<Vec<_>>())
+        .param("limit", remaining as i64 + 1);
+
+        let mut result = graph.execute(query).await?;
+        let mut next = Vec::new();
+        while let Some(row) = result.next().await? {
+            let id: String = row.get("id").unwrap_or_default();
+            if !known_set.contains(&id) {
+                if known.len() == NODE_BUDGET {
+                    return Ok(true);
+                }
+                known_set.insert(id.clone());
+                known.push(id.clone());
+            }
+            seen.insert(id.clone());
+            next.push(id);
+        }
+        frontier = next;
+    }
+
+    Ok(false)
+}
+
+async fn fetch_edges(
+    graph: &Graph,
+    types: &str,
+    ids: &[String],
+    index: &HashMap<&str, usize>,
+    weights: &HashMap<String, f64>,
+) -> Result<Vec<Edge>, ServiceError> {
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (a)-[r{}]->(b)
+        WHERE elementId(a) IN $ids AND elementId(b) IN $ids
+        RETURN elementId(r) AS id, type(r) AS type, elementId(a) AS start, elementId(b) AS end
+        ORDER BY id
+        "#,
+        types
+    ))
+    .param("ids", ids.to_vec());
+
+    let mut result = graph.execute(query).await?;
+    let mut edges = Vec::new();
+
+    while let Some(row) = result.next().await? {
+        let start: String = row.get("start").unwrap_or_default();
+        let end: String = row.get("end").unwrap_or_default();
+        let relationship_type: String = row.get("type").unwrap_or_default();
+        let (Some(&start), Some(&end), Some(&weight)) = (
+            index.get(start.as_str()),
+            index.get(end.as_str()),
+            weights.get(&relationship_type),
+        ) else {
+            continue;
+        };
+        edges.push(Edge {
+            id: row.get("id").unwrap_or_default(),
+            start,
+            end,
+            weight,
+        });
+    }
+
+    Ok(edges)
+}
+
+/// `{id, elementId, labels, props}` of every node on the paths, by element id
+async fn fetch_node_meta(
+    graph: &Graph,
+    paths: &[Path],
+    known: &[String],
+) -> Result<HashMap<String, Value>, ServiceError> {
+    let ids: Vec<String> = paths
+        .iter()
+        .flat_map(|p| &p.nodes)
+        .map(|&n| known[n].clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (n)
+        WHERE elementId(n) IN $ids
+        RETURN elementId(n) AS elementId,
+               {id: id(n), elementId: elementId(n), labels: labels(n), props: n {.*, embedding: null}} AS meta
+        "#
+        .to_string(),
+    )
+    .param("ids", ids);
+
+    let mut result = graph.execute(query).await?;
+    let mut meta = HashMap::new();
+    while let Some(row) = result.next().await? {
+        meta.insert(
+            row.get("elementId").unwrap_or_default(),
+            row.get("meta").unwrap_or_default(),
+        );
+    }
+    Ok(meta)
+}
+
+/// Relationship meta of every relationship on the paths, looked up from its
+/// start node so the query does not scan all relationships
+async fn fetch_relationship_meta(
+    graph: &Graph,
+    paths: &[Path],
+    edges: &[Edge],
+    known: &[String],
+) -> Result<HashMap<String, Value>, ServiceError> {
+    let used: HashSet<usize> = paths.iter().flat_map(|p| p.edges.iter().copied()).collect();
+    if used.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let rows: Vec<BoltType> = used
+        .into_iter()
+        .map(|e| {
+            let mut row = BoltMap::new();
+            row.put("id".into(), edges[e].id.clone().into());
+            row.put("start".into(), known[edges[e].start].clone().into());
+            BoltType::Map(row)
+        })
+        .collect();
+    let query = Neo4jQuery::new(
+        r#"
+        UNWIND $rows AS row
+        MATCH (a)-[r]->(b)
+        WHERE elementId(a) = row.start AND elementId(r) = row.id
+        RETURN elementId(r) AS elementId,
+               {id: id(r), elementId: elementId(r), startId: id(a), startElementId: elementId(a),
+                endId: id(b), endElementId: elementId(b), type: type(r), props: properties(r)} AS meta
+        "#
+        .to_string(),
+    )
+    .param("rows", rows);
+
+    let mut result = graph.execute(query).await?;
+    let mut meta = HashMap::new();
+    while let Some(row) = result.next().await? {
+        meta.insert(
+            row.get("elementId").unwrap_or_default(),
+            row.get("meta").unwrap_or_default(),
+        );
+    }
+    Ok(meta)
+}
+
+/// Yen's algorithm: up to `k` loopless paths by increasing cost, each of at
+/// most `max_hops` edges
+fn k_shortest_paths(
+    adjacency: &[Vec<(usize, usize)>],
+    edges: &[Edge],
+    source: usize,
+    target: usize,
+    k: usize,
+    max_hops: usize,
+) -> Vec<Path> {
+    let Some(first) = shortest_path(
+        adjacency,
+        edges,
+        source,
+        target,
+        &HashSet::new(),
+        &HashSet::new(),
+    ) else {
+        return Vec::new();
+    };
+    let mut accepted: Vec<Path> = Vec::new();
+    let mut found = vec![first.clone()];
+    let mut candidates: Vec<Path> = vec![first];
+    let mut deviations = 0;
+
+    while accepted.len() < k && !candidates.is_empty() && deviations < MAX_DEVIATIONS {
+        deviations += 1;
+        // Cheapest candidate first, fewer hops on ties
+        let best = candidates
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                a.cost
+                    .total_cmp(&b.cost)
+                    .then(a.edges.len().cmp(&b.edges.len()))
+            })
+            .map(|(i, _)| i)
+            .unwrap_or_default();
+        let path = candidates.swap_remove(best);
+
+        for i in 0..path.edges.len() {
+            let spur = path.nodes[i];
+            let root_nodes = &path.nodes[..=i];
+            let removed_edges: HashSet<usize> = found
+                .iter()
+                .filter(|p| p.nodes.len() > i + 1 && p.nodes[..=i] == *root_nodes)
+                .map(|p| p.edges[i])
+                .collect();
+            let removed_nodes: HashSet<usize> = root_nodes[..i].iter().copied().collect();
+
+            let Some(spur_path) = shortest_path(
+                adjacency,
+                edges,
+                spur,
+                target,
+                &removed_edges,
+                &removed_nodes,
+            ) else {
+                continue;
+            };
+            let root_cost: f64 = path.edges[..i].iter().map(|&e| edges[e].weight).sum();
+            let candidate = Path {
+                cost: root_cost + spur_path.cost,
+                nodes: [&path.nodes[..i], &spur_path.nodes[..]].concat(),
+                edges: [&path.edges[..i], &spur_path.edges[..]].concat(),
+            };
+            if found.iter().any(|p| p.edges == candidate.edges) {
+                continue;
+            }
+            // Over-length paths still block their edges for later deviations,
+            // but are never expanded or returned
+            let within_hops = candidate.edges.len() <= max_hops;
+            found.push(candidate.clone());
+            if within_hops {
+                candidates.push(candidate);
+            }
+        }
+
+        // Only the initial shortest path can get here over-length
+        if path.edges.len() <= max_hops {
+            accepted.push(path);
+        }
+    }
+
+    accepted
+}
+
+/// Dijkstra frontier entry, ordered so the heap pops the cheapest first
+struct Entry {
+    cost: f64,
+    node: usize,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then(other.node.cmp(&self.node))
+    }
+}
+
+fn shortest_path(
+    adjacency: &[Vec<(usize, usize)>],
+    edges: &[Edge],
+    source: usize,
+    target: usize,
+    removed_edges: &HashSet<usize>,
+    removed_nodes: &HashSet<usize>,
+) -> Option<Path> {
+    let mut cost = vec![f64::INFINITY; adjacency.len()];
+    // Node and edge each node was reached through
+    let mut previous: Vec<Option<(usize, usize)>> = vec![None; adjacency.len()];
+    let mut heap = BinaryHeap::new();
+    cost[source] = 0.0;
+    heap.push(Entry {
+        cost: 0.0,
+        node: source,
+    });
+
+    while let Some(Entry { cost: c, node }) = heap.pop() {
+        if node == target {
+            break;
+        }
+        if c > cost[node] {
+            continue;
+        }
+        for &(next, edge) in &adjacency[node] {
+            if removed_edges.contains(&edge) || removed_nodes.contains(&next) {
+                continue;
+            }
+            let next_cost = c + edges[edge].weight;
+            if next_cost < cost[next] {
+                cost[next] = next_cost;
+                previous[next] = Some((node, edge));
+                heap.push(Entry {
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    if cost[target].is_infinite() {
+        return None;
+    }
+    let mut nodes = vec![target];
+    let mut path_edges = Vec::new();
+    let mut current = target;
+    while let Some((node, edge)) = previous[current] {
+        nodes.push(node);
+        path_edges.push(edge);
+        current = node;
+    }
+    nodes.reverse();
+    path_edges.reverse();
+
+    Some(Path {
+        cost: cost[target],
+        nodes,
+        edges: path_edges,
+    })
+}
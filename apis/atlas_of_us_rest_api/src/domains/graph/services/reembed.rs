@@ -0,0 +1,269 @@
+//! Background job moving stored embeddings to the configured model.
+//!
+//! Every embedded node records the `embedding_model` and `embedding_version`
+//! (text recipe) that produced its vector. After either changes, the job walks
+//! the stale nodes in element id order, re-embedding one batch at a time and
+//! pausing between batches. Progress is checkpointed on a `_ReembedJob` node,
+//! so a failed or interrupted run resumes where it stopped. Once every node is
+//! current, the `_EmbeddingSpace` node is switched to the new model and text
+//! queries embedded with it are accepted by the similarity search again.
+//!
+//! Only the model can change this way: a different vector length needs the
+//! `nodeEmbeddings` index rebuilt (see `migrations::verify`).
+
+use neo4rs::{BoltMap, BoltType, Graph, Query as Neo4jQuery};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::common::embedding::{
+    EMBEDDING_VERSION, configured_model, generate_embeddings, node_embedding_text,
+};
+use crate::domains::graph::models::{ReembedJob, ServiceError, StartReembedRequest};
+use crate::domains::graph::schema::{EMBEDDED_LABEL, EMBEDDING_SPACE_LABEL};
+
+use super::search::VECTOR_INDEX;
+
+const DEFAULT_BATCH_SIZE: i64 = 32;
+const MAX_BATCH_SIZE: i64 = 256;
+const DEFAULT_DELAY_MS: u64 = 250;
+
+/// Set while a run is in progress in this process
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Cypher predicate matching embedded nodes not produced by `$model`/`$version`
+const STALE: &str = "n.embedding IS NOT NULL \
+     AND (n.embedding_model IS NULL OR n.embedding_model <> $model \
+          OR coalesce(n.embedding_version, 0) <> $version)";
+
+/// The persisted job, if a run was ever started
+pub async fn reembed_status(graph: &Graph) -> Result<Option<ReembedJob>, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (j:_ReembedJob {index: $index})
+        RETURN {
+            model: j.model,
+            version: j.version,
+            status: j.status,
+            checkpoint: j.checkpoint,
+            processed: j.processed,
+            total: j.total,
+            startedAt: j.started_at,
+            updatedAt: j.updated_at,
+            error: j.error
+        } AS job
+        "#
+        .to_string(),
+    )
+    .param("index", VECTOR_INDEX);
+    let mut result = graph.execute(query).await?;
+    match result.next().await? {
+        Some(row) => row.get("job").map(Some).map_err(|e| {
+            ServiceError::DatabaseError(format!("Failed to read re-embed job: {}", e))
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Start (or resume) re-embedding stale nodes in the background and return
+/// the job as persisted before the first batch. Starting while a run is in
+/// progress is a `ValidationError`.
+pub async fn start_reembed(
+    graph: &Graph,
+    request: StartReembedRequest,
+) -> Result<ReembedJob, ServiceError> {
+    let model = configured_model().map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+    let batch_size = request
+        .batch_size
+        .unwrap_or(DEFAULT_BATCH_SIZE)
+        .clamp(1, MAX_BATCH_SIZE);
+    let delay = Duration::from_millis(request.delay_ms.unwrap_or(DEFAULT_DELAY_MS));
+
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(ServiceError::ValidationError(
+            "a re-embed job is already running".to_string(),
+        ));
+    }
+
+    let job = match prepare_job(graph, &model, request.restart).await {
+        Ok(job) => job,
+        Err(e) => {
+            RUNNING.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+
+    let graph = graph.clone();
+    let checkpoint = job.checkpoint.clone();
+    tokio::spawn(async move {
+        if let Err(e) = run(&graph, &model, checkpoint, batch_size, delay).await {
+            tracing::error!("Re-embed job failed: {}", e);
+            if let Err(e) = finish(&graph, "failed", Some(e.to_string())).await {
+                tracing::error!("Failed to record re-embed failure: {}", e);
+            }
+        }
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+
+    Ok(job)
+}
+
+/// Reset the job for a new model or on request, otherwise resume an
+/// unfinished run from its checkpoint
+async fn prepare_job(
+    graph: &Graph,
+    model: &str,
+    restart: bool,
+) -> Result<ReembedJob, ServiceError> {
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MERGE (j:_ReembedJob {{index: $index}})
+        WITH j, ($restart OR j.status IS NULL OR j.status = 'completed'
+                 OR j.model <> $model OR j.version <> $version) AS fresh
+        FOREACH (_ IN CASE WHEN fresh THEN [1] ELSE [] END |
+            SET j.model = $model, j.version = $version, j.checkpoint = null,
+                j.processed = 0, j.started_at = toString(datetime())
+        )
+        WITH j
+        CALL {{
+            MATCH (n:{label}) WHERE {stale}
+            RETURN count(n) AS remaining
+        }}
+        SET j.status = 'running', j.error = null, j.total = j.processed + remaining,
+            j.updated_at = toString(datetime())
+        "#,
+        label = EMBEDDED_LABEL,
+        stale = STALE
+    ))
+    .param("index", VECTOR_INDEX)
+    .param("model", model)
+    .param("version", EMBEDDING_VERSION)
+    .param("restart", restart);
+    graph.run(query).await?;
+
+    reembed_status(graph)
+        .await?
+        .ok_or_else(|| ServiceError::DatabaseError("Re-embed job was not recorded".to_string()))
+}
+
+async fn run(
+    graph: &Graph,
+    model: &str,
+    mut checkpoint: Option<String>,
+    batch_size: i64,
+    delay: Duration,
+) -> Result<(), ServiceError> {
+    loop {
+        let batch = next_batch(graph, model, checkpoint.as_deref(), batch_size).await?;
+        let Some((last, _)) = batch.last() else {
+            break;
+        };
+        let last = last.clone();
+
+        let texts: Vec<String> = batch.iter().map(|(_, text)| text.clone()).collect();
+        let embeddings = generate_embeddings(&texts)
+            .await
+            .map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+
+        let rows: Vec<BoltType> = batch
+            .iter()
+            .zip(embeddings)
+            .map(|((element_id, _), embedding)| {
+                let mut row = BoltMap::new();
+                row.put("elementId".into(), element_id.as_str().into());
+                row.put("embedding".into(), embedding.into());
+                BoltType::Map(row)
+            })
+            .collect();
+        let update = Neo4jQuery::new(
+            r#"
+            UNWIND $rows AS row
+            MATCH (n) WHERE elementId(n) = row.elementId
+            SET n.embedding = row.embedding,
+                n.embedding_model = $model, n.embedding_version = $version
+            WITH count(n) AS updated
+            MATCH (j:_ReembedJob {index: $index})
+            SET j.checkpoint = $checkpoint, j.processed = j.processed + updated,
+                j.updated_at = toString(datetime())
+            "#
+            .to_string(),
+        )
+        .param("rows", rows)
+        .param("model", model)
+        .param("version", EMBEDDING_VERSION)
+        .param("index", VECTOR_INDEX)
+        .param("checkpoint", last.as_str());
+        graph.run(update).await?;
+
+        tracing::info!("Re-embedded {} nodes up to {}", batch.len(), last);
+        checkpoint = Some(last);
+        tokio::time::sleep(delay).await;
+    }
+
+    let space = Neo4jQuery::new(format!(
+        "MERGE (s:{} {{index: $index}}) SET s.model = $model, s.version = $version",
+        EMBEDDING_SPACE_LABEL
+    ))
+    .param("index", VECTOR_INDEX)
+    .param("model", model)
+    .param("version", EMBEDDING_VERSION);
+    graph.run(space).await?;
+
+    finish(graph, "completed", None).await?;
+    tracing::info!(
+        "Re-embed job completed; index now holds '{}' embeddings",
+        model
+    );
+    Ok(())
+}
+
+/// Up to `batch_size` stale nodes after `checkpoint`, with the text to embed
+async fn next_batch(
+    graph: &Graph,
+    model: &str,
+    checkpoint: Option<&str>,
+    batch_size: i64,
+) -> Result<Vec<(String, String)>, ServiceError> {
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (n:{label})
+        WHERE {stale} AND ($after IS NULL OR elementId(n) > $after)
+        RETURN elementId(n) AS elementId, n.name AS name,
+               coalesce(n.description, '') AS description
+        ORDER BY elementId ASC
+        LIMIT $limit
+        "#,
+        label = EMBEDDED_LABEL,
+        stale = STALE
+    ))
+    .param("model", model)
+    .param("version", EMBEDDING_VERSION)
+    .param("after", checkpoint)
+    .param("limit", batch_size);
+
+    let mut result = graph.execute(query).await?;
+    let mut batch = Vec::new();
+    while let Some(row) = result.next().await? {
+        let name: String = row.get("name").unwrap_or_default();
+        let description: String = row.get("description").unwrap_or_default();
+        batch.push((
+            row.get("elementId").unwrap_or_default(),
+            node_embedding_text(&name, &description),
+        ));
+    }
+    Ok(batch)
+}
+
+async fn finish(graph: &Graph, status: &str, error: Option<String>) -> Result<(), ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (j:_ReembedJob {index: $index})
+        SET j.status = $status, j.error = $error, j.updated_at = toString(datetime())
+        "#
+        .to_string(),
+    )
+    .param("index", VECTOR_INDEX)
+    .param("status", status)
+    .param("error", error);
+    graph.run(query).await?;
+    Ok(())
+}
@@ -1,23 +1,136 @@
-use neo4rs::{Graph, Query as Neo4jQuery};
-use serde_json::{json, Value};
+use neo4rs::{Graph, Query as Neo4jQuery, Txn};
+use serde_json::{Value, json};
 use std::collections::HashMap;
 
-use crate::common::neo4j_utils::json_value_to_bolt_type;
+use crate::common::{cypher, neo4j_utils::json_value_to_bolt_type};
 
 use crate::domains::graph::models::{
     CreateRelationshipRequest, CreateRelationshipResult, ServiceError,
 };
+use crate::domains::graph::schema;
 
-/// Create a relationship between two nodes (uses MERGE for idempotency)
+use super::node::{get_node_labels, get_node_labels_in_txn};
+
+/// Get the labels of a relationship's start and end nodes - returns None if it doesn't exist
+async fn get_relationship_endpoint_labels(
+    graph: &Graph,
+    element_id: &str,
+) -> Result<Option<(Vec<String>, Vec<String>)>, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (source)-[r]->(target)
+        WHERE elementId(r) = $relId
+        RETURN labels(source) AS sourceLabels, labels(target) AS targetLabels
+        "#
+        .to_string(),
+    )
+    .param("relId", element_id);
+
+    match graph.execute(query).await {
+        Ok(mut result) => {
+            if let Ok(Some(row)) = result.next().await {
+                let source_labels: Vec<String> = row.get("sourceLabels").unwrap_or_default();
+                let target_labels: Vec<String> = row.get("targetLabels").unwrap_or_default();
+                Ok(Some((source_labels, target_labels)))
+            } else {
+                Ok(None)
+            }
+        }
+        Err(e) => Err(ServiceError::DatabaseError(format!(
+            "Database error: {}",
+            e
+        ))),
+    }
+}
+
+/// Create a relationship between two nodes (uses MERGE for idempotency).
+/// The relationship type must be allowed between the two nodes' labels by the schema registry.
 pub async fn create_relationship(
     graph: &Graph,
     request: CreateRelationshipRequest,
 ) -> Result<CreateRelationshipResult, ServiceError> {
     let properties = request.properties.clone().unwrap_or_default();
-    let set_clauses: Vec<String> = properties
-        .keys()
-        .map(|key| format!("r.{} = ${}", key, key))
-        .collect();
+    schema::validate_relationship_properties(&properties)?;
+
+    let source_labels = get_node_labels(graph, &request.source_id)
+        .await?
+        .ok_or_else(|| ServiceError::NotFound(format!("Node {} not found", request.source_id)))?;
+    let target_labels = get_node_labels(graph, &request.target_id)
+        .await?
+        .ok_or_else(|| ServiceError::NotFound(format!("Node {} not found", request.target_id)))?;
+
+    schema::validate_relationship(&request.relationship_type, &source_labels, &target_labels)?;
+
+    let query = build_create_relationship_query(&request, properties)?;
+
+    match graph.execute(query).await {
+        Ok(mut result) => {
+            if let Ok(Some(row)) = result.next().await {
+                let element_id: String = row.get("elementId").unwrap_or_default();
+
+                Ok(CreateRelationshipResult {
+                    element_id,
+                    relationship_type: request.relationship_type,
+                    source_id: request.source_id,
+                    target_id: request.target_id,
+                })
+            } else {
+                Err(ServiceError::DatabaseError(
+                    "Failed to get element ID after relationship creation".to_string(),
+                ))
+            }
+        }
+        Err(e) => {
+            tracing::error!("Error creating relationship: {}", e);
+            Err(ServiceError::DatabaseError(format!(
+                "Database error: {}",
+                e
+            )))
+        }
+    }
+}
+
+/// Create a relationship inside an existing transaction.
+/// Endpoint labels are read through the transaction, so nodes created earlier in it can be linked.
+pub async fn create_relationship_in_txn(
+    txn: &mut Txn,
+    request: CreateRelationshipRequest,
+) -> Result<CreateRelationshipResult, ServiceError> {
+    let properties = request.properties.clone().unwrap_or_default();
+    schema::validate_relationship_properties(&properties)?;
+
+    let source_labels = get_node_labels_in_txn(txn, &request.source_id)
+        .await?
+        .ok_or_else(|| ServiceError::NotFound(format!("Node {} not found", request.source_id)))?;
+    let target_labels = get_node_labels_in_txn(txn, &request.target_id)
+        .await?
+        .ok_or_else(|| ServiceError::NotFound(format!("Node {} not found", request.target_id)))?;
+
+    schema::validate_relationship(&request.relationship_type, &source_labels, &target_labels)?;
+
+    let query = build_create_relationship_query(&request, properties)?;
+
+    let mut result = txn.execute(query).await?;
+    match result.next(txn.handle()).await? {
+        Some(row) => Ok(CreateRelationshipResult {
+            element_id: row.get("elementId").unwrap_or_default(),
+            relationship_type: request.relationship_type,
+            source_id: request.source_id,
+            target_id: request.target_id,
+        }),
+        None => Err(ServiceError::DatabaseError(
+            "Failed to get element ID after relationship creation".to_string(),
+        )),
+    }
+}
+
+/// Build the MERGE query for a validated relationship
+fn build_create_relationship_query(
+    request: &CreateRelationshipRequest,
+    properties: HashMap<String, Value>,
+) -> Result<Neo4jQuery, ServiceError> {
+    let relationship_type = cypher::relationship_type(&request.relationship_type)?;
+    let set_clauses = cypher::set_clauses("r", properties.keys())?;
 
     let query_string = if set_clauses.is_empty() {
         format!(
@@ -27,7 +140,7 @@ pub async fn create_relationship(
             MERGE (source)-[r:{}]->(target)
             RETURN elementId(r) AS elementId
             "#,
-            request.relationship_type
+            relationship_type
         )
     } else {
         format!(
@@ -38,8 +151,7 @@ pub async fn create_relationship(
             SET {}
             RETURN elementId(r) AS elementId
             "#,
-            request.relationship_type,
-            set_clauses.join(", ")
+            relationship_type, set_clauses
         )
     };
 
@@ -51,28 +163,7 @@ pub async fn create_relationship(
         query = query.param(&key, json_value_to_bolt_type(&value));
     }
 
-    match graph.execute(query).await {
-        Ok(mut result) => {
-            if let Ok(Some(row)) = result.next().await {
-                let element_id: String = row.get("elementId").unwrap_or_default();
-
-                Ok(CreateRelationshipResult {
-                    element_id,
-                    relationship_type: request.relationship_type,
-                    source_id: request.source_id,
-                    target_id: request.target_id,
-                })
-            } else {
-                Err(ServiceError::DatabaseError(
-                    "Failed to get element ID after relationship creation".to_string(),
-                ))
-            }
-        }
-        Err(e) => {
-            tracing::error!("Error creating relationship: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
-        }
-    }
+    Ok(query)
 }
 
 /// Update a relationship's type and/or properties
@@ -83,12 +174,21 @@ pub async fn update_relationship(
     properties: Option<HashMap<String, Value>>,
 ) -> Result<Vec<Value>, ServiceError> {
     let props = properties.unwrap_or_default();
-    let set_clauses: Vec<String> = props
-        .keys()
-        .map(|key| format!("r.{} = ${}", key, key))
-        .collect();
+    schema::validate_relationship_properties(&props)?;
+
+    if !relationship_type.is_empty() {
+        let (source_labels, target_labels) = get_relationship_endpoint_labels(graph, target_id)
+            .await?
+            .ok_or_else(|| {
+                ServiceError::NotFound(format!("Relationship {} not found", target_id))
+            })?;
+        schema::validate_relationship(relationship_type, &source_labels, &target_labels)?;
+    }
 
     let query_string = if !relationship_type.is_empty() {
+        let relationship_type = cypher::relationship_type(relationship_type)?;
+        let set_clauses = cypher::set_clauses("newR", props.keys())?;
+
         // If type needs to be updated, create new relationship and delete old one
         if set_clauses.is_empty() {
             format!(
@@ -111,12 +211,12 @@ pub async fn update_relationship(
                 DELETE r
                 RETURN newR AS r
                 "#,
-                relationship_type,
-                set_clauses.join(", ").replace("r.", "newR.")
+                relationship_type, set_clauses
             )
         }
     } else {
         // Just update properties
+        let set_clauses = cypher::set_clauses("r", props.keys())?;
         if set_clauses.is_empty() {
             r#"
             MATCH ()-[r]->()
@@ -132,7 +232,7 @@ pub async fn update_relationship(
                 SET {}
                 RETURN r
                 "#,
-                set_clauses.join(", ")
+                set_clauses
             )
         }
     };
@@ -157,7 +257,10 @@ pub async fn update_relationship(
         }
         Err(e) => {
             tracing::error!("Error updating relationship: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
+            Err(ServiceError::DatabaseError(format!(
+                "Database error: {}",
+                e
+            )))
         }
     }
 }
@@ -185,7 +288,10 @@ pub async fn delete_relationship(graph: &Graph, element_id: &str) -> Result<i64,
         }
         Err(e) => {
             tracing::error!("Error deleting relationship: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
+            Err(ServiceError::DatabaseError(format!(
+                "Database error: {}",
+                e
+            )))
         }
     }
 }
@@ -1,89 +1,415 @@
 use neo4rs::{Graph, Query as Neo4jQuery};
-use serde_json::{json, Value};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::time::Duration;
 
-use crate::common::similarity::{find_similar_nodes as similarity_find_similar_nodes, FindSimilarNodesRequest};
+use crate::common::cypher::{self, CypherError};
+use crate::common::embedding::generate_embedding;
+use crate::common::pagination::{Page, decode_cursor, encode_cursor, page_size};
+use crate::common::similarity::{
+    IncompatibleEmbedding, find_similar_nodes as similarity_find_similar_nodes,
+};
 
-use crate::domains::graph::models::ServiceError;
+use crate::domains::graph::models::{ServiceError, SimilarNodesRequest};
+use crate::domains::graph::schema::EMBEDDED_LABEL;
 
-/// Search nodes by text query with optional label filter
+use super::node::NodeProjection;
+
+/// Full-text index over node names and descriptions
+pub const FULLTEXT_INDEX: &str = "nodeSearch";
+/// Vector index over node embeddings
+pub const VECTOR_INDEX: &str = "nodeEmbeddings";
+
+/// Candidates taken from each index before fusion
+const CANDIDATES: i64 = 100;
+/// Vector hits below this similarity are noise rather than matches
+const VECTOR_MIN_SCORE: f64 = 0.6;
+/// Longest the search waits on the embedding service before going full-text only
+const EMBEDDING_TIMEOUT: Duration = Duration::from_secs(3);
+/// Damping constant from the original reciprocal-rank fusion paper
+const RRF_K: f64 = 60.0;
+const SNIPPET_CHARS: usize = 160;
+/// Nearest matches the similar-nodes endpoint pages through
+const SIMILAR_NODE_CANDIDATES: i32 = 100;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchCursor {
+    offset: usize,
+}
+
+/// Hybrid search: full-text and vector candidates fused by reciprocal rank.
+///
+/// Both indexes are queried concurrently. If the embedding service is
+/// unavailable, or slower than `EMBEDDING_TIMEOUT`, the search degrades to
+/// full-text results only. Each index contributes at most `CANDIDATES` hits, so
+/// `total` counts the fused candidates rather than every node that matches.
 pub async fn search_nodes(
     graph: &Graph,
     query_text: &str,
     labels: Option<Vec<&str>>,
     limit: i64,
-) -> Result<Vec<Value>, ServiceError> {
-    // Build label filter
-    let label_filter = if let Some(labels_vec) = labels {
-        let label_conditions: Vec<String> =
-            labels_vec.iter().map(|l| format!("n:`{}`", l)).collect();
-        format!("({})", label_conditions.join(" OR "))
-    } else {
-        "(n:Knowledge OR n:Skill OR n:Trait OR n:Milestone)".to_string()
+    cursor: Option<&str>,
+    projection: &NodeProjection,
+) -> Result<Page<Value>, ServiceError> {
+    let label_filter = match &labels {
+        Some(labels_vec) => cypher::label_predicate("node", labels_vec)?,
+        None => "(node:Knowledge OR node:Skill OR node:Trait OR node:Milestone)".to_string(),
     };
+    let offset = match cursor {
+        Some(raw) => {
+            decode_cursor::<SearchCursor>(raw)
+                .ok_or_else(|| ServiceError::ValidationError("invalid cursor".to_string()))?
+                .offset
+        }
+        None => 0,
+    };
+    let limit = page_size(Some(limit)) as usize;
+
+    let terms = search_terms(query_text);
+    if terms.is_empty() {
+        return Ok(Page {
+            items: Vec::new(),
+            next_cursor: None,
+            total: 0,
+        });
+    }
+
+    let vector_search = async {
+        let embedding = tokio::time::timeout(EMBEDDING_TIMEOUT, generate_embedding(query_text))
+            .await
+            .map_err(|_| ServiceError::EmbeddingFailed("timed out".to_string()))?
+            .map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+        vector_candidates(graph, embedding, &label_filter).await
+    };
+    let (text_hits, vector_hits) = tokio::join!(
+        fulltext_candidates(graph, &terms, &label_filter),
+        vector_search
+    );
+    let text_hits = text_hits?;
+    let vector_hits = vector_hits.unwrap_or_else(|e| {
+        tracing::warn!("Vector search unavailable, using full-text only: {}", e);
+        Vec::new()
+    });
+
+    let fused = fuse(&[("text", &text_hits), ("vector", &vector_hits)]);
+    // Capped at the candidates both indexes returned
+    let total = fused.len() as i64;
+    let page: Vec<_> = fused.into_iter().skip(offset).take(limit).collect();
+    let next_offset = offset + page.len();
+    let next_cursor = (page.len() == limit && (next_offset as i64) < total).then(|| {
+        encode_cursor(&SearchCursor {
+            offset: next_offset,
+        })
+    });
+
+    let ids: Vec<String> = page.iter().map(|hit| hit.id.clone()).collect();
+    let mut nodes = fetch_nodes(graph, ids).await?;
+
+    let items = page
+        .into_iter()
+        .filter_map(|hit| {
+            let mut node = nodes.remove(&hit.id)?;
+            let props = node.get("props");
+            let highlights = json!({
+                "name": props
+                    .and_then(|p| p.get("name"))
+                    .and_then(Value::as_str)
+                    .and_then(|name| highlight(name, &terms, None)),
+                "description": props
+                    .and_then(|p| p.get("description"))
+                    .and_then(Value::as_str)
+                    .and_then(|desc| highlight(desc, &terms, Some(SNIPPET_CHARS))),
+            });
+            // Highlights are taken before `fields` can drop name or description
+            if let Some(props) = node.get_mut("props").and_then(Value::as_object_mut) {
+                projection.apply(props);
+            }
+            if let Some(obj) = node.as_object_mut() {
+                obj.insert("score".to_string(), json!(hit.score));
+                obj.insert("matchedBy".to_string(), json!(hit.sources));
+                obj.insert("highlights".to_string(), highlights);
+            }
+            Some(node)
+        })
+        .collect();
+
+    Ok(Page {
+        items,
+        next_cursor,
+        total,
+    })
+}
 
-    let query_string = format!(
+/// Lowercased query terms, empty ones dropped
+fn search_terms(query_text: &str) -> Vec<String> {
+    query_text
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Escape Lucene query syntax so user input is matched literally
+fn escape_lucene(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        if "+-&|!(){}[]^\"~*?:\\/".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Element ids ranked by the full-text index. Every term must match, either
+/// exactly or as a prefix so results appear while the user is still typing.
+/// Draft and archived levels are indexed too but never returned.
+async fn fulltext_candidates(
+    graph: &Graph,
+    terms: &[String],
+    label_filter: &str,
+) -> Result<Vec<String>, ServiceError> {
+    let lucene = terms
+        .iter()
+        .map(|t| {
+            let t = escape_lucene(t);
+            format!("({0} OR {0}*)", t)
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ");
+
+    let query = Neo4jQuery::new(format!(
+        r#"
+        CALL db.index.fulltext.queryNodes($index, $search)
+        YIELD node, score
+        WHERE {} AND (node:{} OR NOT node:Domain_Level)
+        RETURN elementId(node) AS id
+        ORDER BY score DESC
+        LIMIT $limit
+        "#,
+        label_filter, EMBEDDED_LABEL
+    ))
+    .param("index", FULLTEXT_INDEX)
+    .param("search", lucene)
+    .param("limit", CANDIDATES);
+
+    collect_ids(graph, query).await
+}
+
+/// Element ids ranked by embedding similarity
+async fn vector_candidates(
+    graph: &Graph,
+    embedding: Vec<f64>,
+    label_filter: &str,
+) -> Result<Vec<String>, ServiceError> {
+    // The label filter runs after the top-k cut, so over-fetch to leave room
+    let query = Neo4jQuery::new(format!(
+        r#"
+        CALL db.index.vector.queryNodes($index, $k, $embedding)
+        YIELD node, score
+        WHERE {} AND score >= $minScore
+        RETURN elementId(node) AS id
+        ORDER BY score DESC
+        LIMIT $limit
+        "#,
+        label_filter
+    ))
+    .param("index", VECTOR_INDEX)
+    .param("k", CANDIDATES * 4)
+    .param("embedding", embedding)
+    .param("minScore", VECTOR_MIN_SCORE)
+    .param("limit", CANDIDATES);
+
+    collect_ids(graph, query).await
+}
+
+async fn collect_ids(graph: &Graph, query: Neo4jQuery) -> Result<Vec<String>, ServiceError> {
+    let mut result = graph.execute(query).await?;
+    let mut ids = Vec::new();
+    while let Some(row) = result.next().await? {
+        ids.push(row.get("id").unwrap_or_default());
+    }
+    Ok(ids)
+}
+
+struct FusedHit {
+    id: String,
+    score: f64,
+    sources: Vec<&'static str>,
+}
+
+/// Reciprocal-rank fusion: each list contributes `1 / (RRF_K + rank)`
+fn fuse(rankings: &[(&'static str, &Vec<String>)]) -> Vec<FusedHit> {
+    let mut hits: Vec<FusedHit> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (source, ids) in rankings {
+        for (rank, id) in ids.iter().enumerate() {
+            let contribution = 1.0 / (RRF_K + rank as f64 + 1.0);
+            match positions.get(id) {
+                Some(&i) => {
+                    hits[i].score += contribution;
+                    hits[i].sources.push(source);
+                }
+                None => {
+                    positions.insert(id.clone(), hits.len());
+                    hits.push(FusedHit {
+                        id: id.clone(),
+                        score: contribution,
+                        sources: vec![source],
+                    });
+                }
+            }
+        }
+    }
+
+    // Stable sort keeps full-text order among equal scores
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits
+}
+
+/// Node payloads keyed by element id, with every property
+async fn fetch_nodes(
+    graph: &Graph,
+    ids: Vec<String>,
+) -> Result<HashMap<String, Value>, ServiceError> {
+    let query = Neo4jQuery::new(
         r#"
         MATCH (n)
-        WHERE {} AND toLower(n.name) CONTAINS toLower($query)
-        RETURN {{
+        WHERE elementId(n) IN $ids
+        RETURN elementId(n) AS id, {
             elementId: elementId(n),
             labels: labels(n),
             props: properties(n)
-        }} AS node
-        ORDER BY n.name
-        LIMIT $limit
-        "#,
-        label_filter
-    );
+        } AS node
+        "#
+        .to_string(),
+    )
+    .param("ids", ids);
 
-    let mut query = Neo4jQuery::new(query_string);
-    query = query.param("query", query_text);
-    query = query.param("limit", limit);
+    let mut result = graph.execute(query).await?;
+    let mut nodes = HashMap::new();
+    while let Some(row) = result.next().await? {
+        let id: String = row.get("id").unwrap_or_default();
+        let node: Value = row.get("node").unwrap_or(json!({}));
+        nodes.insert(id, node);
+    }
+    Ok(nodes)
+}
 
-    match graph.execute(query).await {
-        Ok(mut result) => {
-            let mut nodes = Vec::new();
+/// HTML-escaped `text` with every term occurrence wrapped in `<mark>`.
+///
+/// With `max_chars`, long text is cut to a window around the first match.
+/// Returns `None` when no term occurs in the text.
+fn highlight(text: &str, terms: &[String], max_chars: Option<usize>) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let mut terms: Vec<Vec<char>> = terms.iter().map(|t| t.chars().collect()).collect();
+    // Prefer the longest term where several start at the same position
+    terms.sort_by_key(|t| std::cmp::Reverse(t.len()));
 
-            while let Ok(Some(row)) = result.next().await {
-                let node: Value = row.get("node").unwrap_or(json!({}));
-                nodes.push(node);
+    let mut matches: Vec<(usize, usize)> = Vec::new();
+    let mut i = 0;
+    while i < folded.len() {
+        match terms
+            .iter()
+            .find(|t| !t.is_empty() && folded[i..].starts_with(t))
+        {
+            Some(term) => {
+                matches.push((i, i + term.len()));
+                i += term.len();
             }
+            None => i += 1,
+        }
+    }
+    let first = matches.first()?.0;
 
-            Ok(nodes)
+    let (start, end) = match max_chars {
+        Some(max) if chars.len() > max => {
+            let start = first.saturating_sub(max / 3).min(chars.len() - max);
+            (start, start + max)
         }
-        Err(e) => {
-            tracing::error!("Error in search_nodes: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
+        _ => (0, chars.len()),
+    };
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    let mut pos = start;
+    for (m_start, m_end) in matches {
+        if m_end <= start || m_start >= end {
+            continue;
+        }
+        let (m_start, m_end) = (m_start.max(start), m_end.min(end));
+        push_escaped(&mut out, &chars[pos..m_start]);
+        out.push_str("<mark>");
+        push_escaped(&mut out, &chars[m_start..m_end]);
+        out.push_str("</mark>");
+        pos = m_end;
+    }
+    push_escaped(&mut out, &chars[pos..end]);
+    if end < chars.len() {
+        out.push('…');
+    }
+    Some(out)
+}
+
+fn push_escaped(out: &mut String, chars: &[char]) {
+    for &c in chars {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
         }
     }
 }
 
-/// Find similar nodes by nodeId or embedding
+/// Find similar nodes by nodeId, embedding or text, with optional filters, a
+/// page at a time from the most similar. Only the `SIMILAR_NODE_CANDIDATES`
+/// nearest matches are paged through, so `total` is capped at that.
 pub async fn find_similar_nodes(
     graph: &Graph,
-    node_id: Option<String>,
-    embedding: Option<Vec<f64>>,
-    limit: Option<i32>,
-) -> Result<Value, ServiceError> {
-    let has_node_id = node_id.is_some();
-    let has_embedding = embedding.is_some() && !embedding.as_ref().unwrap().is_empty();
-
-    if (!has_node_id && !has_embedding) || (has_node_id && has_embedding) {
+    request: SimilarNodesRequest,
+) -> Result<Page<Value>, ServiceError> {
+    let SimilarNodesRequest {
+        mut search,
+        cursor,
+        fields,
+    } = request;
+    let projection = NodeProjection::new(fields.as_deref(), false)?;
+    let sources = [
+        search.node_id.is_some(),
+        search.embedding.as_ref().is_some_and(|e| !e.is_empty()),
+        search.text.as_ref().is_some_and(|t| !t.trim().is_empty()),
+    ];
+    if sources.iter().filter(|&&given| given).count() != 1 {
         return Err(ServiceError::ValidationError(
-            "Exactly one of 'nodeId' or 'embedding' must be provided".to_string(),
+            "Exactly one of 'nodeId', 'embedding' or 'text' must be provided".to_string(),
         ));
     }
+    if let Some(min_score) = search.min_score
+        && !(0.0..=1.0).contains(&min_score)
+    {
+        return Err(ServiceError::ValidationError(format!(
+            "'min_score' must be between 0 and 1, got {}",
+            min_score
+        )));
+    }
 
-    let request = FindSimilarNodesRequest {
-        node_id,
-        embedding,
-        text: None,
-        label: None,
-        limit,
-    };
-
-    match similarity_find_similar_nodes(graph, request).await {
-        Ok(result) => Ok(json!(result)),
+    // `limit` is the page size; the search itself always looks at the same candidates
+    let limit = search.limit.map(i64::from);
+    search.limit = Some(SIMILAR_NODE_CANDIDATES);
+    match similarity_find_similar_nodes(graph, search).await {
+        Ok(matches) => projection.page_of(matches, limit, cursor.as_deref()),
+        Err(e) if e.is::<IncompatibleEmbedding>() || e.is::<CypherError>() => {
+            Err(ServiceError::ValidationError(e.to_string()))
+        }
         Err(e) => {
             tracing::error!("Error finding similar nodes: {}", e);
             Err(ServiceError::DatabaseError(format!(
@@ -0,0 +1,368 @@
+//! De-duplicated node/edge lists for rendering the constellation.
+//!
+//! The subgraph grows breadth-first from the seeds, one query per hop, until it
+//! is `depth` hops deep or holds `max_nodes` nodes. Only Layer 1 nodes are
+//! followed, so people never show up in the (public) constellation; every path
+//! that creates a component, domain or live level labels it. Edges are
+//! fetched once for the final node set, so each relationship between two
+//! returned nodes appears exactly once.
+
+use neo4rs::{Graph, Query as Neo4jQuery};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+
+use crate::common::cypher;
+use crate::domains::graph::models::{
+    ServiceError, Subgraph, SubgraphEdge, SubgraphNode, SubgraphParams,
+};
+use crate::domains::graph::schema::{
+    ANALYTICS_PROPERTIES, EMBEDDED_LABEL, LAYOUT_PROPERTIES, SOFT_DELETE_PROPERTY,
+    SYSTEM_PROPERTIES,
+};
+
+const DEFAULT_DEPTH: i64 = 2;
+const MAX_DEPTH: i64 = 5;
+const DEFAULT_MAX_NODES: i64 = 300;
+const MAX_NODES: i64 = 2000;
+const DEFAULT_SEED_LABEL: &str = "Domain";
+/// Distance between consecutive rings of the radial layout
+const RING_SPACING: f64 = 100.0;
+
+/// A node reached by the expansion
+struct Discovered {
+    labels: Vec<String>,
+    properties: Value,
+    depth: i64,
+    /// Node it was first reached from; `None` for seeds
+    parent: Option<String>,
+    /// Coordinates stored by the layout job
+    stored: Option<(f64, f64)>,
+    cluster: Option<i64>,
+}
+
+pub async fn get_subgraph(graph: &Graph, params: SubgraphParams) -> Result<Subgraph, ServiceError> {
+    let depth = params.depth.unwrap_or(DEFAULT_DEPTH);
+    if !(0..=MAX_DEPTH).contains(&depth) {
+        return Err(ServiceError::ValidationError(format!(
+            "depth must be between 0 and {}",
+            MAX_DEPTH
+        )));
+    }
+    let max_nodes = params.max_nodes.unwrap_or(DEFAULT_MAX_NODES);
+    if !(1..=MAX_NODES).contains(&max_nodes) {
+        return Err(ServiceError::ValidationError(format!(
+            "maxNodes must be between 1 and {}",
+            MAX_NODES
+        )));
+    }
+    let types = relationship_pattern(params.relationship_types.as_deref())?;
+
+    let mut order: Vec<String> = Vec::new();
+    let mut nodes: HashMap<String, Discovered> = HashMap::new();
+
+    let seed_query = match &params.root {
+        Some(root) => Neo4jQuery::new(format!(
+            r#"
+            MATCH (n:{})
+            WHERE elementId(n) = $root AND n.{} IS NULL
+            RETURN elementId(n) AS id, labels(n) AS labels, n {{.*, embedding: null}} AS props,
+                   null AS parent
+            "#,
+            EMBEDDED_LABEL, SOFT_DELETE_PROPERTY
+        ))
+        .param("root", root.clone()),
+        None => {
+            let labels: Vec<&str> = params
+                .labels
+                .as_deref()
+                .unwrap_or(DEFAULT_SEED_LABEL)
+                .split(',')
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .collect();
+            if labels.is_empty() {
+                return Err(ServiceError::ValidationError(
+                    "labels must name at least one label".to_string(),
+                ));
+            }
+            Neo4jQuery::new(format!(
+                r#"
+                MATCH (n:{})
+                WHERE {} AND n.{} IS NULL
+                RETURN elementId(n) AS id, labels(n) AS labels, n {{.*, embedding: null}} AS props,
+                       null AS parent
+                ORDER BY n.name, id
+                LIMIT $limit
+                "#,
+                EMBEDDED_LABEL,
+                cypher::label_predicate("n", &labels)?,
+                SOFT_DELETE_PROPERTY
+            ))
+            .param("limit", max_nodes + 1)
+        }
+    };
+    let mut truncated =
+        collect_nodes(graph, seed_query, 0, max_nodes, &mut order, &mut nodes).await?;
+
+    if let Some(root) = &params.root
+        && nodes.is_empty()
+    {
+        return Err(ServiceError::NotFound(format!("Node '{}' not found", root)));
+    }
+
+    let mut frontier = order.clone();
+    for hop in 1..=depth {
+        if frontier.is_empty() || truncated {
+            break;
+        }
+        let remaining = max_nodes - order.len() as i64;
+        let query = Neo4jQuery::new(format!(
+            r#"
+            MATCH (n)-[{}]-(m:{})
+            WHERE elementId(n) IN $frontier AND NOT elementId(m) IN $known AND m.{} IS NULL
+            WITH m, min(elementId(n)) AS parent
+            RETURN elementId(m) AS id, labels(m) AS labels, m {{.*, embedding: null}} AS props,
+                   parent
+            ORDER BY parent, m.name, id
+            LIMIT $limit
+            "#,
+            types, EMBEDDED_LABEL, SOFT_DELETE_PROPERTY
+        ))
+        .param("frontier", frontier.clone())
+        .param("known", order.clone())
+        .param("limit", remaining + 1);
+
+        let before = order.len();
+        truncated = collect_nodes(graph, query, hop, remaining, &mut order, &mut nodes).await?;
+        frontier = order[before..].to_vec();
+    }
+
+    let edges = fetch_edges(graph, &types, &order).await?;
+    let degrees = fetch_degrees(graph, &types, &order).await?;
+
+    let mut visible: HashMap<&str, i64> = HashMap::new();
+    for edge in &edges {
+        *visible.entry(edge.source.as_str()).or_default() += 1;
+        if edge.target != edge.source {
+            *visible.entry(edge.target.as_str()).or_default() += 1;
+        }
+    }
+
+    let coordinates = if !params.layout {
+        HashMap::new()
+    } else if nodes.values().all(|node| node.stored.is_some()) {
+        nodes
+            .iter()
+            .filter_map(|(id, node)| Some((id.clone(), node.stored?)))
+            .collect()
+    } else {
+        radial_layout(&order, &nodes)
+    };
+
+    let subgraph_nodes = order
+        .iter()
+        .map(|id| {
+            let node = &nodes[id];
+            let degree = degrees.get(id).copied().unwrap_or_default();
+            let position = coordinates.get(id);
+            SubgraphNode {
+                id: id.clone(),
+                labels: node.labels.clone(),
+                properties: node.properties.clone(),
+                depth: node.depth,
+                degree,
+                truncated: degree > visible.get(id.as_str()).copied().unwrap_or_default(),
+                cluster: node.cluster,
+                x: position.map(|p| p.0),
+                y: position.map(|p| p.1),
+            }
+        })
+        .collect();
+
+    Ok(Subgraph {
+        nodes: subgraph_nodes,
+        edges,
+        truncated,
+        max_nodes,
+    })
+}
+
+/// Relationship pattern such as `` :`HAS_DOMAIN_LEVEL`|`REQUIRES_SKILL` ``,
+/// or an empty string to follow every type
+fn relationship_pattern(types: Option<&str>) -> Result<String, ServiceError> {
+    let types = types
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(cypher::relationship_type)
+        .collect::<Result<Vec<_>, _>>()?;
+    if types.is_empty() {
+        Ok(String::new())
+    } else {
+        Ok(format!(":{}", types.join("|")))
+    }
+}
+
+/// Add the nodes returned by `query` until `budget` is spent; returns whether
+/// any had to be left out
+async fn collect_nodes(
+    graph: &Graph,
+    query: Neo4jQuery,
+    depth: i64,
+    budget: i64,
+    order: &mut Vec<String>,
+    nodes: &mut HashMap<String, Discovered>,
+) -> Result<bool, ServiceError> {
+    let mut result = graph.execute(query).await?;
+    let mut added = 0;
+
+    while let Some(row) = result.next().await? {
+        if added == budget {
+            return Ok(true);
+        }
+        let id: String = row.get("id").unwrap_or_default();
+        let mut properties: Value = row.get("props").unwrap_or(json!({}));
+        let mut stored = None;
+        let mut cluster = None;
+        if let Some(map) = properties.as_object_mut() {
+            let x = map.get("layout_x").and_then(Value::as_f64);
+            let y = map.get("layout_y").and_then(Value::as_f64);
+            stored = x.zip(y);
+            cluster = map.get("cluster_id").and_then(Value::as_i64);
+            for key in SYSTEM_PROPERTIES
+                .iter()
+                .chain(LAYOUT_PROPERTIES)
+                .chain(ANALYTICS_PROPERTIES)
+            {
+                map.remove(*key);
+            }
+        }
+        nodes.insert(
+            id.clone(),
+            Discovered {
+                labels: row.get("labels").unwrap_or_default(),
+                properties,
+                depth,
+                parent: row.get("parent").ok(),
+                stored,
+                cluster,
+            },
+        );
+        order.push(id);
+        added += 1;
+    }
+
+    Ok(false)
+}
+
+async fn fetch_edges(
+    graph: &Graph,
+    types: &str,
+    ids: &[String],
+) -> Result<Vec<SubgraphEdge>, ServiceError> {
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (a)-[r{}]->(b)
+        WHERE elementId(a) IN $ids AND elementId(b) IN $ids
+        RETURN elementId(r) AS id, type(r) AS type, elementId(a) AS source,
+               elementId(b) AS target, properties(r) AS props
+        ORDER BY id
+        "#,
+        types
+    ))
+    .param("ids", ids.to_vec());
+
+    let mut result = graph.execute(query).await?;
+    let mut edges = Vec::new();
+
+    while let Some(row) = result.next().await? {
+        edges.push(SubgraphEdge {
+            id: row.get("id").unwrap_or_default(),
+            relationship_type: row.get("type").unwrap_or_default(),
+            source: row.get("source").unwrap_or_default(),
+            target: row.get("target").unwrap_or_default(),
+            properties: row.get("props").unwrap_or(json!({})),
+        });
+    }
+
+    Ok(edges)
+}
+
+/// Relationships each node has to other live Layer 1 nodes, counted in the
+/// whole graph
+async fn fetch_degrees(
+    graph: &Graph,
+    types: &str,
+    ids: &[String],
+) -> Result<HashMap<String, i64>, ServiceError> {
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (n)
+        WHERE elementId(n) IN $ids
+        RETURN elementId(n) AS id,
+               COUNT {{ (n)-[{}]-(m:{}) WHERE m.{} IS NULL }} AS degree
+        "#,
+        types, EMBEDDED_LABEL, SOFT_DELETE_PROPERTY
+    ))
+    .param("ids", ids.to_vec());
+
+    let mut result = graph.execute(query).await?;
+    let mut degrees = HashMap::new();
+
+    while let Some(row) = result.next().await? {
+        degrees.insert(
+            row.get("id").unwrap_or_default(),
+            row.get("degree").unwrap_or_default(),
+        );
+    }
+
+    Ok(degrees)
+}
+
+/// Place seeds at the centre and each hop on the next ring out, ordering a
+/// ring by the angle of each node's parent so branches stay together
+fn radial_layout(
+    order: &[String],
+    nodes: &HashMap<String, Discovered>,
+) -> HashMap<String, (f64, f64)> {
+    let mut rings: Vec<Vec<&String>> = Vec::new();
+    for id in order {
+        let depth = nodes[id].depth as usize;
+        if rings.len() <= depth {
+            rings.resize(depth + 1, Vec::new());
+        }
+        rings[depth].push(id);
+    }
+
+    // A single seed sits at the origin; several share the first ring
+    let offset = if rings.first().is_some_and(|seeds| seeds.len() > 1) {
+        1.0
+    } else {
+        0.0
+    };
+    let mut angles: HashMap<&str, f64> = HashMap::new();
+    let mut coordinates = HashMap::new();
+
+    for (depth, ring) in rings.iter_mut().enumerate() {
+        let parent_angle = |id: &String| {
+            nodes[id]
+                .parent
+                .as_deref()
+                .and_then(|p| angles.get(p))
+                .copied()
+                .unwrap_or_default()
+        };
+        ring.sort_by(|a, b| parent_angle(a).total_cmp(&parent_angle(b)));
+
+        let radius = (depth as f64 + offset) * RING_SPACING;
+        for (i, id) in ring.iter().enumerate() {
+            let angle = TAU * i as f64 / ring.len() as f64;
+            angles.insert(id.as_str(), angle);
+            coordinates.insert((*id).clone(), (radius * angle.cos(), radius * angle.sin()));
+        }
+    }
+
+    coordinates
+}
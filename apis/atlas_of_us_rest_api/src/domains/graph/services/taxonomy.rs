@@ -0,0 +1,362 @@
+//! Domain taxonomy: hierarchical categories and free-form tags.
+//!
+//! ```text
+//! (Domain)-[:IN_CATEGORY]->(Category)-[:SUBCATEGORY_OF]->(Category)
+//! ```
+//!
+//! Category names are unique across the whole tree and each category has at
+//! most one parent. Tags live on the Domain itself as a `tags` list, stored
+//! trimmed and lowercased.
+
+use neo4rs::{Graph, Query as Neo4jQuery, Txn};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::common::neo4j_utils::with_transaction;
+use crate::common::pagination::Page;
+use crate::domains::graph::models::{
+    CategoryNode, CreateCategoryRequest, DomainSummary, ServiceError, SetDomainTaxonomyRequest,
+    TagCount, TaxonomyListParams,
+};
+
+use super::node::NodeProjection;
+
+/// Cypher expression for the category path of domain `d` from the root, e.g.
+/// `["Sports", "Combat Sports"]`; empty when it is not filed anywhere
+pub(super) const CATEGORY_PATH: &str = r#"coalesce(head(COLLECT {
+                MATCH path = (d)-[:IN_CATEGORY]->(:Category)-[:SUBCATEGORY_OF*0..]->(root:Category)
+                WHERE NOT (root)-[:SUBCATEGORY_OF]->(:Category)
+                RETURN reverse([n IN tail(nodes(path)) | n.name])
+                ORDER BY length(path) DESC
+            }), [])"#;
+
+/// Flat category row: (description, parent, direct domain count)
+type CategoryRow = (Option<String>, Option<String>, i64);
+
+/// The category tree a page of roots at a time, roots sorted by name
+pub async fn list_categories(
+    graph: &Graph,
+    params: &TaxonomyListParams,
+) -> Result<Page<Value>, ServiceError> {
+    NodeProjection::new(params.fields.as_deref(), false)?.page_of(
+        category_tree(graph).await?,
+        params.limit,
+        params.cursor.as_deref(),
+    )
+}
+
+/// The full category tree, roots sorted by name
+pub async fn category_tree(graph: &Graph) -> Result<Vec<CategoryNode>, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (c:Category)
+        OPTIONAL MATCH (c)-[:SUBCATEGORY_OF]->(parent:Category)
+        OPTIONAL MATCH (d:Domain)-[:IN_CATEGORY]->(c)
+        RETURN c.name AS name, c.description AS description, parent.name AS parent,
+               count(DISTINCT d) AS domains
+        "#
+        .to_string(),
+    );
+
+    let mut result = graph.execute(query).await?;
+    let mut rows: BTreeMap<String, CategoryRow> = BTreeMap::new();
+    while let Some(row) = result.next().await? {
+        rows.insert(
+            row.get("name").unwrap_or_default(),
+            (
+                row.get("description").unwrap_or_default(),
+                row.get("parent").unwrap_or_default(),
+                row.get("domains").unwrap_or_default(),
+            ),
+        );
+    }
+
+    let roots: Vec<&String> = rows
+        .iter()
+        .filter(|(_, (_, parent, _))| parent.as_ref().is_none_or(|p| !rows.contains_key(p)))
+        .map(|(name, _)| name)
+        .collect();
+
+    Ok(roots
+        .into_iter()
+        .map(|name| build_category(name, &rows, &mut BTreeSet::new()))
+        .collect())
+}
+
+fn build_category(
+    name: &str,
+    rows: &BTreeMap<String, CategoryRow>,
+    visited: &mut BTreeSet<String>,
+) -> CategoryNode {
+    visited.insert(name.to_string());
+    let (description, _, domain_count) = rows.get(name).cloned().unwrap_or_default();
+
+    // `visited` guards against a hand-edited SUBCATEGORY_OF cycle
+    let children: Vec<CategoryNode> = rows
+        .iter()
+        .filter(|(child, (_, parent, _))| {
+            parent.as_deref() == Some(name) && !visited.contains(*child)
+        })
+        .map(|(child, _)| child.clone())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|child| build_category(&child, rows, visited))
+        .collect();
+
+    CategoryNode {
+        name: name.to_string(),
+        description,
+        domain_count,
+        total_domain_count: domain_count
+            + children.iter().map(|c| c.total_domain_count).sum::<i64>(),
+        children,
+    }
+}
+
+/// Create a category, optionally under an existing parent
+pub async fn create_category(
+    graph: &Graph,
+    request: CreateCategoryRequest,
+) -> Result<CategoryNode, ServiceError> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ServiceError::ValidationError(
+            "Category name is required".to_string(),
+        ));
+    }
+
+    with_transaction(graph, async |txn: &mut Txn| {
+        if category_exists(txn, &name).await? {
+            return Err(ServiceError::ValidationError(format!(
+                "Category '{}' already exists",
+                name
+            )));
+        }
+        if let Some(parent) = &request.parent
+            && !category_exists(txn, parent).await?
+        {
+            return Err(ServiceError::NotFound(format!(
+                "Category '{}' not found",
+                parent
+            )));
+        }
+
+        create_category_in_txn(
+            txn,
+            &name,
+            request.description.as_deref(),
+            request.parent.as_deref(),
+        )
+        .await
+    })
+    .await?;
+
+    Ok(CategoryNode {
+        name,
+        description: request.description,
+        domain_count: 0,
+        total_domain_count: 0,
+        children: Vec::new(),
+    })
+}
+
+async fn category_exists(txn: &mut Txn, name: &str) -> Result<bool, ServiceError> {
+    let query = Neo4jQuery::new("MATCH (c:Category {name: $name}) RETURN c LIMIT 1".to_string())
+        .param("name", name);
+    let mut result = txn.execute(query).await?;
+    Ok(result.next(txn.handle()).await?.is_some())
+}
+
+async fn create_category_in_txn(
+    txn: &mut Txn,
+    name: &str,
+    description: Option<&str>,
+    parent: Option<&str>,
+) -> Result<(), ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        CREATE (c:Category {name: $name, description: $description})
+        WITH c
+        OPTIONAL MATCH (parent:Category {name: $parent})
+        FOREACH (_ IN CASE WHEN parent IS NULL THEN [] ELSE [1] END |
+            CREATE (c)-[:SUBCATEGORY_OF]->(parent))
+        "#
+        .to_string(),
+    )
+    .param("name", name)
+    .param("description", description)
+    .param("parent", parent);
+
+    txn.run(query).await?;
+    Ok(())
+}
+
+/// Set a domain's category and/or tags; fields left out are unchanged
+pub async fn set_domain_taxonomy(
+    graph: &Graph,
+    request: SetDomainTaxonomyRequest,
+) -> Result<DomainSummary, ServiceError> {
+    with_transaction(graph, async |txn: &mut Txn| {
+        let check_query =
+            Neo4jQuery::new("MATCH (d:Domain {name: $name}) RETURN d LIMIT 1".to_string())
+                .param("name", request.domain_name.clone());
+        let mut existing = txn.execute(check_query).await?;
+        if existing.next(txn.handle()).await?.is_none() {
+            return Err(ServiceError::NotFound(format!(
+                "Domain '{}' not found",
+                request.domain_name
+            )));
+        }
+
+        if let Some(category) = &request.category {
+            if !category.is_empty() && !category_exists(txn, category).await? {
+                return Err(ServiceError::NotFound(format!(
+                    "Category '{}' not found",
+                    category
+                )));
+            }
+            set_category_in_txn(txn, &request.domain_name, category).await?;
+        }
+
+        if let Some(tags) = &request.tags {
+            let query =
+                Neo4jQuery::new("MATCH (d:Domain {name: $name}) SET d.tags = $tags".to_string())
+                    .param("name", request.domain_name.clone())
+                    .param("tags", normalize_tags(tags));
+            txn.run(query).await?;
+        }
+
+        Ok(())
+    })
+    .await?;
+
+    domain_summary(graph, &request.domain_name)
+        .await?
+        .ok_or_else(|| {
+            ServiceError::NotFound(format!("Domain '{}' not found", request.domain_name))
+        })
+}
+
+/// Replace the domain's category; an empty name just removes the current one
+async fn set_category_in_txn(
+    txn: &mut Txn,
+    domain_name: &str,
+    category: &str,
+) -> Result<(), ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain {name: $name})
+        OPTIONAL MATCH (d)-[old:IN_CATEGORY]->(:Category)
+        DELETE old
+        WITH DISTINCT d
+        OPTIONAL MATCH (c:Category {name: $category})
+        FOREACH (_ IN CASE WHEN c IS NULL THEN [] ELSE [1] END |
+            CREATE (d)-[:IN_CATEGORY]->(c))
+        "#
+        .to_string(),
+    )
+    .param("name", domain_name)
+    .param("category", category);
+
+    txn.run(query).await?;
+    Ok(())
+}
+
+/// File a domain under a category path from the root, creating any missing
+/// categories along the way. Existing categories keep their current parent.
+pub async fn file_domain_under_path(
+    graph: &Graph,
+    domain_name: &str,
+    path: &[String],
+) -> Result<(), ServiceError> {
+    let path: Vec<&str> = path
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect();
+    let Some(leaf) = path.last() else {
+        return Err(ServiceError::ValidationError(
+            "Category path is empty".to_string(),
+        ));
+    };
+
+    with_transaction(graph, async |txn: &mut Txn| {
+        let mut parent: Option<&str> = None;
+        for name in &path {
+            if !category_exists(txn, name).await? {
+                create_category_in_txn(txn, name, None, parent).await?;
+            }
+            parent = Some(name);
+        }
+        set_category_in_txn(txn, domain_name, leaf).await
+    })
+    .await
+}
+
+/// Summary of one domain with its category path, as returned after filing it
+async fn domain_summary(
+    graph: &Graph,
+    domain_name: &str,
+) -> Result<Option<DomainSummary>, ServiceError> {
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (d:Domain {{name: $domainName}})
+        RETURN {{
+            elementId: elementId(d),
+            name: d.name,
+            description: d.description,
+            avatarUrl: d.avatar_url,
+            categoryPath: {},
+            tags: coalesce(d.tags, [])
+        }} AS domain
+        "#,
+        CATEGORY_PATH
+    ))
+    .param("domainName", domain_name);
+
+    let mut result = graph.execute(query).await?;
+    match result.next().await? {
+        Some(row) => Ok(Some(row.get("domain").map_err(|e| {
+            ServiceError::DatabaseError(format!("Failed to read domain summary: {}", e))
+        })?)),
+        None => Ok(None),
+    }
+}
+
+/// Every tag in use with the number of domains carrying it, most used first,
+/// a page at a time
+pub async fn list_tags(
+    graph: &Graph,
+    params: &TaxonomyListParams,
+) -> Result<Page<Value>, ServiceError> {
+    let projection = NodeProjection::new(params.fields.as_deref(), false)?;
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain)
+        UNWIND coalesce(d.tags, []) AS tag
+        RETURN tag, count(DISTINCT d) AS count
+        ORDER BY count DESC, tag
+        "#
+        .to_string(),
+    );
+
+    let mut result = graph.execute(query).await?;
+    let mut tags = Vec::new();
+    while let Some(row) = result.next().await? {
+        tags.push(TagCount {
+            tag: row.get("tag").unwrap_or_default(),
+            count: row.get("count").unwrap_or_default(),
+        });
+    }
+    projection.page_of(tags, params.limit, params.cursor.as_deref())
+}
+
+/// Trim, lowercase and dedupe tags, dropping empty ones
+pub(super) fn normalize_tags(tags: &[String]) -> Vec<String> {
+    tags.iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
@@ -0,0 +1,966 @@
+//! Bulk import/export of subgraphs between environments.
+//!
+//! Export produces a `GraphDocument` (or GraphML) where nodes are identified by
+//! their natural key - primary label + `name` - instead of element IDs, so the
+//! document can be replayed against another database. Import upserts by that
+//! same key inside a single transaction, and can run as a dry run that only
+//! reports what would be created, updated or rejected.
+
+use neo4rs::{Graph, Query as Neo4jQuery, Txn};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::common::{
+    cypher,
+    embedding::{EMBEDDING_VERSION, configured_model, generate_embeddings, node_embedding_text},
+    neo4j_utils::{json_value_to_bolt_type, with_transaction},
+};
+use crate::domains::graph::models::{
+    ExportedNode, ExportedRelationship, GraphDocument, ImportAction, ImportChange, ImportConflict,
+    ImportCounts, ImportReport, NodeKey, ServiceError,
+};
+use crate::domains::graph::schema;
+
+use super::layout;
+use super::node::insert_embedding;
+
+/// Version of the `GraphDocument` format written by export and accepted by import
+pub const GRAPH_DOCUMENT_VERSION: u32 = 1;
+
+/// Prefix for the parameters carrying imported property values, so a property
+/// named like one of the query's own parameters (`targetId`, ...) cannot clash
+const PROPERTY_PARAM_PREFIX: &str = "prop_";
+
+/// Labels holding account data, which never move between environments
+const NON_TRANSFERABLE_LABELS: &[&str] = &["Person"];
+
+/// Only live levels, linked from their Domain, and their requirement groups are
+/// exported or matched on import: draft and archived versions reuse the
+/// published level names
+const LIVE_LEVEL_PREDICATE: &str = "\
+    (NOT n:Domain_Level OR EXISTS { MATCH (:Domain)-[:HAS_DOMAIN_LEVEL]->(n) }) \
+    AND (NOT n:Requirement_Group OR EXISTS { \
+    MATCH (:Domain)-[:HAS_DOMAIN_LEVEL]->(:Domain_Level)-[:HAS_REQUIREMENT_GROUP]->(n) })";
+
+/// Requirement groups are keyed within their level; name of that level
+const OWNER_LEVEL_COLUMN: &str =
+    "head([(owner:Domain_Level)-[:HAS_REQUIREMENT_GROUP]->(n) | owner.name]) AS level";
+
+const REQUIREMENT_GROUP_LABEL: &str = "Requirement_Group";
+
+/// Which part of the graph to export
+pub enum ExportSelection {
+    /// A domain, its levels and every component those levels require
+    Domain(String),
+    /// Nodes carrying any of `labels` and matching all `properties`
+    Filter {
+        labels: Vec<String>,
+        properties: HashMap<String, Value>,
+    },
+}
+
+// ========== Export ==========
+
+/// Export the selected nodes and the relationships between them.
+/// Embeddings are stripped unless `include_embeddings` is set; soft-deleted
+/// nodes are left out.
+pub async fn export_graph(
+    graph: &Graph,
+    selection: &ExportSelection,
+    include_embeddings: bool,
+) -> Result<GraphDocument, ServiceError> {
+    let query = match selection {
+        ExportSelection::Domain(name) => domain_selection_query(name),
+        ExportSelection::Filter { labels, properties } => {
+            filter_selection_query(labels, properties)?
+        }
+    };
+
+    let mut result = graph.execute(query).await?;
+    let mut nodes_by_id: HashMap<String, ExportedNode> = HashMap::new();
+
+    while let Some(row) = result.next().await? {
+        let element_id: String = row.get("elementId").unwrap_or_default();
+        let labels: Vec<String> = row.get("labels").unwrap_or_default();
+        let mut properties = value_to_property_map(row.get("props").unwrap_or(json!({})));
+
+        let level: Option<String> = row.get("level").unwrap_or_default();
+        let Some(key) = natural_key(&labels, &properties, level) else {
+            tracing::warn!("Skipping node {} without a natural key", element_id);
+            continue;
+        };
+        if NON_TRANSFERABLE_LABELS.contains(&key.label.as_str()) {
+            continue;
+        }
+        if !include_embeddings {
+            for property in schema::SYSTEM_PROPERTIES {
+                properties.remove(*property);
+            }
+        }
+        for property in schema::LAYOUT_PROPERTIES
+            .iter()
+            .chain(schema::ANALYTICS_PROPERTIES)
+        {
+            properties.remove(*property);
+        }
+
+        nodes_by_id.insert(
+            element_id,
+            ExportedNode {
+                key,
+                labels,
+                properties,
+            },
+        );
+    }
+
+    if let ExportSelection::Domain(name) = selection
+        && nodes_by_id.is_empty()
+    {
+        return Err(ServiceError::NotFound(format!(
+            "Domain '{}' not found",
+            name
+        )));
+    }
+
+    let ids: Vec<String> = nodes_by_id.keys().cloned().collect();
+    let rel_query = Neo4jQuery::new(
+        r#"
+        MATCH (a)-[r]->(b)
+        WHERE elementId(a) IN $ids AND elementId(b) IN $ids
+        RETURN elementId(a) AS fromId, type(r) AS type, elementId(b) AS toId,
+               properties(r) AS props
+        "#
+        .to_string(),
+    )
+    .param("ids", ids);
+
+    let mut result = graph.execute(rel_query).await?;
+    let mut relationships = Vec::new();
+
+    while let Some(row) = result.next().await? {
+        let from_id: String = row.get("fromId").unwrap_or_default();
+        let to_id: String = row.get("toId").unwrap_or_default();
+        let (Some(from), Some(to)) = (nodes_by_id.get(&from_id), nodes_by_id.get(&to_id)) else {
+            continue;
+        };
+
+        relationships.push(ExportedRelationship {
+            relationship_type: row.get("type").unwrap_or_default(),
+            from: from.key.clone(),
+            to: to.key.clone(),
+            properties: value_to_property_map(row.get("props").unwrap_or(json!({}))),
+        });
+    }
+
+    // Sort so repeated exports of the same data produce identical documents
+    let mut nodes: Vec<ExportedNode> = nodes_by_id.into_values().collect();
+    nodes.sort_by(|a, b| a.key.cmp(&b.key));
+    relationships.sort_by(|a, b| {
+        (&a.from, &a.relationship_type, &a.to).cmp(&(&b.from, &b.relationship_type, &b.to))
+    });
+
+    Ok(GraphDocument {
+        format_version: GRAPH_DOCUMENT_VERSION,
+        nodes,
+        relationships,
+    })
+}
+
+fn domain_selection_query(name: &str) -> Neo4jQuery {
+    Neo4jQuery::new(format!(
+        r#"
+        MATCH (d:Domain {{name: $name}})
+        OPTIONAL MATCH (d)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+        OPTIONAL MATCH (l)-[:REQUIRES_KNOWLEDGE|REQUIRES_SKILL|REQUIRES_TRAIT|REQUIRES_MILESTONE]->(c)
+        OPTIONAL MATCH (l)-[:HAS_REQUIREMENT_GROUP]->(g:Requirement_Group)
+        WITH collect(DISTINCT d) + collect(DISTINCT l) + collect(DISTINCT c)
+             + collect(DISTINCT g) AS selected
+        UNWIND selected AS n
+        WITH DISTINCT n
+        WHERE n.{} IS NULL
+        RETURN elementId(n) AS elementId, labels(n) AS labels, properties(n) AS props, {}
+        "#,
+        schema::SOFT_DELETE_PROPERTY,
+        OWNER_LEVEL_COLUMN
+    ))
+    .param("name", name)
+}
+
+fn filter_selection_query(
+    labels: &[String],
+    properties: &HashMap<String, Value>,
+) -> Result<Neo4jQuery, ServiceError> {
+    let labels: Vec<&str> = if labels.is_empty() {
+        schema::LABEL_SCHEMAS
+            .iter()
+            .map(|s| s.label)
+            .filter(|l| !NON_TRANSFERABLE_LABELS.contains(l))
+            .collect()
+    } else {
+        labels.iter().map(|l| l.as_str()).collect()
+    };
+
+    if let Some(label) = labels.iter().find(|l| NON_TRANSFERABLE_LABELS.contains(l)) {
+        return Err(ServiceError::ValidationError(format!(
+            "{} nodes cannot be exported",
+            label
+        )));
+    }
+
+    let mut where_clauses = vec![
+        cypher::label_predicate("n", &labels)?,
+        LIVE_LEVEL_PREDICATE.to_string(),
+        format!("n.{} IS NULL", schema::SOFT_DELETE_PROPERTY),
+    ];
+    let mut params: Vec<(String, Value)> = Vec::new();
+    for (key, value) in properties {
+        let param_name = format!("filter{}", params.len());
+        where_clauses.push(format!(
+            "n.{} = ${}",
+            cypher::property_key(key)?,
+            param_name
+        ));
+        params.push((param_name, value.clone()));
+    }
+
+    let query_string = format!(
+        r#"
+        MATCH (n)
+        WHERE {}
+        RETURN elementId(n) AS elementId, labels(n) AS labels, properties(n) AS props, {}
+        "#,
+        where_clauses.join(" AND "),
+        OWNER_LEVEL_COLUMN
+    );
+
+    let mut query = Neo4jQuery::new(query_string);
+    for (param_name, value) in params {
+        query = query.param(&param_name, json_value_to_bolt_type(&value));
+    }
+
+    Ok(query)
+}
+
+/// Natural key of a node: its registered primary label and its `name`, plus
+/// the owning `level` of a requirement group
+fn natural_key(
+    labels: &[String],
+    properties: &BTreeMap<String, Value>,
+    level: Option<String>,
+) -> Option<NodeKey> {
+    let label = labels.iter().find(|l| schema::label_schema(l).is_some())?;
+    let name = properties.get("name")?.as_str()?;
+    let level = match label.as_str() {
+        REQUIREMENT_GROUP_LABEL => Some(level?),
+        _ => None,
+    };
+
+    Some(NodeKey {
+        label: label.clone(),
+        name: name.to_string(),
+        level,
+    })
+}
+
+fn value_to_property_map(value: Value) -> BTreeMap<String, Value> {
+    match value {
+        Value::Object(map) => map.into_iter().collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+// ========== GraphML ==========
+
+/// Render a graph document as GraphML.
+/// Property keys become `<key>` declarations typed from their values; lists are
+/// written as JSON strings since GraphML has no list type.
+pub fn to_graphml(document: &GraphDocument) -> String {
+    let node_keys = graphml_key_types(document.nodes.iter().map(|n| &n.properties));
+    let edge_keys = graphml_key_types(document.relationships.iter().map(|r| &r.properties));
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    out.push_str("  <key id=\"labels\" for=\"node\" attr.name=\"labels\" attr.type=\"string\"/>\n");
+    out.push_str("  <key id=\"type\" for=\"edge\" attr.name=\"type\" attr.type=\"string\"/>\n");
+    for (name, attr_type) in &node_keys {
+        out.push_str(&format!(
+            "  <key id=\"node_{0}\" for=\"node\" attr.name=\"{0}\" attr.type=\"{1}\"/>\n",
+            xml_escape(name),
+            attr_type
+        ));
+    }
+    for (name, attr_type) in &edge_keys {
+        out.push_str(&format!(
+            "  <key id=\"edge_{0}\" for=\"edge\" attr.name=\"{0}\" attr.type=\"{1}\"/>\n",
+            xml_escape(name),
+            attr_type
+        ));
+    }
+    out.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+
+    let mut node_ids: HashMap<&NodeKey, String> = HashMap::new();
+    for (i, node) in document.nodes.iter().enumerate() {
+        let id = format!("n{}", i);
+        out.push_str(&format!("    <node id=\"{}\">\n", id));
+        out.push_str(&format!(
+            "      <data key=\"labels\">:{}</data>\n",
+            xml_escape(&node.labels.join(":"))
+        ));
+        for (name, value) in &node.properties {
+            out.push_str(&format!(
+                "      <data key=\"node_{}\">{}</data>\n",
+                xml_escape(name),
+                xml_escape(&graphml_value(value))
+            ));
+        }
+        out.push_str("    </node>\n");
+        node_ids.insert(&node.key, id);
+    }
+
+    for (i, rel) in document.relationships.iter().enumerate() {
+        let (Some(source), Some(target)) = (node_ids.get(&rel.from), node_ids.get(&rel.to)) else {
+            continue;
+        };
+        out.push_str(&format!(
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n",
+            i, source, target
+        ));
+        out.push_str(&format!(
+            "      <data key=\"type\">{}</data>\n",
+            xml_escape(&rel.relationship_type)
+        ));
+        for (name, value) in &rel.properties {
+            out.push_str(&format!(
+                "      <data key=\"edge_{}\">{}</data>\n",
+                xml_escape(name),
+                xml_escape(&graphml_value(value))
+            ));
+        }
+        out.push_str("    </edge>\n");
+    }
+
+    out.push_str("  </graph>\n");
+    out.push_str("</graphml>\n");
+    out
+}
+
+/// Collect every property key with a GraphML type; keys seen with mixed types fall back to string
+fn graphml_key_types<'a>(
+    property_maps: impl Iterator<Item = &'a BTreeMap<String, Value>>,
+) -> BTreeMap<String, &'static str> {
+    let mut types: BTreeMap<String, &'static str> = BTreeMap::new();
+    for properties in property_maps {
+        for (name, value) in properties {
+            let attr_type = match value {
+                Value::Null => continue,
+                Value::Bool(_) => "boolean",
+                Value::Number(n) if n.is_i64() => "long",
+                Value::Number(_) => "double",
+                _ => "string",
+            };
+            types
+                .entry(name.clone())
+                .and_modify(|existing| {
+                    if *existing != attr_type {
+                        *existing = "string";
+                    }
+                })
+                .or_insert(attr_type);
+        }
+    }
+    types
+}
+
+fn graphml_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+// ========== Import ==========
+
+struct NodePlan<'a> {
+    node: &'a ExportedNode,
+    action: ImportAction,
+    element_id: Option<String>,
+    properties: HashMap<String, Value>,
+    /// Vector to store with the node: the document's own when it is still
+    /// comparable with the index, otherwise a fresh one if the text changed
+    embedding: Option<Vec<f64>>,
+}
+
+struct RelationshipPlan<'a> {
+    relationship: &'a ExportedRelationship,
+    action: ImportAction,
+    from_id: Option<String>,
+    to_id: Option<String>,
+}
+
+struct ExistingNode {
+    element_id: String,
+    labels: Vec<String>,
+    properties: HashMap<String, Value>,
+}
+
+struct Endpoint {
+    labels: Vec<String>,
+    element_id: Option<String>,
+}
+
+/// Upsert a graph document by natural key.
+/// Everything is planned first; if any node or relationship conflicts, or
+/// `dry_run` is set, nothing is written and the report says what would happen.
+pub async fn import_graph(
+    graph: &Graph,
+    document: GraphDocument,
+    dry_run: bool,
+) -> Result<ImportReport, ServiceError> {
+    if document.format_version != GRAPH_DOCUMENT_VERSION {
+        return Err(ServiceError::ValidationError(format!(
+            "unsupported formatVersion {} (expected {})",
+            document.format_version, GRAPH_DOCUMENT_VERSION
+        )));
+    }
+
+    // Embed up front, so the transaction never waits on the provider
+    let fresh_embeddings = if dry_run {
+        HashMap::new()
+    } else {
+        embed_stale_nodes(&document.nodes).await?
+    };
+
+    with_transaction(graph, async |txn: &mut Txn| {
+        let mut report = ImportReport {
+            dry_run,
+            applied: false,
+            nodes: ImportCounts::default(),
+            relationships: ImportCounts::default(),
+            changes: Vec::new(),
+            conflicts: Vec::new(),
+        };
+
+        let node_plans = plan_nodes(txn, &document.nodes, &fresh_embeddings, &mut report).await?;
+        let relationship_plans =
+            plan_relationships(txn, &document.relationships, &node_plans, &mut report).await?;
+
+        if dry_run || !report.conflicts.is_empty() {
+            return Ok(report);
+        }
+
+        apply_import(txn, &node_plans, &relationship_plans).await?;
+        report.applied = true;
+
+        Ok(report)
+    })
+    .await
+    .inspect(|report| {
+        if report.applied {
+            layout::request_refresh();
+        }
+    })
+}
+
+fn push_conflict(report: &mut ImportReport, key: String, reason: impl Into<String>) {
+    report.conflicts.push(ImportConflict {
+        key,
+        reason: reason.into(),
+    });
+}
+
+fn push_change(report: &mut ImportReport, kind: &'static str, action: ImportAction, key: String) {
+    match kind {
+        "node" => report.nodes.record(action),
+        _ => report.relationships.record(action),
+    }
+    if action != ImportAction::Unchanged {
+        report.changes.push(ImportChange { kind, action, key });
+    }
+}
+
+/// Check that a node's key agrees with its labels and properties
+fn check_node_key(node: &ExportedNode) -> Result<(), String> {
+    let label = node.key.label.as_str();
+    if schema::label_schema(label).is_none() {
+        return Err(format!("'{}' is not a primary label", label));
+    }
+    if NON_TRANSFERABLE_LABELS.contains(&label) {
+        return Err(format!("{} nodes cannot be imported", label));
+    }
+    if !node.labels.iter().any(|l| l == label) {
+        return Err(format!(
+            "key label '{}' is not among the node's labels",
+            label
+        ));
+    }
+    if node.properties.get("name").and_then(|v| v.as_str()) != Some(node.key.name.as_str()) {
+        return Err("key name does not match the node's name property".to_string());
+    }
+    match (label, &node.key.level) {
+        (REQUIREMENT_GROUP_LABEL, None) => {
+            Err("requirement group keys need the name of their level".to_string())
+        }
+        (REQUIREMENT_GROUP_LABEL, Some(_)) | (_, None) => Ok(()),
+        (_, Some(_)) => Err(format!("'{}' keys do not take a level", label)),
+    }
+}
+
+/// Remove the embedding properties from an imported node, returning its vector
+/// if it was produced by the configured model and text recipe. A vector from
+/// another model or recipe is not comparable with the index, so such nodes are
+/// re-embedded instead
+fn take_embedding(
+    properties: &mut HashMap<String, Value>,
+    current_model: &str,
+) -> Result<Option<Vec<f64>>, &'static str> {
+    let embedding = match properties.remove("embedding") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(
+            serde_json::from_value::<Vec<f64>>(value)
+                .map_err(|_| "embedding must be a list of numbers")?,
+        ),
+    };
+    let embedding_model = properties.remove("embedding_model");
+    let embedding_version = properties.remove("embedding_version");
+
+    Ok(embedding.filter(|_| {
+        embedding_model.as_ref().and_then(Value::as_str) == Some(current_model)
+            && embedding_version.as_ref().and_then(Value::as_i64) == Some(EMBEDDING_VERSION)
+    }))
+}
+
+/// Embed every document node without a usable embedding of its own whose label
+/// stores one (not requirement groups or categories), keyed by natural key
+async fn embed_stale_nodes(
+    nodes: &[ExportedNode],
+) -> Result<HashMap<&NodeKey, Vec<f64>>, ServiceError> {
+    let current_model =
+        configured_model().map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+
+    let mut keys = Vec::new();
+    let mut texts = Vec::new();
+    for node in nodes {
+        let embedded = schema::label_schema(&node.key.label)
+            .is_some_and(|s| s.property("embedding").is_some());
+        if !embedded {
+            continue;
+        }
+        let mut properties: HashMap<String, Value> = node.properties.clone().into_iter().collect();
+        if let Ok(None) = take_embedding(&mut properties, &current_model) {
+            let text = |key: &str| properties.get(key).and_then(Value::as_str).unwrap_or("");
+            texts.push(node_embedding_text(text("name"), text("description")));
+            keys.push(&node.key);
+        }
+    }
+    if texts.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let embeddings = generate_embeddings(&texts)
+        .await
+        .map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+    Ok(keys.into_iter().zip(embeddings).collect())
+}
+
+async fn plan_nodes<'a>(
+    txn: &mut Txn,
+    nodes: &'a [ExportedNode],
+    fresh_embeddings: &HashMap<&NodeKey, Vec<f64>>,
+    report: &mut ImportReport,
+) -> Result<Vec<NodePlan<'a>>, ServiceError> {
+    let mut plans = Vec::new();
+    let mut seen: HashSet<&NodeKey> = HashSet::new();
+    let current_model =
+        configured_model().map_err(|e| ServiceError::EmbeddingFailed(e.to_string()))?;
+
+    for node in nodes {
+        let key = node.key.to_string();
+
+        if !seen.insert(&node.key) {
+            push_conflict(report, key, "duplicate natural key in document");
+            continue;
+        }
+        if let Err(reason) = check_node_key(node) {
+            push_conflict(report, key, reason);
+            continue;
+        }
+
+        let mut properties: HashMap<String, Value> = node.properties.clone().into_iter().collect();
+        let embedding = match take_embedding(&mut properties, &current_model) {
+            Ok(embedding) => embedding,
+            Err(reason) => {
+                push_conflict(report, key, reason);
+                continue;
+            }
+        };
+
+        if let Err(e) = schema::validate_new_node(&node.labels, &properties) {
+            push_conflict(report, key, e.to_string());
+            continue;
+        }
+
+        let mut existing = find_nodes_by_key(txn, &node.key).await?;
+        let (action, element_id, reembed) = match existing.len() {
+            0 => (ImportAction::Create, None, embedding.is_none()),
+            1 => {
+                let current = existing.remove(0);
+                let differs = |k: &String, v: &Value| current.properties.get(k) != Some(v);
+                let changed = properties.iter().any(|(k, v)| differs(k, v));
+                let text_changed = properties
+                    .iter()
+                    .any(|(k, v)| (k == "name" || k == "description") && differs(k, v));
+                let action = if changed {
+                    ImportAction::Update
+                } else {
+                    ImportAction::Unchanged
+                };
+                (
+                    action,
+                    Some(current.element_id),
+                    text_changed && embedding.is_none(),
+                )
+            }
+            n => {
+                push_conflict(report, key, format!("matches {} existing nodes", n));
+                continue;
+            }
+        };
+
+        let embedding = if reembed {
+            fresh_embeddings.get(&node.key).cloned()
+        } else {
+            embedding
+        };
+
+        push_change(report, "node", action, key);
+        plans.push(NodePlan {
+            node,
+            action,
+            element_id,
+            properties,
+            embedding,
+        });
+    }
+
+    Ok(plans)
+}
+
+async fn plan_relationships<'a>(
+    txn: &mut Txn,
+    relationships: &'a [ExportedRelationship],
+    nodes: &[NodePlan<'_>],
+    report: &mut ImportReport,
+) -> Result<Vec<RelationshipPlan<'a>>, ServiceError> {
+    let planned: HashMap<&NodeKey, &NodePlan> = nodes.iter().map(|p| (&p.node.key, p)).collect();
+    let mut plans = Vec::new();
+    let mut seen: HashSet<(&NodeKey, &str, &NodeKey)> = HashSet::new();
+
+    for rel in relationships {
+        let key = format!("({})-[{}]->({})", rel.from, rel.relationship_type, rel.to);
+
+        if !seen.insert((&rel.from, rel.relationship_type.as_str(), &rel.to)) {
+            push_conflict(report, key, "duplicate relationship in document");
+            continue;
+        }
+
+        let properties: HashMap<String, Value> = rel.properties.clone().into_iter().collect();
+        if let Err(e) = schema::validate_relationship_properties(&properties) {
+            push_conflict(report, key, e.to_string());
+            continue;
+        }
+
+        let Some(from) = resolve_endpoint(txn, &planned, &rel.from).await? else {
+            push_conflict(
+                report,
+                key,
+                format!("node {} not found in document or database", rel.from),
+            );
+            continue;
+        };
+        let Some(to) = resolve_endpoint(txn, &planned, &rel.to).await? else {
+            push_conflict(
+                report,
+                key,
+                format!("node {} not found in document or database", rel.to),
+            );
+            continue;
+        };
+
+        if let Err(e) =
+            schema::validate_relationship(&rel.relationship_type, &from.labels, &to.labels)
+        {
+            push_conflict(report, key, e.to_string());
+            continue;
+        }
+
+        let action = match (&from.element_id, &to.element_id) {
+            (Some(from_id), Some(to_id)) => {
+                match find_relationship(txn, from_id, &rel.relationship_type, to_id).await? {
+                    None => ImportAction::Create,
+                    Some(current) => {
+                        if properties.iter().any(|(k, v)| current.get(k) != Some(v)) {
+                            ImportAction::Update
+                        } else {
+                            ImportAction::Unchanged
+                        }
+                    }
+                }
+            }
+            _ => ImportAction::Create,
+        };
+
+        push_change(report, "relationship", action, key);
+        plans.push(RelationshipPlan {
+            relationship: rel,
+            action,
+            from_id: from.element_id,
+            to_id: to.element_id,
+        });
+    }
+
+    Ok(plans)
+}
+
+/// Resolve a relationship endpoint from the document, falling back to a unique node in the database
+async fn resolve_endpoint(
+    txn: &mut Txn,
+    planned: &HashMap<&NodeKey, &NodePlan<'_>>,
+    key: &NodeKey,
+) -> Result<Option<Endpoint>, ServiceError> {
+    if let Some(plan) = planned.get(key) {
+        return Ok(Some(Endpoint {
+            labels: plan.node.labels.clone(),
+            element_id: plan.element_id.clone(),
+        }));
+    }
+    if schema::label_schema(&key.label).is_none() {
+        return Ok(None);
+    }
+
+    let mut existing = find_nodes_by_key(txn, key).await?;
+    if existing.len() != 1 {
+        return Ok(None);
+    }
+    let node = existing.remove(0);
+
+    Ok(Some(Endpoint {
+        labels: node.labels,
+        element_id: Some(node.element_id),
+    }))
+}
+
+async fn find_nodes_by_key(
+    txn: &mut Txn,
+    key: &NodeKey,
+) -> Result<Vec<ExistingNode>, ServiceError> {
+    // A requirement group is only matched under its own level
+    let owner_filter = if key.level.is_some() {
+        "AND EXISTS { MATCH (:Domain_Level {name: $level})-[:HAS_REQUIREMENT_GROUP]->(n) }"
+    } else {
+        ""
+    };
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (n:{} {{name: $name}})
+        WHERE {} {}
+        RETURN elementId(n) AS elementId, labels(n) AS labels, properties(n) AS props
+        "#,
+        cypher::label(&key.label)?,
+        LIVE_LEVEL_PREDICATE,
+        owner_filter
+    ))
+    .param("name", key.name.clone())
+    .param("level", key.level.clone());
+
+    let mut result = txn.execute(query).await?;
+    let mut nodes = Vec::new();
+
+    while let Some(row) = result.next(txn.handle()).await? {
+        nodes.push(ExistingNode {
+            element_id: row.get("elementId").unwrap_or_default(),
+            labels: row.get("labels").unwrap_or_default(),
+            properties: value_to_property_map(row.get("props").unwrap_or(json!({})))
+                .into_iter()
+                .collect(),
+        });
+    }
+
+    Ok(nodes)
+}
+
+async fn find_relationship(
+    txn: &mut Txn,
+    from_id: &str,
+    relationship_type: &str,
+    to_id: &str,
+) -> Result<Option<HashMap<String, Value>>, ServiceError> {
+    let query = Neo4jQuery::new(format!(
+        r#"
+        MATCH (a)-[r:{}]->(b)
+        WHERE elementId(a) = $fromId AND elementId(b) = $toId
+        RETURN properties(r) AS props
+        LIMIT 1
+        "#,
+        cypher::relationship_type(relationship_type)?
+    ))
+    .param("fromId", from_id)
+    .param("toId", to_id);
+
+    let mut result = txn.execute(query).await?;
+    Ok(result.next(txn.handle()).await?.map(|row| {
+        value_to_property_map(row.get("props").unwrap_or(json!({})))
+            .into_iter()
+            .collect()
+    }))
+}
+
+async fn apply_import(
+    txn: &mut Txn,
+    nodes: &[NodePlan<'_>],
+    relationships: &[RelationshipPlan<'_>],
+) -> Result<(), ServiceError> {
+    let mut element_ids: HashMap<&NodeKey, String> = HashMap::new();
+
+    for plan in nodes {
+        let mut properties = plan.properties.clone();
+        if let Some(embedding) = &plan.embedding {
+            insert_embedding(&mut properties, embedding.clone())?;
+        }
+
+        let element_id = match (plan.action, &plan.element_id) {
+            (ImportAction::Create, _) => {
+                // Components and domains are graph nodes whether or not they
+                // carry an embedding; levels become one when linked to their domain
+                let mut labels = plan.node.labels.clone();
+                let graph_node = plan.node.key.label == "Domain"
+                    || schema::COMPONENT_LABELS.contains(&plan.node.key.label.as_str());
+                if graph_node && !labels.iter().any(|l| l == schema::EMBEDDED_LABEL) {
+                    labels.push(schema::EMBEDDED_LABEL.to_string());
+                }
+                let query_string = format!(
+                    "CREATE (n{}) SET {} RETURN elementId(n) AS elementId",
+                    cypher::label_suffix(&labels)?,
+                    cypher::prefixed_set_clauses("n", PROPERTY_PARAM_PREFIX, properties.keys())?
+                );
+                let mut query = Neo4jQuery::new(query_string);
+                for (key, value) in &properties {
+                    query = query.param(
+                        &format!("{}{}", PROPERTY_PARAM_PREFIX, key),
+                        json_value_to_bolt_type(value),
+                    );
+                }
+
+                let mut result = txn.execute(query).await?;
+                match result.next(txn.handle()).await? {
+                    Some(row) => row.get("elementId").unwrap_or_default(),
+                    None => {
+                        return Err(ServiceError::DatabaseError(format!(
+                            "Failed to create node {}",
+                            plan.node.key
+                        )));
+                    }
+                }
+            }
+            (ImportAction::Update, Some(element_id)) => {
+                let mut set_clauses =
+                    cypher::prefixed_set_clauses("n", PROPERTY_PARAM_PREFIX, properties.keys())?;
+                if properties.contains_key("embedding") {
+                    set_clauses.push_str(&format!(", n:{}", schema::EMBEDDED_LABEL));
+                }
+                let query_string = format!(
+                    "MATCH (n) WHERE elementId(n) = $targetId SET {}",
+                    set_clauses
+                );
+                let mut query = Neo4jQuery::new(query_string).param("targetId", element_id.clone());
+                for (key, value) in &properties {
+                    query = query.param(
+                        &format!("{}{}", PROPERTY_PARAM_PREFIX, key),
+                        json_value_to_bolt_type(value),
+                    );
+                }
+
+                txn.run(query).await?;
+                element_id.clone()
+            }
+            (_, element_id) => element_id.clone().unwrap_or_default(),
+        };
+
+        element_ids.insert(&plan.node.key, element_id);
+    }
+
+    for plan in relationships {
+        if plan.action == ImportAction::Unchanged {
+            continue;
+        }
+
+        let rel = plan.relationship;
+        let from_id = plan
+            .from_id
+            .clone()
+            .or_else(|| element_ids.get(&rel.from).cloned());
+        let to_id = plan
+            .to_id
+            .clone()
+            .or_else(|| element_ids.get(&rel.to).cloned());
+        let (Some(from_id), Some(to_id)) = (from_id, to_id) else {
+            return Err(ServiceError::DatabaseError(format!(
+                "Unresolved endpoint for ({})-[{}]->({})",
+                rel.from, rel.relationship_type, rel.to
+            )));
+        };
+
+        let properties: HashMap<String, Value> = rel.properties.clone().into_iter().collect();
+        let set_clause = if properties.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "SET {}",
+                cypher::prefixed_set_clauses("r", PROPERTY_PARAM_PREFIX, properties.keys())?
+            )
+        };
+
+        // A level linked from its domain is live
+        let live_level = if rel.relationship_type == "HAS_DOMAIN_LEVEL" {
+            format!("SET target:{}", schema::EMBEDDED_LABEL)
+        } else {
+            String::new()
+        };
+
+        let query_string = format!(
+            r#"
+            MATCH (source), (target)
+            WHERE elementId(source) = $sourceId AND elementId(target) = $targetId
+            MERGE (source)-[r:{}]->(target)
+            {}
+            {}
+            "#,
+            cypher::relationship_type(&rel.relationship_type)?,
+            set_clause,
+            live_level
+        );
+
+        let mut query = Neo4jQuery::new(query_string)
+            .param("sourceId", from_id)
+            .param("targetId", to_id);
+        for (key, value) in &properties {
+            query = query.param(
+                &format!("{}{}", PROPERTY_PARAM_PREFIX, key),
+                json_value_to_bolt_type(value),
+            );
+        }
+
+        txn.run(query).await?;
+    }
+
+    Ok(())
+}
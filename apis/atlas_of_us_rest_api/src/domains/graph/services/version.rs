@@ -0,0 +1,899 @@
+//! Domain versioning.
+//!
+//! Every domain keeps a history of `Domain_Version` nodes, each owning its own
+//! set of levels:
+//!
+//! ```text
+//! (Domain)-[:HAS_VERSION]->(Domain_Version {version, status})-[:HAS_DOMAIN_LEVEL]->(Domain_Level)
+//! ```
+//!
+//! At most one version is `draft` and one is `published`; older published
+//! versions become `archived`. The Domain's own `HAS_DOMAIN_LEVEL` edges always
+//! point at the published version's levels, so readers that don't care about
+//! versions keep seeing the live domain. Only those live levels carry the `L1`
+//! label, so graph traversals skip draft and archived levels. Domains created
+//! before versioning get their history lazily: the current levels become version 1.
+//!
+//! `(Person)-[:PURSUING {version}]->(Domain)` pins the version a user started
+//! on. Edges without a version belong to the published version and are pinned
+//! to it when a newer one is published.
+
+use neo4rs::{Graph, Query as Neo4jQuery, Txn};
+use serde::Deserialize;
+use std::collections::{HashMap, hash_map::Entry};
+
+use crate::common::neo4j_utils::with_transaction;
+use crate::domains::graph::level_template::{BLOOM_LEVELS, DREYFUS_LEVELS, scale_rank};
+use crate::domains::graph::models::{
+    CreatedNodeInfo, DomainInfo, DomainLevel, DomainProgress, DomainVersionInfo,
+    DomainVersionStatus, DomainVersions, DomainVersionsParams, LevelProgress,
+    MigratePursuersRequest, MigrationReport, PublishDomainResult, PursuerMigration,
+    RequirementGroupMode, ServiceError,
+};
+use crate::domains::graph::schema::EMBEDDED_LABEL;
+
+use super::domain::{ComponentEmbeddings, create_levels_with_requirements};
+use super::layout;
+use super::node::NodeProjection;
+
+/// Make sure a domain has a version history, creating version 1 from its
+/// current levels if it has none. Returns the published version number.
+async fn ensure_version_history(
+    txn: &mut Txn,
+    domain_element_id: &str,
+) -> Result<i64, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain) WHERE elementId(d) = $domainId
+        OPTIONAL MATCH (d)-[:HAS_VERSION]->(v:Domain_Version)
+        RETURN count(v) AS versions, d.published_version AS published
+        "#
+        .to_string(),
+    )
+    .param("domainId", domain_element_id);
+
+    let mut result = txn.execute(query).await?;
+    let (versions, published) = match result.next(txn.handle()).await? {
+        Some(row) => (
+            row.get::<i64>("versions").unwrap_or(0),
+            row.get::<Option<i64>>("published").unwrap_or_default(),
+        ),
+        None => return Err(ServiceError::NotFound("Domain not found".to_string())),
+    };
+
+    if versions > 0 {
+        return published.ok_or_else(|| {
+            ServiceError::DatabaseError(format!(
+                "Domain {} has versions but none is published",
+                domain_element_id
+            ))
+        });
+    }
+
+    let bootstrap = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain) WHERE elementId(d) = $domainId
+        CREATE (d)-[:HAS_VERSION]->(v:Domain_Version {
+            version: 1,
+            status: 'published',
+            name: d.name,
+            description: d.description,
+            created_at: datetime(),
+            published_at: datetime()
+        })
+        SET d.published_version = 1
+        WITH d, v
+        MATCH (d)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+        CREATE (v)-[:HAS_DOMAIN_LEVEL]->(l)
+        "#
+        .to_string(),
+    )
+    .param("domainId", domain_element_id);
+    txn.run(bootstrap).await?;
+
+    Ok(1)
+}
+
+/// Link the domain's live levels to its published version, bootstrapping the
+/// history if needed. Used after the live levels were (re)written directly.
+pub(super) async fn sync_published_levels(
+    txn: &mut Txn,
+    domain_element_id: &str,
+) -> Result<(), ServiceError> {
+    let published = ensure_version_history(txn, domain_element_id).await?;
+
+    let link = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain)-[:HAS_VERSION]->(v:Domain_Version {version: $version})
+        WHERE elementId(d) = $domainId
+        MATCH (d)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+        MERGE (v)-[:HAS_DOMAIN_LEVEL]->(l)
+        "#
+        .to_string(),
+    )
+    .param("domainId", domain_element_id)
+    .param("version", published);
+    txn.run(link).await?;
+
+    Ok(())
+}
+
+/// Write domain info and levels into the domain's draft version, replacing
+/// any previous draft content. Returns the draft version number and the
+/// component nodes that had to be created.
+pub(super) async fn save_draft(
+    txn: &mut Txn,
+    domain_element_id: &str,
+    info: &DomainInfo,
+    levels: &[DomainLevel],
+    embeddings: &ComponentEmbeddings,
+) -> Result<(i64, Vec<CreatedNodeInfo>), ServiceError> {
+    let (draft_element_id, draft_version) =
+        open_draft(txn, domain_element_id, &info.name, &info.description).await?;
+
+    let created_nodes =
+        create_levels_with_requirements(txn, &draft_element_id, levels, embeddings).await?;
+
+    Ok((draft_version, created_nodes))
+}
+
+/// Get the domain's draft version with its levels cleared, creating it if
+/// needed. Returns the draft's element id and version number.
+pub(super) async fn open_draft(
+    txn: &mut Txn,
+    domain_element_id: &str,
+    name: &str,
+    description: &str,
+) -> Result<(String, i64), ServiceError> {
+    ensure_version_history(txn, domain_element_id).await?;
+
+    let clear_draft = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain)-[:HAS_VERSION]->(:Domain_Version {status: 'draft'})-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+        WHERE elementId(d) = $domainId
+        OPTIONAL MATCH (l)-[:HAS_REQUIREMENT_GROUP]->(g:Requirement_Group)
+        DETACH DELETE l, g
+        "#
+        .to_string(),
+    )
+    .param("domainId", domain_element_id);
+    txn.run(clear_draft).await?;
+
+    let upsert_draft = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain) WHERE elementId(d) = $domainId
+        OPTIONAL MATCH (d)-[:HAS_VERSION]->(existing:Domain_Version)
+        WITH d, coalesce(max(existing.version), 0) + 1 AS nextVersion
+        MERGE (d)-[:HAS_VERSION]->(v:Domain_Version {status: 'draft'})
+        ON CREATE SET v.version = nextVersion, v.created_at = datetime()
+        SET v.name = $name, v.description = $description
+        RETURN elementId(v) AS elementId, v.version AS version
+        "#
+        .to_string(),
+    )
+    .param("domainId", domain_element_id)
+    .param("name", name)
+    .param("description", description);
+
+    let mut result = txn.execute(upsert_draft).await?;
+    match result.next(txn.handle()).await? {
+        Some(row) => Ok((
+            row.get("elementId").unwrap_or_default(),
+            row.get("version").unwrap_or_default(),
+        )),
+        None => Err(ServiceError::DatabaseError(
+            "Failed to create draft version".to_string(),
+        )),
+    }
+}
+
+/// Record components removed by the editor on the draft, so their user progress
+/// can be cleaned up on publish. Returns the user progress edges on them.
+pub(super) async fn record_removals(
+    txn: &mut Txn,
+    domain_element_id: &str,
+    removed_node_element_ids: &[String],
+) -> Result<i64, ServiceError> {
+    if removed_node_element_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain)-[:HAS_VERSION]->(v:Domain_Version {status: 'draft'})
+        WHERE elementId(d) = $domainId
+        SET v.removed_node_element_ids =
+            [id IN coalesce(v.removed_node_element_ids, []) WHERE NOT id IN $nodeIds] + $nodeIds
+        WITH DISTINCT v
+        OPTIONAL MATCH (:Person)-[r:HAS_KNOWLEDGE|HAS_SKILL|HAS_TRAIT|ACHIEVED]->(n)
+        WHERE elementId(n) IN $nodeIds
+        RETURN count(r) AS affected
+        "#
+        .to_string(),
+    )
+    .param("domainId", domain_element_id)
+    .param("nodeIds", removed_node_element_ids.to_vec());
+
+    let mut result = txn.execute(query).await?;
+    Ok(match result.next(txn.handle()).await? {
+        Some(row) => row.get("affected").unwrap_or(0),
+        None => 0,
+    })
+}
+
+/// List a domain's versions, oldest first, a page at a time
+pub async fn list_domain_versions(
+    graph: &Graph,
+    params: &DomainVersionsParams,
+) -> Result<DomainVersions, ServiceError> {
+    let name = params.name.as_str();
+    let projection = NodeProjection::new(params.fields.as_deref(), false)?;
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain {name: $name})
+        OPTIONAL MATCH (d)-[:HAS_VERSION]->(v:Domain_Version)
+        OPTIONAL MATCH (v)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+        WITH d, v, count(l) AS levelCount
+        ORDER BY v.version
+        RETURN elementId(d) AS elementId,
+               d.published_version AS publishedVersion,
+               collect(CASE WHEN v IS NOT NULL THEN {
+                   version: v.version,
+                   status: v.status,
+                   name: v.name,
+                   description: v.description,
+                   createdAt: toString(v.created_at),
+                   publishedAt: toString(v.published_at),
+                   levelCount: levelCount
+               } ELSE NULL END) AS versions
+        "#
+        .to_string(),
+    )
+    .param("name", name);
+
+    let mut result = graph.execute(query).await?;
+    let Some(row) = result.next().await? else {
+        return Err(ServiceError::NotFound(format!(
+            "Domain '{}' not found",
+            name
+        )));
+    };
+
+    let versions: Vec<DomainVersionInfo> = row.get("versions").unwrap_or_default();
+    Ok(DomainVersions {
+        domain_element_id: row.get("elementId").unwrap_or_default(),
+        domain_name: name.to_string(),
+        published_version: row.get("publishedVersion").unwrap_or_default(),
+        versions: projection.page_of(versions, params.limit, params.cursor.as_deref())?,
+    })
+}
+
+/// Highest version of a domain (the draft, if any); `None` before versioning
+pub(super) async fn latest_version(graph: &Graph, name: &str) -> Result<Option<i64>, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain {name: $name})
+        OPTIONAL MATCH (d)-[:HAS_VERSION]->(v:Domain_Version)
+        RETURN max(v.version) AS latest
+        "#
+        .to_string(),
+    )
+    .param("name", name);
+
+    let mut result = graph.execute(query).await?;
+    match result.next().await? {
+        Some(row) => Ok(row.get::<Option<i64>>("latest").unwrap_or_default()),
+        None => Err(ServiceError::NotFound(format!(
+            "Domain '{}' not found",
+            name
+        ))),
+    }
+}
+
+/// Publish a domain's draft: it becomes the live version and the previously
+/// published one is archived. Users already pursuing the domain stay pinned
+/// to the version they started on.
+pub async fn publish_domain(
+    graph: &Graph,
+    domain_element_id: &str,
+) -> Result<PublishDomainResult, ServiceError> {
+    let result = with_transaction(graph, async |txn: &mut Txn| {
+        let previous_version = ensure_version_history(txn, domain_element_id).await?;
+
+        let pin_pursuers = Neo4jQuery::new(
+            r#"
+            MATCH (:Person)-[r:PURSUING]->(d:Domain)
+            WHERE elementId(d) = $domainId AND r.version IS NULL
+            SET r.version = $version
+            RETURN count(r) AS pinned
+            "#
+            .to_string(),
+        )
+        .param("domainId", domain_element_id)
+        .param("version", previous_version);
+
+        let mut pinned = txn.execute(pin_pursuers).await?;
+        let pinned_pursuers: i64 = match pinned.next(txn.handle()).await? {
+            Some(row) => row.get("pinned").unwrap_or(0),
+            None => 0,
+        };
+
+        let unlink_live_levels = Neo4jQuery::new(format!(
+            r#"
+            MATCH (d:Domain)-[r:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+            WHERE elementId(d) = $domainId
+            DELETE r
+            REMOVE l:{}
+            "#,
+            EMBEDDED_LABEL
+        ))
+        .param("domainId", domain_element_id);
+        txn.run(unlink_live_levels).await?;
+
+        let archive_previous = Neo4jQuery::new(
+            r#"
+            MATCH (d:Domain)-[:HAS_VERSION]->(v:Domain_Version {status: 'published'})
+            WHERE elementId(d) = $domainId
+            SET v.status = 'archived'
+            "#
+            .to_string(),
+        )
+        .param("domainId", domain_element_id);
+        txn.run(archive_previous).await?;
+
+        let publish_draft = Neo4jQuery::new(format!(
+            r#"
+            MATCH (d:Domain)-[:HAS_VERSION]->(v:Domain_Version {{status: 'draft'}})
+            WHERE elementId(d) = $domainId
+            SET v.status = 'published',
+                v.published_at = datetime(),
+                d.name = v.name,
+                d.description = v.description,
+                d.published_version = v.version
+            WITH d, v
+            OPTIONAL MATCH (v)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+            FOREACH (level IN CASE WHEN l IS NULL THEN [] ELSE [l] END |
+                CREATE (d)-[:HAS_DOMAIN_LEVEL]->(level)
+                SET level:{})
+            RETURN DISTINCT v.version AS version, d.name AS name
+            "#,
+            EMBEDDED_LABEL
+        ))
+        .param("domainId", domain_element_id);
+
+        let mut published = txn.execute(publish_draft).await?;
+        let Some(row) = published.next(txn.handle()).await? else {
+            return Err(ServiceError::NotFound(
+                "Domain has no draft version to publish".to_string(),
+            ));
+        };
+        let domain_name: String = row.get("name").unwrap_or_default();
+        let version: i64 = row.get("version").unwrap_or_default();
+
+        // Progress on removed components goes once nothing live or pinned requires them
+        let delete_removed_progress = Neo4jQuery::new(format!(
+            r#"
+            MATCH (d:Domain)-[:HAS_VERSION]->(v:Domain_Version {{version: $version}})
+            WHERE elementId(d) = $domainId
+            UNWIND coalesce(v.removed_node_element_ids, []) AS nodeId
+            MATCH (n) WHERE elementId(n) = nodeId
+              AND NOT EXISTS {{ MATCH (:Domain_Level:{})-[]->(n) }}
+              AND NOT EXISTS {{
+                  MATCH (:Person)-[p:PURSUING]->(:Domain)-[:HAS_VERSION]->(pinned:Domain_Version)
+                        -[:HAS_DOMAIN_LEVEL]->(:Domain_Level)-[]->(n)
+                  WHERE p.version = pinned.version
+              }}
+            MATCH (:Person)-[r:HAS_KNOWLEDGE|HAS_SKILL|HAS_TRAIT|ACHIEVED]->(n)
+            DELETE r
+            RETURN count(r) AS deleted
+            "#,
+            EMBEDDED_LABEL
+        ))
+        .param("domainId", domain_element_id)
+        .param("version", version);
+
+        let mut deleted = txn.execute(delete_removed_progress).await?;
+        let deleted_user_progress: i64 = match deleted.next(txn.handle()).await? {
+            Some(row) => row.get("deleted").unwrap_or(0),
+            None => 0,
+        };
+
+        Ok(PublishDomainResult {
+            domain_element_id: domain_element_id.to_string(),
+            domain_name,
+            version,
+            previous_version,
+            pinned_pursuers,
+            deleted_user_progress,
+        })
+    })
+    .await;
+
+    let result = result
+        .inspect_err(|e| tracing::error!("Error publishing domain {}: {}", domain_element_id, e))?;
+    layout::request_refresh();
+    Ok(result)
+}
+
+/// Compute, for every user pursuing the domain, their level on the version they
+/// are pinned to and on `to_version` (by default the latest, i.e. the draft if
+/// there is one). Nothing is written.
+pub async fn migration_report(
+    graph: &Graph,
+    domain_name: &str,
+    to_version: Option<i64>,
+) -> Result<MigrationReport, ServiceError> {
+    let history = version_history(graph, domain_name).await?;
+    let to_version = match to_version {
+        Some(version) if history.statuses.contains_key(&version) => version,
+        Some(version) => {
+            return Err(ServiceError::NotFound(format!(
+                "Domain '{}' has no version {}",
+                domain_name, version
+            )));
+        }
+        None => history
+            .statuses
+            .keys()
+            .copied()
+            .max()
+            .unwrap_or(history.published),
+    };
+
+    let pursuers = load_pursuers(graph, domain_name).await?;
+    let mut levels_by_version: HashMap<i64, Vec<VersionLevel>> = HashMap::new();
+    let mut migrations = Vec::new();
+
+    for pursuer in pursuers {
+        let from_version = pursuer.version.unwrap_or(history.published);
+        for version in [from_version, to_version] {
+            if let Entry::Vacant(entry) = levels_by_version.entry(version) {
+                entry.insert(load_version_levels(graph, domain_name, version).await?);
+            }
+        }
+
+        let progress = pursuer.progress_by_component();
+        let from_level = computed_level(&levels_by_version[&from_version], &progress);
+        let to_level = computed_level(&levels_by_version[&to_version], &progress);
+
+        migrations.push(PursuerMigration {
+            username: pursuer.username,
+            from_version,
+            from_level,
+            to_level,
+            level_changed: from_level != to_level,
+        });
+    }
+
+    Ok(MigrationReport {
+        domain_name: domain_name.to_string(),
+        to_version,
+        applied: false,
+        changed: migrations.iter().filter(|m| m.level_changed).count(),
+        pursuers: migrations,
+    })
+}
+
+/// Evaluate each level of the version `username` is pinned to (the published
+/// one if they aren't pursuing the domain) against their progress.
+pub async fn domain_progress(
+    graph: &Graph,
+    domain_name: &str,
+    username: &str,
+) -> Result<DomainProgress, ServiceError> {
+    let history = version_history(graph, domain_name).await?;
+    let Some(person) = load_person(graph, domain_name, username).await? else {
+        return Err(ServiceError::NotFound(format!(
+            "User '{}' not found",
+            username
+        )));
+    };
+
+    let version = person.version.unwrap_or(history.published);
+    let levels = load_version_levels(graph, domain_name, version).await?;
+    let progress = person.progress_by_component();
+
+    Ok(DomainProgress {
+        domain_name: domain_name.to_string(),
+        username: person.username.clone(),
+        version,
+        current_level: computed_level(&levels, &progress),
+        levels: levels
+            .iter()
+            .map(|level| LevelProgress {
+                level: level.level,
+                met: level_met(level, &progress),
+            })
+            .collect(),
+    })
+}
+
+/// Move pursuers to a published version, updating their recorded level.
+/// Returns the migration report for the users that were moved.
+pub async fn migrate_pursuers(
+    graph: &Graph,
+    request: &MigratePursuersRequest,
+) -> Result<MigrationReport, ServiceError> {
+    let history = version_history(graph, &request.domain_name).await?;
+    let to_version = request.to_version.unwrap_or(history.published);
+    if history.statuses.get(&to_version) == Some(&DomainVersionStatus::Draft) {
+        return Err(ServiceError::ValidationError(
+            "Pursuers cannot be moved to a draft version; publish it first".to_string(),
+        ));
+    }
+
+    let mut report = migration_report(graph, &request.domain_name, Some(to_version)).await?;
+    if let Some(usernames) = &request.usernames {
+        report.pursuers.retain(|p| usernames.contains(&p.username));
+        report.changed = report.pursuers.iter().filter(|m| m.level_changed).count();
+    }
+
+    with_transaction(graph, async |txn: &mut Txn| {
+        for pursuer in &report.pursuers {
+            let query = Neo4jQuery::new(
+                r#"
+                MATCH (:Person {username: $username})-[r:PURSUING]->(:Domain {name: $domainName})
+                SET r.version = $version, r.current_level = $level
+                "#
+                .to_string(),
+            )
+            .param("username", pursuer.username.clone())
+            .param("domainName", request.domain_name.clone())
+            .param("version", to_version)
+            .param("level", pursuer.to_level);
+            txn.run(query).await?;
+        }
+        Ok::<_, ServiceError>(())
+    })
+    .await
+    .inspect_err(|e| {
+        tracing::error!("Error migrating pursuers of {}: {}", request.domain_name, e)
+    })?;
+
+    report.applied = true;
+    Ok(report)
+}
+
+// ========== Level computation ==========
+
+struct VersionHistory {
+    published: i64,
+    statuses: HashMap<i64, DomainVersionStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionStatusRow {
+    version: i64,
+    status: DomainVersionStatus,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Requirement {
+    #[serde(rename = "type")]
+    relationship_type: String,
+    component_id: String,
+    bloom_level: Option<String>,
+    dreyfus_level: Option<String>,
+    min_score: Option<i64>,
+    #[serde(default)]
+    groups: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Group {
+    name: String,
+    mode: RequirementGroupMode,
+    count: Option<i64>,
+    proficiency: Option<String>,
+}
+
+struct VersionLevel {
+    level: i64,
+    requirements: Vec<Requirement>,
+    groups: Vec<Group>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Progress {
+    #[serde(rename = "type")]
+    relationship_type: String,
+    component_id: String,
+    bloom_level: Option<String>,
+    dreyfus_level: Option<String>,
+    score: Option<i64>,
+}
+
+struct Pursuer {
+    username: String,
+    version: Option<i64>,
+    progress: Vec<Progress>,
+}
+
+impl Pursuer {
+    fn progress_by_component(&self) -> HashMap<&str, &Progress> {
+        self.progress
+            .iter()
+            .map(|p| (p.component_id.as_str(), p))
+            .collect()
+    }
+}
+
+async fn version_history(graph: &Graph, domain_name: &str) -> Result<VersionHistory, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (d:Domain {name: $name})
+        OPTIONAL MATCH (d)-[:HAS_VERSION]->(v:Domain_Version)
+        RETURN d.published_version AS published,
+               collect(CASE WHEN v IS NOT NULL THEN {version: v.version, status: v.status} ELSE NULL END) AS versions
+        "#
+        .to_string(),
+    )
+    .param("name", domain_name);
+
+    let mut result = graph.execute(query).await?;
+    let Some(row) = result.next().await? else {
+        return Err(ServiceError::NotFound(format!(
+            "Domain '{}' not found",
+            domain_name
+        )));
+    };
+
+    let Some(published) = row.get::<Option<i64>>("published").unwrap_or_default() else {
+        return Err(ServiceError::ValidationError(format!(
+            "Domain '{}' has no version history yet",
+            domain_name
+        )));
+    };
+    let versions: Vec<VersionStatusRow> = row.get("versions").unwrap_or_default();
+
+    Ok(VersionHistory {
+        published,
+        statuses: versions
+            .into_iter()
+            .map(|v| (v.version, v.status))
+            .collect(),
+    })
+}
+
+async fn load_version_levels(
+    graph: &Graph,
+    domain_name: &str,
+    version: i64,
+) -> Result<Vec<VersionLevel>, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (:Domain {name: $name})-[:HAS_VERSION]->(:Domain_Version {version: $version})-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level)
+        OPTIONAL MATCH (l)-[r:REQUIRES_KNOWLEDGE|REQUIRES_SKILL|REQUIRES_TRAIT|REQUIRES_MILESTONE]->(c)
+        WITH l, collect(CASE WHEN c IS NOT NULL THEN {
+            type: type(r),
+            componentId: elementId(c),
+            bloomLevel: r.bloom_level,
+            dreyfusLevel: r.dreyfus_level,
+            minScore: r.min_score,
+            groups: coalesce(r.groups, [])
+        } ELSE NULL END) AS requirements
+        RETURN l.level AS level, requirements,
+               [(l)-[:HAS_REQUIREMENT_GROUP]->(g:Requirement_Group) | {
+                   name: g.name, mode: g.mode, count: g.count, proficiency: g.proficiency
+               }] AS groups
+        ORDER BY level
+        "#
+        .to_string(),
+    )
+    .param("name", domain_name)
+    .param("version", version);
+
+    let mut result = graph.execute(query).await?;
+    let mut levels = Vec::new();
+    while let Some(row) = result.next().await? {
+        levels.push(VersionLevel {
+            level: row.get("level").unwrap_or_default(),
+            requirements: row.get("requirements").unwrap_or_default(),
+            groups: row.get("groups").unwrap_or_default(),
+        });
+    }
+    Ok(levels)
+}
+
+async fn load_pursuers(graph: &Graph, domain_name: &str) -> Result<Vec<Pursuer>, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (p:Person)-[pursuing:PURSUING]->(:Domain {name: $name})
+        OPTIONAL MATCH (p)-[r:HAS_KNOWLEDGE|HAS_SKILL|HAS_TRAIT|ACHIEVED]->(c)
+        WITH p, pursuing, collect(CASE WHEN c IS NOT NULL THEN {
+            type: type(r),
+            componentId: elementId(c),
+            bloomLevel: r.bloom_level,
+            dreyfusLevel: r.dreyfus_level,
+            score: r.score
+        } ELSE NULL END) AS progress
+        RETURN p.username AS username, pursuing.version AS version, progress
+        ORDER BY username
+        "#
+        .to_string(),
+    )
+    .param("name", domain_name);
+
+    let mut result = graph.execute(query).await?;
+    let mut pursuers = Vec::new();
+    while let Some(row) = result.next().await? {
+        pursuers.push(Pursuer {
+            username: row.get("username").unwrap_or_default(),
+            version: row.get::<Option<i64>>("version").unwrap_or_default(),
+            progress: row.get("progress").unwrap_or_default(),
+        });
+    }
+    Ok(pursuers)
+}
+
+async fn load_person(
+    graph: &Graph,
+    domain_name: &str,
+    username: &str,
+) -> Result<Option<Pursuer>, ServiceError> {
+    let query = Neo4jQuery::new(
+        r#"
+        MATCH (p:Person {username: $username})
+        OPTIONAL MATCH (p)-[pursuing:PURSUING]->(:Domain {name: $name})
+        OPTIONAL MATCH (p)-[r:HAS_KNOWLEDGE|HAS_SKILL|HAS_TRAIT|ACHIEVED]->(c)
+        WITH p, pursuing, collect(CASE WHEN c IS NOT NULL THEN {
+            type: type(r),
+            componentId: elementId(c),
+            bloomLevel: r.bloom_level,
+            dreyfusLevel: r.dreyfus_level,
+            score: r.score
+        } ELSE NULL END) AS progress
+        RETURN p.username AS username, pursuing.version AS version, progress
+        "#
+        .to_string(),
+    )
+    .param("name", domain_name)
+    .param("username", username);
+
+    let mut result = graph.execute(query).await?;
+    let Some(row) = result.next().await? else {
+        return Ok(None);
+    };
+    Ok(Some(Pursuer {
+        username: row.get("username").unwrap_or_default(),
+        version: row.get::<Option<i64>>("version").unwrap_or_default(),
+        progress: row.get("progress").unwrap_or_default(),
+    }))
+}
+
+/// Whether a requirement is met, optionally at a group's proficiency floor
+/// instead of (or on top of) the requirement's own
+fn requirement_met(
+    requirement: &Requirement,
+    floor: Option<&str>,
+    progress: &HashMap<&str, &Progress>,
+) -> bool {
+    let Some(held) = progress.get(requirement.component_id.as_str()) else {
+        return false;
+    };
+
+    match (
+        requirement.relationship_type.as_str(),
+        held.relationship_type.as_str(),
+    ) {
+        ("REQUIRES_KNOWLEDGE", "HAS_KNOWLEDGE") => {
+            let required = scale_rank(BLOOM_LEVELS, requirement.bloom_level.as_deref())
+                .max(scale_rank(BLOOM_LEVELS, floor));
+            scale_rank(BLOOM_LEVELS, held.bloom_level.as_deref()) >= required
+        }
+        ("REQUIRES_SKILL", "HAS_SKILL") => {
+            let required = scale_rank(DREYFUS_LEVELS, requirement.dreyfus_level.as_deref())
+                .max(scale_rank(DREYFUS_LEVELS, floor));
+            scale_rank(DREYFUS_LEVELS, held.dreyfus_level.as_deref()) >= required
+        }
+        ("REQUIRES_TRAIT", "HAS_TRAIT") => {
+            let required = requirement
+                .min_score
+                .unwrap_or(0)
+                .max(floor.and_then(|f| f.parse().ok()).unwrap_or(0));
+            held.score.unwrap_or(0) >= required
+        }
+        ("REQUIRES_MILESTONE", "ACHIEVED") => true,
+        _ => false,
+    }
+}
+
+/// Whether a level is reached: ungrouped requirements must all be met, and
+/// every requirement group must be satisfied on its own terms
+fn level_met(level: &VersionLevel, progress: &HashMap<&str, &Progress>) -> bool {
+    let ungrouped_met = level
+        .requirements
+        .iter()
+        .filter(|r| r.groups.is_empty())
+        .all(|r| requirement_met(r, None, progress));
+
+    ungrouped_met
+        && level.groups.iter().all(|group| {
+            let members: Vec<&Requirement> = level
+                .requirements
+                .iter()
+                .filter(|r| r.groups.contains(&group.name))
+                .collect();
+            let floor = match group.mode {
+                RequirementGroupMode::AtLeast => group.proficiency.as_deref(),
+                _ => None,
+            };
+            let met = members
+                .iter()
+                .filter(|r| requirement_met(r, floor, progress))
+                .count() as i64;
+
+            match group.mode {
+                RequirementGroupMode::AllOf => met == members.len() as i64,
+                RequirementGroupMode::AnyOf => met >= group.count.unwrap_or(1),
+                RequirementGroupMode::AtLeast => met >= group.count.unwrap_or(members.len() as i64),
+            }
+        })
+}
+
+/// Highest level whose requirements, and those of every level below it, are met
+fn computed_level(levels: &[VersionLevel], progress: &HashMap<&str, &Progress>) -> i64 {
+    let mut reached = 0;
+    for level in levels {
+        if !level_met(level, progress) {
+            break;
+        }
+        reached = level.level;
+    }
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn milestone(id: &str, groups: &[&str]) -> Requirement {
+        Requirement {
+            relationship_type: "REQUIRES_MILESTONE".to_string(),
+            component_id: id.to_string(),
+            bloom_level: None,
+            dreyfus_level: None,
+            min_score: None,
+            groups: groups.iter().map(|g| g.to_string()).collect(),
+        }
+    }
+
+    fn achieved(id: &str) -> Progress {
+        Progress {
+            relationship_type: "ACHIEVED".to_string(),
+            component_id: id.to_string(),
+            bloom_level: None,
+            dreyfus_level: None,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn any_of_group_needs_one_option_and_every_ungrouped_requirement() {
+        let level = VersionLevel {
+            level: 1,
+            requirements: vec![
+                milestone("a", &["Milestone options"]),
+                milestone("b", &["Milestone options"]),
+                milestone("c", &[]),
+            ],
+            groups: vec![Group {
+                name: "Milestone options".to_string(),
+                mode: RequirementGroupMode::AnyOf,
+                count: Some(1),
+                proficiency: None,
+            }],
+        };
+
+        let held = [achieved("b")];
+        let progress = held.iter().map(|p| (p.component_id.as_str(), p)).collect();
+        assert!(!level_met(&level, &progress));
+
+        let held = [achieved("b"), achieved("c")];
+        let progress = held.iter().map(|p| (p.component_id.as_str(), p)).collect();
+        assert!(level_met(&level, &progress));
+        assert_eq!(computed_level(std::slice::from_ref(&level), &progress), 1);
+    }
+}
@@ -68,7 +68,10 @@ pub struct AffiliatedNode {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none", rename = "generalizesToElementId")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        rename = "generalizesToElementId"
+    )]
     pub generalizes_to_element_id: Option<String>,
     /// Additional properties from the node
     #[serde(flatten)]
@@ -1,5 +1,5 @@
 use neo4rs::{Graph, Query};
-use serde_json::{json, Value};
+use serde_json::{Value, json};
 
 use crate::common::neo4j_utils::map_bolt4_to_bolt5;
 
@@ -81,7 +81,10 @@ pub async fn get_user_profile(graph: &Graph, username: &str) -> Result<Value, Se
         }
         Err(e) => {
             tracing::error!("Error in get_user_profile: {}", e);
-            Err(ServiceError::DatabaseError(format!("Database error: {}", e)))
+            Err(ServiceError::DatabaseError(format!(
+                "Database error: {}",
+                e
+            )))
         }
     }
 }
@@ -1,9 +1,12 @@
 use axum::{
+    Json,
     http::StatusCode,
     response::{IntoResponse, Response},
-    Json,
 };
 use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::domains::graph::schema::SchemaViolation;
 
 /// Unified API error response format
 #[derive(Debug, Serialize)]
@@ -13,13 +16,19 @@ pub struct ApiErrorResponse {
     pub details: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<String>,
+    /// Field-level validation failures
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<SchemaViolation>,
 }
 
 /// Unified application error type with automatic HTTP response conversion
 #[derive(Debug)]
 pub enum AppError {
     // Validation errors (400)
-    ValidationError(String),
+    ValidationError {
+        message: String,
+        fields: Vec<SchemaViolation>,
+    },
 
     // Authentication errors (401)
     Unauthorized(String),
@@ -31,7 +40,10 @@ pub enum AppError {
     NotFound(String),
 
     // Conflict errors (409)
-    SimilarNodeExists { score: f64, details: String },
+    SimilarNodeExists {
+        score: f64,
+        details: String,
+    },
     AlreadyExists(String),
 
     // External service errors (502/503)
@@ -50,7 +62,7 @@ pub enum AppError {
 impl std::fmt::Display for AppError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            AppError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            AppError::ValidationError { message, .. } => write!(f, "Validation error: {}", message),
             AppError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
             AppError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
             AppError::NotFound(msg) => write!(f, "Not found: {}", msg),
@@ -70,13 +82,19 @@ impl std::fmt::Display for AppError {
 
 impl std::error::Error for AppError {}
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
+impl AppError {
+    /// Status and body, for handlers that build their own error tuples
+    pub fn into_json(self) -> (StatusCode, Json<Value>) {
+        let (status, body) = self.into_parts();
+        (status, Json(json!(body)))
+    }
+
+    fn into_parts(self) -> (StatusCode, ApiErrorResponse) {
         let (status, error, details, code) = match &self {
-            AppError::ValidationError(msg) => (
+            AppError::ValidationError { message, .. } => (
                 StatusCode::BAD_REQUEST,
                 "Validation failed".to_string(),
-                Some(msg.clone()),
+                Some(message.clone()),
                 Some("VALIDATION_ERROR".to_string()),
             ),
             AppError::Unauthorized(msg) => (
@@ -147,13 +165,27 @@ impl IntoResponse for AppError {
             ),
         };
 
-        let body = Json(ApiErrorResponse {
-            error,
-            details,
-            code,
-        });
+        let fields = match self {
+            AppError::ValidationError { fields, .. } => fields,
+            _ => Vec::new(),
+        };
 
-        (status, body).into_response()
+        (
+            status,
+            ApiErrorResponse {
+                error,
+                details,
+                code,
+                fields,
+            },
+        )
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, body) = self.into_parts();
+        (status, Json(body)).into_response()
     }
 }
 
@@ -180,7 +212,18 @@ impl From<ServiceError> for AppError {
                 AppError::SimilarNodeExists { score, details }
             }
             ServiceError::NotFound(msg) => AppError::NotFound(msg),
-            ServiceError::ValidationError(msg) => AppError::ValidationError(msg),
+            ServiceError::ValidationError(message) => AppError::ValidationError {
+                message,
+                fields: Vec::new(),
+            },
+            ServiceError::InvalidFields(fields) => AppError::ValidationError {
+                message: fields
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; "),
+                fields,
+            },
         }
     }
 }
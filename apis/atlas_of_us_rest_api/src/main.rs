@@ -2,11 +2,12 @@ pub mod common;
 pub mod config;
 pub mod domains;
 pub mod error;
+pub mod migrations;
 pub mod router;
 
 use axum::http::{
-    header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
     HeaderValue, Method,
+    header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
 };
 use dotenvy::dotenv;
 use neo4rs::*;
@@ -27,9 +28,31 @@ async fn main() {
     // Load environment variables
     dotenv().ok();
 
+    // Offline threshold calibration needs no database
+    if std::env::args().nth(1).as_deref() == Some("calibrate-thresholds") {
+        run_calibrate_command().await;
+        return;
+    }
+
     // Connect to Neo4j
     let graph = create_neo4j_connection();
 
+    // Schema migrations, either as a one-off command or before serving
+    let migration_config = migrations::MigrationConfig::from_env();
+    if std::env::args().nth(1).as_deref() == Some("migrate") {
+        run_migrate_command(&graph, &migration_config).await;
+        return;
+    }
+    if let Err(e) = prepare_schema(&graph, &migration_config).await {
+        tracing::error!("Refusing to start: {}", e);
+        std::process::exit(1);
+    }
+
+    // Keep the constellation layout current as domains change
+    domains::graph::services::layout::spawn_layout_scheduler(graph.clone());
+    // Recompute component centrality metrics periodically
+    domains::graph::services::analytics::spawn_analytics_scheduler(graph.clone());
+
     // Configure CORS
     let cors = create_cors_layer();
 
@@ -42,6 +65,80 @@ async fn main() {
     axum::serve(listener, app).await.unwrap();
 }
 
+/// Apply pending migrations (unless `RUN_MIGRATIONS=false`) and verify the
+/// live schema matches what the code expects
+async fn prepare_schema(
+    graph: &Graph,
+    config: &migrations::MigrationConfig,
+) -> Result<(), migrations::MigrationError> {
+    let run_migrations = std::env::var("RUN_MIGRATIONS")
+        .map(|v| v != "false")
+        .unwrap_or(true);
+    if run_migrations {
+        let applied = migrations::migrate(graph, config).await?;
+        if !applied.is_empty() {
+            tracing::info!("Applied migrations {:?}", applied);
+        }
+    }
+    migrations::verify(graph, config).await
+}
+
+/// `migrate` applies pending migrations; `migrate status` lists them
+async fn run_migrate_command(graph: &Graph, config: &migrations::MigrationConfig) {
+    let result = if std::env::args().nth(2).as_deref() == Some("status") {
+        migrations::status(graph).await.map(|status| {
+            println!("Applied: {:?}", status.applied);
+            for migration in status.pending {
+                println!("Pending: {} {}", migration.version, migration.description);
+            }
+        })
+    } else {
+        match migrations::migrate(graph, config).await {
+            Ok(applied) => {
+                println!("Applied migrations: {:?}", applied);
+                migrations::verify(graph, config).await
+            }
+            Err(e) => Err(e),
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+/// `calibrate-thresholds <pairs.jsonl> [--json]` replays labelled pairs and
+/// reports precision and recall per similarity threshold
+async fn run_calibrate_command() {
+    use common::thresholds::{calibration, similarity_thresholds};
+
+    let Some(path) = std::env::args().nth(2) else {
+        eprintln!("usage: atlas_of_us_rest_api calibrate-thresholds <pairs.jsonl> [--json]");
+        std::process::exit(2);
+    };
+    let json = std::env::args().any(|arg| arg == "--json");
+
+    let result = match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|input| calibration::parse_pairs(&input))
+    {
+        Ok(pairs) => calibration::calibrate(&pairs)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(format!("{}: {}", path, e)),
+    };
+
+    match result {
+        Ok(report) if json => println!("{}", serde_json::json!(report)),
+        Ok(report) => print!("{}", calibration::render(&report, similarity_thresholds())),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn create_neo4j_connection() -> Graph {
     let config: Config = ConfigBuilder::default()
         .uri(std::env::var("NEO4J_URI").unwrap_or_else(|_| "127.0.0.1:7687".to_string()))
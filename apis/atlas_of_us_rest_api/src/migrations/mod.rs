@@ -0,0 +1,486 @@
+//! Schema migrations for the indexes and constraints the services rely on.
+//!
+//! Migrations are applied in version order and each applied version is
+//! recorded as a `(:_Migration {version, description, applied_at})` node, so a
+//! migration runs at most once per database. They run on startup unless
+//! `RUN_MIGRATIONS=false`, or explicitly with:
+//!
+//! ```text
+//! atlas_of_us_rest_api migrate          # apply pending migrations
+//! atlas_of_us_rest_api migrate status   # list applied and pending versions
+//! ```
+//!
+//! After migrating, `verify` compares the live schema with what the code
+//! expects and the server refuses to start on a mismatch (for example a
+//! vector index built for a different embedding size).
+
+use neo4rs::{Graph, Query};
+use std::collections::BTreeSet;
+
+use crate::common::embedding::{EMBEDDING_VERSION, configured_model};
+use crate::common::similarity::embedding_space;
+use crate::config;
+use crate::domains::graph::schema::{EMBEDDED_LABEL, EMBEDDING_SPACE_LABEL};
+use crate::domains::graph::services::curated::any_of_milestones_statement;
+use crate::domains::graph::services::search::{FULLTEXT_INDEX, VECTOR_INDEX};
+
+/// Labels whose `name` and `description` are indexed for full-text search
+const FULLTEXT_LABELS: &[&str] = &[
+    "Knowledge",
+    "Skill",
+    "Trait",
+    "Milestone",
+    "Domain",
+    "Domain_Level",
+];
+
+/// (constraint name, label, property) for every uniqueness constraint
+const UNIQUE_CONSTRAINTS: &[(&str, &str, &str)] = &[
+    ("domain_name_unique", "Domain", "name"),
+    ("person_username_unique", "Person", "username"),
+    ("category_name_unique", "Category", "name"),
+];
+
+/// Settings that shape the generated schema statements
+#[derive(Debug, Clone)]
+pub struct MigrationConfig {
+    pub embedding_dimensions: usize,
+    /// Model assumed to have produced embeddings written before models were tracked
+    pub embedding_model: String,
+}
+
+impl MigrationConfig {
+    pub fn from_env() -> Self {
+        MigrationConfig {
+            embedding_dimensions: config::embedding_dimensions(),
+            embedding_model: configured_model().unwrap_or_default(),
+        }
+    }
+}
+
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    /// Statements run one by one in auto-commit transactions, since Neo4j
+    /// does not allow schema and data changes in the same transaction
+    statements: fn(&MigrationConfig) -> Vec<String>,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Uniqueness constraints on Domain.name, Person.username and Category.name",
+        statements: |_| {
+            UNIQUE_CONSTRAINTS
+                .iter()
+                .map(|(name, label, property)| {
+                    format!(
+                        "CREATE CONSTRAINT {} IF NOT EXISTS FOR (n:{}) REQUIRE n.{} IS UNIQUE",
+                        name, label, property
+                    )
+                })
+                .collect()
+        },
+    },
+    Migration {
+        version: 2,
+        description: "Label embedded nodes and create the nodeEmbeddings vector index",
+        statements: |config| {
+            vec![
+                format!(
+                    "MATCH (n) WHERE n.embedding IS NOT NULL AND NOT n:{0} SET n:{0}",
+                    EMBEDDED_LABEL
+                ),
+                format!(
+                    r#"
+                    CREATE VECTOR INDEX {} IF NOT EXISTS
+                    FOR (n:{}) ON (n.embedding)
+                    OPTIONS {{indexConfig: {{
+                        `vector.dimensions`: {},
+                        `vector.similarity_function`: 'cosine'
+                    }}}}
+                    "#,
+                    VECTOR_INDEX, EMBEDDED_LABEL, config.embedding_dimensions
+                ),
+            ]
+        },
+    },
+    Migration {
+        version: 3,
+        description: "Full-text index over node names and descriptions",
+        statements: |_| vec![create_fulltext_index()],
+    },
+    Migration {
+        version: 4,
+        description: "Record the model and recipe version of existing embeddings",
+        statements: |config| {
+            let model = cypher_string(&config.embedding_model);
+            vec![
+                format!(
+                    r#"
+                    MATCH (n:{}) WHERE n.embedding IS NOT NULL AND n.embedding_model IS NULL
+                    SET n.embedding_model = {}, n.embedding_version = {}
+                    "#,
+                    EMBEDDED_LABEL, model, EMBEDDING_VERSION
+                ),
+                format!(
+                    "MERGE (s:{} {{index: '{}'}}) SET s.model = {}, s.version = {}",
+                    EMBEDDING_SPACE_LABEL, VECTOR_INDEX, model, EMBEDDING_VERSION
+                ),
+            ]
+        },
+    },
+    Migration {
+        version: 5,
+        description: "Label domains and their live levels as graph nodes",
+        statements: |_| {
+            vec![
+                format!("MATCH (d:Domain) WHERE NOT d:{0} SET d:{0}", EMBEDDED_LABEL),
+                format!(
+                    "MATCH (:Domain)-[:HAS_DOMAIN_LEVEL]->(l:Domain_Level) WHERE NOT l:{0} SET l:{0}",
+                    EMBEDDED_LABEL
+                ),
+                format!(
+                    r#"
+                    MATCH (l:Domain_Level:{0})
+                    WHERE NOT EXISTS {{ (:Domain)-[:HAS_DOMAIN_LEVEL]->(l) }}
+                    REMOVE l:{0}
+                    "#,
+                    EMBEDDED_LABEL
+                ),
+            ]
+        },
+    },
+    Migration {
+        version: 6,
+        description: "Backfill Domain.created_date from the version history or curated load",
+        statements: |_| {
+            vec![
+                r#"
+                MATCH (d:Domain) WHERE d.created_date IS NULL
+                OPTIONAL MATCH (d)-[:HAS_VERSION]->(v:Domain_Version)
+                OPTIONAL MATCH (s:_DomainSource {domain: d.name})
+                WITH d, min(v.created_at) AS versioned, min(s.loaded_at) AS loaded
+                SET d.created_date = toString(date(coalesce(versioned, loaded, datetime())))
+                "#
+                .to_string(),
+            ]
+        },
+    },
+    Migration {
+        version: 7,
+        description: "Turn any_of milestone requirements into requirement groups",
+        statements: |_| vec![any_of_milestones_statement("MATCH (l:Domain_Level)")],
+    },
+    Migration {
+        version: 8,
+        description: "Add domain levels to the full-text index",
+        statements: |_| {
+            vec![
+                format!("DROP INDEX {} IF EXISTS", FULLTEXT_INDEX),
+                create_fulltext_index(),
+            ]
+        },
+    },
+    Migration {
+        version: 9,
+        description: "Label every component as a graph node",
+        statements: |_| {
+            vec![format!(
+                r#"
+                MATCH (n)
+                WHERE (n:Knowledge OR n:Skill OR n:Trait OR n:Milestone) AND NOT n:{0}
+                SET n:{0}
+                "#,
+                EMBEDDED_LABEL
+            )]
+        },
+    },
+    Migration {
+        version: 10,
+        description: "Store curated domain creation dates as ISO strings",
+        statements: |_| {
+            vec![
+                r#"
+                MATCH (d:Domain)
+                WHERE d.created_date IS NOT NULL AND NOT d.created_date IS :: STRING
+                SET d.created_date = toString(d.created_date)
+                "#
+                .to_string(),
+            ]
+        },
+    },
+];
+
+fn create_fulltext_index() -> String {
+    format!(
+        "CREATE FULLTEXT INDEX {} IF NOT EXISTS FOR (n:{}) ON EACH [n.name, n.description]",
+        FULLTEXT_INDEX,
+        FULLTEXT_LABELS.join("|")
+    )
+}
+
+/// Single-quoted Cypher string literal
+fn cypher_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Database(neo4rs::Error),
+    Failed {
+        version: i64,
+        error: neo4rs::Error,
+    },
+    /// The live schema does not match what this build expects
+    Incompatible(Vec<String>),
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::Database(e) => write!(f, "Database error: {}", e),
+            MigrationError::Failed { version, error } => {
+                write!(f, "Migration {} failed: {}", version, error)
+            }
+            MigrationError::Incompatible(problems) => {
+                write!(f, "Incompatible database schema: {}", problems.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<neo4rs::Error> for MigrationError {
+    fn from(e: neo4rs::Error) -> Self {
+        MigrationError::Database(e)
+    }
+}
+
+pub struct MigrationStatus {
+    pub applied: Vec<i64>,
+    pub pending: Vec<&'static Migration>,
+}
+
+/// Versions recorded on `_Migration` nodes, ascending
+pub async fn applied_versions(graph: &Graph) -> Result<Vec<i64>, MigrationError> {
+    let query =
+        Query::new("MATCH (m:_Migration) RETURN m.version AS version ORDER BY version".to_string());
+    let mut result = graph.execute(query).await?;
+    let mut versions = Vec::new();
+    while let Some(row) = result.next().await? {
+        versions.push(row.get("version").unwrap_or_default());
+    }
+    Ok(versions)
+}
+
+pub async fn status(graph: &Graph) -> Result<MigrationStatus, MigrationError> {
+    let applied = applied_versions(graph).await?;
+    let pending = MIGRATIONS
+        .iter()
+        .filter(|m| !applied.contains(&m.version))
+        .collect();
+    Ok(MigrationStatus { applied, pending })
+}
+
+/// Apply every pending migration in order, returning the versions applied
+pub async fn migrate(graph: &Graph, config: &MigrationConfig) -> Result<Vec<i64>, MigrationError> {
+    let status = status(graph).await?;
+    check_not_newer(&status.applied)?;
+
+    let mut applied = Vec::new();
+    for migration in status.pending {
+        tracing::info!(
+            "Applying migration {}: {}",
+            migration.version,
+            migration.description
+        );
+        for statement in (migration.statements)(config) {
+            graph
+                .run(Query::new(statement))
+                .await
+                .map_err(|error| MigrationError::Failed {
+                    version: migration.version,
+                    error,
+                })?;
+        }
+
+        let record = Query::new(
+            r#"
+            MERGE (m:_Migration {version: $version})
+            SET m.description = $description, m.applied_at = toString(datetime())
+            "#
+            .to_string(),
+        )
+        .param("version", migration.version)
+        .param("description", migration.description);
+        graph.run(record).await?;
+        applied.push(migration.version);
+    }
+
+    Ok(applied)
+}
+
+/// Refuse databases migrated by a newer build
+fn check_not_newer(applied: &[i64]) -> Result<(), MigrationError> {
+    let latest = MIGRATIONS.iter().map(|m| m.version).max().unwrap_or(0);
+    match applied.iter().max() {
+        Some(&newest) if newest > latest => Err(MigrationError::Incompatible(vec![format!(
+            "database is at migration {} but this build only knows up to {}",
+            newest, latest
+        )])),
+        _ => Ok(()),
+    }
+}
+
+struct LiveIndex {
+    name: String,
+    index_type: String,
+    labels: Vec<String>,
+    properties: Vec<String>,
+    dimensions: Option<i64>,
+}
+
+/// Check that every migration is applied and the indexes and constraints the
+/// code depends on exist in the expected shape
+pub async fn verify(graph: &Graph, config: &MigrationConfig) -> Result<(), MigrationError> {
+    let status = status(graph).await?;
+    check_not_newer(&status.applied)?;
+
+    let mut problems: Vec<String> = status
+        .pending
+        .iter()
+        .map(|m| {
+            format!(
+                "migration {} ({}) has not been applied",
+                m.version, m.description
+            )
+        })
+        .collect();
+
+    let indexes = live_indexes(graph).await?;
+    let find_index = |name: &str| indexes.iter().find(|i| i.name == name);
+
+    match find_index(VECTOR_INDEX) {
+        None => problems.push(format!("vector index '{}' is missing", VECTOR_INDEX)),
+        Some(index) => {
+            if index.index_type != "VECTOR"
+                || index.labels != [EMBEDDED_LABEL]
+                || index.properties != ["embedding"]
+            {
+                problems.push(format!(
+                    "index '{}' must be a VECTOR index on (:{}).embedding, found {} on {:?}.{:?}",
+                    VECTOR_INDEX, EMBEDDED_LABEL, index.index_type, index.labels, index.properties
+                ));
+            }
+            if index.dimensions != Some(config.embedding_dimensions as i64) {
+                problems.push(format!(
+                    "vector index '{}' has {} dimensions but EMBEDDING_DIMENSIONS is {}; \
+                     drop the index, re-run migrations and re-embed nodes",
+                    VECTOR_INDEX,
+                    index
+                        .dimensions
+                        .map_or_else(|| "unknown".to_string(), |d| d.to_string()),
+                    config.embedding_dimensions
+                ));
+            }
+        }
+    }
+
+    match find_index(FULLTEXT_INDEX) {
+        None => problems.push(format!("full-text index '{}' is missing", FULLTEXT_INDEX)),
+        Some(index) => {
+            let labels: BTreeSet<&str> = index.labels.iter().map(String::as_str).collect();
+            let properties: BTreeSet<&str> = index.properties.iter().map(String::as_str).collect();
+            if index.index_type != "FULLTEXT"
+                || labels != FULLTEXT_LABELS.iter().copied().collect()
+                || properties != BTreeSet::from(["name", "description"])
+            {
+                problems.push(format!(
+                    "index '{}' must be a FULLTEXT index on name and description of {}",
+                    FULLTEXT_INDEX,
+                    FULLTEXT_LABELS.join(", ")
+                ));
+            }
+        }
+    }
+
+    let unique = live_unique_constraints(graph).await?;
+    for (_, label, property) in UNIQUE_CONSTRAINTS {
+        if !unique.contains(&(label.to_string(), property.to_string())) {
+            problems.push(format!(
+                "uniqueness constraint on {}.{} is missing",
+                label, property
+            ));
+        }
+    }
+
+    // Not fatal: the re-embed job moves stored vectors to the configured model
+    if let Some((model, version)) = embedding_space(graph).await?
+        && (model != config.embedding_model || version != EMBEDDING_VERSION)
+    {
+        tracing::warn!(
+            "Index holds '{}' v{} embeddings but '{}' v{} is configured; \
+             similarity search rejects text queries until POST /api/secure/graph/reembed completes",
+            model,
+            version,
+            config.embedding_model,
+            EMBEDDING_VERSION
+        );
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(MigrationError::Incompatible(problems))
+    }
+}
+
+async fn live_indexes(graph: &Graph) -> Result<Vec<LiveIndex>, MigrationError> {
+    let query = Query::new(
+        r#"
+        SHOW INDEXES YIELD name, type, labelsOrTypes, properties, options
+        RETURN name, type, labelsOrTypes, properties,
+               options.indexConfig['vector.dimensions'] AS dimensions
+        "#
+        .to_string(),
+    );
+    let mut result = graph.execute(query).await?;
+    let mut indexes = Vec::new();
+    while let Some(row) = result.next().await? {
+        indexes.push(LiveIndex {
+            name: row.get("name").unwrap_or_default(),
+            index_type: row.get("type").unwrap_or_default(),
+            labels: row.get("labelsOrTypes").unwrap_or_default(),
+            properties: row.get("properties").unwrap_or_default(),
+            dimensions: row.get("dimensions").ok(),
+        });
+    }
+    Ok(indexes)
+}
+
+/// (label, property) pairs covered by a single-property uniqueness or node key constraint
+async fn live_unique_constraints(
+    graph: &Graph,
+) -> Result<BTreeSet<(String, String)>, MigrationError> {
+    let query = Query::new(
+        r#"
+        SHOW CONSTRAINTS YIELD type, entityType, labelsOrTypes, properties
+        WHERE entityType = 'NODE'
+          AND (type CONTAINS 'UNIQUENESS' OR type = 'NODE_KEY')
+          AND size(labelsOrTypes) = 1 AND size(properties) = 1
+        RETURN labelsOrTypes[0] AS label, properties[0] AS property
+        "#
+        .to_string(),
+    );
+    let mut result = graph.execute(query).await?;
+    let mut constraints = BTreeSet::new();
+    while let Some(row) = result.next().await? {
+        constraints.insert((
+            row.get("label").unwrap_or_default(),
+            row.get("property").unwrap_or_default(),
+        ));
+    }
+    Ok(constraints)
+}
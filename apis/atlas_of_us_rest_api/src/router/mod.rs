@@ -1,19 +1,26 @@
 use axum::{
-    middleware,
+    Router, middleware,
     routing::{get, post, put},
-    Router,
 };
 use neo4rs::Graph;
 use tower_http::cors::CorsLayer;
 
-use crate::common::handlers::{create_embedding_from_text, return_s3_object, upload_s3_object};
+use crate::common::handlers::{
+    create_embedding_from_text, get_embedding_cache_stats, return_s3_object, upload_s3_object,
+};
 use crate::common::logging_middleware::logging_middleware;
 use crate::domains::agent::generate_domain_sse;
 use crate::domains::auth::{healthcheck, jwt_auth_middleware, login, signup};
 use crate::domains::graph::handlers::{
-    create_domain, create_node, create_relationship, delete_relationship, get_domain,
-    get_node_with_relationships_by_search_term, get_nodes, get_similar_nodes, search_nodes,
-    update_domain, update_node, update_relationship, validate_domain_name,
+    analytics_status, create_category, create_domain, create_node, create_relationship,
+    delete_relationship, diff_domains, domain_integrity, domain_migration_report, export_graph,
+    fork_domain, get_domain, get_domain_progress, get_node_with_relationships_by_search_term,
+    get_nodes, get_paths, get_similar_nodes, get_subgraph, get_transferable_components,
+    import_graph, integrity_report, layout_status, list_categories, list_domain_versions,
+    list_domains, list_level_templates, list_tags, load_curated_domains, migrate_domain_pursuers,
+    publish_domain, reembed_status, search_nodes, set_domain_taxonomy, soft_delete_node,
+    start_analytics, start_layout, start_reembed, update_domain, update_node, update_relationship,
+    validate_domain_name,
 };
 use crate::domains::profile::handlers::get_user_profile;
 
@@ -48,6 +55,10 @@ fn create_helper_routes() -> Router<Graph> {
             "/api/secure/helper/embedding",
             post(create_embedding_from_text),
         )
+        .route(
+            "/api/secure/helper/embedding-cache-stats",
+            get(get_embedding_cache_stats),
+        )
         .route_layer(middleware::from_fn(jwt_auth_middleware))
 }
 
@@ -56,12 +67,15 @@ fn create_graph_routes() -> Router<Graph> {
     Router::new()
         // Node operations
         .route("/api/secure/graph/get-nodes", get(get_nodes))
+        .route("/api/secure/graph/subgraph", get(get_subgraph))
+        .route("/api/secure/graph/paths", get(get_paths))
         .route(
             "/api/secure/graph/get-node-with-relationships-by-search-term",
             get(get_node_with_relationships_by_search_term),
         )
         .route("/api/secure/graph/create-node", post(create_node))
         .route("/api/secure/graph/update-node", put(update_node))
+        .route("/api/secure/graph/soft-delete-node", post(soft_delete_node))
         // Relationship operations
         .route(
             "/api/secure/graph/create-relationship",
@@ -86,13 +100,79 @@ fn create_graph_routes() -> Router<Graph> {
         )
         .route("/api/secure/graph/create-domain", post(create_domain))
         .route("/api/secure/graph/update-domain", put(update_domain))
+        .route("/api/secure/graph/fork-domain", post(fork_domain))
+        .route(
+            "/api/secure/graph/level-templates",
+            get(list_level_templates),
+        )
+        // Domain versioning
+        .route(
+            "/api/secure/graph/domain-versions",
+            get(list_domain_versions),
+        )
+        .route("/api/secure/graph/publish-domain", post(publish_domain))
+        .route(
+            "/api/secure/graph/domain-migration-report",
+            get(domain_migration_report),
+        )
+        .route(
+            "/api/secure/graph/migrate-domain-pursuers",
+            post(migrate_domain_pursuers),
+        )
+        .route(
+            "/api/secure/graph/domain-progress",
+            get(get_domain_progress),
+        )
+        .route("/api/secure/graph/domain-diff", get(diff_domains))
+        .route("/api/secure/graph/domains", get(list_domains))
+        // Taxonomy
+        .route("/api/secure/graph/create-category", post(create_category))
+        .route(
+            "/api/secure/graph/domain-taxonomy",
+            put(set_domain_taxonomy),
+        )
+        // Integrity checks
+        .route("/api/secure/graph/domain-integrity", get(domain_integrity))
+        .route("/api/secure/graph/integrity-report", get(integrity_report))
+        // Bulk transfer operations
+        .route("/api/secure/graph/export", get(export_graph))
+        .route("/api/secure/graph/import", post(import_graph))
+        .route(
+            "/api/secure/graph/load-curated-domains",
+            post(load_curated_domains),
+        )
+        // Embedding maintenance
+        .route(
+            "/api/secure/graph/reembed",
+            get(reembed_status).post(start_reembed),
+        )
+        // Constellation layout
+        .route(
+            "/api/secure/graph/layout",
+            get(layout_status).post(start_layout),
+        )
+        // Component analytics
+        .route(
+            "/api/secure/graph/analytics",
+            get(analytics_status).post(start_analytics),
+        )
+        .route(
+            "/api/secure/graph/analytics/transferable-components",
+            get(get_transferable_components),
+        )
         .route_layer(middleware::from_fn(jwt_auth_middleware))
 }
 
 /// Public graph routes (no JWT required).
-/// Used by the public maintenance/landing page to render a starting constellation.
+/// Used by the public maintenance/landing page to render a starting constellation
+/// and to browse it by category and tag.
 fn create_public_graph_routes() -> Router<Graph> {
-    Router::new().route("/api/public/graph/get-nodes", get(get_nodes))
+    Router::new()
+        .route("/api/public/graph/get-nodes", get(get_nodes))
+        .route("/api/public/graph/subgraph", get(get_subgraph))
+        .route("/api/public/graph/categories", get(list_categories))
+        .route("/api/public/graph/domains", get(list_domains))
+        .route("/api/public/graph/tags", get(list_tags))
 }
 
 /// User profile routes (JWT protected)
@@ -108,6 +188,9 @@ fn create_profile_routes() -> Router<Graph> {
 /// Agent routes for AI-powered domain generation (JWT protected)
 fn create_agent_routes() -> Router<Graph> {
     Router::new()
-        .route("/api/secure/agent/generate-domain", post(generate_domain_sse))
+        .route(
+            "/api/secure/agent/generate-domain",
+            post(generate_domain_sse),
+        )
         .route_layer(middleware::from_fn(jwt_auth_middleware))
 }
@@ -50,7 +50,7 @@ export const GlobalProvider: React.FC<GlobalProviderProps> = ({ children }) => {
             if (decodedToken.sub) {
               const httpService = new HttpService();
               try {
-                const response = await httpService.fetchNodes(`secure/profile/user-profile/${decodedToken.sub}`);
+                const response = await httpService.fetchUserProfile(decodedToken.sub);
                 if (response?.nodeRoot) {
                   setProfileData(response);
                 }
@@ -94,7 +94,7 @@ export function CreateAccount() {
         localStorage.setItem("jwt", responseBody.token);
         
         httpService
-          .fetchNodes(`secure/profile/user-profile/${username}`)
+          .fetchUserProfile(username)
           .then((response) => {
             console.log('profile data: ', response)
             setLoggedIn(true);
@@ -63,7 +63,7 @@ export function Login() {
         localStorage.setItem("jwt", responseBody.token);
         
         httpService
-          .fetchNodes(`secure/profile/user-profile/${username}`)
+          .fetchUserProfile(username)
           .then((response) => {
             console.log('profile data: ', response)
             setLoggedIn(true);
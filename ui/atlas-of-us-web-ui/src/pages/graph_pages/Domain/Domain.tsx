@@ -1,7 +1,7 @@
 import { useEffect, useState, useMemo, useCallback } from "react";
 import { useNavigate, useParams } from "react-router";
 import { NavBar } from "../../../common-components/navbar/nav";
-import { getHttpService } from "../../../services/http-service";
+import { getHttpService, type DomainProgress } from "../../../services/http-service";
 import { useGlobal } from "../../../GlobalProvider";
 import type { DomainData, GeneralizationMap, GeneralizationSource } from "./domain-interfaces";
 import type { Neo4jRelationship, Neo4jNode } from "../Graph/graph-interfaces.interface";
@@ -21,6 +21,7 @@ export function Domain() {
   const [searchTerm, setSearchTerm] = useState("");
   const [selectedNode, setSelectedNode] = useState<CanvasNode | null>(null);
   const [showDescription, setShowDescription] = useState(false);
+  const [domainProgress, setDomainProgress] = useState<DomainProgress | null>(null);
 
   // Build maps for user progress (Props for canvas, ElementIds for deletion)
   const { userProgressMap, relationshipIdMap } = useMemo(() => {
@@ -84,8 +85,8 @@ export function Domain() {
   const refreshProfileData = useCallback(async () => {
     if (!loggedIn || !profileData?.nodeRoot?.Props?.username) return;
     const httpService = getHttpService();
-    const data = await httpService.fetchNodes(
-      `secure/profile/user-profile/${profileData.nodeRoot.Props.username}`
+    const data = await httpService.fetchUserProfile(
+      profileData.nodeRoot.Props.username
     );
     if (data?.nodeRoot) {
       setProfileData(data);
@@ -131,6 +132,16 @@ export function Domain() {
     }
   }, [loggedIn, refreshProfileData]);
 
+  // Levels reached, evaluated by the server against the version the user is pinned to
+  const username = profileData?.nodeRoot?.Props?.username;
+  useEffect(() => {
+    if (!loggedIn || !username || !domainData?.name) {
+      setDomainProgress(null);
+      return;
+    }
+    getHttpService().fetchDomainProgress(domainData.name, username).then(setDomainProgress);
+  }, [loggedIn, username, domainData?.name, profileData?.relationships]);
+
   // Fetch domain data
   useEffect(() => {
     if (!domainName) {
@@ -217,6 +228,9 @@ export function Domain() {
             onClick={() => setShowDescription(!showDescription)}
           >
             <span className="domain-title">{domainData?.name || domainName}</span>
+            {domainProgress && domainProgress.currentLevel > 0 && (
+              <span className="domain-level-badge">Level {domainProgress.currentLevel}</span>
+            )}
             {domainData?.description && (
               <svg className="chevron-icon" width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
                 <path d="M6 9l6 6 6-6" />
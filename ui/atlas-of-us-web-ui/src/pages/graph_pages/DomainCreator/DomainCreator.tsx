@@ -2,7 +2,12 @@ import { useState, useCallback, useMemo, useEffect } from "react";
 import { useNavigate, useParams } from "react-router";
 import { NavBar } from "../../../common-components/navbar/nav";
 import { useGlobal } from "../../../GlobalProvider";
-import { HttpService, type CreateDomainRequest, type UpdateDomainRequest } from "../../../services/http-service";
+import {
+  HttpService,
+  type CreateDomainRequest,
+  type DomainVersionInfo,
+  type UpdateDomainRequest,
+} from "../../../services/http-service";
 import { CreatorCanvas } from "./CreatorCanvas/CreatorCanvas";
 import { NodeSearchPanel } from "./NodeSearchPanel/NodeSearchPanel";
 import { NodeCreationModal } from "./NodeCreationModal/NodeCreationModal";
@@ -35,9 +40,14 @@ export function DomainCreator() {
   const [originalDomainName, setOriginalDomainName] = useState<string>('');
   const [isLoadingDomain, setIsLoadingDomain] = useState(false);
 
-  // Track removed nodes for edit mode (to delete user progress)
+  // Track removed nodes for edit mode (their user progress is deleted on publish)
   const [removedNodeElementIds, setRemovedNodeElementIds] = useState<string[]>([]);
 
+  // Version history for edit mode; saving writes a draft that has to be published
+  const [versions, setVersions] = useState<DomainVersionInfo[]>([]);
+  const [isPublishing, setPublishing] = useState(false);
+  const [saveNotice, setSaveNotice] = useState<string | null>(null);
+
   // UI state
   const [selectedLevelIndex, setSelectedLevelIndex] = useState<number>(0);
   const [selectedNode, setSelectedNode] = useState<EditableNode | null>(null);
@@ -51,6 +61,12 @@ export function DomainCreator() {
 
   // Current level helper
   const currentLevel = domain.levels[selectedLevelIndex];
+  const draft = versions.find(v => v.status === 'draft');
+
+  const loadVersions = useCallback(async (name: string) => {
+    const data = await httpService.fetchDomainVersions(name);
+    setVersions(data?.items ?? []);
+  }, []);
 
   // Fetch existing domain in edit mode
   useEffect(() => {
@@ -64,6 +80,7 @@ export function DomainCreator() {
         const editableDomain = domainDataToEditableDomain(data);
         setDomain(editableDomain);
         setOriginalDomainName(data.name);
+        await loadVersions(data.name);
       } else {
         setSaveError(`Domain "${domainName}" not found`);
       }
@@ -366,14 +383,22 @@ export function DomainCreator() {
 
     setSaving(true);
     setSaveError(null);
+    setSaveNotice(null);
 
     if (isEditMode && domain.elementId) {
-      // Edit mode: update existing domain
+      // Edit mode: save into the draft version; it goes live once published
       const request = domainToUpdateRequest();
       const result = await httpService.updateDomain(request);
 
       if (result.success && result.domain) {
-        navigate(`/Domain/${encodeURIComponent(result.domain.name)}`);
+        // The removals are recorded on the draft now
+        setRemovedNodeElementIds([]);
+        const affected = result.affectedUserProgressCount ?? 0;
+        setSaveNotice(
+          `Saved draft v${result.draftVersion}.` +
+          (affected > 0 ? ` ${affected} user progress entries on removed nodes will be deleted on publish.` : "")
+        );
+        await loadVersions(originalDomainName);
       } else {
         setSaveError(result.error || "Failed to update domain");
       }
@@ -403,7 +428,24 @@ export function DomainCreator() {
     }
 
     setSaving(false);
-  }, [domain.name, domain.elementId, isEditMode, validateDomain, domainToCreateRequest, domainToUpdateRequest, navigate]);
+  }, [domain.name, domain.elementId, isEditMode, originalDomainName, validateDomain, domainToCreateRequest, domainToUpdateRequest, loadVersions, navigate]);
+
+  // Publish the draft, making it the live version
+  const handlePublish = useCallback(async () => {
+    if (!domain.elementId) return;
+
+    setPublishing(true);
+    setSaveError(null);
+
+    const result = await httpService.publishDomain(domain.elementId);
+    if (result.success && result.domainName) {
+      navigate(`/Domain/${encodeURIComponent(result.domainName)}`);
+    } else {
+      setSaveError(result.error || "Failed to publish domain");
+    }
+
+    setPublishing(false);
+  }, [domain.elementId, navigate]);
 
   // Count total nodes
   const totalNodeCount = useMemo(() => {
@@ -475,10 +517,19 @@ export function DomainCreator() {
             <button
               className="save-btn btn-cosmic"
               onClick={handleSave}
-              disabled={isSaving}
+              disabled={isSaving || isPublishing}
             >
-              {isSaving ? "Saving..." : isEditMode ? "Update Domain" : "Save Domain"}
+              {isSaving ? "Saving..." : isEditMode ? "Save Draft" : "Save Domain"}
             </button>
+            {isEditMode && draft && (
+              <button
+                className="save-btn btn-cosmic"
+                onClick={handlePublish}
+                disabled={isSaving || isPublishing}
+              >
+                {isPublishing ? "Publishing..." : `Publish v${draft.version}`}
+              </button>
+            )}
           </div>
 
           {/* Description */}
@@ -492,6 +543,28 @@ export function DomainCreator() {
             />
           </div>
 
+          {/* Version history */}
+          {isEditMode && versions.length > 0 && (
+            <div className="domain-versions-row">
+              <span className="domain-versions-label">Versions</span>
+              {versions.map(v => (
+                <span
+                  key={v.version}
+                  className={`domain-version-badge ${v.status}`}
+                  title={v.publishedAt ? `Published ${v.publishedAt}` : `Created ${v.createdAt ?? ""}`}
+                >
+                  v{v.version} · {v.status} · {v.levelCount} levels
+                </span>
+              ))}
+            </div>
+          )}
+
+          {saveNotice && (
+            <div className="domain-creator-notice">
+              {saveNotice}
+            </div>
+          )}
+
           {/* Error message */}
           {saveError && (
             <div className="domain-creator-error">
@@ -49,7 +49,7 @@ export function NodeSearchPanel({
     const response = await httpService.searchNodes(searchQuery, [label], 20);
 
     if (response) {
-      setResults(response.nodes);
+      setResults(response.items);
     } else {
       setResults([]);
     }
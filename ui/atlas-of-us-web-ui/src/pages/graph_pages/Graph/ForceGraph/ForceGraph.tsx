@@ -15,6 +15,8 @@ export interface GraphData {
   links: Neo4jRelationship[]
 }
 
+const PUBLIC_CONSTELLATION_NODES = 300;
+
 export function ForceGraph({ initialNodeId, usePublicEndpoint = false, initialData }: {
   initialNodeId: string | null;
   usePublicEndpoint?: boolean;
@@ -27,14 +29,18 @@ export function ForceGraph({ initialNodeId, usePublicEndpoint = false, initialDa
     initialData ?? ({} as Neo4jApiResponse)
   );
 
-  //load the initial data based on component input (skipped when initialData is provided)
+  //load the initial data based on component input
+  //the public constellation replaces initialData once loaded; otherwise initialData skips the load
   useEffect(() => {
+    if (usePublicEndpoint) {
+      graphUtils.loadPublicConstellation(PUBLIC_CONSTELLATION_NODES).then((data) => {
+        if (data) setNeo4jResponse(data);
+      });
+      return;
+    }
     if (initialData) return;
     if (initialNodeId) {
-      const loader = usePublicEndpoint
-        ? graphUtils.loadPublicNodeById(initialNodeId, 6)
-        : graphUtils.loadNodeById(initialNodeId, 1);
-      loader.then((data) => {
+      graphUtils.loadNodeById(initialNodeId, 1).then((data) => {
         setNeo4jResponse(data);
       });
     }
@@ -7,6 +7,9 @@ export interface Neo4jNode {
   },
   // Added for GENERALIZES_TO tracking - what general node this skill/knowledge links to
   GeneralizesToElementId?: string | null,
+  // Pinned position from the precomputed constellation layout
+  fx?: number,
+  fy?: number,
 }
 
 export interface Neo4jRelationship {
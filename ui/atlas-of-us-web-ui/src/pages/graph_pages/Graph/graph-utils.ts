@@ -1,5 +1,9 @@
 import type { HttpService } from "../../../services/http-service";
-import type { Neo4jApiResponse } from "./graph-interfaces.interface";
+import type {
+  Neo4jApiResponse,
+  Neo4jNode,
+  Neo4jRelationship,
+} from "./graph-interfaces.interface";
 import * as THREE from "three";
 import { OrbitControls } from "three/examples/jsm/controls/OrbitControls.js";
 import { useThree } from "@react-three/fiber";
@@ -100,7 +104,7 @@ export class GraphUtils {
     depth: number
   ): Promise<Neo4jApiResponse> {
     return this.httpService.fetchNodes(
-      `secure/graph/get-node-with-relationships-by-search-term?searchTerm=${searchTerm}&depth=${depth}`
+      `secure/graph/get-node-with-relationships-by-search-term?searchTerm=${encodeURIComponent(searchTerm)}&depth=${depth}&limit=1`
     );
   }
 
@@ -119,19 +123,39 @@ export class GraphUtils {
     );
   }
 
-  async loadPublicNodeById(
-    elementId: string,
-    depth: number
-  ): Promise<Neo4jApiResponse> {
-    const queryParams = new URLSearchParams({
-      properties: JSON.stringify({
-        elementId: elementId
-      }),
-      depth: depth.toString()
-    });
-    return this.httpService.fetchNodes(
-      `public/graph/get-nodes?${queryParams.toString()}`
+  // Public constellation with the stored layout; null when it can't be loaded
+  async loadPublicConstellation(
+    maxNodes: number
+  ): Promise<Neo4jApiResponse | null> {
+    const subgraph = await this.httpService.fetchSubgraph(
+      { layout: true, maxNodes },
+      "public"
     );
+    if (!subgraph || subgraph.nodes.length === 0) {
+      return null;
+    }
+
+    const nodes: Neo4jNode[] = subgraph.nodes.map((node) => ({
+      ElementId: node.id,
+      Id: node.id,
+      Labels: node.labels,
+      Props: node.properties,
+      fx: node.x,
+      fy: node.y,
+    }));
+    const relationships: Neo4jRelationship[] = subgraph.edges.map((edge) => ({
+      ElementId: edge.id,
+      StartElementId: edge.source,
+      EndElementId: edge.target,
+      Type: edge.type,
+      Props: edge.properties,
+    }));
+
+    return {
+      nodeRoot: nodes[0],
+      relationships,
+      affiliates: nodes.slice(1),
+    };
   }
 
   centerCameraOnMesh = (object: THREE.Object3D, distanceFactor: number) => {
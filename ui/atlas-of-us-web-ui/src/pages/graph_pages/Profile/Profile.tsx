@@ -20,10 +20,10 @@ export function Profile() {
     const jwt = localStorage.getItem("jwt");
     if (jwt) {
       const decoded = jwtDecode(jwt);
-      const username = decoded.iss;
+      const username = decoded.iss ?? "";
 
       httpService
-        .fetchNodes(`secure/profile/user-profile/${username}`)
+        .fetchUserProfile(username)
         .then((response) => {
           setLoading(false);
           setProfileData(response);
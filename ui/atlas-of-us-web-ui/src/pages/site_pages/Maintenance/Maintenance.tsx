@@ -25,7 +25,11 @@ export function Maintenance() {
           <TrackballControls makeDefault />
           <ambientLight intensity={0.5} />
           <directionalLight position={[10, 10, 5]} intensity={1} />
-          <ForceGraph initialNodeId={null} initialData={FALLBACK_GRAPH_DATA} />
+          <ForceGraph
+            initialNodeId={null}
+            usePublicEndpoint
+            initialData={FALLBACK_GRAPH_DATA}
+          />
         </Canvas>
       </div>
       <div className="maintenance-overlay">
@@ -59,13 +59,44 @@ export class HttpService {
         throw new Error(`HTTP error! status: ${response.status}`);
       }
 
-      const [body] = await response.json();
+      const { items: [body] } = await response.json();
       const mappedBody = {
+        nodeRoot: body.node as Neo4jNode,
+        relationships: body.relationships as Neo4jRelationship[],
+        affiliates: body.affiliatedNodes as Neo4jNode[]
+      };
+      return mappedBody;
+    } catch (err) {
+      console.error(err);
+      return {
+        nodeRoot: {},
+        relationships: [],
+        affiliates: []
+      } as Neo4jApiResponse;
+    }
+  }
+
+  // The profile endpoint returns the raw Bolt rows ([{Keys, Values}]) rather than the paged envelope
+  async fetchUserProfile(username: string): Promise<Neo4jApiResponse> {
+    try {
+      const response = await fetch(`${this.API_BASE}/secure/profile/user-profile/${username}`, {
+        method: "GET",
+        headers: {
+          "Content-Type": "application/json",
+          Authorization: `Bearer ${localStorage.getItem("jwt")}`,
+        },
+      });
+
+      if (!response.ok) {
+        throw new Error(`HTTP error! status: ${response.status}`);
+      }
+
+      const [body] = await response.json();
+      return {
         nodeRoot: body.Values[0] as Neo4jNode,
         relationships: body.Values[1] as Neo4jRelationship[],
         affiliates: body.Values[2] as Neo4jNode[]
       };
-      return mappedBody;
     } catch (err) {
       console.error(err);
       return {
@@ -76,6 +107,104 @@ export class HttpService {
     }
   }
 
+  async fetchSubgraph(
+    options: SubgraphOptions = {},
+    scope: 'public' | 'secure' = 'public'
+  ): Promise<Subgraph | null> {
+    try {
+      const params = new URLSearchParams();
+      if (options.root) params.set('root', options.root);
+      if (options.labels?.length) params.set('labels', options.labels.join(','));
+      if (options.relationshipTypes?.length) {
+        params.set('relationshipTypes', options.relationshipTypes.join(','));
+      }
+      if (options.depth !== undefined) params.set('depth', options.depth.toString());
+      if (options.maxNodes !== undefined) params.set('maxNodes', options.maxNodes.toString());
+      if (options.layout) params.set('layout', 'true');
+
+      const response = await fetch(`${this.API_BASE}/${scope}/graph/subgraph?${params}`, {
+        method: 'GET',
+        headers: {
+          'Content-Type': 'application/json',
+          Authorization: `Bearer ${localStorage.getItem('jwt')}`,
+        },
+      });
+
+      if (!response.ok) {
+        throw new Error(`HTTP error! status: ${response.status}`);
+      }
+
+      return await response.json();
+    } catch (err) {
+      console.error('Error fetching subgraph:', err);
+      return null;
+    }
+  }
+
+  async fetchPaths(
+    from: string,
+    to: string,
+    options: PathOptions = {}
+  ): Promise<PathsResult | null> {
+    try {
+      const params = new URLSearchParams({ from, to });
+      if (options.k !== undefined) params.set('k', options.k.toString());
+      if (options.maxHops !== undefined) params.set('maxHops', options.maxHops.toString());
+      if (options.relationshipTypes?.length) {
+        params.set('relationshipTypes', options.relationshipTypes.join(','));
+      }
+
+      const response = await fetch(`${this.API_BASE}/secure/graph/paths?${params}`, {
+        method: 'GET',
+        headers: {
+          'Content-Type': 'application/json',
+          Authorization: `Bearer ${localStorage.getItem('jwt')}`,
+        },
+      });
+
+      if (!response.ok) {
+        throw new Error(`HTTP error! status: ${response.status}`);
+      }
+
+      return await response.json();
+    } catch (err) {
+      console.error('Error fetching paths:', err);
+      return null;
+    }
+  }
+
+  async fetchTransferableComponents(
+    options: TransferableComponentsOptions = {}
+  ): Promise<ComponentMetrics[] | null> {
+    try {
+      const params = new URLSearchParams();
+      if (options.label) params.set('label', options.label);
+      if (options.orderBy) params.set('orderBy', options.orderBy);
+      if (options.limit !== undefined) params.set('limit', options.limit.toString());
+
+      const response = await fetch(
+        `${this.API_BASE}/secure/graph/analytics/transferable-components?${params}`,
+        {
+          method: 'GET',
+          headers: {
+            'Content-Type': 'application/json',
+            Authorization: `Bearer ${localStorage.getItem('jwt')}`,
+          },
+        }
+      );
+
+      if (!response.ok) {
+        throw new Error(`HTTP error! status: ${response.status}`);
+      }
+
+      const body = await response.json();
+      return body.components;
+    } catch (err) {
+      console.error('Error fetching transferable components:', err);
+      return null;
+    }
+  }
+
   async fetchDomain(domainName: string): Promise<DomainData | null> {
     try {
       const response = await fetch(
@@ -205,7 +334,7 @@ export class HttpService {
     query: string,
     labels?: string[],
     limit?: number
-  ): Promise<{ nodes: SearchNodeResult[] } | null> {
+  ): Promise<{ items: SearchNodeResult[]; nextCursor?: string | null; total: number } | null> {
     try {
       const params = new URLSearchParams({ query });
       if (labels && labels.length > 0) {
@@ -306,6 +435,7 @@ export class HttpService {
         success: true,
         domain: data.domain,
         createdNodes: data.createdNodes,
+        draftVersion: data.draftVersion,
         affectedUserProgressCount: data.affectedUserProgressCount,
       };
     } catch (err) {
@@ -314,6 +444,78 @@ export class HttpService {
     }
   }
 
+  async fetchDomainVersions(domainName: string): Promise<DomainVersions | null> {
+    try {
+      const response = await fetch(
+        `${this.API_BASE}/secure/graph/domain-versions?name=${encodeURIComponent(domainName)}&limit=100`,
+        {
+          method: 'GET',
+          headers: {
+            'Content-Type': 'application/json',
+            Authorization: `Bearer ${localStorage.getItem('jwt')}`,
+          },
+        }
+      );
+
+      if (!response.ok) {
+        throw new Error(`HTTP error! status: ${response.status}`);
+      }
+
+      return (await response.json()) as DomainVersions;
+    } catch (err) {
+      console.error('Error fetching domain versions:', err);
+      return null;
+    }
+  }
+
+  async fetchDomainProgress(domainName: string, username: string): Promise<DomainProgress | null> {
+    try {
+      const response = await fetch(
+        `${this.API_BASE}/secure/graph/domain-progress?name=${encodeURIComponent(domainName)}&username=${encodeURIComponent(username)}`,
+        {
+          method: 'GET',
+          headers: {
+            'Content-Type': 'application/json',
+            Authorization: `Bearer ${localStorage.getItem('jwt')}`,
+          },
+        }
+      );
+
+      if (!response.ok) {
+        throw new Error(`HTTP error! status: ${response.status}`);
+      }
+
+      return (await response.json()) as DomainProgress;
+    } catch (err) {
+      console.error('Error fetching domain progress:', err);
+      return null;
+    }
+  }
+
+  async publishDomain(domainElementId: string): Promise<PublishDomainResponse> {
+    try {
+      const response = await fetch(`${this.API_BASE}/secure/graph/publish-domain`, {
+        method: 'POST',
+        headers: {
+          'Content-Type': 'application/json',
+          Authorization: `Bearer ${localStorage.getItem('jwt')}`,
+        },
+        body: JSON.stringify({ domainElementId }),
+      });
+
+      if (!response.ok) {
+        const errorData = await response.json();
+        return { success: false, error: errorData.error || 'Failed to publish domain' };
+      }
+
+      const data = await response.json();
+      return { success: true, ...data };
+    } catch (err) {
+      console.error('Error publishing domain:', err);
+      return { success: false, error: 'Network error' };
+    }
+  }
+
   // Domain Generator endpoints
   async findSimilarDomains(
     domainName: string,
@@ -337,7 +539,8 @@ export class HttpService {
         throw new Error(`HTTP error! status: ${response.status}`);
       }
 
-      return await response.json();
+      const { items } = await response.json();
+      return items;
     } catch (err) {
       console.error('Error finding similar domains:', err);
       return null;
@@ -430,6 +633,12 @@ export interface SearchNodeResult {
     how_to_achieve?: string;
     [key: string]: unknown;
   };
+  score?: number;
+  matchedBy?: ('text' | 'vector')[];
+  highlights?: {
+    name?: string | null;
+    description?: string | null;
+  };
 }
 
 export interface NewNodeData {
@@ -532,16 +741,126 @@ export interface UpdateDomainResponse {
     name: string;
     labels: string[];
   }>;
+  draftVersion?: number;
   affectedUserProgressCount?: number;
   error?: string;
 }
 
+export interface DomainVersionInfo {
+  version: number;
+  status: 'draft' | 'published' | 'archived';
+  name: string;
+  description?: string;
+  createdAt?: string;
+  publishedAt?: string;
+  levelCount: number;
+}
+
+export interface DomainProgress {
+  domainName: string;
+  username: string;
+  version: number;
+  currentLevel: number;
+  levels: { level: number; met: boolean }[];
+}
+
+export interface DomainVersions {
+  domainElementId: string;
+  domainName: string;
+  publishedVersion?: number;
+  items: DomainVersionInfo[];
+  nextCursor?: string;
+  total: number;
+}
+
+export interface PublishDomainResponse {
+  success: boolean;
+  domainName?: string;
+  version?: number;
+  previousVersion?: number;
+  pinnedPursuers?: number;
+  deletedUserProgress?: number;
+  error?: string;
+}
+
 // Domain Generator types
+export interface SubgraphOptions {
+  root?: string;
+  labels?: string[];
+  relationshipTypes?: string[];
+  depth?: number;
+  maxNodes?: number;
+  layout?: boolean;
+}
+
+export interface SubgraphNode {
+  id: string;
+  labels: string[];
+  properties: Record<string, unknown>;
+  depth: number;
+  degree: number;
+  truncated: boolean;
+  cluster?: number;
+  x?: number;
+  y?: number;
+}
+
+export interface SubgraphEdge {
+  id: string;
+  type: string;
+  source: string;
+  target: string;
+  properties: Record<string, unknown>;
+}
+
+export interface Subgraph {
+  nodes: SubgraphNode[];
+  edges: SubgraphEdge[];
+  truncated: boolean;
+  maxNodes: number;
+}
+
+export interface PathOptions {
+  k?: number;
+  maxHops?: number;
+  relationshipTypes?: string[];
+}
+
+export interface GraphPath {
+  cost: number;
+  hops: number;
+  node: Neo4jNode;
+  relationships: Neo4jRelationship[];
+  affiliatedNodes: Neo4jNode[];
+}
+
+export interface PathsResult {
+  paths: GraphPath[];
+  truncated: boolean;
+}
+
+export interface TransferableComponentsOptions {
+  label?: 'Knowledge' | 'Skill' | 'Trait' | 'Milestone';
+  orderBy?: 'domains' | 'pagerank';
+  limit?: number;
+}
+
+export interface ComponentMetrics {
+  elementId: string;
+  label: string;
+  name: string;
+  domainCount: number;
+  pagerank: number;
+  avgProficiency?: number | null;
+}
+
 export interface SimilarDomainResult {
   name: string;
   description: string | null;
   id: string;
   score: number;
+  labels?: string[];
+  domains?: string[];
 }
 
 export type DomainGenerationEventType =